@@ -47,7 +47,10 @@ fn benchmark_http(c: &mut Criterion) {
                                 compression: *compression,
                                 method: Default::default(),
                                 auth: Default::default(),
+                                oauth2: Default::default(),
                                 headers: Default::default(),
+                                query: Default::default(),
+                                wrapper: Default::default(),
                                 batch: sinks::util::BatchConfig {
                                     max_bytes: Some(num_lines * line_size),
                                     ..Default::default()
@@ -45,8 +45,11 @@ fn bench_add_fields(c: &mut Criterion) {
                 key, value
             );
             Transform::task(
-                transforms::lua::v2::Lua::new(&toml::from_str::<LuaConfig>(&config).unwrap())
-                    .unwrap(),
+                transforms::lua::v2::Lua::new(
+                    &toml::from_str::<LuaConfig>(&config).unwrap(),
+                    Default::default(),
+                )
+                .unwrap(),
             )
         }),
     ];
@@ -131,7 +134,8 @@ fn bench_field_filter(c: &mut Criterion) {
                 """
             "#};
             Transform::task(
-                transforms::lua::v2::Lua::new(&toml::from_str(config).unwrap()).unwrap(),
+                transforms::lua::v2::Lua::new(&toml::from_str(config).unwrap(), Default::default())
+                    .unwrap(),
             )
         }),
     ];
@@ -1,3 +1,5 @@
+#![allow(clippy::upper_case_acronyms)]
+
 use crate::{BoxedSubscription, QueryResult};
 use async_trait::async_trait;
 use graphql_client::GraphQLQuery;
@@ -182,7 +184,7 @@ impl fmt::Display for component_added_subscription::ComponentAddedSubscriptionCo
             component_added_subscription::ComponentAddedSubscriptionComponentAddedOn::Transform => {
                 "transform"
             }
-            component_added_subscription::ComponentAddedSubscriptionComponentAddedOn::Sink => {
+            component_added_subscription::ComponentAddedSubscriptionComponentAddedOn::Sink(_) => {
                 "sink"
             }
         };
@@ -1,4 +1,5 @@
-//! Health queries/subscriptions, for asserting a GraphQL API server is alive.
+//! Health queries/subscriptions, for asserting a GraphQL API server is alive, and
+//! observing the health of its components.
 
 use async_trait::async_trait;
 use graphql_client::GraphQLQuery;
@@ -28,6 +29,16 @@ pub struct HealthQuery;
 )]
 pub struct HeartbeatSubscription;
 
+/// ComponentHealthsSubscription returns the health status of each component, derived
+/// from its latest healthcheck and requests, on a regular interval.
+#[derive(GraphQLQuery, Debug, Copy, Clone)]
+#[graphql(
+    schema_path = "graphql/schema.json",
+    query_path = "graphql/subscriptions/component_healths.graphql",
+    response_derives = "Debug"
+)]
+pub struct ComponentHealthsSubscription;
+
 /// Extension methods for health queries.
 #[async_trait]
 pub trait HealthQueryExt {
@@ -51,6 +62,12 @@ pub trait HealthSubscriptionExt {
         &self,
         interval: i64,
     ) -> crate::BoxedSubscription<HeartbeatSubscription>;
+
+    /// Executes a component healths subscription, on a millisecond `interval`.
+    fn component_healths_subscription(
+        &self,
+        interval: i64,
+    ) -> crate::BoxedSubscription<ComponentHealthsSubscription>;
 }
 
 impl HealthSubscriptionExt for crate::SubscriptionClient {
@@ -64,4 +81,17 @@ impl HealthSubscriptionExt for crate::SubscriptionClient {
 
         self.start::<HeartbeatSubscription>(&request_body)
     }
+
+    /// Executes a component healths subscription, on a millisecond `interval`.
+    fn component_healths_subscription(
+        &self,
+        interval: i64,
+    ) -> crate::BoxedSubscription<ComponentHealthsSubscription> {
+        let request_body =
+            ComponentHealthsSubscription::build_query(component_healths_subscription::Variables {
+                interval,
+            });
+
+        self.start::<ComponentHealthsSubscription>(&request_body)
+    }
 }
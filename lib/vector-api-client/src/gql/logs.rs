@@ -0,0 +1,31 @@
+use crate::BoxedSubscription;
+use graphql_client::GraphQLQuery;
+
+/// Shorthand for a Chrono datetime, set to UTC.
+type DateTime = chrono::DateTime<chrono::Utc>;
+
+/// InternalLogsSubscription streams the warnings and errors logged by a Vector
+/// instance, along with the component that logged them when known.
+#[derive(GraphQLQuery, Debug, Copy, Clone)]
+#[graphql(
+    schema_path = "graphql/schema.json",
+    query_path = "graphql/subscriptions/internal_logs.graphql",
+    response_derives = "Debug"
+)]
+pub struct InternalLogsSubscription;
+
+/// Extension methods for internal logs subscriptions
+pub trait LogsSubscriptionExt {
+    /// Executes an internal logs subscription.
+    fn internal_logs_subscription(&self) -> crate::BoxedSubscription<InternalLogsSubscription>;
+}
+
+impl LogsSubscriptionExt for crate::SubscriptionClient {
+    /// Executes an internal logs subscription.
+    fn internal_logs_subscription(&self) -> BoxedSubscription<InternalLogsSubscription> {
+        let request_body =
+            InternalLogsSubscription::build_query(internal_logs_subscription::Variables);
+
+        self.start::<InternalLogsSubscription>(&request_body)
+    }
+}
@@ -163,6 +163,36 @@ pub struct ComponentEventsOutThroughputsSubscription;
 )]
 pub struct ComponentEventsOutTotalsSubscription;
 
+/// ComponentLatenciesSubscription contains the mean time taken by events to cross
+/// transforms and sinks between `interval` samples.
+#[derive(GraphQLQuery, Debug, Copy, Clone)]
+#[graphql(
+    schema_path = "graphql/schema.json",
+    query_path = "graphql/subscriptions/component_latencies.graphql",
+    response_derives = "Debug"
+)]
+pub struct ComponentLatenciesSubscription;
+
+/// ComponentEventAgesSubscription contains the mean age of the events finalized by
+/// sinks between `interval` samples.
+#[derive(GraphQLQuery, Debug, Copy, Clone)]
+#[graphql(
+    schema_path = "graphql/schema.json",
+    query_path = "graphql/subscriptions/component_event_ages.graphql",
+    response_derives = "Debug"
+)]
+pub struct ComponentEventAgesSubscription;
+
+/// ComponentErrorsTotalsSubscription contains the total number of errors raised by
+/// each component.
+#[derive(GraphQLQuery, Debug, Copy, Clone)]
+#[graphql(
+    schema_path = "graphql/schema.json",
+    query_path = "graphql/subscriptions/component_errors_totals.graphql",
+    response_derives = "Debug"
+)]
+pub struct ComponentErrorsTotalsSubscription;
+
 /// Extension methods for metrics subscriptions
 pub trait MetricsSubscriptionExt {
     /// Executes an uptime metrics subscription.
@@ -257,6 +287,24 @@ pub trait MetricsSubscriptionExt {
         &self,
         interval: i64,
     ) -> crate::BoxedSubscription<ComponentEventsOutThroughputsSubscription>;
+
+    /// Executes a component latencies subscription.
+    fn component_latencies_subscription(
+        &self,
+        interval: i64,
+    ) -> crate::BoxedSubscription<ComponentLatenciesSubscription>;
+
+    /// Executes a component event ages subscription.
+    fn component_event_ages_subscription(
+        &self,
+        interval: i64,
+    ) -> crate::BoxedSubscription<ComponentEventAgesSubscription>;
+
+    /// Executes a component errors totals subscription.
+    fn component_errors_totals_subscription(
+        &self,
+        interval: i64,
+    ) -> crate::BoxedSubscription<ComponentErrorsTotalsSubscription>;
 }
 
 impl MetricsSubscriptionExt for crate::SubscriptionClient {
@@ -448,4 +496,40 @@ impl MetricsSubscriptionExt for crate::SubscriptionClient {
 
         self.start::<ComponentEventsOutThroughputsSubscription>(&request_body)
     }
+
+    /// Executes a component latencies subscription.
+    fn component_latencies_subscription(
+        &self,
+        interval: i64,
+    ) -> BoxedSubscription<ComponentLatenciesSubscription> {
+        let request_body = ComponentLatenciesSubscription::build_query(
+            component_latencies_subscription::Variables { interval },
+        );
+
+        self.start::<ComponentLatenciesSubscription>(&request_body)
+    }
+
+    /// Executes a component event ages subscription.
+    fn component_event_ages_subscription(
+        &self,
+        interval: i64,
+    ) -> BoxedSubscription<ComponentEventAgesSubscription> {
+        let request_body = ComponentEventAgesSubscription::build_query(
+            component_event_ages_subscription::Variables { interval },
+        );
+
+        self.start::<ComponentEventAgesSubscription>(&request_body)
+    }
+
+    /// Executes a component errors totals subscription.
+    fn component_errors_totals_subscription(
+        &self,
+        interval: i64,
+    ) -> BoxedSubscription<ComponentErrorsTotalsSubscription> {
+        let request_body = ComponentErrorsTotalsSubscription::build_query(
+            component_errors_totals_subscription::Variables { interval },
+        );
+
+        self.start::<ComponentErrorsTotalsSubscription>(&request_body)
+    }
 }
@@ -2,6 +2,7 @@
 
 mod components;
 mod health;
+mod logs;
 mod meta;
 mod metrics;
 mod tap;
@@ -9,5 +10,6 @@ mod tap;
 pub use self::meta::*;
 pub use components::*;
 pub use health::*;
+pub use logs::*;
 pub use metrics::*;
 pub use tap::*;
@@ -47,6 +47,37 @@ impl From<TapEncodingFormat> for output_events_subscription::EventEncodingType {
     }
 }
 
+/// Language of a tap filter expression, more convenient to use for public clients than the
+/// generated `output_events_subscription::EventFilterLanguage`.
+#[derive(Debug, Clone, Copy)]
+pub enum TapFilterLanguage {
+    Vrl,
+    DatadogSearch,
+}
+
+/// String -> TapFilterLanguage, typically for parsing user input.
+impl std::str::FromStr for TapFilterLanguage {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "vrl" => Ok(Self::Vrl),
+            "datadog_search" => Ok(Self::DatadogSearch),
+            _ => Err("Invalid filter language".to_string()),
+        }
+    }
+}
+
+/// Map the public-facing `TapFilterLanguage` to the internal `EventFilterLanguage`.
+impl From<TapFilterLanguage> for output_events_subscription::EventFilterLanguage {
+    fn from(language: TapFilterLanguage) -> Self {
+        match language {
+            TapFilterLanguage::Vrl => Self::VRL,
+            TapFilterLanguage::DatadogSearch => Self::DATADOG_SEARCH,
+        }
+    }
+}
+
 impl output_events_subscription::OutputEventsSubscriptionOutputEvents {
     pub fn as_log(
         &self,
@@ -56,16 +87,52 @@ impl output_events_subscription::OutputEventsSubscriptionOutputEvents {
             _ => None,
         }
     }
+
+    pub fn as_metric(
+        &self,
+    ) -> Option<&output_events_subscription::OutputEventsSubscriptionOutputEventsOnMetric> {
+        match self {
+            output_events_subscription::OutputEventsSubscriptionOutputEvents::Metric(ev) => {
+                Some(ev)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Restrictions on the events of a tap subscription, applied by the Vector instance before
+/// events are sent.
+#[derive(Debug, Clone)]
+pub struct TapFilter {
+    /// Only events matching this expression are sent, if any.
+    pub expression: Option<String>,
+    /// Language of the `expression`.
+    pub language: TapFilterLanguage,
+    /// 1 in every `sample_rate` matching events are sent.
+    pub sample_rate: i64,
+}
+
+impl Default for TapFilter {
+    fn default() -> Self {
+        Self {
+            expression: None,
+            language: TapFilterLanguage::Vrl,
+            sample_rate: 1,
+        }
+    }
 }
 
 pub trait TapSubscriptionExt {
-    /// Executes an output events subscription.
+    /// Executes an output events subscription. The outputs of components matching
+    /// `component_ids` are observed, as well as the inputs of those matching `inputs_of`.
     fn output_events_subscription(
         &self,
         component_ids: Vec<String>,
+        inputs_of: Vec<String>,
         encoding: TapEncodingFormat,
         limit: i64,
         interval: i64,
+        filter: TapFilter,
     ) -> crate::BoxedSubscription<OutputEventsSubscription>;
 }
 
@@ -74,16 +141,22 @@ impl TapSubscriptionExt for crate::SubscriptionClient {
     fn output_events_subscription(
         &self,
         component_ids: Vec<String>,
+        inputs_of: Vec<String>,
         encoding: TapEncodingFormat,
         limit: i64,
         interval: i64,
+        filter: TapFilter,
     ) -> BoxedSubscription<OutputEventsSubscription> {
         let request_body =
             OutputEventsSubscription::build_query(output_events_subscription::Variables {
                 component_ids,
+                inputs_of,
                 limit,
                 interval,
                 encoding: encoding.into(),
+                filter: filter.expression,
+                filter_language: filter.language.into(),
+                sample_rate: filter.sample_rate,
             });
 
         self.start::<OutputEventsSubscription>(&request_body)
@@ -38,6 +38,10 @@ pub struct GlobalOptions {
     pub timezone: TimeZone,
     #[serde(skip_serializing_if = "crate::serde::skip_serializing_if_default")]
     pub proxy: ProxyConfig,
+    /// Maximum number of bytes held by the events admitted by sources and not yet
+    /// finalized. Sources wait for room in the budget before admitting more events.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_budget_bytes: Option<usize>,
 }
 
 impl GlobalOptions {
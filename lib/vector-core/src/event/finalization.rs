@@ -1,14 +1,15 @@
 #![deny(missing_docs)]
 
-use crate::ByteSizeOf;
+use crate::{memory_budget::MemoryReservation, ByteSizeOf};
 use atomig::{Atom, Atomic, Ordering};
 use futures::future::FutureExt;
+use metrics::histogram;
 use serde::{Deserialize, Serialize};
 use std::future::Future;
 use std::iter::{self, ExactSizeIterator};
 use std::pin::Pin;
 use std::task::Poll;
-use std::{cmp, mem, sync::Arc};
+use std::{cmp, mem, sync::Arc, time::Instant};
 use tokio::sync::oneshot;
 
 type ImmutVec<T> = Box<[T]>;
@@ -200,6 +201,10 @@ impl BatchStatusReceiver {
 pub struct BatchNotifier {
     status: Atomic<BatchStatus>,
     notifier: Option<oneshot::Sender<BatchStatus>>,
+    // Released along with the notifier, once all the events of the batch are finalized.
+    _reservation: Option<MemoryReservation>,
+    // Likewise, records its timings once the event is finalized.
+    _timer: Option<SinkTimer>,
 }
 
 impl BatchNotifier {
@@ -210,10 +215,38 @@ impl BatchNotifier {
         let notifier = Self {
             status: Atomic::new(BatchStatus::Delivered),
             notifier: Some(sender),
+            _reservation: None,
+            _timer: None,
         };
         (Arc::new(notifier), BatchStatusReceiver(receiver))
     }
 
+    /// Create a new `BatchNotifier` holding a reservation of the memory
+    /// budget, released once all the events of the batch are finalized.
+    pub fn with_reservation(reservation: MemoryReservation) -> Arc<Self> {
+        Arc::new(Self {
+            status: Atomic::new(BatchStatus::Delivered),
+            notifier: None,
+            _reservation: Some(reservation),
+            _timer: None,
+        })
+    }
+
+    /// Create a new `BatchNotifier` for an event received by a sink at
+    /// `received_at`, which records the latency of the sink and the age of
+    /// the event once the sink finalizes it.
+    pub fn with_timer(received_at: Instant, ingest_timestamp: Option<Instant>) -> Arc<Self> {
+        Arc::new(Self {
+            status: Atomic::new(BatchStatus::Delivered),
+            notifier: None,
+            _reservation: None,
+            _timer: Some(SinkTimer {
+                received_at,
+                ingest_timestamp,
+            }),
+        })
+    }
+
     /// Update this notifier's status from the status of a finalized event.
     #[allow(clippy::missing_panics_doc)] // Panic is unreachable
     fn update_status(&self, status: EventStatus) {
@@ -245,6 +278,25 @@ impl Drop for BatchNotifier {
     }
 }
 
+#[derive(Debug)]
+struct SinkTimer {
+    received_at: Instant,
+    ingest_timestamp: Option<Instant>,
+}
+
+impl Drop for SinkTimer {
+    fn drop(&mut self) {
+        let now = Instant::now();
+        // WARN these strings are duplicates of the metric names in
+        // `ROOT/src/internal_events/common.rs`, as vector-core can't depend on
+        // the internal events of vector.
+        histogram!("component_latency_seconds", now - self.received_at);
+        if let Some(ingest_timestamp) = self.ingest_timestamp {
+            histogram!("event_age_seconds", now - ingest_timestamp);
+        }
+    }
+}
+
 /// The status of an individual batch as a whole.
 #[derive(Atom, Copy, Clone, Debug, Derivative, Deserialize, Eq, PartialEq, Serialize)]
 #[derivative(Default)]
@@ -340,6 +392,7 @@ pub trait Finalizable {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::event::MetricValue;
     use tokio::sync::oneshot::error::TryRecvError::Empty;
 
     #[test]
@@ -444,6 +497,37 @@ mod tests {
         assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
     }
 
+    #[test]
+    fn records_sink_timings() {
+        let _ = crate::metrics::init();
+        let histogram_count = |name: &str| {
+            crate::metrics::capture_metrics(crate::metrics::get_controller().unwrap())
+                .filter(|metric| metric.name() == name)
+                .map(|metric| match metric.value() {
+                    MetricValue::AggregatedHistogram { count, .. } => *count,
+                    _ => 0,
+                })
+                .sum::<u32>()
+        };
+        let latencies = histogram_count("component_latency_seconds");
+        let ages = histogram_count("event_age_seconds");
+
+        let now = Instant::now();
+        let mut fin = EventFinalizers::new(EventFinalizer::new(BatchNotifier::with_timer(
+            now,
+            Some(now),
+        )));
+        let copy = fin.clone();
+        fin.update_status(EventStatus::Delivered);
+        fin.update_sources();
+        // Nothing is recorded until every copy of the event is finalized.
+        assert_eq!(histogram_count("component_latency_seconds"), latencies);
+
+        drop(copy);
+        assert_eq!(histogram_count("component_latency_seconds"), latencies + 1);
+        assert_eq!(histogram_count("event_age_seconds"), ages + 1);
+    }
+
     fn make_finalizer() -> (EventFinalizers, BatchStatusReceiver) {
         let (batch, receiver) = BatchNotifier::new_with_receiver();
         let finalizer = EventFinalizers::new(EventFinalizer::new(batch));
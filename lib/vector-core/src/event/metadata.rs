@@ -2,16 +2,16 @@
 
 use super::{BatchNotifier, EventFinalizer, EventFinalizers, EventStatus};
 use crate::ByteSizeOf;
+use derivative::Derivative;
 use getset::{Getters, Setters};
 use serde::{Deserialize, Serialize};
 use shared::EventDataEq;
-use std::sync::Arc;
+use std::{sync::Arc, time::Instant};
 
 /// The top-level metadata structure contained by both `struct Metric`
 /// and `struct LogEvent` types.
-#[derive(
-    Clone, Debug, Default, Deserialize, Getters, PartialEq, PartialOrd, Serialize, Setters,
-)]
+#[derive(Clone, Debug, Default, Derivative, Deserialize, Getters, Serialize, Setters)]
+#[derivative(PartialEq, PartialOrd)]
 pub struct EventMetadata {
     /// Used to store the datadog API from sources to sinks
     #[getset(get = "pub", set = "pub")]
@@ -19,6 +19,24 @@ pub struct EventMetadata {
     datadog_api_key: Option<Arc<str>>,
     #[serde(default, skip)]
     finalizers: EventFinalizers,
+    /// Identifies the event while it's observed by a tap, so that the inputs and outputs
+    /// of a component can be matched up. It's ignored when comparing events.
+    #[getset(get_copy = "pub", set = "pub")]
+    #[serde(default, skip)]
+    #[derivative(PartialEq = "ignore", PartialOrd = "ignore")]
+    correlation_id: Option<u64>,
+    /// When the event entered the topology, used to measure its end-to-end age. Like
+    /// `received_at`, it's ignored when comparing events.
+    #[getset(get_copy = "pub", set = "pub")]
+    #[serde(default, skip)]
+    #[derivative(PartialEq = "ignore", PartialOrd = "ignore")]
+    ingest_timestamp: Option<Instant>,
+    /// When the event was received by the transform currently processing it, used to
+    /// measure the latency of that transform
+    #[getset(get_copy = "pub", set = "pub")]
+    #[serde(default, skip)]
+    #[derivative(PartialEq = "ignore", PartialOrd = "ignore")]
+    received_at: Option<Instant>,
 }
 
 impl ByteSizeOf for EventMetadata {
@@ -44,11 +62,14 @@ impl EventMetadata {
 
     /// Merge the other `EventMetadata` into this.
     /// If a Datadog API key is not set in `self`, the one from `other` will be used.
+    /// The earliest of the timestamps of both is kept.
     pub fn merge(&mut self, other: Self) {
         self.finalizers.merge(other.finalizers);
         if self.datadog_api_key.is_none() {
             self.datadog_api_key = other.datadog_api_key;
         }
+        self.ingest_timestamp = earliest(self.ingest_timestamp, other.ingest_timestamp);
+        self.received_at = earliest(self.received_at, other.received_at);
     }
 
     /// Update the finalizer(s) status.
@@ -72,6 +93,13 @@ impl EventMetadata {
     }
 }
 
+fn earliest(a: Option<Instant>, b: Option<Instant>) -> Option<Instant> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
 impl EventDataEq for EventMetadata {
     fn event_data_eq(&self, _other: &Self) -> bool {
         // Don't compare the metadata, it is not "event data".
@@ -56,3 +56,12 @@ fn event_iteration_order() {
         ]
     );
 }
+
+#[test]
+fn event_equality_ignores_correlation_id() {
+    let event = Event::from("foo");
+    let mut correlated = event.clone();
+    correlated.metadata_mut().set_correlation_id(Some(42));
+
+    assert_eq!(event, correlated);
+}
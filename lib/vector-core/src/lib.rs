@@ -26,6 +26,7 @@ pub mod api;
 pub mod config;
 pub mod event;
 pub mod mapping;
+pub mod memory_budget;
 pub mod metrics;
 pub mod sink;
 pub mod source;
@@ -0,0 +1,178 @@
+//! A process-wide budget of the memory held by events.
+//!
+//! Sources reserve the size of the events they admit, as measured by
+//! [`ByteSizeOf`](crate::ByteSizeOf), and the reservations are released once
+//! the events are finalized or dropped. Once the budget is used up, sources
+//! wait for reservations to be released before admitting more events, turning
+//! memory pressure into backpressure.
+
+use metrics::{decrement_gauge, gauge, increment_gauge};
+use once_cell::sync::OnceCell;
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll, Waker},
+};
+use tracing::Span;
+
+static GLOBAL: OnceCell<Arc<MemoryBudget>> = OnceCell::new();
+
+/// Returns the process-wide budget, initializing it with `limit` bytes on the
+/// first call. As global options can't change on reload, later limits are
+/// ignored.
+#[allow(clippy::cast_precision_loss)]
+pub fn init_global(limit: usize) -> Arc<MemoryBudget> {
+    let budget = GLOBAL.get_or_init(|| {
+        gauge!("memory_budget_limit_bytes", limit as f64);
+        MemoryBudget::new(limit)
+    });
+    Arc::clone(budget)
+}
+
+/// Returns the process-wide budget, if it was initialized.
+pub fn global() -> Option<Arc<MemoryBudget>> {
+    GLOBAL.get().cloned()
+}
+
+#[derive(Debug)]
+pub struct MemoryBudget {
+    limit: usize,
+    used: AtomicUsize,
+    /// Tasks waiting for reservations to be released
+    waiters: Mutex<Vec<Waker>>,
+}
+
+impl MemoryBudget {
+    pub fn new(limit: usize) -> Arc<Self> {
+        Arc::new(Self {
+            limit,
+            used: AtomicUsize::new(0),
+            waiters: Mutex::new(Vec::new()),
+        })
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Returns the number of bytes currently reserved.
+    pub fn used(&self) -> usize {
+        self.used.load(Ordering::Acquire)
+    }
+
+    /// Polls for room in the budget. When there is none, the task is woken
+    /// once enough reservations are released.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the mutex of the waiting tasks is poisoned.
+    pub fn poll_available(&self, cx: &mut Context<'_>) -> Poll<()> {
+        if self.used() < self.limit {
+            return Poll::Ready(());
+        }
+
+        let mut waiters = self.waiters.lock().expect("Poisoned lock");
+        // Reservations released before the lock was taken wouldn't wake this
+        // task, so the budget is checked again.
+        if self.used() < self.limit {
+            return Poll::Ready(());
+        }
+        if !waiters.iter().any(|waker| waker.will_wake(cx.waker())) {
+            waiters.push(cx.waker().clone());
+        }
+        Poll::Pending
+    }
+
+    /// Reserves `bytes` of the budget, held by the component of the current
+    /// span until the reservation is dropped. Reservations may exceed the
+    /// limit, as admission is gated by `poll_available` instead.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn reserve(self: &Arc<Self>, bytes: usize) -> MemoryReservation {
+        self.used.fetch_add(bytes, Ordering::AcqRel);
+        increment_gauge!("memory_budget_held_bytes", bytes as f64);
+        MemoryReservation {
+            budget: Arc::clone(self),
+            bytes,
+            span: Span::current(),
+        }
+    }
+
+    fn release(&self, bytes: usize) {
+        let previous = self.used.fetch_sub(bytes, Ordering::AcqRel);
+        if previous >= self.limit && previous - bytes < self.limit {
+            let waiters = std::mem::take(&mut *self.waiters.lock().expect("Poisoned lock"));
+            for waker in waiters {
+                waker.wake();
+            }
+        }
+    }
+}
+
+/// Bytes of a `MemoryBudget` held until dropped.
+#[derive(Debug)]
+pub struct MemoryReservation {
+    budget: Arc<MemoryBudget>,
+    bytes: usize,
+    /// Span of the component holding the reservation, so that releasing it
+    /// updates the metrics of that component wherever it happens
+    span: Span,
+}
+
+impl MemoryReservation {
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+}
+
+impl Drop for MemoryReservation {
+    #[allow(clippy::cast_precision_loss)]
+    fn drop(&mut self) {
+        let _enter = self.span.enter();
+        decrement_gauge!("memory_budget_held_bytes", self.bytes as f64);
+        self.budget.release(self.bytes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio_test::{assert_pending, assert_ready, task};
+
+    #[test]
+    fn waits_for_reservations_to_be_released() {
+        let budget = MemoryBudget::new(100);
+        let mut task = task::spawn(());
+
+        let first = budget.reserve(60);
+        assert_ready!(task.enter(|cx, _| budget.poll_available(cx)));
+
+        // Reservations may exceed the limit.
+        let second = budget.reserve(60);
+        assert_eq!(budget.used(), 120);
+        assert_pending!(task.enter(|cx, _| budget.poll_available(cx)));
+
+        drop(first);
+        assert!(task.is_woken());
+        assert_ready!(task.enter(|cx, _| budget.poll_available(cx)));
+
+        drop(second);
+        assert_eq!(budget.used(), 0);
+    }
+
+    #[test]
+    fn wakes_when_dropping_under_the_limit() {
+        let budget = MemoryBudget::new(100);
+        let mut task = task::spawn(());
+
+        let reservations = (0..4).map(|_| budget.reserve(40)).collect::<Vec<_>>();
+        assert_pending!(task.enter(|cx, _| budget.poll_available(cx)));
+
+        let mut reservations = reservations.into_iter();
+        drop(reservations.next());
+        assert!(!task.is_woken());
+        drop(reservations.next());
+        assert!(task.is_woken());
+    }
+}
@@ -1,6 +1,13 @@
 //! Time utilities for vector-core
 
-use std::task::{Context, Poll};
+use futures::{future::BoxFuture, stream::BoxStream, FutureExt, StreamExt};
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+    time::{Duration, Instant},
+};
 
 /// A trait for representing a timer which holds multiple subtimers, mapped by an arbitrary key, `K`.
 ///
@@ -32,3 +39,185 @@ pub trait KeyedTimer<K> {
     /// Used primarily for property testing vis-á-vis `vector_core::stream::batcher::Batcher`.
     fn poll_expired(&mut self, cx: &mut Context) -> Poll<Option<K>>;
 }
+
+/// The source of time for components whose output depends on it, such as
+/// transforms flushing on an interval.
+///
+/// Components use the system clock unless built with a mock one, which only
+/// advances when told to. `vector test` relies on the latter to run
+/// time-driven transforms deterministically.
+#[derive(Clone, Debug)]
+pub enum Clock {
+    System,
+    Mock(MockClock),
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Self::System
+    }
+}
+
+impl Clock {
+    pub fn now(&self) -> Instant {
+        match self {
+            Self::System => tokio::time::Instant::now().into_std(),
+            Self::Mock(clock) => clock.now(),
+        }
+    }
+
+    /// Waits until `deadline` is reached.
+    pub fn sleep_until(&self, deadline: Instant) -> BoxFuture<'static, ()> {
+        match self {
+            Self::System => tokio::time::sleep_until(deadline.into()).boxed(),
+            Self::Mock(clock) => clock.sleep_until(deadline).boxed(),
+        }
+    }
+
+    /// Yields immediately, and then every `period`, like
+    /// `tokio::time::interval`.
+    pub fn interval(&self, period: Duration) -> BoxStream<'static, ()> {
+        let clock = self.clone();
+        let start = self.now();
+        futures::stream::unfold(start, move |deadline| {
+            clock
+                .sleep_until(deadline)
+                .map(move |()| Some(((), deadline + period)))
+        })
+        .boxed()
+    }
+}
+
+/// A clock that stands still until advanced, shared between its clones.
+#[derive(Clone, Debug)]
+pub struct MockClock {
+    state: Arc<Mutex<MockState>>,
+}
+
+#[derive(Debug)]
+struct MockState {
+    now: Instant,
+    next_id: u64,
+    /// Sleeps waiting for the clock, by id
+    sleeps: Vec<(u64, Instant, Waker)>,
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockClock {
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(MockState {
+                now: Instant::now(),
+                next_id: 0,
+                sleeps: Vec::new(),
+            })),
+        }
+    }
+
+    /// # Panics
+    ///
+    /// Panics if the mutex of the clock state is poisoned.
+    pub fn now(&self) -> Instant {
+        self.state.lock().expect("Poisoned lock").now
+    }
+
+    /// Advances the clock to the earliest deadline any sleep is waiting for,
+    /// waking the sleeps that are due. Returns `false` if no sleep is waiting.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the mutex of the clock state is poisoned.
+    pub fn advance_to_next_deadline(&self) -> bool {
+        let mut state = self.state.lock().expect("Poisoned lock");
+        let next = match state.sleeps.iter().map(|(_, deadline, _)| *deadline).min() {
+            Some(next) => next,
+            None => return false,
+        };
+        state.now = state.now.max(next);
+
+        let now = state.now;
+        let (due, waiting) = std::mem::take(&mut state.sleeps)
+            .into_iter()
+            .partition::<Vec<_>, _>(|(_, deadline, _)| *deadline <= now);
+        state.sleeps = waiting;
+        drop(state);
+
+        for (_, _, waker) in due {
+            waker.wake();
+        }
+        true
+    }
+
+    fn sleep_until(&self, deadline: Instant) -> MockSleep {
+        let mut state = self.state.lock().expect("Poisoned lock");
+        let id = state.next_id;
+        state.next_id += 1;
+        MockSleep {
+            state: Arc::clone(&self.state),
+            id,
+            deadline,
+        }
+    }
+}
+
+struct MockSleep {
+    state: Arc<Mutex<MockState>>,
+    id: u64,
+    deadline: Instant,
+}
+
+impl Future for MockSleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = self.state.lock().expect("Poisoned lock");
+        state.sleeps.retain(|(id, _, _)| *id != self.id);
+        if state.now >= self.deadline {
+            Poll::Ready(())
+        } else {
+            state
+                .sleeps
+                .push((self.id, self.deadline, cx.waker().clone()));
+            Poll::Pending
+        }
+    }
+}
+
+impl Drop for MockSleep {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.sleeps.retain(|(id, _, _)| *id != self.id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::task::noop_waker_ref;
+
+    #[test]
+    fn mock_interval_ticks_as_the_clock_advances() {
+        let clock = MockClock::new();
+        let start = clock.now();
+        let mut interval = Clock::Mock(clock.clone()).interval(Duration::from_secs(10));
+        let mut cx = Context::from_waker(noop_waker_ref());
+
+        assert_eq!(interval.poll_next_unpin(&mut cx), Poll::Ready(Some(())));
+        assert_eq!(interval.poll_next_unpin(&mut cx), Poll::Pending);
+
+        assert!(clock.advance_to_next_deadline());
+        assert_eq!(clock.now() - start, Duration::from_secs(10));
+        assert_eq!(interval.poll_next_unpin(&mut cx), Poll::Ready(Some(())));
+        assert_eq!(interval.poll_next_unpin(&mut cx), Poll::Pending);
+
+        drop(interval);
+        assert!(!clock.advance_to_next_deadline());
+        assert_eq!(clock.now() - start, Duration::from_secs(10));
+    }
+}
@@ -1,6 +1,8 @@
-use crate::config::GlobalOptions;
+use crate::{config::GlobalOptions, time::Clock};
 use async_trait::async_trait;
 use indexmap::IndexMap;
+#[cfg(feature = "vrl")]
+use vrl_core::prelude::TypeDef;
 
 #[derive(Debug, Clone, PartialEq, Copy)]
 pub enum DataType {
@@ -15,23 +17,62 @@ pub enum ExpandType {
     Serial,
 }
 
+/// The result of checking a transform against the schema of the log events it
+/// receives.
+#[cfg(feature = "vrl")]
+#[derive(Debug, Clone)]
+pub struct TransformSchema {
+    /// The schema of the log events emitted by the transform.
+    pub output: TypeDef,
+
+    /// Whether the transform only ever emits events of the data types it
+    /// receives.
+    pub preserves_data_type: bool,
+
+    /// Problems found while checking the transform against its input schema.
+    pub warnings: Vec<String>,
+}
+
+#[cfg(feature = "vrl")]
+impl TransformSchema {
+    /// The output schema can't be determined from the input schema.
+    pub fn unknown() -> Self {
+        Self {
+            output: TypeDef::new().unknown(),
+            preserves_data_type: false,
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Events are emitted with the same schema, and data type, as they are
+    /// received.
+    pub fn passthrough(input: &TypeDef) -> Self {
+        Self {
+            output: input.clone(),
+            preserves_data_type: true,
+            warnings: Vec::new(),
+        }
+    }
+}
+
 #[cfg(feature = "vrl")]
 #[derive(Debug, Default)]
 pub struct TransformContext {
     pub globals: GlobalOptions,
     pub enrichment_tables: enrichment::TableRegistry,
+    /// The clock of time-driven transforms.
+    pub clock: Clock,
 }
 
 #[cfg(not(feature = "vrl"))]
 #[derive(Debug, Default)]
 pub struct TransformContext {
     pub globals: GlobalOptions,
+    /// The clock of time-driven transforms.
+    pub clock: Clock,
 }
 
 impl TransformContext {
-    // clippy allow avoids an issue where vrl is flagged off and `globals` is
-    // the sole field in the struct
-    #[allow(clippy::needless_update)]
     pub fn new_with_globals(globals: GlobalOptions) -> Self {
         Self {
             globals,
@@ -52,6 +93,16 @@ pub trait TransformConfig: core::fmt::Debug + Send + Sync + dyn_clone::DynClone
 
     fn transform_type(&self) -> &'static str;
 
+    /// Checks the transform against the schema of the log events it receives,
+    /// returning the schema of the log events it emits.
+    ///
+    /// Used for static validation only, the default assumes nothing about the
+    /// emitted events.
+    #[cfg(feature = "vrl")]
+    fn schema(&self, _input: &TypeDef) -> TransformSchema {
+        TransformSchema::unknown()
+    }
+
     /// Allows a transform configuration to expand itself into multiple "child"
     /// transformations to replace it. This allows a transform to act as a macro
     /// for various patterns.
@@ -3,6 +3,8 @@ use futures::Stream;
 use std::pin::Pin;
 #[cfg(any(feature = "lua"))]
 pub mod runtime_transform;
+#[cfg(feature = "vrl")]
+pub use config::TransformSchema;
 pub use config::{DataType, ExpandType, TransformConfig, TransformContext};
 
 mod config;
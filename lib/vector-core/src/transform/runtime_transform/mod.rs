@@ -1,13 +1,11 @@
 mod vec_stream;
 
-use crate::{event::Event, transform::TaskTransform};
+use crate::{event::Event, time::Clock, transform::TaskTransform};
 use futures::{
     stream::{self, BoxStream},
     FutureExt, Stream, StreamExt,
 };
-use std::{future::ready, pin::Pin};
-use tokio::time;
-use tokio_stream::wrappers::IntervalStream;
+use std::{future::ready, pin::Pin, time::Duration};
 use vec_stream::VecStreamExt;
 
 /// A structure representing user-defined timer.
@@ -50,6 +48,11 @@ pub trait RuntimeTransform {
         Vec::new()
     }
 
+    /// Return the clock driving the timers.
+    fn clock(&self) -> Clock {
+        Clock::default()
+    }
+
     fn transform(&mut self, output: &mut Vec<Event>, event: Event) {
         let mut maybe = None;
         self.hook_process(event, |event| maybe = Some(event));
@@ -77,6 +80,7 @@ where
         Self: 'static,
     {
         let timers = self.timers();
+        let clock = self.clock();
         let mut is_shutdown: bool = false; // TODO: consider using an enum describing the state instead of a
                                            // a single boolean variable.
                                            // It is used to prevent timers to emit messages after the source
@@ -99,7 +103,7 @@ where
                     let rest_events_and_shutdown_msg =
                         rest.chain(stream::once(ready(Message::Shutdown)));
                     // A stream of `Message::Timer(..)` events generated by timers.
-                    let timer_msgs = make_timer_msgs_stream(timers);
+                    let timer_msgs = make_timer_msgs_stream(timers, &clock);
 
                     init_msg
                         .chain(first_event)
@@ -139,10 +143,10 @@ where
     }
 }
 
-fn make_timer_msgs_stream(timers: Vec<Timer>) -> BoxStream<'static, Message> {
+fn make_timer_msgs_stream(timers: Vec<Timer>, clock: &Clock) -> BoxStream<'static, Message> {
     let streams = timers.into_iter().map(|timer| {
-        let period = time::Duration::from_secs(timer.interval_seconds);
-        IntervalStream::new(time::interval(period)).map(move |_| Message::Timer(timer))
+        let period = Duration::from_secs(timer.interval_seconds);
+        clock.interval(period).map(move |()| Message::Timer(timer))
     });
     stream::select_all(streams).boxed()
 }
@@ -297,8 +297,13 @@ impl<'a> Compiler<'a> {
     fn compile_query(&mut self, node: Node<ast::Query>) -> Query {
         let ast::Query { target, path } = node.into_inner();
         let target = self.compile_query_target(target);
+        let path = path.into_inner();
 
-        Query::new(target, path.into_inner())
+        if matches!(target, query::Target::External) {
+            self.state.record_external_query(path.clone());
+        }
+
+        Query::new(target, path)
     }
 
     fn compile_query_target(&mut self, node: Node<ast::QueryTarget>) -> query::Target {
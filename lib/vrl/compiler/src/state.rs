@@ -1,5 +1,6 @@
 use crate::expression::assignment;
 use crate::{parser::ast::Ident, TypeDef, Value};
+use lookup::LookupBuf;
 use std::{any::Any, collections::HashMap};
 
 /// The state held by the compiler.
@@ -14,6 +15,9 @@ pub struct Compiler {
     /// stored internal variable type definitions
     variables: HashMap<Ident, assignment::Details>,
 
+    /// paths queried on the external target, in the order they were compiled.
+    external_queries: Vec<ExternalQuery>,
+
     /// context passed between the client program and a VRL function.
     external_context: Option<Box<dyn Any>>,
 
@@ -72,10 +76,12 @@ impl Compiler {
     pub(crate) fn snapshot(&mut self) {
         let target = self.target.clone();
         let variables = self.variables.clone();
+        let external_queries = self.external_queries.clone();
 
         let snapshot = Self {
             target,
             variables,
+            external_queries,
             external_context: None,
             snapshot: None,
         };
@@ -92,6 +98,19 @@ impl Compiler {
         }
     }
 
+    /// Records a query of the given path on the external target, along with
+    /// the type definition of the target at the time of the query.
+    pub(crate) fn record_external_query(&mut self, path: LookupBuf) {
+        let target = self.target.as_ref().map(|details| details.type_def.clone());
+
+        self.external_queries.push(ExternalQuery { path, target });
+    }
+
+    /// Returns all queries of the external target made by the compiled program.
+    pub fn external_queries(&self) -> &[ExternalQuery] {
+        &self.external_queries
+    }
+
     /// Returns the root typedef for the paths (not the variables) of the object.
     pub fn target_type_def(&self) -> Option<&TypeDef> {
         self.target.as_ref().map(|assignment| &assignment.type_def)
@@ -118,6 +137,17 @@ impl Compiler {
     }
 }
 
+/// A query of a path on the external target, as seen by the compiler.
+#[derive(Debug, Clone)]
+pub struct ExternalQuery {
+    /// The queried path.
+    pub path: LookupBuf,
+
+    /// The type definition of the external target at the time of the query,
+    /// if known.
+    pub target: Option<TypeDef>,
+}
+
 /// The state used at runtime to track changes as they happen.
 #[derive(Debug, Default)]
 pub struct Runtime {
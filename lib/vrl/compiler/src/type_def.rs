@@ -305,6 +305,43 @@ impl KindInfo {
         info.at_path(LookupBuf::from_segments(iter.collect()))
     }
 
+    /// Returns `true` if the provided path can never resolve to a value.
+    ///
+    /// This is only the case if every object along the path is fully known
+    /// (e.g. it has no [`Field::Any`] entry) and lacks the requested field. Any
+    /// unknown kind, non-object kind or array index along the path makes the
+    /// answer inconclusive, in which case `false` is returned.
+    pub fn path_never_exists(&self, path: &LookupBuf) -> bool {
+        let mut kind = self;
+
+        for segment in path.iter() {
+            let field = match segment {
+                SegmentBuf::Field(FieldBuf { name, .. }) => Field::Field(name.as_str().to_owned()),
+                SegmentBuf::Coalesce(_) | SegmentBuf::Index(_) => return false,
+            };
+
+            let set = match kind {
+                KindInfo::Unknown => return false,
+                KindInfo::Known(set) => set,
+            };
+
+            if set.iter().any(|k| !matches!(k, TypeKind::Object(_))) {
+                return false;
+            }
+
+            match kind.object() {
+                None => return false,
+                Some(object) if object.contains_key(&Field::Any) => return false,
+                Some(object) => match object.get(&field) {
+                    None => return true,
+                    Some(inner) => kind = inner,
+                },
+            }
+        }
+
+        false
+    }
+
     fn merge(self, rhs: Self, shallow: bool, overwrite: bool) -> Self {
         use KindInfo::*;
 
@@ -1315,6 +1352,14 @@ impl TypeDef {
         }
     }
 
+    /// Returns `true` if the provided path can never resolve to a value, given
+    /// this type definition.
+    ///
+    /// See [`KindInfo::path_never_exists`] for details.
+    pub fn path_never_exists(&self, path: &LookupBuf) -> bool {
+        self.kind.path_never_exists(path)
+    }
+
     /// Removes the given path from the typedef
     pub fn remove_path(&self, path: &LookupBuf) -> Self {
         let segments = path.as_segments();
@@ -1659,6 +1704,76 @@ mod tests {
         }
     }
 
+    #[test]
+    fn path_never_exists() {
+        struct TestCase {
+            type_def: TypeDef,
+            path: &'static str,
+            want: bool,
+        }
+
+        let cases = vec![
+            // A field missing from a fully known object.
+            TestCase {
+                type_def: type_def! { object {
+                    "nork" => type_def! { bytes },
+                } },
+                path: "noog",
+                want: true,
+            },
+            // A field present in a fully known object.
+            TestCase {
+                type_def: type_def! { object {
+                    "nork" => type_def! { bytes },
+                } },
+                path: "nork",
+                want: false,
+            },
+            // A nested field missing from a fully known object.
+            TestCase {
+                type_def: type_def! { object {
+                    "nonk" => type_def! { object {
+                        "nork" => type_def! { bytes },
+                    } },
+                } },
+                path: "nonk.noog",
+                want: true,
+            },
+            // An object that can contain any field.
+            TestCase {
+                type_def: TypeDef::new().object::<(), TypeDef>(btreemap! (
+                    () => type_def! { unknown },
+                )),
+                path: "noog",
+                want: false,
+            },
+            // An unknown type definition.
+            TestCase {
+                type_def: type_def! { unknown },
+                path: "noog",
+                want: false,
+            },
+            // A field nested in a non-object kind.
+            TestCase {
+                type_def: type_def! { object {
+                    "nork" => type_def! { bytes },
+                } },
+                path: "nork.noog",
+                want: false,
+            },
+        ];
+
+        for case in cases {
+            let path = LookupBuf::from_str(case.path).unwrap();
+            assert_eq!(
+                case.type_def.path_never_exists(&path),
+                case.want,
+                "{}",
+                path
+            );
+        }
+    }
+
     mod kind_info {
         use super::*;
 
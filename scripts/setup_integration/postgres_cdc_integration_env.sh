@@ -0,0 +1,50 @@
+#!/usr/bin/env bash
+set -o pipefail
+
+# postgres_cdc_integration_env.sh
+#
+# SUMMARY
+#
+#   Builds and pulls down the Vector PostgreSQL CDC Integration test environment
+
+if [ $# -ne 1 ]
+then
+    echo "Usage: $0 {stop|start}" 1>&2; exit 1;
+    exit 1
+fi
+ACTION=$1
+
+#
+# Functions
+#
+
+start_podman () {
+  podman pod create --replace --name vector-test-integration-postgres_cdc -p 5432:5432
+  podman run -d --pod=vector-test-integration-postgres_cdc --name vector_postgres_cdc \
+  --volume "$(pwd)"/tests/data/postgres_cdc-initdb/:/docker-entrypoint-initdb.d/ \
+  --env POSTGRES_USER=vector --env POSTGRES_PASSWORD=vector postgres:13.1 \
+  -c wal_level=logical
+}
+
+start_docker () {
+  docker network create vector-test-integration-postgres_cdc
+  docker run -d --network=vector-test-integration-postgres_cdc -p 5432:5432 --name vector_postgres_cdc \
+  --volume "$(pwd)"/tests/data/postgres_cdc-initdb/:/docker-entrypoint-initdb.d/ \
+  --env POSTGRES_USER=vector --env POSTGRES_PASSWORD=vector postgres:13.1 \
+  -c wal_level=logical
+}
+
+stop_podman () {
+  podman rm --force vector_postgres_cdc 2>/dev/null; true
+  podman pod stop vector-test-integration-postgres_cdc 2>/dev/null; true
+  podman pod rm --force vector-test-integration-postgres_cdc 2>/dev/null; true
+}
+
+stop_docker () {
+  docker rm --force vector_postgres_cdc 2>/dev/null; true
+  docker network rm vector-test-integration-postgres_cdc 2>/dev/null; true
+}
+
+echo "Running $ACTION action for PostgreSQL CDC integration tests environment"
+
+"${ACTION}"_"${CONTAINER_TOOL}"
@@ -0,0 +1,24 @@
+use lapin::{Channel, Connection, ConnectionProperties};
+use serde::{Deserialize, Serialize};
+
+/// Connection settings shared by the `amqp` source and sink.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub(crate) struct AmqpConfig {
+    /// URI of the broker, in the form of
+    /// `amqp[s]://user:password@host:port/vhost`.
+    pub connection_string: String,
+}
+
+impl AmqpConfig {
+    /// Opens a connection and a channel on it. The connection is returned
+    /// alongside the channel so it is kept open for as long as it is held.
+    pub(crate) async fn connect(&self) -> Result<(Connection, Channel), lapin::Error> {
+        let properties = ConnectionProperties::default()
+            .with_executor(tokio_executor_trait::Tokio::current())
+            .with_reactor(tokio_reactor_trait::Tokio);
+
+        let connection = Connection::connect(&self.connection_string, properties).await?;
+        let channel = connection.create_channel().await?;
+        Ok((connection, channel))
+    }
+}
@@ -1,8 +1,34 @@
+use crate::api::schema::health::{component_healths, HealthStatus};
 use serde_json::json;
-use warp::reply::json;
+use warp::http::StatusCode;
+use warp::reply::{json, with_status};
 use warp::{Rejection, Reply};
 
 // Health handler, responds with { ok: true }
 pub async fn health() -> Result<impl Reply, Rejection> {
     Ok(json(&json!({"ok": true})))
 }
+
+// Liveness handler, responds with { ok: true } as long as the API is served
+pub async fn live() -> Result<impl Reply, Rejection> {
+    Ok(json(&json!({"ok": true})))
+}
+
+// Readiness handler, responds with 503 and the ids of the unhealthy components if any,
+// or { ok: true } otherwise
+pub async fn ready() -> Result<impl Reply, Rejection> {
+    let unhealthy = component_healths()
+        .into_iter()
+        .filter(|health| health.status() == HealthStatus::Unhealthy)
+        .map(|health| health.component_key().to_string())
+        .collect::<Vec<_>>();
+
+    Ok(if unhealthy.is_empty() {
+        with_status(json(&json!({"ok": true})), StatusCode::OK)
+    } else {
+        with_status(
+            json(&json!({"ok": false, "unhealthy": unhealthy})),
+            StatusCode::SERVICE_UNAVAILABLE,
+        )
+    })
+}
@@ -293,6 +293,7 @@ pub fn update_config(config: &Config) {
                 component_key: component_key.clone(),
                 component_type: sink.inner.sink_type().to_string(),
                 inputs: sink.inputs.clone(),
+                buffer: sink.buffer.clone(),
             })),
         );
     }
@@ -332,6 +333,7 @@ mod tests {
     use super::*;
     use crate::{
         api::schema::sort,
+        buffers::BufferConfig,
         config::{ComponentKey, DataType},
     };
 
@@ -362,6 +364,7 @@ mod tests {
                 component_key: ComponentKey::from("devnull"),
                 component_type: "blackhole".to_string(),
                 inputs: vec![ComponentKey::from("gen3"), ComponentKey::from("parse_json")],
+                buffer: BufferConfig::default(),
             })),
         ]
     }
@@ -486,11 +489,13 @@ mod tests {
                 component_key: ComponentKey::from("a"),
                 component_type: "blackhole".to_string(),
                 inputs: vec![ComponentKey::from("gen3"), ComponentKey::from("parse_json")],
+                buffer: BufferConfig::default(),
             })),
             Component::Sink(sink::Sink(sink::Data {
                 component_key: ComponentKey::from("b"),
                 component_type: "blackhole".to_string(),
                 inputs: vec![ComponentKey::from("gen3"), ComponentKey::from("parse_json")],
+                buffer: BufferConfig::default(),
             })),
             Component::Transform(transform::Transform(transform::Data {
                 component_key: ComponentKey::from("c"),
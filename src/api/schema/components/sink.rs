@@ -5,6 +5,7 @@ use crate::{
         metrics::{self, IntoSinkMetrics},
         sort,
     },
+    buffers::{BufferConfig, WhenFull},
     config::ComponentKey,
     filter_check,
 };
@@ -16,6 +17,7 @@ pub struct Data {
     pub component_key: ComponentKey,
     pub component_type: String,
     pub inputs: Vec<ComponentKey>,
+    pub buffer: BufferConfig,
 }
 
 #[derive(Debug, Clone)]
@@ -31,6 +33,69 @@ impl Sink {
     }
 }
 
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum BufferType {
+    Memory,
+    Disk,
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum BufferWhenFull {
+    Block,
+    DropNewest,
+}
+
+impl From<WhenFull> for BufferWhenFull {
+    fn from(when_full: WhenFull) -> Self {
+        match when_full {
+            WhenFull::Block => Self::Block,
+            WhenFull::DropNewest => Self::DropNewest,
+        }
+    }
+}
+
+pub struct SinkBuffer(BufferConfig);
+
+#[Object]
+impl SinkBuffer {
+    /// Where events are buffered
+    #[graphql(name = "type")]
+    pub async fn buffer_type(&self) -> BufferType {
+        match self.0 {
+            BufferConfig::Memory { .. } => BufferType::Memory,
+            #[cfg(feature = "disk-buffer")]
+            BufferConfig::Disk { .. } => BufferType::Disk,
+        }
+    }
+
+    /// Maximum number of events held by a memory buffer
+    pub async fn max_events(&self) -> Option<i64> {
+        match self.0 {
+            BufferConfig::Memory { max_events, .. } => Some(max_events as i64),
+            #[cfg(feature = "disk-buffer")]
+            BufferConfig::Disk { .. } => None,
+        }
+    }
+
+    /// Maximum size in bytes of a disk buffer
+    pub async fn max_size(&self) -> Option<i64> {
+        match self.0 {
+            BufferConfig::Memory { .. } => None,
+            #[cfg(feature = "disk-buffer")]
+            BufferConfig::Disk { max_size, .. } => Some(max_size as i64),
+        }
+    }
+
+    /// What happens to new events once the buffer is full
+    pub async fn when_full(&self) -> BufferWhenFull {
+        match self.0 {
+            BufferConfig::Memory { when_full, .. } => when_full.into(),
+            #[cfg(feature = "disk-buffer")]
+            BufferConfig::Disk { when_full, .. } => when_full.into(),
+        }
+    }
+}
+
 #[derive(Default, InputObject)]
 pub struct SinksFilter {
     component_id: Option<Vec<filter::StringFilter>>,
@@ -120,6 +185,11 @@ impl Sink {
             .collect()
     }
 
+    /// Sink buffer
+    pub async fn buffer(&self) -> SinkBuffer {
+        SinkBuffer(self.0.buffer.clone())
+    }
+
     /// Sink metrics
     pub async fn metrics(&self) -> metrics::SinkMetrics {
         metrics::by_component_key(self.get_component_key())
@@ -137,16 +207,19 @@ mod tests {
                 component_key: ComponentKey::from("webserver"),
                 component_type: "http".to_string(),
                 inputs: vec![],
+                buffer: BufferConfig::default(),
             }),
             Sink(Data {
                 component_key: ComponentKey::from("db"),
                 component_type: "clickhouse".to_string(),
                 inputs: vec![],
+                buffer: BufferConfig::default(),
             }),
             Sink(Data {
                 component_key: ComponentKey::from("zip_drive"),
                 component_type: "file".to_string(),
                 inputs: vec![],
+                buffer: BufferConfig::default(),
             }),
         ]
     }
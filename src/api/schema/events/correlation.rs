@@ -0,0 +1,202 @@
+use super::output::OutputEventsPayload;
+use crate::{
+    api::tap::{TapDirection, TapPayload},
+    config::ComponentKey,
+};
+
+use async_graphql::{Enum, SimpleObject};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+/// The number of input events kept to diff outputs against.
+const CAPACITY: usize = 1000;
+
+#[derive(Enum, Debug, Copy, Clone, PartialEq, Eq)]
+/// Whether an event was received or emitted by a component
+pub enum EventDirection {
+    /// The event was received by the component
+    Input,
+    /// The event was emitted by the component
+    Output,
+}
+
+impl From<TapDirection> for EventDirection {
+    fn from(direction: TapDirection) -> Self {
+        match direction {
+            TapDirection::Input => Self::Input,
+            TapDirection::Output => Self::Output,
+        }
+    }
+}
+
+#[derive(Debug, Clone, SimpleObject)]
+/// A field that differs between an event received by a component and what the component
+/// emitted for it
+pub struct EventFieldDiff {
+    /// Path of the field
+    path: String,
+
+    /// JSON encoded value of the field in the input event, if it was present
+    input: Option<String>,
+
+    /// JSON encoded value of the field in the output event, if it's present
+    output: Option<String>,
+}
+
+/// Event fields by path, with nested objects and arrays flattened.
+type Fields = BTreeMap<String, Value>;
+
+/// Matches the outputs of components with the inputs that have the same correlation id,
+/// attaching the differences between the two to outputs.
+#[derive(Default)]
+pub struct Correlator {
+    inputs: HashMap<(ComponentKey, u64), Fields>,
+    order: VecDeque<(ComponentKey, u64)>,
+}
+
+impl Correlator {
+    pub fn correlate(&mut self, payload: TapPayload) -> OutputEventsPayload {
+        let diff = match &payload {
+            TapPayload::Log(component_key, direction, log) => self.observe(
+                component_key,
+                *direction,
+                log.metadata().correlation_id(),
+                log,
+            ),
+            TapPayload::Metric(component_key, direction, metric) => self.observe(
+                component_key,
+                *direction,
+                metric.metadata().correlation_id(),
+                metric,
+            ),
+            TapPayload::Notification(..) => None,
+        };
+
+        OutputEventsPayload::from(payload).with_diff(diff)
+    }
+
+    fn observe(
+        &mut self,
+        component_key: &ComponentKey,
+        direction: TapDirection,
+        correlation_id: Option<u64>,
+        event: &impl Serialize,
+    ) -> Option<Vec<EventFieldDiff>> {
+        let key = (component_key.clone(), correlation_id?);
+        let fields = flatten(event);
+
+        match direction {
+            TapDirection::Input => {
+                if self.inputs.insert(key.clone(), fields).is_none() {
+                    self.order.push_back(key);
+                }
+                if self.order.len() > CAPACITY {
+                    if let Some(oldest) = self.order.pop_front() {
+                        self.inputs.remove(&oldest);
+                    }
+                }
+                None
+            }
+            TapDirection::Output => self.inputs.get(&key).map(|input| diff(input, &fields)),
+        }
+    }
+}
+
+fn flatten(event: &impl Serialize) -> Fields {
+    fn insert(fields: &mut Fields, path: String, value: Value) {
+        match value {
+            Value::Object(map) if !map.is_empty() => {
+                for (key, value) in map {
+                    let path = if path.is_empty() {
+                        key
+                    } else {
+                        format!("{}.{}", path, key)
+                    };
+                    insert(fields, path, value);
+                }
+            }
+            Value::Array(array) if !array.is_empty() => {
+                for (index, value) in array.into_iter().enumerate() {
+                    insert(fields, format!("{}[{}]", path, index), value);
+                }
+            }
+            value => {
+                fields.insert(path, value);
+            }
+        }
+    }
+
+    let mut fields = Fields::new();
+    if let Ok(value) = serde_json::to_value(event) {
+        insert(&mut fields, String::new(), value);
+    }
+    fields
+}
+
+fn diff(input: &Fields, output: &Fields) -> Vec<EventFieldDiff> {
+    let mut paths = input.keys().chain(output.keys()).collect::<Vec<_>>();
+    paths.sort();
+    paths.dedup();
+
+    paths
+        .into_iter()
+        .filter(|path| input.get(*path) != output.get(*path))
+        .map(|path| EventFieldDiff {
+            path: path.clone(),
+            input: input.get(path).map(Value::to_string),
+            output: output.get(path).map(Value::to_string),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::{Event, LogEvent};
+
+    fn payload(direction: TapDirection, id: u64, fields: &[(&str, &str)]) -> TapPayload {
+        let mut log = LogEvent::default();
+        for (key, value) in fields {
+            log.insert(*key, *value);
+        }
+        let mut event = Event::from(log);
+        event.metadata_mut().set_correlation_id(Some(id));
+        TapPayload::Log(ComponentKey::from("transform"), direction, event.into_log())
+    }
+
+    #[test]
+    fn diffs_outputs_against_inputs() {
+        let mut correlator = Correlator::default();
+
+        let input = correlator.correlate(payload(
+            TapDirection::Input,
+            1,
+            &[("message", "a=1"), ("host", "h")],
+        ));
+        assert!(input.get_diff().is_none());
+
+        let output = correlator.correlate(payload(
+            TapDirection::Output,
+            1,
+            &[("a", "1"), ("host", "h")],
+        ));
+        let paths = output
+            .get_diff()
+            .unwrap()
+            .iter()
+            .map(|diff| (diff.path.as_str(), diff.input.clone(), diff.output.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            vec![
+                ("a", None, Some(r#""1""#.to_owned())),
+                ("message", Some(r#""a=1""#.to_owned()), None),
+            ]
+        );
+
+        // Outputs without a matching input have no diff.
+        let output = correlator.correlate(payload(TapDirection::Output, 2, &[("a", "1")]));
+        assert!(output.get_diff().is_none());
+    }
+}
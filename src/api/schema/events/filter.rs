@@ -0,0 +1,41 @@
+use crate::conditions::{AnyCondition, Condition};
+use async_graphql::Enum;
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq)]
+/// Language of an event filter expression
+pub enum EventFilterLanguage {
+    /// Vector Remap Language condition
+    Vrl,
+    /// Datadog search query
+    DatadogSearch,
+}
+
+impl Default for EventFilterLanguage {
+    fn default() -> Self {
+        Self::Vrl
+    }
+}
+
+/// Builds the condition evaluating a filter expression.
+pub fn build_condition(
+    filter: &str,
+    language: EventFilterLanguage,
+) -> crate::Result<Box<dyn Condition>> {
+    let enrichment_tables = Default::default();
+    match language {
+        EventFilterLanguage::Vrl => {
+            AnyCondition::String(filter.to_owned()).build(&enrichment_tables)
+        }
+        #[cfg(feature = "transforms-filter")]
+        EventFilterLanguage::DatadogSearch => AnyCondition::Map(Box::new(
+            crate::conditions::datadog_search::DatadogSearchConfig {
+                source: filter.to_owned(),
+            },
+        ))
+        .build(&enrichment_tables),
+        #[cfg(not(feature = "transforms-filter"))]
+        EventFilterLanguage::DatadogSearch => {
+            Err("Datadog search filters aren't supported by this build of Vector".into())
+        }
+    }
+}
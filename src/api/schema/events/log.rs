@@ -1,4 +1,8 @@
-use super::EventEncodingType;
+use super::{
+    correlation::{EventDirection, EventFieldDiff},
+    EventEncodingType,
+};
+use crate::api::tap::TapDirection;
 use crate::config::ComponentKey;
 use crate::event::{self, Value};
 
@@ -8,17 +12,34 @@ use chrono::{DateTime, Utc};
 #[derive(Debug)]
 pub struct Log {
     component_key: ComponentKey,
+    direction: TapDirection,
     event: event::LogEvent,
+    diff: Option<Vec<EventFieldDiff>>,
 }
 
 impl Log {
-    pub const fn new(component_key: ComponentKey, event: event::LogEvent) -> Self {
+    pub const fn new(
+        component_key: ComponentKey,
+        direction: TapDirection,
+        event: event::LogEvent,
+    ) -> Self {
         Self {
             component_key,
+            direction,
             event,
+            diff: None,
         }
     }
 
+    pub fn with_diff(mut self, diff: Option<Vec<EventFieldDiff>>) -> Self {
+        self.diff = diff;
+        self
+    }
+
+    pub fn get_diff(&self) -> Option<&[EventFieldDiff]> {
+        self.diff.as_deref()
+    }
+
     pub fn get_message(&self) -> Option<String> {
         Some(self.event.get("message")?.to_string_lossy())
     }
@@ -41,6 +62,26 @@ impl Log {
         self.component_key.pipeline_str()
     }
 
+    /// Whether the log event was received or emitted by the component
+    async fn direction(&self) -> EventDirection {
+        self.direction.into()
+    }
+
+    /// Id shared by the log event received by a component and the events emitted for it,
+    /// when the inputs of the component are observed
+    async fn correlation_id(&self) -> Option<String> {
+        self.event
+            .metadata()
+            .correlation_id()
+            .map(|id| id.to_string())
+    }
+
+    /// Fields of an emitted log event that differ from those of the observed input it
+    /// was emitted for
+    async fn diff(&self) -> Option<Vec<EventFieldDiff>> {
+        self.diff.clone()
+    }
+
     /// Log message
     async fn message(&self) -> Option<String> {
         self.get_message()
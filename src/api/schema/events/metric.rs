@@ -0,0 +1,147 @@
+use super::{
+    correlation::{EventDirection, EventFieldDiff},
+    EventEncodingType,
+};
+use crate::api::tap::TapDirection;
+use crate::config::ComponentKey;
+use crate::event;
+
+use async_graphql::{Enum, Object, SimpleObject};
+use chrono::{DateTime, Utc};
+
+#[derive(Enum, Debug, Copy, Clone, PartialEq, Eq)]
+/// Kind of a metric event
+pub enum MetricKind {
+    /// The value is a change since the last report
+    Incremental,
+    /// The value is the current value
+    Absolute,
+}
+
+impl From<event::MetricKind> for MetricKind {
+    fn from(kind: event::MetricKind) -> Self {
+        match kind {
+            event::MetricKind::Incremental => Self::Incremental,
+            event::MetricKind::Absolute => Self::Absolute,
+        }
+    }
+}
+
+#[derive(Debug, SimpleObject)]
+/// A tag of a metric event
+pub struct MetricTag {
+    /// Tag key
+    key: String,
+
+    /// Tag value
+    value: String,
+}
+
+#[derive(Debug)]
+pub struct Metric {
+    component_key: ComponentKey,
+    direction: TapDirection,
+    event: event::Metric,
+    diff: Option<Vec<EventFieldDiff>>,
+}
+
+impl Metric {
+    pub const fn new(
+        component_key: ComponentKey,
+        direction: TapDirection,
+        event: event::Metric,
+    ) -> Self {
+        Self {
+            component_key,
+            direction,
+            event,
+            diff: None,
+        }
+    }
+
+    pub fn with_diff(mut self, diff: Option<Vec<EventFieldDiff>>) -> Self {
+        self.diff = diff;
+        self
+    }
+
+    pub fn get_diff(&self) -> Option<&[EventFieldDiff]> {
+        self.diff.as_deref()
+    }
+}
+
+#[Object]
+/// Metric event with fields for querying metric data
+impl Metric {
+    /// Id of the component associated with the metric event
+    async fn component_id(&self) -> &str {
+        self.component_key.id()
+    }
+
+    /// Id of the pipeline associated with the metric event
+    async fn pipeline_id(&self) -> Option<&str> {
+        self.component_key.pipeline_str()
+    }
+
+    /// Whether the metric event was received or emitted by the component
+    async fn direction(&self) -> EventDirection {
+        self.direction.into()
+    }
+
+    /// Id shared by the metric event received by a component and the events emitted for it,
+    /// when the inputs of the component are observed
+    async fn correlation_id(&self) -> Option<String> {
+        self.event
+            .metadata()
+            .correlation_id()
+            .map(|id| id.to_string())
+    }
+
+    /// Fields of an emitted metric event that differ from those of the observed input it
+    /// was emitted for
+    async fn diff(&self) -> Option<Vec<EventFieldDiff>> {
+        self.diff.clone()
+    }
+
+    /// Metric timestamp
+    async fn timestamp(&self) -> Option<DateTime<Utc>> {
+        self.event.timestamp()
+    }
+
+    /// Metric name
+    async fn name(&self) -> &str {
+        self.event.name()
+    }
+
+    /// Metric namespace
+    async fn namespace(&self) -> Option<&str> {
+        self.event.namespace()
+    }
+
+    /// Metric kind
+    async fn kind(&self) -> MetricKind {
+        self.event.kind().into()
+    }
+
+    /// Metric tags
+    async fn tags(&self) -> Vec<MetricTag> {
+        self.event
+            .tags()
+            .into_iter()
+            .flatten()
+            .map(|(key, value)| MetricTag {
+                key: key.clone(),
+                value: value.clone(),
+            })
+            .collect()
+    }
+
+    /// Metric event as an encoded string format
+    async fn string(&self, encoding: EventEncodingType) -> String {
+        match encoding {
+            EventEncodingType::Json => serde_json::to_string(&self.event)
+                .expect("JSON serialization of metric event failed. Please report."),
+            EventEncodingType::Yaml => serde_yaml::to_string(&self.event)
+                .expect("YAML serialization of metric event failed. Please report."),
+        }
+    }
+}
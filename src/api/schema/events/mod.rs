@@ -1,12 +1,20 @@
+mod correlation;
 mod encoding;
+mod filter;
 mod log;
+mod metric;
 mod notification;
 mod output;
 
+use correlation::Correlator;
 use encoding::EventEncodingType;
+use filter::EventFilterLanguage;
 use output::OutputEventsPayload;
 
-use crate::{api::tap::TapController, topology::WatchRx};
+use crate::{
+    api::tap::{TapController, TapFilter},
+    topology::WatchRx,
+};
 
 use async_graphql::{validators::IntRange, Context, Subscription};
 use futures::Stream;
@@ -20,18 +28,38 @@ pub struct EventsSubscription;
 
 #[Subscription]
 impl EventsSubscription {
-    /// A stream of events emitted from matched component(s)
+    /// A stream of events emitted from matched component(s), and received by those matching
+    /// `inputsOf`. Events can be restricted to those matching a filter expression, and
+    /// sampled at a rate of 1 in `sampleRate`. Events received by a component share a
+    /// correlation id with what it emits for them, which comes with a diff.
     pub async fn output_events<'a>(
         &'a self,
         ctx: &'a Context<'a>,
         component_ids: Vec<String>,
+        #[graphql(default)] inputs_of: Vec<String>,
         #[graphql(default = 500)] interval: u32,
         #[graphql(default = 100, validator(IntRange(min = "1", max = "10_000")))] limit: u32,
-    ) -> impl Stream<Item = Vec<OutputEventsPayload>> + 'a {
+        filter: Option<String>,
+        #[graphql(default)] filter_language: EventFilterLanguage,
+        #[graphql(default = 1, validator(IntRange(min = "1", max = "1_000_000")))] sample_rate: u32,
+    ) -> async_graphql::Result<impl Stream<Item = Vec<OutputEventsPayload>> + 'a> {
         let watch_rx = ctx.data_unchecked::<WatchRx>().clone();
 
+        let condition = filter
+            .map(|filter| filter::build_condition(&filter, filter_language))
+            .transpose()
+            .map_err(|error| async_graphql::Error::new(format!("Invalid filter: {}", error)))?;
+        let filter = TapFilter::new(condition, sample_rate as u64);
+
         // Client input is confined to `u32` to provide sensible bounds.
-        create_events_stream(watch_rx, component_ids, interval as u64, limit as usize)
+        Ok(create_events_stream(
+            watch_rx,
+            component_ids,
+            inputs_of,
+            filter,
+            interval as u64,
+            limit as usize,
+        ))
     }
 }
 
@@ -41,6 +69,8 @@ impl EventsSubscription {
 fn create_events_stream(
     watch_rx: WatchRx,
     component_ids: Vec<String>,
+    inputs_of: Vec<String>,
+    filter: TapFilter,
     interval: u64,
     limit: usize,
 ) -> impl Stream<Item = Vec<OutputEventsPayload>> {
@@ -56,7 +86,8 @@ fn create_events_stream(
     tokio::spawn(async move {
         // Create a tap controller. When this drops out of scope, clean up will be performed on the
         // event handlers and topology observation that the tap controller provides.
-        let _tap_controller = TapController::new(watch_rx, tap_tx, &component_ids);
+        let _tap_controller =
+            TapController::new(watch_rx, tap_tx, &component_ids, &inputs_of, filter);
 
         // A tick interval to represent when to 'cut' the results back to the client.
         let mut interval = time::interval(time::Duration::from_millis(interval));
@@ -79,13 +110,16 @@ fn create_events_stream(
         // per the sampling strategy used below.
         let mut batch = 0;
 
+        // Matches outputs with the observed inputs they were emitted for.
+        let mut correlator = Correlator::default();
+
         loop {
             select! {
                 // Process `TapPayload`s. A tap payload could contain log/metric events or a
                 // notification. Notifications are emitted immediately; events buffer until
                 // the next `interval`.
                 Some(payload) = tap_rx.recv() => {
-                    let payload = payload.into();
+                    let payload = correlator.correlate(payload);
 
                     // Emit notifications immediately; these don't count as a 'batch'.
                     if let OutputEventsPayload::Notification(_) = payload {
@@ -1,5 +1,7 @@
 use super::{
+    correlation::EventFieldDiff,
     log::Log,
+    metric::Metric,
     notification::{EventNotification, EventNotificationType},
 };
 use crate::api::tap::{TapNotification, TapPayload};
@@ -12,6 +14,9 @@ pub enum OutputEventsPayload {
     /// Log event
     Log(Log),
 
+    /// Metric event
+    Metric(Metric),
+
     // Notification
     Notification(EventNotification),
 }
@@ -20,7 +25,12 @@ pub enum OutputEventsPayload {
 impl From<TapPayload> for OutputEventsPayload {
     fn from(t: TapPayload) -> Self {
         match t {
-            TapPayload::Log(component_key, ev) => Self::Log(Log::new(component_key, ev)),
+            TapPayload::Log(component_key, direction, ev) => {
+                Self::Log(Log::new(component_key, direction, ev))
+            }
+            TapPayload::Metric(component_key, direction, ev) => {
+                Self::Metric(Metric::new(component_key, direction, ev))
+            }
             TapPayload::Notification(component_key, n) => match n {
                 TapNotification::Matched => Self::Notification(EventNotification::new(
                     component_key,
@@ -31,7 +41,25 @@ impl From<TapPayload> for OutputEventsPayload {
                     EventNotificationType::NotMatched,
                 )),
             },
-            _ => unreachable!("TODO: implement metrics"),
+        }
+    }
+}
+
+impl OutputEventsPayload {
+    /// Attaches the differences from the input of an event to its output.
+    pub fn with_diff(self, diff: Option<Vec<EventFieldDiff>>) -> Self {
+        match self {
+            Self::Log(log) => Self::Log(log.with_diff(diff)),
+            Self::Metric(metric) => Self::Metric(metric.with_diff(diff)),
+            notification => notification,
+        }
+    }
+
+    pub fn get_diff(&self) -> Option<&[EventFieldDiff]> {
+        match self {
+            Self::Log(log) => log.get_diff(),
+            Self::Metric(metric) => metric.get_diff(),
+            Self::Notification(_) => None,
         }
     }
 }
@@ -1,8 +1,18 @@
-use async_graphql::{validators::IntRange, Object, SimpleObject, Subscription};
+use super::components::state;
+use crate::{
+    config::ComponentKey,
+    event::{Metric, MetricValue},
+    metrics::{capture_metrics, get_controller},
+};
+use async_graphql::{validators::IntRange, Enum, Object, SimpleObject, Subscription};
 use chrono::{DateTime, Utc};
 use tokio::time::Duration;
 use tokio_stream::{wrappers::IntervalStream, Stream, StreamExt};
 
+/// Number of consecutive failed requests from which a component is unhealthy, rather than
+/// degraded.
+const UNHEALTHY_CONSECUTIVE_FAILURES: u64 = 5;
+
 #[derive(SimpleObject)]
 pub struct Heartbeat {
     utc: DateTime<Utc>,
@@ -14,6 +24,195 @@ impl Heartbeat {
     }
 }
 
+#[derive(Enum, Copy, Clone, Debug, Eq, PartialEq)]
+pub enum HealthStatus {
+    /// The latest requests and healthcheck succeeded
+    Healthy,
+    /// Some of the latest requests failed, or the circuit breaker is probing
+    Degraded,
+    /// The latest healthcheck or requests failed, or the circuit breaker is open
+    Unhealthy,
+    /// Neither requests nor healthchecks completed yet
+    Unknown,
+}
+
+/// The signals the health of a component is derived from, collected from the metrics emitted by
+/// its healthchecks and requests. Timestamps are in seconds since the Unix epoch.
+#[derive(Debug, Default, Clone, PartialEq)]
+struct HealthSignals {
+    consecutive_failures: u64,
+    last_success: Option<f64>,
+    healthcheck_passed: Option<bool>,
+    last_healthcheck: Option<f64>,
+    circuit_breaker: CircuitBreakerState,
+}
+
+/// State of the circuit breaker of a sink, as reported by the `sink_circuit_breaker_state`
+/// gauge.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum CircuitBreakerState {
+    Closed,
+    HalfOpen,
+    Open,
+}
+
+impl Default for CircuitBreakerState {
+    fn default() -> Self {
+        Self::Closed
+    }
+}
+
+impl CircuitBreakerState {
+    fn from_gauge(value: f64) -> Self {
+        if value >= 2.0 {
+            Self::Open
+        } else if value >= 1.0 {
+            Self::HalfOpen
+        } else {
+            Self::Closed
+        }
+    }
+}
+
+impl HealthSignals {
+    fn from_metrics<'a>(metrics: impl IntoIterator<Item = &'a Metric>) -> Self {
+        let latest =
+            |current: Option<f64>, value: f64| Some(current.map_or(value, |c| c.max(value)));
+
+        let mut signals = Self::default();
+        for metric in metrics {
+            let value = match metric.value() {
+                MetricValue::Gauge { value } => *value,
+                _ => continue,
+            };
+            match metric.name() {
+                "sink_consecutive_failures" => {
+                    signals.consecutive_failures = signals.consecutive_failures.max(value as u64)
+                }
+                "sink_last_success_timestamp_seconds" => {
+                    signals.last_success = latest(signals.last_success, value)
+                }
+                "sink_healthcheck_passed" => signals.healthcheck_passed = Some(value > 0.0),
+                "sink_last_healthcheck_timestamp_seconds" => {
+                    signals.last_healthcheck = latest(signals.last_healthcheck, value)
+                }
+                "sink_circuit_breaker_state" => {
+                    signals.circuit_breaker = signals
+                        .circuit_breaker
+                        .max(CircuitBreakerState::from_gauge(value))
+                }
+                _ => {}
+            }
+        }
+        signals
+    }
+
+    fn status(&self) -> HealthStatus {
+        // A failed healthcheck no longer counts once a request succeeded after it.
+        let healthcheck_failed = self.healthcheck_passed == Some(false)
+            && match (self.last_success, self.last_healthcheck) {
+                (Some(success), Some(healthcheck)) => success < healthcheck,
+                _ => true,
+            };
+
+        if healthcheck_failed
+            || self.consecutive_failures >= UNHEALTHY_CONSECUTIVE_FAILURES
+            || self.circuit_breaker == CircuitBreakerState::Open
+        {
+            HealthStatus::Unhealthy
+        } else if self.consecutive_failures > 0
+            || self.circuit_breaker == CircuitBreakerState::HalfOpen
+        {
+            HealthStatus::Degraded
+        } else if self.healthcheck_passed.is_some() || self.last_success.is_some() {
+            HealthStatus::Healthy
+        } else {
+            HealthStatus::Unknown
+        }
+    }
+}
+
+pub struct ComponentHealth {
+    component_key: ComponentKey,
+    signals: HealthSignals,
+}
+
+impl ComponentHealth {
+    pub const fn component_key(&self) -> &ComponentKey {
+        &self.component_key
+    }
+
+    pub fn status(&self) -> HealthStatus {
+        self.signals.status()
+    }
+}
+
+#[Object]
+impl ComponentHealth {
+    /// Component id
+    async fn component_id(&self) -> &str {
+        self.component_key.id()
+    }
+
+    /// Pipeline id
+    async fn pipeline_id(&self) -> Option<&str> {
+        self.component_key.pipeline_str()
+    }
+
+    /// Health status, derived from the latest healthcheck and requests
+    #[graphql(name = "status")]
+    async fn health_status(&self) -> HealthStatus {
+        self.status()
+    }
+
+    /// Number of failed requests since the last successful one
+    async fn consecutive_failures(&self) -> i64 {
+        self.signals.consecutive_failures as i64
+    }
+
+    /// Seconds since the last successful request, if any
+    async fn seconds_since_last_success(&self) -> Option<f64> {
+        let now = Utc::now().timestamp_millis() as f64 / 1000.0;
+        self.signals
+            .last_success
+            .map(|last_success| (now - last_success).max(0.0))
+    }
+
+    /// Whether the latest healthcheck passed, if any ran
+    async fn healthcheck_passed(&self) -> Option<bool> {
+        self.signals.healthcheck_passed
+    }
+}
+
+/// Returns the health of the current components, sorted by key.
+pub fn component_healths() -> Vec<ComponentHealth> {
+    let metrics = match get_controller() {
+        Ok(controller) => capture_metrics(controller)
+            .filter(|m| m.name().starts_with("sink_"))
+            .collect::<Vec<_>>(),
+        Err(_) => Vec::new(),
+    };
+
+    let mut component_keys = state::get_component_keys().into_iter().collect::<Vec<_>>();
+    component_keys.sort();
+
+    component_keys
+        .into_iter()
+        .map(|component_key| {
+            let signals = HealthSignals::from_metrics(metrics.iter().filter(|m| {
+                m.tag_matches("component_id", component_key.id())
+                    && component_key
+                        .pipeline_str()
+                        .map_or(true, |pipeline| m.tag_matches("pipeline_id", pipeline))
+            }));
+            ComponentHealth {
+                component_key,
+                signals,
+            }
+        })
+        .collect()
+}
+
 #[derive(Default)]
 pub struct HealthQuery;
 
@@ -23,6 +222,11 @@ impl HealthQuery {
     async fn health(&self) -> bool {
         true
     }
+
+    /// Health of each component
+    async fn component_healths(&self) -> Vec<ComponentHealth> {
+        component_healths()
+    }
 }
 
 #[derive(Default)]
@@ -40,4 +244,81 @@ impl HealthSubscription {
         )))
         .map(|_| Heartbeat::new())
     }
+
+    /// Health of each component, every `interval`
+    async fn component_healths(
+        &self,
+        #[graphql(default = 1000, validator(IntRange(min = "10", max = "60_000")))] interval: i32,
+    ) -> impl Stream<Item = Vec<ComponentHealth>> {
+        IntervalStream::new(tokio::time::interval(Duration::from_millis(
+            interval as u64,
+        )))
+        .map(|_| component_healths())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gauge(name: &str, value: f64) -> Metric {
+        Metric::new(
+            name,
+            crate::event::MetricKind::Absolute,
+            MetricValue::Gauge { value },
+        )
+    }
+
+    #[test]
+    fn unknown_without_signals() {
+        assert_eq!(HealthSignals::default().status(), HealthStatus::Unknown);
+    }
+
+    #[test]
+    fn degrades_then_fails_with_consecutive_failures() {
+        let status = |failures: f64| {
+            HealthSignals::from_metrics(&[
+                gauge("sink_last_success_timestamp_seconds", 10.0),
+                gauge("sink_consecutive_failures", failures),
+            ])
+            .status()
+        };
+
+        assert_eq!(status(0.0), HealthStatus::Healthy);
+        assert_eq!(status(1.0), HealthStatus::Degraded);
+        assert_eq!(
+            status(UNHEALTHY_CONSECUTIVE_FAILURES as f64),
+            HealthStatus::Unhealthy
+        );
+    }
+
+    #[test]
+    fn failed_healthcheck_until_request_succeeds() {
+        let status = |last_success: f64| {
+            HealthSignals::from_metrics(&[
+                gauge("sink_healthcheck_passed", 0.0),
+                gauge("sink_last_healthcheck_timestamp_seconds", 20.0),
+                gauge("sink_last_success_timestamp_seconds", last_success),
+            ])
+            .status()
+        };
+
+        assert_eq!(status(10.0), HealthStatus::Unhealthy);
+        assert_eq!(status(30.0), HealthStatus::Healthy);
+    }
+
+    #[test]
+    fn circuit_breaker_state() {
+        let status = |state: f64| {
+            HealthSignals::from_metrics(&[
+                gauge("sink_last_success_timestamp_seconds", 10.0),
+                gauge("sink_circuit_breaker_state", state),
+            ])
+            .status()
+        };
+
+        assert_eq!(status(0.0), HealthStatus::Healthy);
+        assert_eq!(status(1.0), HealthStatus::Degraded);
+        assert_eq!(status(2.0), HealthStatus::Unhealthy);
+    }
 }
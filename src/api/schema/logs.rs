@@ -0,0 +1,57 @@
+use crate::{event::LogEvent, trace};
+use async_graphql::{SimpleObject, Subscription};
+use chrono::{DateTime, Utc};
+use tokio_stream::{wrappers::BroadcastStream, Stream, StreamExt};
+
+#[derive(SimpleObject)]
+pub struct InternalLog {
+    /// Time at which the log was emitted
+    timestamp: DateTime<Utc>,
+
+    /// Log level, either `WARN` or `ERROR`
+    level: String,
+
+    /// Log message
+    message: String,
+
+    /// Id of the component the log was emitted by, if known
+    component_id: Option<String>,
+
+    /// Kind of the component the log was emitted by, if known
+    component_kind: Option<String>,
+}
+
+impl InternalLog {
+    /// Returns an `InternalLog` for warnings and errors, or `None` for other levels.
+    fn from_log(log: LogEvent) -> Option<Self> {
+        let level = log.get("metadata.level")?.to_string_lossy();
+        if level != "WARN" && level != "ERROR" {
+            return None;
+        }
+        let string = |key: &str| log.get(key).map(|value| value.to_string_lossy());
+
+        Some(Self {
+            timestamp: log
+                .get("timestamp")
+                .and_then(|value| value.as_timestamp())
+                .copied()
+                .unwrap_or_else(Utc::now),
+            level,
+            message: string("message").unwrap_or_default(),
+            component_id: string("component_id"),
+            component_kind: string("component_kind"),
+        })
+    }
+}
+
+#[derive(Default)]
+pub struct LogsSubscription;
+
+#[Subscription]
+impl LogsSubscription {
+    /// Warnings and errors logged by Vector from the time of subscribing
+    async fn internal_logs(&self) -> impl Stream<Item = InternalLog> {
+        BroadcastStream::new(trace::subscribe_logs())
+            .filter_map(|log| log.ok().and_then(InternalLog::from_log))
+    }
+}
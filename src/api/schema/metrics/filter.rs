@@ -252,3 +252,38 @@ pub fn component_counter_throughputs(
         // Ignore the first, since we only care about sampling between `interval`
         .skip(1)
 }
+
+/// Returns the mean of the values recorded by a 'histogram' metric over each `interval`
+/// milliseconds, filtered by the provided `filter_fn` and aggregated against each component.
+/// Components that didn't record any value during an interval are omitted.
+pub fn component_histogram_means(
+    interval: i32,
+    filter_fn: &'static MetricFilterFn,
+) -> impl Stream<Item = Vec<(Metric, f64)>> {
+    let mut cache = BTreeMap::new();
+
+    get_all_metrics(interval).map(move |m| {
+        m.into_iter()
+            .filter(filter_fn)
+            .filter_map(|m| m.tag_value("component_id").map(|id| (id, m)))
+            .fold(BTreeMap::new(), |mut map, (id, m)| {
+                map.entry(id).or_insert_with(Vec::new).push(m);
+                map
+            })
+            .into_iter()
+            .filter_map(|(id, metrics)| {
+                let m = sum_metrics_owned(metrics)?;
+                match m.value() {
+                    MetricValue::AggregatedHistogram { count, sum, .. } => {
+                        let (last_count, last_sum) =
+                            cache.insert(id, (*count, *sum)).unwrap_or((0, 0.00));
+                        let count = count.saturating_sub(last_count);
+                        let mean = (sum - last_sum) / f64::from(count);
+                        (count > 0).then(|| (m, mean))
+                    }
+                    _ => None,
+                }
+            })
+            .collect()
+    })
+}
@@ -0,0 +1,70 @@
+use crate::config::ComponentKey;
+use async_graphql::Object;
+
+pub struct ComponentLatency {
+    component_key: ComponentKey,
+    mean: f64,
+}
+
+impl ComponentLatency {
+    /// Returns a new `ComponentLatency`, set to the provided id/mean values.
+    pub const fn new(component_key: ComponentKey, mean: f64) -> Self {
+        Self {
+            component_key,
+            mean,
+        }
+    }
+}
+
+#[Object]
+impl ComponentLatency {
+    /// Component id
+    async fn component_id(&self) -> &str {
+        self.component_key.id()
+    }
+
+    /// Pipeline id
+    async fn pipeline_id(&self) -> Option<&str> {
+        self.component_key.pipeline_str()
+    }
+
+    /// Mean time in seconds taken by events to cross the component, from their receipt to
+    /// their output for transforms, or to their acknowledgement for sinks
+    async fn mean(&self) -> f64 {
+        self.mean
+    }
+}
+
+pub struct ComponentEventAge {
+    component_key: ComponentKey,
+    mean: f64,
+}
+
+impl ComponentEventAge {
+    /// Returns a new `ComponentEventAge`, set to the provided id/mean values.
+    pub const fn new(component_key: ComponentKey, mean: f64) -> Self {
+        Self {
+            component_key,
+            mean,
+        }
+    }
+}
+
+#[Object]
+impl ComponentEventAge {
+    /// Component id
+    async fn component_id(&self) -> &str {
+        self.component_key.id()
+    }
+
+    /// Pipeline id
+    async fn pipeline_id(&self) -> Option<&str> {
+        self.component_key.pipeline_str()
+    }
+
+    /// Mean time in seconds between events entering the topology and being finalized
+    /// by the sink
+    async fn mean(&self) -> f64 {
+        self.mean
+    }
+}
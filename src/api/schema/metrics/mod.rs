@@ -2,6 +2,7 @@ mod errors;
 mod events_in;
 mod events_out;
 pub mod filter;
+mod latency;
 mod processed_bytes;
 mod processed_events;
 mod sink;
@@ -22,6 +23,7 @@ pub use errors::{ComponentErrorsTotal, ErrorsTotal};
 pub use events_in::{ComponentEventsInThroughput, ComponentEventsInTotal, EventsInTotal};
 pub use events_out::{ComponentEventsOutThroughput, ComponentEventsOutTotal, EventsOutTotal};
 pub use filter::*;
+pub use latency::{ComponentEventAge, ComponentLatency};
 pub use processed_bytes::{
     ComponentProcessedBytesThroughput, ComponentProcessedBytesTotal, ProcessedBytesTotal,
 };
@@ -292,6 +294,46 @@ impl MetricsSubscription {
             .map(|m| m.into_iter().map(ComponentErrorsTotal::new).collect())
     }
 
+    /// Component latency metrics, averaged over `interval`.
+    async fn component_latencies(
+        &self,
+        #[graphql(default = 1000, validator(IntRange(min = "10", max = "60_000")))] interval: i32,
+    ) -> impl Stream<Item = Vec<ComponentLatency>> {
+        component_histogram_means(interval, &|m| m.name() == "component_latency_seconds").map(|m| {
+            m.into_iter()
+                .map(|(m, mean)| {
+                    ComponentLatency::new(
+                        ComponentKey::from((
+                            m.tag_value("pipeline_id"),
+                            m.tag_value("component_id").unwrap(),
+                        )),
+                        mean,
+                    )
+                })
+                .collect()
+        })
+    }
+
+    /// Age of the events finalized by sinks, averaged over `interval`.
+    async fn component_event_ages(
+        &self,
+        #[graphql(default = 1000, validator(IntRange(min = "10", max = "60_000")))] interval: i32,
+    ) -> impl Stream<Item = Vec<ComponentEventAge>> {
+        component_histogram_means(interval, &|m| m.name() == "event_age_seconds").map(|m| {
+            m.into_iter()
+                .map(|(m, mean)| {
+                    ComponentEventAge::new(
+                        ComponentKey::from((
+                            m.tag_value("pipeline_id"),
+                            m.tag_value("component_id").unwrap(),
+                        )),
+                        mean,
+                    )
+                })
+                .collect()
+        })
+    }
+
     /// All metrics.
     async fn metrics(
         &self,
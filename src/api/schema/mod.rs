@@ -1,7 +1,8 @@
 pub mod components;
 mod events;
 pub mod filter;
-mod health;
+pub mod health;
+mod logs;
 mod meta;
 mod metrics;
 mod relay;
@@ -24,6 +25,7 @@ pub struct Subscription(
     metrics::MetricsSubscription,
     components::ComponentsSubscription,
     events::EventsSubscription,
+    logs::LogsSubscription,
 );
 
 /// Build a new GraphQL schema, comprised of Query, Mutation and Subscription types
@@ -57,7 +57,10 @@ fn make_routes(playground: bool, watch_tx: topology::WatchRx) -> BoxedFilter<(im
     // Routes...
 
     // Health.
-    let health = warp::path("health").and_then(handler::health);
+    let health = warp::path!("health" / "live")
+        .and_then(handler::live)
+        .or(warp::path!("health" / "ready").and_then(handler::ready))
+        .or(warp::path("health").and_then(handler::health));
 
     // 404.
     let not_found = warp::any().and_then(|| async { Err(warp::reject::not_found()) });
@@ -1,15 +1,15 @@
 use super::{ShutdownRx, ShutdownTx};
 use crate::topology::fanout::ControlChannel;
 use crate::{
+    conditions::Condition,
     config::ComponentKey,
-    event::{Event, LogEvent},
+    event::{Event, LogEvent, Metric},
     topology::{fanout, WatchRx},
 };
 use futures::{future::try_join_all, FutureExt, Sink, SinkExt};
 use itertools::Itertools;
 use std::{
     collections::{HashMap, HashSet, VecDeque},
-    iter::FromIterator,
     pin::Pin,
     task::{Context, Poll},
 };
@@ -40,12 +40,19 @@ pub enum TapNotification {
     NotMatched,
 }
 
+/// Whether tapped events are received or emitted by a component.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum TapDirection {
+    Input,
+    Output,
+}
+
 /// A tap payload can either contain a log/metric event or a notification that's intended
 /// to be communicated back to the client to alert them about the status of the tap request.
 #[derive(Debug)]
 pub enum TapPayload {
-    Log(ComponentKey, LogEvent),
-    Metric(ComponentKey, LogEvent),
+    Log(ComponentKey, TapDirection, LogEvent),
+    Metric(ComponentKey, TapDirection, Metric),
     Notification(ComponentKey, TapNotification),
 }
 
@@ -59,19 +66,60 @@ impl TapPayload {
     }
 }
 
+/// A tap filter restricts the events relayed to the client to those matching a condition,
+/// and samples them at a rate of 1 in `sample_rate`. Both are applied in the `TapSink`, so
+/// discarded events never cross the tap channel.
+#[derive(Clone)]
+pub struct TapFilter {
+    condition: Option<Box<dyn Condition>>,
+    sample_rate: u64,
+}
+
+impl TapFilter {
+    pub fn new(condition: Option<Box<dyn Condition>>, sample_rate: u64) -> Self {
+        Self {
+            condition,
+            sample_rate: sample_rate.max(1),
+        }
+    }
+
+    fn matches(&self, event: &Event) -> bool {
+        self.condition
+            .as_ref()
+            .map_or(true, |condition| condition.check(event))
+    }
+}
+
+impl Default for TapFilter {
+    fn default() -> Self {
+        Self::new(None, 1)
+    }
+}
+
 /// A `TapSink` is used as an output channel for a topology component, and receives
-/// `Event`s. If these are of type `Event::LogEvent`, they are relayed to the tap client.
+/// `Event`s. Those passing the tap filter are relayed to the tap client.
 pub struct TapSink {
     tap_tx: TapSender,
     component_key: ComponentKey,
-    buffer: VecDeque<LogEvent>,
+    direction: TapDirection,
+    filter: TapFilter,
+    matched: u64,
+    buffer: VecDeque<Event>,
 }
 
 impl TapSink {
-    pub fn new(tap_tx: TapSender, component_key: ComponentKey) -> Self {
+    pub fn new(
+        tap_tx: TapSender,
+        component_key: ComponentKey,
+        direction: TapDirection,
+        filter: TapFilter,
+    ) -> Self {
         Self {
             tap_tx,
             component_key,
+            direction,
+            filter,
+            matched: 0,
             // Pre-allocate space of 100 events, which matches the default `limit` typically
             // provided to a tap subscription. If there's a higher log volume, this will block
             // until the upstream event handler has processed the event. Generally, there should
@@ -89,13 +137,19 @@ impl Sink<Event> for TapSink {
         Poll::Ready(Ok(()))
     }
 
-    /// If the sink is ready, and the event is of type `LogEvent`, add to the buffer.
+    /// If the sink is ready, and the event passes the filter, add to the buffer.
     fn start_send(mut self: Pin<&mut Self>, item: Event) -> Result<(), Self::Error> {
-        // If we have a `LogEvent`, and space for it in the buffer, queue it.
-        if let Event::Log(ev) = item {
-            if self.buffer.len() < self.buffer.capacity() {
-                self.buffer.push_back(ev);
-            }
+        if !self.filter.matches(&item) {
+            return Ok(());
+        }
+
+        // Sample every `sample_rate`th matching event, starting with the first one.
+        let sampled = self.matched % self.filter.sample_rate == 0;
+        self.matched = self.matched.wrapping_add(1);
+
+        // If the event is sampled, and there's space for it in the buffer, queue it.
+        if sampled && self.buffer.len() < self.buffer.capacity() {
+            self.buffer.push_back(item);
         }
 
         Ok(())
@@ -111,13 +165,16 @@ impl Sink<Event> for TapSink {
         while let Some(ev) = self.buffer.pop_front() {
             // Attempt to send upstream. If the channel is closed, log and break. If it's
             // full, return pending to reattempt later.
-            match self
-                .tap_tx
-                .try_send(TapPayload::Log(self.component_key.clone(), ev))
-            {
+            let payload = match ev {
+                Event::Log(ev) => TapPayload::Log(self.component_key.clone(), self.direction, ev),
+                Event::Metric(ev) => {
+                    TapPayload::Metric(self.component_key.clone(), self.direction, ev)
+                }
+            };
+            match self.tap_tx.try_send(payload) {
                 Err(tokio_mpsc::error::TrySendError::Closed(payload)) => {
                     debug!(
-                        message = "Couldn't send log event.",
+                        message = "Couldn't send event.",
                         payload = ?payload,
                         component_id = ?self.component_key.id(),
                         component_scope = ?self.component_key.scope(),
@@ -148,12 +205,21 @@ pub struct TapController {
 impl TapController {
     /// Creates a new tap sink, and spawns a handler for watching for topology changes
     /// and a separate inner handler for events. Uses a oneshot channel to trigger shutdown
-    /// of handlers when the `TapSink` drops out of scope.
-    pub fn new(watch_rx: WatchRx, tap_tx: TapSender, patterns: &[String]) -> Self {
+    /// of handlers when the `TapSink` drops out of scope. The outputs of components matching
+    /// `patterns` are observed, and the inputs of those matching `input_patterns`.
+    pub fn new(
+        watch_rx: WatchRx,
+        tap_tx: TapSender,
+        patterns: &[String],
+        input_patterns: &[String],
+        filter: TapFilter,
+    ) -> Self {
         let (_shutdown, shutdown_rx) = oneshot::channel();
 
         tokio::spawn(tap_handler(
             patterns.iter().cloned().collect(),
+            input_patterns.iter().cloned().collect(),
+            filter,
             tap_tx,
             watch_rx,
             shutdown_rx,
@@ -195,19 +261,64 @@ async fn send_not_matched(tx: TapSender, pattern: &str) -> Result<(), SendError<
     tx.send(TapPayload::not_matched(pattern)).await
 }
 
+/// Connects a tap sink to the fanout behind `control_tx`, returning a trigger that
+/// disconnects it when dropped. Correlated sinks have the fanout assign correlation ids
+/// to events, so that they can be matched with the outputs of downstream components.
+async fn connect_sink(
+    mut control_tx: ControlChannel,
+    sink: TapSink,
+    correlated: bool,
+) -> Option<ShutdownTx> {
+    let sink_id = Uuid::new_v4().to_string();
+    let component_key = sink.component_key.clone();
+    let sink = Box::new(sink);
+
+    let message = if correlated {
+        fanout::ControlMessage::AddCorrelated(ComponentKey::from(&sink_id), sink)
+    } else {
+        fanout::ControlMessage::Add(ComponentKey::from(&sink_id), sink)
+    };
+
+    match control_tx.send(message).await {
+        Ok(_) => {
+            debug!(message = "Sink connected.", ?sink_id, ?component_key);
+
+            Some(shutdown_trigger(control_tx, ComponentKey::global(&sink_id)))
+        }
+        Err(error) => {
+            error!(
+                message = "Couldn't connect sink.",
+                ?error,
+                ?component_key,
+                ?sink_id,
+            );
+
+            None
+        }
+    }
+}
+
 /// Returns a tap handler that listens for topology changes, and connects sinks to observe
-/// `LogEvent`s` when a component matches one or more of the provided patterns.
+/// events when a component matches one or more of the provided patterns. The outputs of
+/// components matching `patterns` are observed, as well as the inputs of transforms and
+/// sinks matching `input_patterns`.
 async fn tap_handler(
     patterns: HashSet<String>,
+    input_patterns: HashSet<String>,
+    filter: TapFilter,
     tx: TapSender,
     mut watch_rx: WatchRx,
     mut shutdown_rx: ShutdownRx,
 ) {
-    debug!(message = "Started tap.", patterns = ?patterns);
+    debug!(message = "Started tap.", patterns = ?patterns, input_patterns = ?input_patterns);
+
+    // Sinks registered for the current tap, per matched component and direction. Contains
+    // shutdown triggers for sending remove control messages when matching sinks change. An
+    // input tap has a sink on the fanout of each of the component's inputs.
+    let mut sinks: HashMap<(ComponentKey, TapDirection), Vec<ShutdownTx>> = HashMap::new();
 
-    // Sinks register for the current tap. Contains the id of the matched component, and
-    // a shutdown trigger for sending a remove control message when matching sinks change.
-    let mut sinks: HashMap<ComponentKey, _> = HashMap::new();
+    // Patterns in both sets are notified once.
+    let all_patterns = patterns.union(&input_patterns).collect::<HashSet<_>>();
 
     loop {
         tokio::select! {
@@ -215,21 +326,31 @@ async fn tap_handler(
             Ok(_) = watch_rx.changed() => {
                 // Get the patterns that matched on the last iteration, to compare with the latest
                 // round of matches when sending notifications.
-                let last_matches = patterns
-                    .iter()
-                    .filter(|pattern| sinks.keys().any(|id| pattern.matches_glob(&id.to_string())))
+                let matches = |patterns: &HashSet<String>, direction| {
+                    patterns
+                        .iter()
+                        .filter(|pattern| {
+                            sinks.keys().any(|(id, d)| {
+                                *d == direction && pattern.matches_glob(&id.to_string())
+                            })
+                        })
+                        .collect::<Vec<_>>()
+                };
+                let last_matches = matches(&patterns, TapDirection::Output)
+                    .into_iter()
+                    .chain(matches(&input_patterns, TapDirection::Input))
                     .collect::<HashSet<_>>();
 
                 // Cache of matched patterns. A `HashSet` is used here to ignore repetition.
                 let mut matched = HashSet::new();
 
-                // Borrow and clone the latest outputs to register sinks. Since this blocks the
+                // Borrow and clone the latest resources to register sinks. Since this blocks the
                 // watch channel and the returned ref isn't `Send`, this requires a clone.
-                let outputs = watch_rx.borrow().clone();
+                let resource = watch_rx.borrow().clone();
 
                 // Loop over all outputs, and connect sinks for the components that match one
                 // or more patterns.
-                for (component_key, mut control_tx) in outputs.iter() {
+                for (component_key, control_tx) in resource.outputs.iter() {
                     match patterns
                         .iter()
                         .filter(|pattern| pattern.matches_glob(&component_key.to_string()))
@@ -244,32 +365,14 @@ async fn tap_handler(
                             // (Re)connect the sink. This is necessary because a sink may be
                             // reconfigured with the same id as a previous, and we are not
                             // getting involved in config diffing at this point.
-                            let sink_id = Uuid::new_v4().to_string();
-                            let sink = TapSink::new(tx.clone(), component_key.clone());
-
-                            // Attempt to connect the sink.
-                            match control_tx
-                                .send(fanout::ControlMessage::Add(ComponentKey::from(&sink_id), Box::new(sink)))
-                                .await
-                            {
-                                Ok(_) => {
-                                    debug!(
-                                        message = "Sink connected.", ?sink_id, ?component_key,
-                                    );
-
-                                    // Create a sink shutdown trigger to remove the sink
-                                    // when matched components change.
-                                    sinks
-                                        .insert(component_key.clone(), shutdown_trigger(control_tx.clone(), ComponentKey::global(&sink_id)));
-                                }
-                                Err(error) => {
-                                    error!(
-                                        message = "Couldn't connect sink.",
-                                        ?error,
-                                        ?component_key,
-                                        ?sink_id,
-                                    );
-                                }
+                            let sink = TapSink::new(
+                                tx.clone(),
+                                component_key.clone(),
+                                TapDirection::Output,
+                                filter.clone(),
+                            );
+                            if let Some(trigger) = connect_sink(control_tx.clone(), sink, false).await {
+                                sinks.insert((component_key.clone(), TapDirection::Output), vec![trigger]);
                             }
 
                             matched.extend(found);
@@ -282,9 +385,46 @@ async fn tap_handler(
                     }
                 }
 
+                // Loop over the inputs of transforms and sinks, and connect sinks to the
+                // fanouts of their inputs for the components that match one or more patterns.
+                for (component_key, inputs) in resource.inputs.iter() {
+                    let found = input_patterns
+                        .iter()
+                        .filter(|pattern| pattern.matches_glob(&component_key.to_string()))
+                        .collect_vec();
+                    if found.is_empty() {
+                        continue;
+                    }
+
+                    debug!(
+                        message="Component inputs matched.",
+                        ?component_key, ?input_patterns, matched = ?found
+                    );
+
+                    let mut triggers = Vec::with_capacity(inputs.len());
+                    for input in inputs {
+                        if let Some(control_tx) = resource.outputs.get(input) {
+                            let sink = TapSink::new(
+                                tx.clone(),
+                                component_key.clone(),
+                                TapDirection::Input,
+                                filter.clone(),
+                            );
+                            triggers.extend(connect_sink(control_tx.clone(), sink, true).await);
+                        }
+                    }
+                    sinks.insert((component_key.clone(), TapDirection::Input), triggers);
+
+                    matched.extend(found);
+                }
+
                 // Remove components that have gone away.
-                sinks.retain(|id, _| {
-                    outputs.contains_key(id) || {
+                sinks.retain(|(id, direction), _| {
+                    let exists = match direction {
+                        TapDirection::Output => resource.outputs.contains_key(id),
+                        TapDirection::Input => resource.inputs.contains_key(id),
+                    };
+                    exists || {
                         debug!(message = "Removing component.", component_id = %id);
                         false
                     }
@@ -292,7 +432,7 @@ async fn tap_handler(
 
                 // Send notifications to the client. The # of notifications will always be
                 // exactly equal to the number of patterns, so we can pre-allocate capacity.
-                let mut notifications = Vec::with_capacity(patterns.len());
+                let mut notifications = Vec::with_capacity(all_patterns.len());
 
                 // Matched notifications.
                 for pattern in matched.difference(&last_matches) {
@@ -300,7 +440,7 @@ async fn tap_handler(
                 }
 
                 // Not matched notifications.
-                for pattern in HashSet::from_iter(&patterns).difference(&matched) {
+                for pattern in all_patterns.difference(&matched) {
                     notifications.push(send_not_matched(tx.clone(), pattern).boxed());
                 }
 
@@ -314,14 +454,16 @@ async fn tap_handler(
         }
     }
 
-    debug!(message = "Stopped tap.", patterns = ?patterns);
+    debug!(message = "Stopped tap.", patterns = ?patterns, input_patterns = ?input_patterns);
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    use crate::event::{Metric, MetricKind, MetricValue};
+    use crate::conditions::{ConditionConfig, VrlConfig};
+    use crate::event::{Metric, MetricKind, MetricValue, Value};
+    use crate::topology::TapResource;
     use futures::SinkExt;
     use tokio::sync::watch;
 
@@ -343,8 +485,8 @@ mod tests {
 
     #[tokio::test]
     /// A tap sink should match a pattern, receive the correct notifications, and
-    /// discard non `LogEvent` events.
-    async fn sink_log_events() {
+    /// relay both log and metric events.
+    async fn sink_events() {
         let pattern_matched = "tes*";
         let pattern_not_matched = "xyz";
         let id = ComponentKey::global("test");
@@ -353,17 +495,24 @@ mod tests {
         let mut outputs = HashMap::new();
         outputs.insert(id.clone(), control_tx);
 
-        let (watch_tx, watch_rx) = watch::channel(HashMap::new());
+        let (watch_tx, watch_rx) = watch::channel(TapResource::default());
         let (sink_tx, mut sink_rx) = tokio_mpsc::channel(10);
 
         let _controller = TapController::new(
             watch_rx,
             sink_tx,
             &[pattern_matched.to_string(), pattern_not_matched.to_string()],
+            &[],
+            TapFilter::default(),
         );
 
         // Add the outputs to trigger a change event.
-        watch_tx.send(outputs).unwrap();
+        watch_tx
+            .send(TapResource {
+                outputs,
+                inputs: HashMap::new(),
+            })
+            .unwrap();
 
         // First two events should contain a notification that one pattern matched, and
         // one that didn't.
@@ -398,10 +547,93 @@ mod tests {
         let _ = fanout.send(metric_event).await.unwrap();
         let _ = fanout.send(log_event).await.unwrap();
 
-        // 3rd payload should be the log event
+        // 3rd payload should be the metric event, and the 4th the log event
         assert!(matches!(
             sink_rx.recv().await,
-            Some(TapPayload::Log(returned_id, _)) if returned_id == id
+            Some(TapPayload::Metric(returned_id, TapDirection::Output, _)) if returned_id == id
         ));
+        assert!(matches!(
+            sink_rx.recv().await,
+            Some(TapPayload::Log(returned_id, TapDirection::Output, _)) if returned_id == id
+        ));
+    }
+
+    #[tokio::test]
+    /// A tap sink should only relay sampled events matching its filter.
+    async fn sink_filters_and_samples() {
+        let id = ComponentKey::global("test");
+        let condition = VrlConfig {
+            source: r#".keep == true"#.to_string(),
+        }
+        .build(&Default::default())
+        .unwrap();
+
+        let (sink_tx, mut sink_rx) = tokio_mpsc::channel(10);
+        let mut sink = TapSink::new(
+            sink_tx,
+            id,
+            TapDirection::Output,
+            TapFilter::new(Some(condition), 2),
+        );
+
+        for index in 0..6i64 {
+            let mut log = LogEvent::default();
+            log.insert("keep", index % 3 != 0);
+            log.insert("index", index);
+            sink.send(Event::from(log)).await.unwrap();
+        }
+        drop(sink);
+
+        // Events 1, 2, 4 and 5 match the filter, and every other one of them is sampled.
+        let mut indexes = vec![];
+        while let Some(payload) = sink_rx.recv().await {
+            match payload {
+                TapPayload::Log(_, _, log) => indexes.push(log.get("index").cloned().unwrap()),
+                _ => panic!("unexpected payload"),
+            }
+        }
+        assert_eq!(indexes, vec![Value::Integer(1), Value::Integer(4)]);
+    }
+
+    #[tokio::test]
+    /// Tapping the inputs of a component should observe the events sent by its upstream
+    /// fanouts, with correlation ids.
+    async fn sink_input_events() {
+        let source_id = ComponentKey::global("source");
+        let transform_id = ComponentKey::global("transform");
+
+        let (mut fanout, control_tx) = fanout::Fanout::new();
+        let mut outputs = HashMap::new();
+        outputs.insert(source_id.clone(), control_tx);
+        let mut inputs = HashMap::new();
+        inputs.insert(transform_id.clone(), vec![source_id.clone()]);
+
+        let (watch_tx, watch_rx) = watch::channel(TapResource::default());
+        let (sink_tx, mut sink_rx) = tokio_mpsc::channel(10);
+
+        let _controller = TapController::new(
+            watch_rx,
+            sink_tx,
+            &[],
+            &["trans*".to_string()],
+            TapFilter::default(),
+        );
+
+        watch_tx.send(TapResource { outputs, inputs }).unwrap();
+
+        assert!(matches!(
+            sink_rx.recv().await,
+            Some(TapPayload::Notification(_, TapNotification::Matched))
+        ));
+
+        let _ = fanout.send(Event::new_empty_log()).await.unwrap();
+
+        match sink_rx.recv().await {
+            Some(TapPayload::Log(returned_id, TapDirection::Input, log)) => {
+                assert_eq!(returned_id, transform_id);
+                assert!(log.metadata().correlation_id().is_some());
+            }
+            _ => panic!("unexpected payload"),
+        }
     }
 }
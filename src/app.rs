@@ -1,6 +1,6 @@
 use crate::{
     cli::{handle_config_errors, Color, LogFormat, Opts, RootOpts, SubCommand},
-    config, generate, graph, heartbeat, list, metrics,
+    config, generate, graph, heartbeat, list, metrics, migrate,
     signal::{self, SignalTo},
     topology::{self, RunningTopology},
     trace, unit_test, validate,
@@ -118,6 +118,7 @@ impl Application {
                         SubCommand::Generate(g) => generate::cmd(&g),
                         SubCommand::Graph(g) => graph::cmd(&g),
                         SubCommand::List(l) => list::cmd(&l),
+                        SubCommand::Migrate(m) => migrate::cmd(&m).await,
                         SubCommand::Test(t) => unit_test::cmd(&t).await,
                         #[cfg(windows)]
                         SubCommand::Service(s) => service::cmd(&s),
@@ -1,4 +1,4 @@
-use crate::{config, generate, get_version, graph, list, unit_test, validate};
+use crate::{config, generate, get_version, graph, list, migrate, unit_test, validate};
 use std::path::PathBuf;
 use structopt::{clap::AppSettings, StructOpt};
 
@@ -207,6 +207,10 @@ pub enum SubCommand {
     /// Output the topology as visual representation using the DOT language which can be rendered by GraphViz
     Graph(graph::Opts),
 
+    /// Rewrite deprecated transforms into equivalent `remap` transforms, with unit tests showing
+    /// the behavior is preserved.
+    Migrate(migrate::Opts),
+
     /// Display topology and metrics in the console, for a local or remote Vector instance
     #[cfg(feature = "api-client")]
     Top(top::Opts),
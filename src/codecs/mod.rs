@@ -16,6 +16,7 @@ pub use framers::*;
 pub use parsers::*;
 use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
+use vrl::prelude::TypeDef;
 
 /// An error that occurred while decoding structured events from a byte stream /
 /// byte messages.
@@ -168,4 +169,12 @@ impl DecodingConfig {
 
         Ok(Decoder::new(framer, parser))
     }
+
+    /// The schema of the log events produced by the configured parser.
+    pub fn schema(&self) -> TypeDef {
+        self.decoding
+            .as_ref()
+            .map(|config| config.schema())
+            .unwrap_or_else(|| BytesParserConfig::new().schema())
+    }
 }
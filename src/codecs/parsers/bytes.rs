@@ -1,10 +1,12 @@
 use crate::{
     codecs::{BoxedParser, Parser, ParserConfig},
+    config::{log_schema, schema},
     event::Event,
 };
 use bytes::Bytes;
 use serde::{Deserialize, Serialize};
 use smallvec::{smallvec, SmallVec};
+use vrl::prelude::TypeDef;
 
 /// Config used to build a `BytesParser`.
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
@@ -22,6 +24,13 @@ impl ParserConfig for BytesParserConfig {
     fn build(&self) -> crate::Result<BoxedParser> {
         Ok(Box::new(BytesParser))
     }
+
+    fn schema(&self) -> TypeDef {
+        schema::log_fields(vec![
+            (log_schema().message_key(), TypeDef::new().bytes()),
+            (log_schema().timestamp_key(), TypeDef::new().timestamp()),
+        ])
+    }
 }
 
 /// Parser that converts bytes to an `Event`.
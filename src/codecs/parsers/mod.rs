@@ -18,6 +18,7 @@ use ::bytes::Bytes;
 use dyn_clone::DynClone;
 use smallvec::SmallVec;
 use std::fmt::Debug;
+use vrl::prelude::TypeDef;
 
 /// Parse structured events from bytes.
 pub trait Parser: DynClone + Send + Sync {
@@ -46,6 +47,13 @@ pub trait ParserConfig: Debug + DynClone + Send + Sync {
     ///
     /// Fails if the configuration is invalid.
     fn build(&self) -> crate::Result<BoxedParser>;
+
+    /// The schema of the log events produced by the parser.
+    ///
+    /// Unknown by default.
+    fn schema(&self) -> TypeDef {
+        TypeDef::new().unknown()
+    }
 }
 
 dyn_clone::clone_trait_object!(ParserConfig);
@@ -222,6 +222,14 @@ impl ConfigBuilder {
             errors.push("conflicting values for 'data_dir' found".to_owned());
         }
 
+        if self.global.memory_budget_bytes.is_none() {
+            self.global.memory_budget_bytes = with.global.memory_budget_bytes;
+        } else if with.global.memory_budget_bytes.is_some()
+            && self.global.memory_budget_bytes != with.global.memory_budget_bytes
+        {
+            errors.push("conflicting values for 'memory_budget_bytes' found".to_owned());
+        }
+
         // If the user has multiple config files, we must *merge* log schemas
         // until we meet a conflict, then we are allowed to error.
         if let Err(merge_errors) = self.global.log_schema.merge(&with.global.log_schema) {
@@ -29,7 +29,7 @@ pub fn compile(mut builder: ConfigBuilder) -> Result<(Config, Vec<String>), Vec<
 
     expand_globs(&mut builder);
 
-    let warnings = validation::warnings(&builder);
+    let mut warnings = validation::warnings(&builder);
 
     if let Err(type_errors) = validation::check_shape(&builder) {
         errors.extend(type_errors);
@@ -44,6 +44,8 @@ pub fn compile(mut builder: ConfigBuilder) -> Result<(Config, Vec<String>), Vec<
     }
 
     if errors.is_empty() {
+        warnings.extend(validation::check_schemas(&builder));
+
         Ok((
             Config {
                 global: builder.global,
@@ -183,15 +185,17 @@ mod test {
     use super::*;
     use crate::{
         config::{
-            DataType, SinkConfig, SinkContext, SourceConfig, SourceContext, TransformConfig,
-            TransformContext,
+            schema, DataType, SinkConfig, SinkContext, SourceConfig, SourceContext,
+            TransformConfig, TransformContext, TransformSchema,
         },
+        event::{PathComponent, PathIter},
         sinks::{Healthcheck, VectorSink},
         sources::Source,
         transforms::Transform,
     };
     use async_trait::async_trait;
     use serde::{Deserialize, Serialize};
+    use vrl::prelude::TypeDef;
 
     #[derive(Debug, Serialize, Deserialize)]
     struct MockSourceConfig;
@@ -254,6 +258,183 @@ mod test {
         }
     }
 
+    #[derive(Debug, Serialize, Deserialize)]
+    struct MockLogSourceConfig;
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct MockMetricSourceConfig;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct MockPassthroughTransformConfig;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct MockLogTransformConfig;
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct MockOnlyFieldsSinkConfig {
+        only_fields: Vec<String>,
+    }
+
+    #[async_trait]
+    #[typetag::serde(name = "mock_log")]
+    impl SourceConfig for MockLogSourceConfig {
+        async fn build(&self, _cx: SourceContext) -> crate::Result<Source> {
+            unimplemented!()
+        }
+
+        fn source_type(&self) -> &'static str {
+            "mock_log"
+        }
+
+        fn output_type(&self) -> DataType {
+            DataType::Log
+        }
+
+        fn output_schema(&self) -> TypeDef {
+            schema::log_fields(vec![("message", TypeDef::new().bytes())])
+        }
+    }
+
+    #[async_trait]
+    #[typetag::serde(name = "mock_metric")]
+    impl SourceConfig for MockMetricSourceConfig {
+        async fn build(&self, _cx: SourceContext) -> crate::Result<Source> {
+            unimplemented!()
+        }
+
+        fn source_type(&self) -> &'static str {
+            "mock_metric"
+        }
+
+        fn output_type(&self) -> DataType {
+            DataType::Metric
+        }
+    }
+
+    #[async_trait]
+    #[typetag::serde(name = "mock_passthrough")]
+    impl TransformConfig for MockPassthroughTransformConfig {
+        async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+            unimplemented!()
+        }
+
+        fn transform_type(&self) -> &'static str {
+            "mock_passthrough"
+        }
+
+        fn input_type(&self) -> DataType {
+            DataType::Any
+        }
+
+        fn output_type(&self) -> DataType {
+            DataType::Any
+        }
+
+        fn schema(&self, input: &TypeDef) -> TransformSchema {
+            TransformSchema::passthrough(input)
+        }
+    }
+
+    #[async_trait]
+    #[typetag::serde(name = "mock_log")]
+    impl TransformConfig for MockLogTransformConfig {
+        async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+            unimplemented!()
+        }
+
+        fn transform_type(&self) -> &'static str {
+            "mock_log"
+        }
+
+        fn input_type(&self) -> DataType {
+            DataType::Log
+        }
+
+        fn output_type(&self) -> DataType {
+            DataType::Log
+        }
+    }
+
+    #[async_trait]
+    #[typetag::serde(name = "mock_only_fields")]
+    impl SinkConfig for MockOnlyFieldsSinkConfig {
+        async fn build(&self, _cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+            unimplemented!()
+        }
+
+        fn sink_type(&self) -> &'static str {
+            "mock_only_fields"
+        }
+
+        fn input_type(&self) -> DataType {
+            DataType::Any
+        }
+
+        fn referenced_fields(&self) -> Vec<Vec<PathComponent>> {
+            self.only_fields
+                .iter()
+                .map(|field| PathIter::new(field).collect())
+                .collect()
+        }
+    }
+
+    #[test]
+    fn schema_warns_unreachable_components() {
+        let mut builder = ConfigBuilder::default();
+        builder.add_source("metrics", MockMetricSourceConfig);
+        builder.add_transform("passthrough", &["metrics"], MockPassthroughTransformConfig);
+        builder.add_transform("logs", &["passthrough"], MockLogTransformConfig);
+        builder.add_sink("out", &["logs"], MockSinkConfig);
+
+        let (_, warnings) = builder.build_with_warnings().expect("build should succeed");
+
+        assert_eq!(
+            warnings,
+            vec!["Transform \"logs\" is unreachable, its inputs only produce metric events."]
+        );
+    }
+
+    #[test]
+    fn schema_warns_fields_that_never_exist() {
+        let mut builder = ConfigBuilder::default();
+        builder.add_source("logs", MockLogSourceConfig);
+        builder.add_transform("passthrough", &["logs"], MockPassthroughTransformConfig);
+        builder.add_sink(
+            "out",
+            &["passthrough"],
+            MockOnlyFieldsSinkConfig {
+                only_fields: vec!["message".to_owned(), "missing".to_owned()],
+            },
+        );
+
+        let (_, warnings) = builder.build_with_warnings().expect("build should succeed");
+
+        assert_eq!(
+            warnings,
+            vec![
+                "Sink \"out\" references field \"missing\", which can never exist in its input events."
+            ]
+        );
+    }
+
+    #[test]
+    fn schema_unknown_transforms_stop_propagation() {
+        let mut builder = ConfigBuilder::default();
+        builder.add_source("logs", MockLogSourceConfig);
+        builder.add_transform("unknown", &["logs"], MockTransformConfig);
+        builder.add_sink(
+            "out",
+            &["unknown"],
+            MockOnlyFieldsSinkConfig {
+                only_fields: vec!["missing".to_owned()],
+            },
+        );
+
+        let (_, warnings) = builder.build_with_warnings().expect("build should succeed");
+
+        assert!(warnings.is_empty());
+    }
+
     #[test]
     fn glob_expansion() {
         let mut builder = ConfigBuilder::default();
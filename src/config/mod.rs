@@ -1,22 +1,27 @@
 use crate::{
     buffers::Acker,
+    codecs::DecodingConfig,
     conditions,
-    event::Metric,
+    event::{self, Event, Metric, PathComponent},
     shutdown::ShutdownSignal,
     sinks::{self, util::UriSerde},
     sources, Pipeline,
 };
 use async_trait::async_trait;
+use bytes::Bytes;
 use component::ComponentDescription;
 use indexmap::IndexMap; // IndexMap preserves insertion order, allowing us to output errors in the same order they are present in the file
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt::{self, Display, Formatter};
 use std::hash::Hash;
 use std::net::SocketAddr;
 use std::path::PathBuf;
 pub use vector_core::config::GlobalOptions;
-pub use vector_core::transform::{DataType, ExpandType, TransformConfig, TransformContext};
+pub use vector_core::transform::{
+    DataType, ExpandType, TransformConfig, TransformContext, TransformSchema,
+};
+use vrl::prelude::TypeDef;
 
 pub mod api;
 mod builder;
@@ -30,6 +35,7 @@ mod id;
 mod loading;
 mod pipeline;
 pub mod provider;
+pub mod schema;
 mod unit_test;
 mod validation;
 mod vars;
@@ -43,7 +49,13 @@ pub use loading::{
     load, load_builder_and_pipelines_from_paths, load_from_paths, load_from_paths_with_provider,
     load_from_str, merge_path_lists, process_paths, CONFIG_PATHS,
 };
-pub use unit_test::build_unit_tests_main as build_unit_tests;
+pub(crate) use unit_test::{
+    build_unit_tests as build_unit_tests_from_builder,
+    generate_unit_tests as generate_unit_tests_from_builder,
+};
+pub use unit_test::{
+    build_unit_tests_main as build_unit_tests, generate_unit_tests_main as generate_unit_tests,
+};
 pub use validation::warnings;
 pub use vector_core::config::proxy::ProxyConfig;
 pub use vector_core::config::{log_schema, LogSchema};
@@ -188,8 +200,20 @@ pub trait SourceConfig: core::fmt::Debug + Send + Sync {
 
     fn output_type(&self) -> DataType;
 
+    /// The schema of the log events produced by the source, used for static
+    /// validation of the topology. Unknown by default.
+    fn output_schema(&self) -> TypeDef {
+        TypeDef::new().unknown()
+    }
+
     fn source_type(&self) -> &'static str;
 
+    /// The configured decoding of the source, if it decodes events from bytes
+    /// with the shared codecs. Used by config unit tests to decode raw inputs.
+    fn decoding(&self) -> Option<&DecodingConfig> {
+        None
+    }
+
     /// Resources that the source is using.
     fn resources(&self) -> Vec<Resource> {
         Vec::new()
@@ -312,6 +336,11 @@ impl SinkOuter {
 pub struct SinkHealthcheckOptions {
     pub enabled: bool,
     pub uri: Option<UriSerde>,
+    /// Runs the healthcheck again at this interval while the sink is running, in
+    /// addition to the healthcheck run on startup, for the sinks implementing
+    /// `SinkConfig::build_healthcheck`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interval_secs: Option<u64>,
 }
 
 impl Default for SinkHealthcheckOptions {
@@ -319,21 +348,25 @@ impl Default for SinkHealthcheckOptions {
         Self {
             enabled: true,
             uri: None,
+            interval_secs: None,
         }
     }
 }
 
 impl From<bool> for SinkHealthcheckOptions {
     fn from(enabled: bool) -> Self {
-        Self { enabled, uri: None }
+        Self {
+            enabled,
+            ..Self::default()
+        }
     }
 }
 
 impl From<UriSerde> for SinkHealthcheckOptions {
     fn from(uri: UriSerde) -> Self {
         Self {
-            enabled: true,
             uri: Some(uri),
+            ..Self::default()
         }
     }
 }
@@ -350,10 +383,64 @@ pub trait SinkConfig: core::fmt::Debug + Send + Sync {
 
     fn sink_type(&self) -> &'static str;
 
+    /// Log fields the sink is explicitly configured to read, such as those
+    /// listed in `encoding.only_fields`, used for static validation of the
+    /// topology.
+    fn referenced_fields(&self) -> Vec<Vec<PathComponent>> {
+        Vec::new()
+    }
+
+    /// Encodes an event exactly as the sink would write it, without performing
+    /// any I/O. Used by config unit tests to assert on the output of sinks.
+    ///
+    /// Returns `Ok(None)` if the sink would drop the event.
+    fn encode_for_test(&self, _event: Event) -> crate::Result<Option<EncodedTestEvent>> {
+        Err(format!(
+            "sink type \"{}\" doesn't support encoding events in unit tests",
+            self.sink_type()
+        )
+        .into())
+    }
+
     /// Resources that the sink is using.
     fn resources(&self) -> Vec<Resource> {
         Vec::new()
     }
+
+    /// Builds the healthcheck of the sink so that it can be repeated at
+    /// `healthcheck.interval_secs` while the sink runs, without building the
+    /// whole sink again.
+    ///
+    /// Returns `Ok(None)` if the healthcheck of the sink can't be repeated.
+    fn build_healthcheck(
+        &self,
+        _cx: &SinkContext,
+    ) -> crate::Result<Option<sinks::HealthcheckBuilder>> {
+        Ok(None)
+    }
+}
+
+/// An event encoded by a sink for config unit tests.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EncodedTestEvent {
+    /// The rendered partition the event would be written to, such as a file
+    /// path or an object key.
+    pub partition: Option<String>,
+    pub bytes: Bytes,
+}
+
+impl EncodedTestEvent {
+    pub fn new(bytes: impl Into<Bytes>) -> Self {
+        Self {
+            partition: None,
+            bytes: bytes.into(),
+        }
+    }
+
+    pub fn with_partition(mut self, partition: impl Into<String>) -> Self {
+        self.partition = Some(partition.into());
+        self
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -375,6 +462,14 @@ impl SinkContext {
         }
     }
 
+    #[cfg(test)]
+    pub fn new_test_with_healthcheck(healthcheck: SinkHealthcheckOptions) -> Self {
+        Self {
+            healthcheck,
+            ..Self::new_test()
+        }
+    }
+
     pub fn acker(&self) -> Acker {
         self.acker.clone()
     }
@@ -515,7 +610,7 @@ impl Display for Resource {
     }
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct TestDefinition {
     pub name: String,
@@ -528,7 +623,7 @@ pub struct TestDefinition {
     pub no_outputs_from: Vec<ComponentKey>,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(untagged)]
 pub enum TestInputValue {
     String(String),
@@ -537,7 +632,7 @@ pub enum TestInputValue {
     Boolean(bool),
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct TestInput {
     pub insert_at: ComponentKey,
@@ -546,17 +641,64 @@ pub struct TestInput {
     pub value: Option<String>,
     pub log_fields: Option<IndexMap<String, TestInputValue>>,
     pub metric: Option<Metric>,
+    /// The time at which the input is inserted, in milliseconds since the start
+    /// of the test. Time-driven transforms see the clock advance accordingly.
+    pub at_ms: Option<u64>,
 }
 
 fn default_test_input_type() -> String {
     "raw".to_string()
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct TestOutput {
     pub extract_from: ComponentKey,
     pub conditions: Option<Vec<conditions::AnyCondition>>,
+    /// The exact output of a sink, in the order events arrive at it. Only
+    /// valid when extracting from a sink.
+    pub encoded: Option<Vec<TestEncodedOutput>>,
+    /// The exact metrics output, in any order. Timestamps are only checked when
+    /// given.
+    pub metrics: Option<Vec<Metric>>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct TestEncodedOutput {
+    pub value: String,
+    pub partition: Option<String>,
+}
+
+/// An event captured by `vector tap --output`, along with the component that
+/// emitted it. Captures are stored as newline-delimited JSON.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct CapturedEvent {
+    pub component_id: ComponentKey,
+    #[serde(flatten)]
+    pub event: CapturedEventData,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum CapturedEventData {
+    Log(serde_json::Map<String, serde_json::Value>),
+    Metric(Metric),
+}
+
+impl CapturedEvent {
+    pub fn into_event(self) -> Event {
+        match self.event {
+            CapturedEventData::Log(fields) => Event::Log(
+                fields
+                    .into_iter()
+                    .map(|(key, value)| (key, value.into()))
+                    .collect::<BTreeMap<_, event::Value>>()
+                    .into(),
+            ),
+            CapturedEventData::Metric(metric) => Event::Metric(metric),
+        }
+    }
 }
 
 impl Config {
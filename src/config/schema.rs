@@ -0,0 +1,120 @@
+//! Helpers for describing the schema of log events flowing between components.
+//!
+//! Schemas are expressed as VRL type definitions, which allows `remap`
+//! programs to be checked against the events they receive. A type definition
+//! with an unknown kind means nothing is known about the events.
+
+use crate::event::{PathComponent, PathIter};
+use lookup::{LookupBuf, SegmentBuf};
+use std::collections::BTreeMap;
+use vrl::prelude::TypeDef;
+
+/// The schema of log events containing exactly the given top-level fields.
+pub fn log_fields<'a>(fields: impl IntoIterator<Item = (&'a str, TypeDef)>) -> TypeDef {
+    let fields = fields
+        .into_iter()
+        .map(|(name, type_def)| (name.to_owned(), type_def))
+        .collect::<BTreeMap<_, _>>();
+
+    TypeDef::new().object(fields)
+}
+
+/// The schema of log events containing the given top-level fields, along with
+/// other fields that can't be known in advance.
+pub fn open_log_fields<'a>(fields: impl IntoIterator<Item = (&'a str, TypeDef)>) -> TypeDef {
+    let other = vec![((), TypeDef::new().unknown())]
+        .into_iter()
+        .collect::<BTreeMap<_, _>>();
+
+    log_fields(fields).merge(TypeDef::new().object(other))
+}
+
+/// Adds the given top-level fields to a schema.
+///
+/// Unknown schemas stay unknown.
+pub fn with_fields<'a>(
+    schema: TypeDef,
+    fields: impl IntoIterator<Item = (&'a str, TypeDef)>,
+) -> TypeDef {
+    if schema.is_unknown() {
+        schema
+    } else {
+        schema.merge(log_fields(fields))
+    }
+}
+
+/// Merges the schemas of events coming from several inputs.
+///
+/// The result is unknown if any of the inputs is unknown.
+pub fn merge(schemas: impl IntoIterator<Item = TypeDef>) -> TypeDef {
+    let mut schemas = schemas.into_iter();
+
+    let first = match schemas.next() {
+        Some(schema) => schema,
+        None => return TypeDef::new().unknown(),
+    };
+
+    schemas.fold(first, |acc, schema| {
+        if acc.is_unknown() || schema.is_unknown() {
+            TypeDef::new().unknown()
+        } else {
+            acc.merge(schema)
+        }
+    })
+}
+
+/// Converts a field path as used throughout the config (e.g. `a.b[0]`) into a
+/// lookup that can be checked against a schema.
+pub fn field_lookup(path: &str) -> Option<LookupBuf> {
+    components_lookup(&PathIter::new(path).collect::<Vec<_>>())
+}
+
+/// Converts parsed path components into a lookup that can be checked against
+/// a schema. Returns `None` if the path is invalid.
+pub fn components_lookup(components: &[PathComponent]) -> Option<LookupBuf> {
+    components
+        .iter()
+        .map(|component| match component {
+            PathComponent::Key(key) => Some(SegmentBuf::from(key.as_str())),
+            PathComponent::Index(index) => Some(SegmentBuf::Index(*index as isize)),
+            PathComponent::Invalid => None,
+        })
+        .collect::<Option<Vec<_>>>()
+        .map(LookupBuf::from_segments)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn merge_unknown_is_unknown() {
+        let known = log_fields(vec![("message", TypeDef::new().bytes())]);
+
+        assert!(merge(vec![known.clone(), TypeDef::new().unknown()]).is_unknown());
+        assert!(merge(Vec::new()).is_unknown());
+        assert_eq!(merge(vec![known.clone()]), known);
+    }
+
+    #[test]
+    fn merge_combines_fields() {
+        let merged = merge(vec![
+            log_fields(vec![("message", TypeDef::new().bytes())]),
+            log_fields(vec![("host", TypeDef::new().bytes())]),
+        ]);
+
+        assert!(!merged.path_never_exists(&field_lookup("message").unwrap()));
+        assert!(!merged.path_never_exists(&field_lookup("host").unwrap()));
+        assert!(merged.path_never_exists(&field_lookup("timestamp").unwrap()));
+    }
+
+    #[test]
+    fn open_fields_may_contain_anything() {
+        let open = open_log_fields(vec![("message", TypeDef::new().bytes())]);
+
+        assert!(!open.is_unknown());
+        assert!(!open.path_never_exists(&field_lookup("message").unwrap()));
+        assert!(!open.path_never_exists(&field_lookup("anything").unwrap()));
+    }
+}
@@ -1,16 +1,28 @@
-use super::{Config, ConfigBuilder, TestDefinition, TestInput, TestInputValue};
+use super::{
+    CapturedEvent, Config, ConfigBuilder, TestDefinition, TestInput, TestInputValue, TestOutput,
+};
 use crate::config::{
-    self, ComponentKey, ConfigDiff, ConfigPath, GlobalOptions, TransformConfig, TransformContext,
+    self, ComponentKey, ConfigDiff, ConfigPath, EncodedTestEvent, GlobalOptions, TransformConfig,
+    TransformContext,
 };
 use crate::{
-    conditions::Condition,
-    event::{Event, Value},
+    codecs::DecodingConfig,
+    conditions::{AnyCondition, Condition},
+    event::{Event, Metric, MetricKind, Value},
+    migrate::vrl_literal,
     topology::builder::load_enrichment_tables,
-    transforms::Transform,
+    transforms::{TaskTransform, Transform},
 };
+use bytes::BytesMut;
+use chrono::SecondsFormat;
+use futures::{FutureExt, StreamExt};
 use indexmap::IndexMap;
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_util::codec::Decoder as _;
+use vector_core::time::{Clock, MockClock};
 
 pub async fn build_unit_tests_main(
     paths: &[ConfigPath],
@@ -23,10 +35,47 @@ pub async fn build_unit_tests_main(
     build_unit_tests(config).await
 }
 
-async fn build_unit_tests(mut builder: ConfigBuilder) -> Result<Vec<UnitTest>, Vec<String>> {
+pub async fn generate_unit_tests_main(
+    paths: &[ConfigPath],
+    pipeline_paths: &[PathBuf],
+    captured: Vec<CapturedEvent>,
+) -> Result<Vec<TestDefinition>, Vec<String>> {
+    config::init_log_schema(paths, pipeline_paths, false)?;
+
+    let (config, _) = super::loading::load_builder_and_pipelines_from_paths(paths, pipeline_paths)?;
+
+    generate_unit_tests(config, captured).await
+}
+
+pub(crate) async fn build_unit_tests(builder: ConfigBuilder) -> Result<Vec<UnitTest>, Vec<String>> {
     let mut tests = vec![];
     let mut errors = vec![];
 
+    let config = Arc::new(build_test_config(builder)?);
+
+    for test in &config.tests {
+        match build_unit_test(test, &config).await {
+            Ok(t) => tests.push(t),
+            Err(errs) => errors.push(test_build_error(&test.name, errs)),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(tests)
+    } else {
+        Err(errors)
+    }
+}
+
+fn test_build_error(name: &str, errs: Vec<String>) -> String {
+    let mut test_err = errs.join("\n");
+    // Indent all line breaks
+    test_err = test_err.replace("\n", "\n  ");
+    test_err.insert_str(0, &format!("Failed to build test '{}':\n  ", name));
+    test_err
+}
+
+fn build_test_config(mut builder: ConfigBuilder) -> Result<Config, Vec<String>> {
     let expansions = super::compiler::expand_macros(&mut builder)?;
 
     // Don't let this escape since it's not validated
@@ -45,44 +94,234 @@ async fn build_unit_tests(mut builder: ConfigBuilder) -> Result<Vec<UnitTest>, V
         expansions,
     };
 
-    for test in &config.tests {
-        match build_unit_test(test, &config).await {
-            Ok(t) => tests.push(t),
+    Ok(config)
+}
+
+/// Generates a test per captured component, feeding the captured events into
+/// the transforms consuming that component. The current outputs of every
+/// transform downstream of it are recorded as the expected outputs.
+pub(crate) async fn generate_unit_tests(
+    builder: ConfigBuilder,
+    captured: Vec<CapturedEvent>,
+) -> Result<Vec<TestDefinition>, Vec<String>> {
+    let config = Arc::new(build_test_config(builder)?);
+    let mut definitions = Vec::new();
+    let mut errors = Vec::new();
+
+    let mut captured_by_component: IndexMap<ComponentKey, Vec<Event>> = IndexMap::new();
+    for captured in captured {
+        captured_by_component
+            .entry(captured.component_id.clone())
+            .or_insert_with(Vec::new)
+            .push(captured.into_event());
+    }
+
+    for (component, events) in captured_by_component {
+        let targets = config
+            .transforms
+            .iter()
+            .filter(|(_, transform)| transform.inputs.contains(&component))
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+        if targets.is_empty() {
+            continue;
+        }
+
+        let mut definition = TestDefinition {
+            name: format!("replay of {}", component),
+            input: None,
+            inputs: targets
+                .iter()
+                .flat_map(|target| events.iter().map(move |event| test_input(target, event)))
+                .collect(),
+            outputs: downstream_transforms(&config, &component)
+                .into_iter()
+                .map(|extract_from| TestOutput {
+                    extract_from,
+                    conditions: None,
+                    encoded: None,
+                    metrics: None,
+                })
+                .collect(),
+            no_outputs_from: Vec::new(),
+        };
+
+        let mut test = match build_unit_test(&definition, &config).await {
+            Ok(test) => test,
             Err(errs) => {
-                let mut test_err = errs.join("\n");
-                // Indent all line breaks
-                test_err = test_err.replace("\n", "\n  ");
-                test_err.insert_str(0, &format!("Failed to build test '{}':\n  ", test.name));
-                errors.push(test_err);
+                errors.push(test_build_error(&definition.name, errs));
+                continue;
             }
-        }
+        };
+        let results = test.run_topology();
+
+        let (outputs, no_outputs_from) =
+            definition
+                .outputs
+                .into_iter()
+                .partition::<Vec<_>, _>(|output| {
+                    results
+                        .get(&output.extract_from)
+                        .map_or(false, |(_, outputs)| !outputs.is_empty())
+                });
+        definition.outputs = outputs
+            .into_iter()
+            .map(|mut output| {
+                let (_, events) = &results[&output.extract_from];
+                output.conditions = Some(events.iter().map(event_condition).collect());
+                output
+            })
+            .collect();
+        definition.no_outputs_from = no_outputs_from
+            .into_iter()
+            .map(|output| output.extract_from)
+            .collect();
+
+        definitions.push(definition);
     }
 
     if errors.is_empty() {
-        Ok(tests)
+        Ok(definitions)
     } else {
         Err(errors)
     }
 }
 
+/// All transforms fed, directly or indirectly, by the given component.
+fn downstream_transforms(config: &Config, component: &ComponentKey) -> Vec<ComponentKey> {
+    let mut downstream = IndexMap::new();
+    let mut queue = vec![component.clone()];
+
+    while let Some(current) = queue.pop() {
+        for (key, transform) in &config.transforms {
+            if transform.inputs.contains(&current) && downstream.insert(key.clone(), ()).is_none() {
+                queue.push(key.clone());
+            }
+        }
+    }
+
+    downstream.into_iter().map(|(key, _)| key).collect()
+}
+
+fn test_input(insert_at: &ComponentKey, event: &Event) -> TestInput {
+    let mut input = TestInput {
+        insert_at: insert_at.clone(),
+        type_str: String::new(),
+        value: None,
+        log_fields: None,
+        metric: None,
+        at_ms: None,
+    };
+
+    match event {
+        Event::Log(log) => {
+            input.type_str = "log".to_owned();
+            input.log_fields = Some(
+                log.all_fields()
+                    .filter_map(|(path, value)| {
+                        let value = match value {
+                            Value::Bytes(bytes) => {
+                                TestInputValue::String(String::from_utf8_lossy(bytes).into_owned())
+                            }
+                            Value::Integer(i) => TestInputValue::Integer(*i),
+                            Value::Float(f) => TestInputValue::Float(*f),
+                            Value::Boolean(b) => TestInputValue::Boolean(*b),
+                            Value::Timestamp(ts) => TestInputValue::String(
+                                ts.to_rfc3339_opts(SecondsFormat::AutoSi, true),
+                            ),
+                            // Test inputs can't express nulls, and empty
+                            // containers have no fields.
+                            Value::Null | Value::Map(_) | Value::Array(_) => return None,
+                        };
+                        Some((path, value))
+                    })
+                    .collect(),
+            );
+        }
+        Event::Metric(metric) => {
+            input.type_str = "metric".to_owned();
+            input.metric = Some(metric.clone());
+        }
+    }
+
+    input
+}
+
+/// A VRL condition matching the given event exactly. Metrics are matched on
+/// their series and kind.
+fn event_condition(event: &Event) -> AnyCondition {
+    let source = match event {
+        Event::Log(log) => format!(". == {}", vrl_literal(&Value::Map(log.as_map().clone()))),
+        Event::Metric(metric) => {
+            let mut checks = vec![format!(
+                ".name == {}",
+                vrl_literal(&Value::from(metric.name()))
+            )];
+            if let Some(namespace) = metric.namespace() {
+                checks.push(format!(
+                    ".namespace == {}",
+                    vrl_literal(&Value::from(namespace))
+                ));
+            }
+            checks.push(format!(
+                ".kind == {}",
+                vrl_literal(&Value::from(match metric.kind() {
+                    MetricKind::Incremental => "incremental",
+                    MetricKind::Absolute => "absolute",
+                }))
+            ));
+            if let Some(tags) = metric.tags() {
+                checks.push(format!(
+                    ".tags == {}",
+                    vrl_literal(&Value::Map(
+                        tags.iter()
+                            .map(|(key, value)| (key.clone(), Value::from(value.as_str())))
+                            .collect()
+                    ))
+                ));
+            }
+            checks.join(" && ")
+        }
+    };
+
+    AnyCondition::String(source)
+}
+
 pub struct UnitTest {
     pub name: String,
-    inputs: Vec<(Vec<ComponentKey>, Event)>,
+    inputs: Vec<(Vec<ComponentKey>, Duration, Event)>,
+    source_outputs: Vec<(ComponentKey, Vec<Event>)>,
     transforms: IndexMap<ComponentKey, UnitTestTransform>,
     checks: Vec<UnitTestCheck>,
     no_outputs_from: Vec<ComponentKey>,
     globals: GlobalOptions,
+    config: Arc<Config>,
 }
 
 struct UnitTestTransform {
     transform: Transform,
     config: Box<dyn TransformConfig>,
     next: Vec<ComponentKey>,
+    clock: MockClock,
 }
 
 struct UnitTestCheck {
     extract_from: ComponentKey,
     conditions: Vec<Box<dyn Condition>>,
+    /// The inputs of the sink the events are extracted from, if any.
+    sink_inputs: Option<Vec<ComponentKey>>,
+    encoded: Option<Vec<EncodedTestEvent>>,
+    metrics: Option<Vec<Metric>>,
+}
+
+impl UnitTestCheck {
+    const fn kind(&self) -> &'static str {
+        if self.sink_inputs.is_some() {
+            "sink"
+        } else {
+            "transform"
+        }
+    }
 }
 
 fn event_to_string(event: &Event) -> String {
@@ -111,9 +350,144 @@ fn events_to_string(name: &str, events: &[Event]) -> String {
     }
 }
 
+fn encoded_to_string(name: &str, encoded: &[EncodedTestEvent]) -> String {
+    if encoded.is_empty() {
+        return format!("  no {}", name);
+    }
+
+    format!(
+        "  {}:\n    {}",
+        name,
+        encoded
+            .iter()
+            .map(|event| {
+                let value = format!("{:?}", String::from_utf8_lossy(&event.bytes));
+                match &event.partition {
+                    Some(partition) => format!("{} (partition {:?})", value, partition),
+                    None => value,
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n    ")
+    )
+}
+
+fn metrics_to_string(name: &str, metrics: &[Metric]) -> String {
+    if metrics.is_empty() {
+        return format!("  no {}", name);
+    }
+
+    format!(
+        "  {}:\n    {}",
+        name,
+        metrics
+            .iter()
+            .map(|metric| serde_json::to_string(metric).unwrap_or_else(|_| "{}".into()))
+            .collect::<Vec<_>>()
+            .join("\n    ")
+    )
+}
+
+/// Whether a metric matches an expected one. Timestamps are only compared if
+/// the expected metric has one.
+fn metric_matches(expected: &Metric, actual: &Metric) -> bool {
+    expected.series() == actual.series()
+        && expected.kind() == actual.kind()
+        && expected.value() == actual.value()
+        && (expected.timestamp().is_none() || expected.timestamp() == actual.timestamp())
+}
+
+/// Checks that the outputs are exactly the expected metrics, in any order.
+fn metrics_match(expected: &[Metric], actual: &[Metric]) -> bool {
+    let mut unmatched = actual.iter().collect::<Vec<_>>();
+
+    expected.len() == actual.len()
+        && expected.iter().all(|expected| {
+            match unmatched
+                .iter()
+                .position(|actual| metric_matches(expected, actual))
+            {
+                Some(index) => {
+                    unmatched.swap_remove(index);
+                    true
+                }
+                None => false,
+            }
+        })
+}
+
+/// Checks the output of a sink against the expected output. Expected outputs
+/// without a partition match any partition.
+fn encoded_matches(expected: &[EncodedTestEvent], actual: &[EncodedTestEvent]) -> bool {
+    expected.len() == actual.len()
+        && expected.iter().zip(actual).all(|(expected, actual)| {
+            expected.bytes == actual.bytes
+                && (expected.partition.is_none() || expected.partition == actual.partition)
+        })
+}
+
+/// Runs a task transform on its mock clock, inserting each input at its time
+/// offset from the start of the test. The clock only advances as far as the
+/// next input or timer once the transform is idle, so time-driven transforms
+/// are deterministic and tests don't have to wait for them.
+fn run_task(
+    transform: Box<dyn TaskTransform>,
+    clock: MockClock,
+    mut inputs: Vec<(Duration, Event)>,
+) -> Vec<(Duration, Event)> {
+    inputs.sort_by_key(|(at, _)| *at);
+
+    // A runtime can't be started from within another one.
+    std::thread::spawn(move || {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .expect("Failed to build the unit test runtime.");
+
+        runtime.block_on(async move {
+            let start = clock.now();
+            let input_clock = Clock::Mock(clock.clone());
+            let in_stream = futures::stream::iter(inputs)
+                .then(move |(at, event)| input_clock.sleep_until(start + at).map(move |()| event));
+            let mut out_stream = transform.transform(Box::pin(in_stream));
+
+            let mut outputs = Vec::new();
+            let mut idle = false;
+            loop {
+                match out_stream.next().now_or_never() {
+                    Some(Some(event)) => {
+                        outputs.push((clock.now() - start, event));
+                        idle = false;
+                    }
+                    Some(None) => break,
+                    None if !idle => {
+                        // Other tasks get to run before the clock advances.
+                        tokio::task::yield_now().await;
+                        idle = true;
+                    }
+                    None => {
+                        idle = false;
+                        if !clock.advance_to_next_deadline() {
+                            // Nothing waits for the clock, so the transform
+                            // waits for something else.
+                            match out_stream.next().await {
+                                Some(event) => outputs.push((clock.now() - start, event)),
+                                None => break,
+                            }
+                        }
+                    }
+                }
+            }
+            outputs
+        })
+    })
+    .join()
+    .expect("Task transform panicked in a unit test.")
+}
+
 fn walk(
     node: &ComponentKey,
-    mut inputs: Vec<Event>,
+    inputs: Vec<(Duration, Event)>,
     transforms: &mut IndexMap<ComponentKey, UnitTestTransform>,
     aggregated_results: &mut HashMap<ComponentKey, (Vec<Event>, Vec<Event>)>,
     globals: &GlobalOptions,
@@ -125,27 +499,31 @@ fn walk(
     if let Some((key, mut target)) = transforms.remove_entry(node) {
         match target.transform {
             Transform::Function(ref mut t) => {
-                for input in inputs.clone() {
-                    t.transform(&mut results, input)
+                let mut outputs = Vec::new();
+                for (at, input) in inputs.clone() {
+                    t.transform(&mut outputs, input);
+                    results.extend(outputs.drain(..).map(|output| (at, output)));
                 }
                 targets = target.next.clone();
                 transforms.insert(key, target);
             }
             Transform::Task(t) => {
                 error!("Using a recently refactored `TaskTransform` in a unit test. You may experience limited support for multiple inputs.");
-                let in_stream = futures::stream::iter(inputs.clone());
-                let out_stream = t.transform(Box::pin(in_stream));
                 // TODO(new-transform-enum): Handle Many
-                let out_iter = futures::executor::block_on_stream(out_stream);
-                results.extend(out_iter);
+                results.extend(run_task(t, target.clock.clone(), inputs.clone()));
                 targets = target.next.clone();
                 // TODO: This is a hack.
                 // Our tasktransforms must consume the transform to attach it to an input stream, so we rebuild it between input streams.
+                let context = TransformContext {
+                    clock: Clock::Mock(target.clock.clone()),
+                    ..TransformContext::new_with_globals(globals.clone())
+                };
                 transforms.insert(key, UnitTestTransform {
-                    transform:  futures::executor::block_on(target.config.clone().build(&TransformContext::new_with_globals(globals.clone())))
+                    transform:  futures::executor::block_on(target.config.clone().build(&context))
                         .expect("Failed to build a known valid transform config. Things may have changed during runtime."),
                     config: target.config,
-                    next: target.next
+                    next: target.next,
+                    clock: target.clock,
                 });
             }
         }
@@ -161,6 +539,14 @@ fn walk(
         );
     }
 
+    let mut inputs = inputs
+        .into_iter()
+        .map(|(_, event)| event)
+        .collect::<Vec<_>>();
+    let mut results = results
+        .into_iter()
+        .map(|(_, event)| event)
+        .collect::<Vec<_>>();
     if let Some((mut e_inputs, mut e_results)) = aggregated_results.remove(node) {
         inputs.append(&mut e_inputs);
         results.append(&mut e_results);
@@ -169,19 +555,26 @@ fn walk(
 }
 
 impl UnitTest {
-    // Executes each test and provides a tuple of inspections and error lists.
-    pub fn run(&mut self) -> (Vec<String>, Vec<String>) {
-        let mut errors = Vec::new();
-        let mut inspections = Vec::new();
+    /// Runs the test inputs through the transforms, providing the inputs and
+    /// outputs of each component.
+    fn run_topology(&mut self) -> HashMap<ComponentKey, (Vec<Event>, Vec<Event>)> {
         let mut results = HashMap::new();
 
+        for (source, events) in &self.source_outputs {
+            let entry = results
+                .entry(source.clone())
+                .or_insert_with(|| (Vec::new(), Vec::new()));
+            entry.0.extend(events.iter().cloned());
+            entry.1.extend(events.iter().cloned());
+        }
+
         let mut inputs_by_target = HashMap::new();
-        for (targets, event) in &self.inputs {
+        for (targets, at, event) in &self.inputs {
             for target in targets {
                 let entry = inputs_by_target
                     .entry(target.clone())
                     .or_insert_with(Vec::new);
-                entry.push(event.clone());
+                entry.push((*at, event.clone()));
             }
         }
 
@@ -195,15 +588,85 @@ impl UnitTest {
             );
         }
 
+        results
+    }
+
+    // Executes each test and provides a tuple of inspections and error lists.
+    pub fn run(&mut self) -> (Vec<String>, Vec<String>) {
+        let mut errors = Vec::new();
+        let mut inspections = Vec::new();
+        let results = self.run_topology();
+
         for check in &self.checks {
-            if let Some((inputs, outputs)) = results.get(&check.extract_from) {
+            // Sinks receive the outputs of all their inputs as is.
+            let extracted = match &check.sink_inputs {
+                Some(sink_inputs) => {
+                    let events = sink_inputs
+                        .iter()
+                        .filter_map(|input| results.get(input))
+                        .flat_map(|(_, outputs)| outputs.iter().cloned())
+                        .collect::<Vec<_>>();
+                    Some((events.clone(), events))
+                }
+                None => results.get(&check.extract_from).cloned(),
+            };
+
+            if let Some((inputs, outputs)) = &extracted {
+                if let Some(expected) = &check.encoded {
+                    let sink = &self.config.sinks[&check.extract_from].inner;
+                    let mut encoded = Vec::new();
+                    for event in outputs.iter().cloned() {
+                        match sink.encode_for_test(event) {
+                            Ok(Some(event)) => encoded.push(event),
+                            Ok(None) => {}
+                            Err(error) => errors.push(format!(
+                                "check sink '{}' failed to encode event: {}",
+                                check.extract_from, error
+                            )),
+                        }
+                    }
+
+                    if !encoded_matches(expected, &encoded) {
+                        errors.push(format!(
+                            "check sink '{}' failed, encoded output doesn't match:\n{}\n{}",
+                            check.extract_from,
+                            encoded_to_string("expected", expected),
+                            encoded_to_string("actual", &encoded),
+                        ));
+                    }
+                }
+
+                if let Some(expected) = &check.metrics {
+                    let actual = outputs
+                        .iter()
+                        .filter_map(|event| match event {
+                            Event::Metric(metric) => Some(metric.clone()),
+                            Event::Log(_) => None,
+                        })
+                        .collect::<Vec<_>>();
+                    if actual.len() != outputs.len() || !metrics_match(expected, &actual) {
+                        errors.push(format!(
+                            "check {} '{}' failed, metrics don't match:\n{}\n{}\npayloads (events encoded as JSON):\n{}\n{}",
+                            check.kind(),
+                            check.extract_from,
+                            metrics_to_string("expected", expected),
+                            metrics_to_string("actual", &actual),
+                            events_to_string(" input", inputs),
+                            events_to_string("output", outputs),
+                        ));
+                    }
+                }
+
                 if check.conditions.is_empty() {
-                    inspections.push(format!(
-                        "check transform '{}' payloads (events encoded as JSON):\n{}\n{}",
-                        check.extract_from,
-                        events_to_string(" input", inputs),
-                        events_to_string("output", outputs),
-                    ));
+                    if check.encoded.is_none() && check.metrics.is_none() {
+                        inspections.push(format!(
+                            "check {} '{}' payloads (events encoded as JSON):\n{}\n{}",
+                            check.kind(),
+                            check.extract_from,
+                            events_to_string(" input", inputs),
+                            events_to_string("output", outputs),
+                        ));
+                    }
                     continue;
                 }
                 let failed_conditions = check
@@ -234,7 +697,8 @@ impl UnitTest {
                     .collect::<Vec<_>>();
                 if !failed_conditions.is_empty() {
                     errors.push(format!(
-                        "check transform '{}' failed conditions:\n  {}\npayloads (events encoded as JSON):\n{}\n{}",
+                        "check {} '{}' failed conditions:\n  {}\npayloads (events encoded as JSON):\n{}\n{}",
+                        check.kind(),
                         check.extract_from,
                         failed_conditions.join("\n  "),
                         events_to_string(" input", inputs),
@@ -243,13 +707,15 @@ impl UnitTest {
                 }
                 if outputs.is_empty() {
                     errors.push(format!(
-                        "check transform {:?} failed, no events received.",
+                        "check {} {:?} failed, no events received.",
+                        check.kind(),
                         check.extract_from,
                     ));
                 }
             } else {
                 errors.push(format!(
-                    "check transform '{}' failed: received zero resulting events.",
+                    "check {} '{}' failed: received zero resulting events.",
+                    check.kind(),
                     check.extract_from,
                 ));
             }
@@ -328,12 +794,34 @@ fn reduce_transforms(
     });
 }
 
-fn build_input(config: &Config, input: &TestInput) -> Result<(Vec<ComponentKey>, Event), String> {
+/// Runs raw bytes through the decoder of a source, as if they were received
+/// by it.
+fn decode_input(decoding: &DecodingConfig, value: &str) -> Result<Vec<Event>, String> {
+    let mut decoder = decoding
+        .build()
+        .map_err(|error| format!("failed to build decoder: {}", error))?;
+    let mut buffer = BytesMut::from(value);
+    let mut events = Vec::new();
+
+    while let Some((decoded, _)) = decoder
+        .decode_eof(&mut buffer)
+        .map_err(|error| format!("failed to decode input: {}", error))?
+    {
+        events.extend(decoded);
+    }
+
+    Ok(events)
+}
+
+fn build_input(
+    config: &Config,
+    input: &TestInput,
+) -> Result<(Vec<ComponentKey>, Vec<Event>), String> {
     let target = config.get_inputs(&input.insert_at);
 
     match input.type_str.as_ref() {
         "raw" => match input.value.as_ref() {
-            Some(v) => Ok((target, Event::from(v.clone()))),
+            Some(v) => Ok((target, vec![Event::from(v.clone())])),
             None => Err("input type 'raw' requires the field 'value'".to_string()),
         },
         "log" => {
@@ -348,49 +836,88 @@ fn build_input(config: &Config, input: &TestInput) -> Result<(Vec<ComponentKey>,
                     };
                     event.as_mut_log().insert(path.to_owned(), value);
                 }
-                Ok((target, event))
+                Ok((target, vec![event]))
             } else {
                 Err("input type 'log' requires the field 'log_fields'".to_string())
             }
         }
         "metric" => {
             if let Some(metric) = &input.metric {
-                Ok((target, Event::Metric(metric.clone())))
+                Ok((target, vec![Event::Metric(metric.clone())]))
             } else {
                 Err("input type 'metric' requires the field 'metric'".to_string())
             }
         }
+        "bytes" => {
+            let value = input
+                .value
+                .as_ref()
+                .ok_or_else(|| "input type 'bytes' requires the field 'value'".to_string())?;
+            let decoding = config
+                .sources
+                .get(&input.insert_at)
+                .ok_or_else(|| {
+                    format!(
+                        "input type 'bytes' must be inserted at a source, but '{}' is not a source",
+                        input.insert_at
+                    )
+                })?
+                .inner
+                .decoding()
+                .ok_or_else(|| {
+                    format!(
+                        "source '{}' doesn't support decoding bytes in unit tests",
+                        input.insert_at
+                    )
+                })?;
+
+            let events = decode_input(decoding, value)?;
+            let targets = config
+                .transforms
+                .iter()
+                .filter(|(_, transform)| transform.inputs.contains(&input.insert_at))
+                .map(|(key, _)| key.clone())
+                .collect();
+
+            Ok((targets, events))
+        }
         _ => Err(format!(
-            "unrecognized input type '{}', expected one of: 'raw', 'log' or 'metric'",
+            "unrecognized input type '{}', expected one of: 'raw', 'log', 'metric' or 'bytes'",
             input.type_str
         )),
     }
 }
 
-fn build_inputs(
-    config: &Config,
-    definition: &TestDefinition,
-) -> Result<Vec<(Vec<ComponentKey>, Event)>, Vec<String>> {
+type TestInputs = (
+    Vec<(Vec<ComponentKey>, Duration, Event)>,
+    Vec<(ComponentKey, Vec<Event>)>,
+);
+
+/// Builds the events inserted into the topology, along with the events decoded
+/// by sources from raw bytes.
+fn build_inputs(config: &Config, definition: &TestDefinition) -> Result<TestInputs, Vec<String>> {
     let mut inputs = Vec::new();
+    let mut source_outputs = Vec::new();
     let mut errors = vec![];
 
-    if let Some(input_def) = &definition.input {
-        match build_input(config, input_def) {
-            Ok(input_event) => inputs.push(input_event),
-            Err(err) => errors.push(err),
-        }
-    } else if definition.inputs.is_empty() {
+    if definition.input.is_none() && definition.inputs.is_empty() {
         errors.push("must specify at least one input.".to_owned());
     }
-    for input_def in &definition.inputs {
+    for input_def in definition.input.iter().chain(&definition.inputs) {
         match build_input(config, input_def) {
-            Ok(input_event) => inputs.push(input_event),
+            Ok((targets, events)) => {
+                if input_def.type_str == "bytes" {
+                    source_outputs.push((input_def.insert_at.clone(), events.clone()));
+                }
+                let at = Duration::from_millis(input_def.at_ms.unwrap_or_default());
+                inputs.extend(events.into_iter().map(|event| (targets.clone(), at, event)));
+            }
             Err(err) => errors.push(err),
         }
     }
 
     if errors.is_empty() {
-        Ok(inputs)
+        Ok((inputs, source_outputs))
     } else {
         Err(errors)
     }
@@ -398,15 +925,15 @@ fn build_inputs(
 
 async fn build_unit_test(
     definition: &TestDefinition,
-    config: &Config,
+    config: &Arc<Config>,
 ) -> Result<UnitTest, Vec<String>> {
     let mut errors = vec![];
 
-    let inputs = match build_inputs(config, definition) {
+    let (inputs, source_outputs) = match build_inputs(config, definition) {
         Ok(inputs) => inputs,
         Err(mut errs) => {
             errors.append(&mut errs);
-            Vec::new()
+            (Vec::new(), Vec::new())
         }
     };
 
@@ -426,7 +953,7 @@ async fn build_unit_test(
         })
     });
 
-    for (i, (input_target, _)) in inputs.iter().enumerate() {
+    for (i, (input_target, _, _)) in inputs.iter().enumerate() {
         for target in input_target {
             if !transform_outputs.contains_key(target) {
                 errors.push(format!(
@@ -440,10 +967,29 @@ async fn build_unit_test(
         return Err(errors);
     }
 
+    // Outputs extracted from a sink are checked against the events its inputs
+    // produce.
+    let sink_inputs = |key: &ComponentKey| {
+        config.sinks.get(key).map(|sink| {
+            sink.inputs
+                .iter()
+                .flat_map(|input| config.get_inputs(input))
+                .collect::<Vec<_>>()
+        })
+    };
+
     let mut leaves: IndexMap<ComponentKey, ()> = IndexMap::new();
-    definition.outputs.iter().for_each(|o| {
-        leaves.insert(o.extract_from.clone(), ());
-    });
+    definition
+        .outputs
+        .iter()
+        .for_each(|o| match sink_inputs(&o.extract_from) {
+            Some(inputs) => inputs.into_iter().for_each(|input| {
+                leaves.insert(input, ());
+            }),
+            None => {
+                leaves.insert(o.extract_from.clone(), ());
+            }
+        });
     definition.no_outputs_from.iter().for_each(|o| {
         leaves.insert(o.clone(), ());
     });
@@ -453,7 +999,7 @@ async fn build_unit_test(
     reduce_transforms(
         inputs
             .iter()
-            .map(|(names, _)| names)
+            .map(|(names, _, _)| names)
             .flatten()
             .cloned()
             .collect::<Vec<_>>(),
@@ -466,15 +1012,16 @@ async fn build_unit_test(
 
     errors.extend(tables_errors);
 
-    let context = TransformContext {
-        globals: config.global.clone(),
-        enrichment_tables: enrichment_tables.clone(),
-    };
-
     // Build reduced transforms.
     let mut transforms: IndexMap<ComponentKey, UnitTestTransform> = IndexMap::new();
     for (id, transform_config) in &config.transforms {
         if let Some(outputs) = transform_outputs.remove(id) {
+            let clock = MockClock::new();
+            let context = TransformContext {
+                globals: config.global.clone(),
+                enrichment_tables: enrichment_tables.clone(),
+                clock: Clock::Mock(clock.clone()),
+            };
             match transform_config.inner.build(&context).await {
                 Ok(transform) => {
                     transforms.insert(
@@ -483,6 +1030,7 @@ async fn build_unit_test(
                             transform,
                             config: transform_config.inner.clone(),
                             next: outputs.into_iter().map(|(k, _)| k).collect(),
+                            clock,
                         },
                     );
                 }
@@ -501,9 +1049,17 @@ async fn build_unit_test(
         return Err(errors);
     }
 
+    let is_reachable = |key: &ComponentKey| {
+        transforms.contains_key(key) || source_outputs.iter().any(|(source, _)| source == key)
+    };
+
     definition.outputs.iter().for_each(|o| {
-        if !transforms.contains_key(&o.extract_from) {
-            let targets = inputs.iter().map(|(i, _)| i).flatten().collect::<Vec<_>>();
+        let reachable = match sink_inputs(&o.extract_from) {
+            Some(inputs) => inputs.iter().any(is_reachable),
+            None => transforms.contains_key(&o.extract_from),
+        };
+        if !reachable {
+            let targets = inputs.iter().map(|(i, _, _)| i).flatten().collect::<Vec<_>>();
             if targets.len() == 1 {
                 errors.push(format!(
                     "unable to complete topology between target transform '{}' and output target '{}'",
@@ -540,9 +1096,33 @@ async fn build_unit_test(
                 }
             }
 
+            let sink_inputs = sink_inputs(&o.extract_from);
+            if o.encoded.is_some() && sink_inputs.is_none() {
+                errors.push(format!(
+                    "output target '{}' must be a sink to check its encoded output",
+                    o.extract_from
+                ));
+            }
+
+            let encoded = o.encoded.as_ref().map(|encoded| {
+                encoded
+                    .iter()
+                    .map(|output| {
+                        let event = EncodedTestEvent::new(output.value.clone());
+                        match &output.partition {
+                            Some(partition) => event.with_partition(partition.clone()),
+                            None => event,
+                        }
+                    })
+                    .collect()
+            });
+
             UnitTestCheck {
                 extract_from: o.extract_from.clone(),
                 conditions,
+                sink_inputs,
+                encoded,
+                metrics: o.metrics.clone(),
             }
         })
         .collect();
@@ -559,10 +1139,12 @@ async fn build_unit_test(
         Ok(UnitTest {
             name: definition.name.clone(),
             inputs,
+            source_outputs,
             transforms,
             checks,
             no_outputs_from: definition.no_outputs_from.clone(),
             globals: config.global.clone(),
+            config: Arc::clone(config),
         })
     }
 }
@@ -829,7 +1411,7 @@ mod tests {
             errs,
             vec![indoc! {r#"
                 Failed to build test 'broken test':
-                  unrecognized input type 'nah', expected one of: 'raw', 'log' or 'metric'"#}
+                  unrecognized input type 'nah', expected one of: 'raw', 'log', 'metric' or 'bytes'"#}
             .to_owned(),]
         );
     }
@@ -1468,6 +2050,61 @@ mod tests {
                 */
     }
 
+    #[tokio::test]
+    async fn generate_tests_from_capture() {
+        let config = indoc! {r#"
+            [transforms.foo]
+              inputs = ["in"]
+              type = "add_fields"
+              [transforms.foo.fields]
+                new_field = "string value"
+
+            [transforms.bar]
+              inputs = ["foo"]
+              type = "add_fields"
+              [transforms.bar.fields]
+                other_field = 10
+        "#};
+        let captured = vec![
+            serde_json::from_str(
+                r#"{"component_id":"in","log":{"message":"first","nested":{"count":1}}}"#,
+            )
+            .unwrap(),
+            serde_json::from_str(r#"{"component_id":"in","log":{"message":"second"}}"#).unwrap(),
+            serde_json::from_str(r#"{"component_id":"unrelated","log":{"message":"ignored"}}"#)
+                .unwrap(),
+        ];
+
+        let tests = generate_unit_tests(toml::from_str(config).unwrap(), captured)
+            .await
+            .unwrap();
+        assert_eq!(tests.len(), 1);
+        assert_eq!(tests[0].name, "replay of in");
+        assert_eq!(tests[0].inputs.len(), 2);
+        assert_eq!(tests[0].inputs[0].insert_at, ComponentKey::from("foo"));
+        assert_eq!(
+            tests[0]
+                .outputs
+                .iter()
+                .map(|output| output.extract_from.to_string())
+                .collect::<Vec<_>>(),
+            vec!["foo", "bar"]
+        );
+        match &tests[0].outputs[1].conditions.as_ref().unwrap()[0] {
+            AnyCondition::String(source) => assert_eq!(
+                source,
+                r#". == {"message": "first", "nested": {"count": 1}, "new_field": "string value", "other_field": 10}"#
+            ),
+            condition => panic!("unexpected condition {:?}", condition),
+        }
+
+        // The generated tests pass against the config they were generated from.
+        let mut builder: ConfigBuilder = toml::from_str(config).unwrap();
+        builder.tests = toml::Value::try_from(tests).unwrap().try_into().unwrap();
+        let mut tests = build_unit_tests(builder).await.unwrap();
+        assert_eq!(tests[0].run(), (Vec::<String>::new(), Vec::<String>::new()));
+    }
+
     #[tokio::test]
     async fn type_inconsistency_while_expanding_transform() {
         let config: ConfigBuilder = toml::from_str(indoc! {r#"
@@ -1512,3 +2149,262 @@ mod tests {
         );
     }
 }
+
+#[cfg(all(
+    test,
+    feature = "sources-stdin",
+    feature = "transforms-remap",
+    feature = "sinks-console",
+    feature = "sinks-file"
+))]
+mod codec_tests {
+    use super::*;
+    use crate::config::ConfigBuilder;
+    use indoc::indoc;
+
+    #[tokio::test]
+    async fn bytes_input_encoded_output() {
+        let config: ConfigBuilder = toml::from_str(indoc! {r#"
+            [sources.in]
+              type = "stdin"
+
+            [transforms.foo]
+              inputs = ["in"]
+              type = "remap"
+              source = '.message = upcase!(.message)'
+
+            [sinks.out]
+              inputs = ["foo"]
+              type = "console"
+              encoding.codec = "json"
+              encoding.only_fields = ["message"]
+
+            [[tests]]
+              name = "encoded"
+
+              [[tests.inputs]]
+                insert_at = "in"
+                type = "bytes"
+                value = "first\nsecond\n"
+
+              [[tests.outputs]]
+                extract_from = "out"
+                [[tests.outputs.encoded]]
+                  value = "{\"message\":\"FIRST\"}\n"
+                [[tests.outputs.encoded]]
+                  value = "{\"message\":\"SECOND\"}\n"
+        "#})
+        .unwrap();
+
+        let mut tests = build_unit_tests(config).await.unwrap();
+        assert_eq!(tests[0].run(), (Vec::<String>::new(), Vec::<String>::new()));
+    }
+
+    #[tokio::test]
+    async fn bytes_input_directly_into_sink() {
+        let config: ConfigBuilder = toml::from_str(indoc! {r#"
+            [sources.in]
+              type = "stdin"
+
+            [sinks.out]
+              inputs = ["in"]
+              type = "file"
+              path = "/tmp/{{ message }}.log"
+              encoding.codec = "text"
+
+            [[tests]]
+              name = "partitioned"
+
+              [[tests.inputs]]
+                insert_at = "in"
+                type = "bytes"
+                value = "one\ntwo"
+
+              [[tests.outputs]]
+                extract_from = "out"
+                [[tests.outputs.encoded]]
+                  value = "one\n"
+                  partition = "/tmp/one.log"
+                [[tests.outputs.encoded]]
+                  value = "two\n"
+                  partition = "/tmp/three.log"
+        "#})
+        .unwrap();
+
+        let mut tests = build_unit_tests(config).await.unwrap();
+        assert_eq!(
+            tests[0].run().1,
+            vec![indoc! {r#"
+                check sink 'out' failed, encoded output doesn't match:
+                  expected:
+                    "one\n" (partition "/tmp/one.log")
+                    "two\n" (partition "/tmp/three.log")
+                  actual:
+                    "one\n" (partition "/tmp/one.log")
+                    "two\n" (partition "/tmp/two.log")"#}
+            .to_owned()]
+        );
+    }
+
+    #[tokio::test]
+    async fn bytes_input_requires_decoding_source() {
+        let config: ConfigBuilder = toml::from_str(indoc! {r#"
+            [sources.in]
+              type = "stdin"
+
+            [transforms.foo]
+              inputs = ["in"]
+              type = "remap"
+              source = '.foo = "bar"'
+
+            [sinks.out]
+              inputs = ["foo"]
+              type = "console"
+              encoding.codec = "json"
+
+            [[tests]]
+              name = "broken"
+
+              [[tests.inputs]]
+                insert_at = "foo"
+                type = "bytes"
+                value = "first"
+
+              [[tests.outputs]]
+                extract_from = "foo"
+                [[tests.outputs.encoded]]
+                  value = "first"
+        "#})
+        .unwrap();
+
+        let errs = build_unit_tests(config).await.err().unwrap();
+        assert_eq!(
+            errs,
+            vec![indoc! {r#"
+                Failed to build test 'broken':
+                  input type 'bytes' must be inserted at a source, but 'foo' is not a source"#}
+            .to_owned()]
+        );
+    }
+}
+
+#[cfg(all(test, feature = "transforms-aggregate"))]
+mod metric_tests {
+    use super::*;
+    use crate::config::ConfigBuilder;
+    use indoc::indoc;
+
+    const AGGREGATE: &str = indoc! {r#"
+        [transforms.agg]
+          inputs = ["in"]
+          type = "aggregate"
+          interval_ms = 1000
+
+        [[tests]]
+          name = "aggregate"
+
+          [[tests.inputs]]
+            insert_at = "agg"
+            type = "metric"
+            at_ms = 100
+            [tests.inputs.metric]
+              name = "requests"
+              kind = "incremental"
+              counter.value = 1.0
+
+          [[tests.inputs]]
+            insert_at = "agg"
+            type = "metric"
+            at_ms = 200
+            [tests.inputs.metric]
+              name = "requests"
+              kind = "incremental"
+              counter.value = 2.0
+
+          [[tests.inputs]]
+            insert_at = "agg"
+            type = "metric"
+            at_ms = 200
+            [tests.inputs.metric]
+              name = "users"
+              kind = "incremental"
+              set.values = ["a"]
+
+          [[tests.inputs]]
+            insert_at = "agg"
+            type = "metric"
+            at_ms = 300
+            [tests.inputs.metric]
+              name = "users"
+              kind = "incremental"
+              set.values = ["b"]
+
+          [[tests.inputs]]
+            insert_at = "agg"
+            type = "metric"
+            at_ms = 300
+            [tests.inputs.metric]
+              name = "latency"
+              kind = "incremental"
+              distribution.samples = [{ value = 1.0, rate = 1 }]
+              distribution.statistic = "histogram"
+
+          [[tests.inputs]]
+            insert_at = "agg"
+            type = "metric"
+            at_ms = 1500
+            [tests.inputs.metric]
+              name = "requests"
+              kind = "incremental"
+              counter.value = 4.0
+
+          [[tests.outputs]]
+            extract_from = "agg"
+
+            [[tests.outputs.metrics]]
+              name = "requests"
+              kind = "incremental"
+              counter.value = 4.0
+
+            [[tests.outputs.metrics]]
+              name = "requests"
+              kind = "incremental"
+              counter.value = 3.0
+
+            [[tests.outputs.metrics]]
+              name = "users"
+              kind = "incremental"
+              set.values = ["a", "b"]
+
+            [[tests.outputs.metrics]]
+              name = "latency"
+              kind = "incremental"
+              distribution.samples = [{ value = 1.0, rate = 1 }]
+              distribution.statistic = "histogram"
+    "#};
+
+    #[tokio::test]
+    async fn metrics_flushed_on_clock() {
+        let config: ConfigBuilder = toml::from_str(AGGREGATE).unwrap();
+
+        let mut tests = build_unit_tests(config).await.unwrap();
+        assert_eq!(tests[0].run(), (Vec::<String>::new(), Vec::<String>::new()));
+    }
+
+    #[tokio::test]
+    async fn metrics_mismatch() {
+        // Without the clock advancing past the interval, all requests are
+        // flushed together.
+        let config: ConfigBuilder =
+            toml::from_str(&AGGREGATE.replace("at_ms = 1500", "at_ms = 400")).unwrap();
+
+        let mut tests = build_unit_tests(config).await.unwrap();
+        let errors = tests[0].run().1;
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with(indoc! {r#"
+            check transform 'agg' failed, metrics don't match:
+              expected:
+                {"name":"requests","kind":"incremental","counter":{"value":4.0}}
+                {"name":"requests","kind":"incremental","counter":{"value":3.0}}"#}));
+    }
+}
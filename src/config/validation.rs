@@ -1,5 +1,9 @@
-use super::{builder::ConfigBuilder, pipeline::Pipelines, ComponentKey, DataType, Resource};
+use super::{
+    builder::ConfigBuilder, pipeline::Pipelines, schema, ComponentKey, DataType, Resource,
+    TransformSchema,
+};
 use std::collections::HashMap;
+use vrl::prelude::TypeDef;
 
 /// Check that provide + topology config aren't present in the same builder, which is an error.
 pub fn check_provider(config: &ConfigBuilder) -> Result<(), Vec<String>> {
@@ -196,6 +200,175 @@ pub fn warnings(config: &ConfigBuilder) -> Vec<String> {
     warnings
 }
 
+/// Propagates the data types and log schemas produced by sources through the
+/// topology, warning about components that can never receive events, fields
+/// that can never exist, and transforms that don't hold up against the schema
+/// of their inputs.
+///
+/// Expects a topology that passed `check_shape` and `typecheck`.
+pub fn check_schemas(config: &ConfigBuilder) -> Vec<String> {
+    let mut checker = SchemaChecker {
+        config,
+        flows: HashMap::new(),
+        warnings: Vec::new(),
+    };
+
+    for key in config.transforms.keys() {
+        checker.flow(key);
+    }
+
+    for (key, sink) in config.sinks.iter() {
+        let input = checker.input_flow(&sink.inputs);
+        let accepted = input.accept(sink.inner.input_type());
+
+        if input.is_reachable() && !accepted.is_reachable() {
+            checker.warnings.push(format!(
+                "Sink \"{}\" is unreachable, its inputs only produce {} events.",
+                key,
+                input.data_types()
+            ));
+        }
+
+        if accepted.logs {
+            for components in sink.inner.referenced_fields() {
+                if let Some(path) = schema::components_lookup(&components) {
+                    if accepted.schema.path_never_exists(&path) {
+                        checker.warnings.push(format!(
+                            "Sink \"{}\" references field \"{}\", which can never exist in its input events.",
+                            key, path
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    checker.warnings
+}
+
+/// The events that can flow out of a component.
+#[derive(Debug, Clone)]
+struct Flow {
+    logs: bool,
+    metrics: bool,
+    /// The schema of the log events, if any.
+    schema: TypeDef,
+}
+
+impl Flow {
+    fn new(data_type: DataType, schema: TypeDef) -> Self {
+        Self {
+            logs: matches!(data_type, DataType::Any | DataType::Log),
+            metrics: matches!(data_type, DataType::Any | DataType::Metric),
+            schema,
+        }
+    }
+
+    const fn is_reachable(&self) -> bool {
+        self.logs || self.metrics
+    }
+
+    /// Restricts the flow to the events accepted by the given data type.
+    fn accept(&self, data_type: DataType) -> Self {
+        Self {
+            logs: self.logs && matches!(data_type, DataType::Any | DataType::Log),
+            metrics: self.metrics && matches!(data_type, DataType::Any | DataType::Metric),
+            schema: self.schema.clone(),
+        }
+    }
+
+    const fn data_types(&self) -> &'static str {
+        match (self.logs, self.metrics) {
+            (true, true) => "log and metric",
+            (true, false) => "log",
+            (false, true) => "metric",
+            (false, false) => "no",
+        }
+    }
+}
+
+struct SchemaChecker<'a> {
+    config: &'a ConfigBuilder,
+    flows: HashMap<ComponentKey, Flow>,
+    warnings: Vec<String>,
+}
+
+impl<'a> SchemaChecker<'a> {
+    fn input_flow(&mut self, inputs: &[ComponentKey]) -> Flow {
+        let flows = inputs
+            .iter()
+            .map(|input| self.flow(input))
+            .collect::<Vec<_>>();
+
+        Flow {
+            logs: flows.iter().any(|flow| flow.logs),
+            metrics: flows.iter().any(|flow| flow.metrics),
+            schema: schema::merge(
+                flows
+                    .into_iter()
+                    .filter(|flow| flow.logs)
+                    .map(|flow| flow.schema),
+            ),
+        }
+    }
+
+    fn flow(&mut self, key: &ComponentKey) -> Flow {
+        if let Some(flow) = self.flows.get(key) {
+            return flow.clone();
+        }
+
+        let flow = if let Some(source) = self.config.sources.get(key) {
+            Flow::new(source.inner.output_type(), source.inner.output_schema())
+        } else if let Some(transform) = self.config.transforms.get(key) {
+            // Guard against cycles, which are reported by `typecheck`.
+            self.flows.insert(
+                key.clone(),
+                Flow::new(DataType::Any, TypeDef::new().unknown()),
+            );
+
+            let input = self.input_flow(&transform.inputs);
+            let accepted = input.accept(transform.inner.input_type());
+
+            if !accepted.is_reachable() {
+                if input.is_reachable() {
+                    self.warnings.push(format!(
+                        "Transform \"{}\" is unreachable, its inputs only produce {} events.",
+                        key,
+                        input.data_types()
+                    ));
+                }
+
+                accepted
+            } else {
+                let checked = if accepted.logs {
+                    transform.inner.schema(&accepted.schema)
+                } else {
+                    TransformSchema::unknown()
+                };
+
+                for warning in checked.warnings {
+                    self.warnings
+                        .push(format!("Transform \"{}\" {}", key, warning));
+                }
+
+                if checked.preserves_data_type {
+                    Flow {
+                        schema: checked.output,
+                        ..accepted
+                    }
+                } else {
+                    Flow::new(transform.inner.output_type(), checked.output)
+                }
+            }
+        } else {
+            Flow::new(DataType::Any, TypeDef::new().unknown())
+        };
+
+        self.flows.insert(key.clone(), flow.clone());
+        flow
+    }
+}
+
 pub fn typecheck(config: &ConfigBuilder) -> Result<(), Vec<String>> {
     Graph::from(config).typecheck()
 }
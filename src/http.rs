@@ -5,7 +5,12 @@ use crate::{
 };
 use futures::future::BoxFuture;
 use headers::{Authorization, HeaderMapExt};
-use http::{header::HeaderValue, request::Builder, uri::InvalidUri, HeaderMap, Request};
+use http::{
+    header::{self, HeaderValue},
+    request::Builder,
+    uri::InvalidUri,
+    HeaderMap, Request, StatusCode, Uri,
+};
 use hyper::{
     body::{Body, HttpBody},
     client::{Client, HttpConnector},
@@ -16,8 +21,14 @@ use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
 use std::{
     fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
     task::{Context, Poll},
+    time::{Duration, Instant},
 };
+use tokio::sync::Mutex;
 use tower::Service;
 use tracing_futures::Instrument;
 
@@ -229,9 +240,160 @@ impl Auth {
     }
 }
 
+/// The OAuth 2.0 client credentials used to fetch the bearer token of the requests.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct OAuth2Config {
+    pub token_endpoint: String,
+    pub client_id: String,
+    pub client_secret: String,
+    #[serde(default)]
+    pub scopes: Vec<String>,
+}
+
+#[derive(Debug, Snafu)]
+pub enum OAuth2Error {
+    #[snafu(display("Invalid token endpoint {:?}: {}", endpoint, source))]
+    InvalidTokenEndpoint {
+        endpoint: String,
+        source: InvalidUri,
+    },
+    #[snafu(display("Token request failed with status {}: {}", status, body))]
+    TokenRequestFailed { status: StatusCode, body: String },
+    #[snafu(display("Invalid token response: {}", source))]
+    InvalidTokenResponse { source: serde_json::Error },
+}
+
+/// Tokens are refreshed this long before they expire, so that they don't
+/// expire while a request is in flight.
+const TOKEN_EXPIRY_MARGIN: Duration = Duration::from_secs(30);
+
+/// Fetches a token with the OAuth 2.0 client credentials grant, and keeps it
+/// until it is about to expire or is rejected.
+#[derive(Clone, Debug)]
+pub struct OAuth2Token {
+    config: Arc<OAuth2Config>,
+    endpoint: Uri,
+    client: HttpClient,
+    token: Arc<Mutex<Option<CachedToken>>>,
+    rejected: Arc<AtomicBool>,
+}
+
+#[derive(Debug)]
+struct CachedToken {
+    access_token: String,
+    expires_at: Option<Instant>,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: Option<u64>,
+}
+
+impl OAuth2Token {
+    pub fn new(config: &OAuth2Config, client: HttpClient) -> Result<Self, OAuth2Error> {
+        let endpoint = config
+            .token_endpoint
+            .parse()
+            .with_context(|| InvalidTokenEndpoint {
+                endpoint: config.token_endpoint.clone(),
+            })?;
+        Ok(Self {
+            config: Arc::new(config.clone()),
+            endpoint,
+            client,
+            token: Arc::new(Mutex::new(None)),
+            rejected: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    /// Sets the bearer token of `request`, fetching a new token first if
+    /// there is none or if it is about to expire.
+    pub async fn apply<B>(&self, request: &mut Request<B>) -> crate::Result<()> {
+        let token = self.get().await?;
+        Auth::Bearer { token }.apply(request);
+        Ok(())
+    }
+
+    /// Drops the cached token if `status` shows that it was rejected, for
+    /// instance because it was revoked, so that the next request fetches a
+    /// new one.
+    pub fn check_response_status(&self, status: StatusCode) {
+        if matches!(status, StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN) {
+            debug!(message = "OAuth2 token was rejected, invalidating it.", %status);
+            self.rejected.store(true, Ordering::Release);
+        }
+    }
+
+    async fn get(&self) -> crate::Result<String> {
+        let mut cached = self.token.lock().await;
+        let rejected = self.rejected.swap(false, Ordering::AcqRel);
+        if let Some(token) = &*cached {
+            let expired = token
+                .expires_at
+                .map_or(false, |at| Instant::now() + TOKEN_EXPIRY_MARGIN >= at);
+            if !expired && !rejected {
+                return Ok(token.access_token.clone());
+            }
+        }
+
+        let token = self.fetch().await?;
+        let access_token = token.access_token.clone();
+        *cached = Some(token);
+        Ok(access_token)
+    }
+
+    async fn fetch(&self) -> crate::Result<CachedToken> {
+        let mut form = url::form_urlencoded::Serializer::new(String::new());
+        form.append_pair("grant_type", "client_credentials");
+        if !self.config.scopes.is_empty() {
+            form.append_pair("scope", &self.config.scopes.join(" "));
+        }
+
+        let mut request = Request::post(&self.endpoint)
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .header(header::ACCEPT, "application/json")
+            .body(Body::from(form.finish()))?;
+        Auth::Basic {
+            user: self.config.client_id.clone(),
+            password: self.config.client_secret.clone(),
+        }
+        .apply(&mut request);
+
+        let requested_at = Instant::now();
+        let response = self.client.send(request).await?;
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body()).await?;
+        if !status.is_success() {
+            return Err(OAuth2Error::TokenRequestFailed {
+                status,
+                body: String::from_utf8_lossy(&body).into_owned(),
+            }
+            .into());
+        }
+
+        let response: TokenResponse =
+            serde_json::from_slice(&body).context(InvalidTokenResponse)?;
+        debug!(message = "Fetched OAuth2 token.", expires_in = ?response.expires_in);
+        Ok(CachedToken {
+            access_token: response.access_token,
+            expires_at: response
+                .expires_in
+                .map(|secs| requested_at + Duration::from_secs(secs)),
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_util::next_addr;
+    use hyper::{
+        service::{make_service_fn, service_fn},
+        Response, Server,
+    };
+    use std::sync::atomic::AtomicUsize;
 
     #[test]
     fn test_default_request_headers_defaults() {
@@ -262,4 +424,85 @@ mod tests {
             Some(&HeaderValue::from_static("foo"))
         );
     }
+
+    /// Serves tokens named after the number of previous fetches, which expire
+    /// after `expires_in(fetch)` seconds.
+    async fn token_server(expires_in: fn(usize) -> u64) -> (OAuth2Config, Arc<AtomicUsize>) {
+        let addr = next_addr();
+        let fetches = Arc::new(AtomicUsize::new(0));
+
+        let server_fetches = Arc::clone(&fetches);
+        let new_service = make_service_fn(move |_| {
+            let fetches = Arc::clone(&server_fetches);
+            let svc = service_fn(move |req: Request<Body>| {
+                let fetches = Arc::clone(&fetches);
+                async move {
+                    assert_eq!(
+                        req.headers().typed_get(),
+                        Some(Authorization::basic("vector", "secret"))
+                    );
+                    let body = hyper::body::to_bytes(req.into_body()).await?;
+                    assert_eq!(&body[..], b"grant_type=client_credentials&scope=a+b");
+
+                    let fetch = fetches.fetch_add(1, Ordering::SeqCst);
+                    let token = serde_json::json!({
+                        "access_token": format!("token{}", fetch),
+                        "token_type": "Bearer",
+                        "expires_in": expires_in(fetch),
+                    });
+                    Ok::<_, crate::Error>(Response::new(Body::from(token.to_string())))
+                }
+            });
+            async move { Ok::<_, std::convert::Infallible>(svc) }
+        });
+        tokio::spawn(Server::bind(&addr).serve(new_service));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let config = OAuth2Config {
+            token_endpoint: format!("http://{}/token", addr),
+            client_id: "vector".into(),
+            client_secret: "secret".into(),
+            scopes: vec!["a".into(), "b".into()],
+        };
+        (config, fetches)
+    }
+
+    async fn assert_applies(token: &OAuth2Token, expected: &str) {
+        let mut request = Request::post("http://example.com").body(()).unwrap();
+        token.apply(&mut request).await.unwrap();
+        assert_eq!(
+            request.headers().typed_get(),
+            Some(Authorization::bearer(expected).unwrap())
+        );
+    }
+
+    #[tokio::test]
+    async fn oauth2_token_is_refreshed_before_expiry() {
+        // The first token expires within the margin, the second one doesn't.
+        let (config, fetches) = token_server(|fetch| if fetch == 0 { 10 } else { 3600 }).await;
+        let client = HttpClient::new(None, &ProxyConfig::default()).unwrap();
+        let token = OAuth2Token::new(&config, client).unwrap();
+
+        for expected in &["token0", "token1", "token1"] {
+            assert_applies(&token, expected).await;
+        }
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn oauth2_token_is_refetched_when_rejected() {
+        let (config, fetches) = token_server(|_| 3600).await;
+        let client = HttpClient::new(None, &ProxyConfig::default()).unwrap();
+        let token = OAuth2Token::new(&config, client).unwrap();
+
+        assert_applies(&token, "token0").await;
+        token.check_response_status(StatusCode::NOT_FOUND);
+        assert_applies(&token, "token0").await;
+        token.check_response_status(StatusCode::UNAUTHORIZED);
+        assert_applies(&token, "token1").await;
+        token.check_response_status(StatusCode::FORBIDDEN);
+        assert_applies(&token, "token2").await;
+        assert_applies(&token, "token2").await;
+        assert_eq!(fetches.load(Ordering::SeqCst), 3);
+    }
 }
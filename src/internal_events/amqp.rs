@@ -0,0 +1,112 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct AmqpEventReceived {
+    pub byte_size: usize,
+}
+
+impl InternalEvent for AmqpEventReceived {
+    fn emit_logs(&self) {
+        trace!(message = "Received one event.", internal_log_rate_secs = 10);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("component_received_events_total", 1);
+        counter!("events_in_total", 1);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct AmqpConnectFailed {
+    pub error: crate::Error,
+}
+
+impl InternalEvent for AmqpConnectFailed {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to connect to AMQP broker; retrying.",
+            error = %self.error,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("connection_errors_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct AmqpConsumeFailed {
+    pub error: lapin::Error,
+}
+
+impl InternalEvent for AmqpConsumeFailed {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to consume AMQP message.",
+            error = %self.error,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("events_failed_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct AmqpAckFailed {
+    pub error: lapin::Error,
+}
+
+impl InternalEvent for AmqpAckFailed {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to acknowledge AMQP message.",
+            error = %self.error,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("connection_send_ack_errors_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct AmqpEventsSent {
+    pub count: usize,
+    pub byte_size: usize,
+}
+
+impl InternalEvent for AmqpEventsSent {
+    fn emit_logs(&self) {
+        trace!(message = "Events sent.", count = %self.count);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", self.count as u64);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct AmqpSendFailed<'a> {
+    pub error: &'a str,
+}
+
+impl<'a> InternalEvent for AmqpSendFailed<'a> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to publish AMQP messages.",
+            error = %self.error,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("send_errors_total", 1);
+    }
+}
@@ -11,3 +11,81 @@ impl InternalEvent for AwsKinesisStreamsEventSent {
         counter!("processed_bytes_total", self.byte_size as u64);
     }
 }
+
+#[derive(Debug)]
+pub struct AwsKinesisStreamsEventReceived {
+    pub byte_size: usize,
+}
+
+impl InternalEvent for AwsKinesisStreamsEventReceived {
+    fn emit_logs(&self) {
+        trace!(message = "Received one event.", internal_log_rate_secs = 10);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("component_received_events_total", 1);
+        counter!("events_in_total", 1);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct AwsKinesisStreamsRequestFailed<'a, E> {
+    pub operation: &'static str,
+    pub shard_id: Option<&'a str>,
+    pub error: &'a E,
+}
+
+impl<'a, E: std::fmt::Display> InternalEvent for AwsKinesisStreamsRequestFailed<'a, E> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Kinesis request failed.",
+            operation = %self.operation,
+            shard_id = ?self.shard_id,
+            error = %self.error,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("request_errors_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct AwsKinesisStreamsBatchFailed<'a> {
+    pub shard_id: &'a str,
+    pub status: crate::event::BatchStatus,
+}
+
+impl InternalEvent for AwsKinesisStreamsBatchFailed<'_> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Records were not delivered, reading shard again from its checkpoint.",
+            shard_id = %self.shard_id,
+            status = ?self.status,
+            internal_log_rate_secs = 10
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct AwsKinesisStreamsCheckpointFailed<'a> {
+    pub path: &'a std::path::Path,
+    pub error: std::io::Error,
+}
+
+impl InternalEvent for AwsKinesisStreamsCheckpointFailed<'_> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed writing checkpoints.",
+            path = %self.path.display(),
+            error = %self.error,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("checkpoint_write_errors_total", 1);
+    }
+}
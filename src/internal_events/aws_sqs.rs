@@ -16,3 +16,115 @@ impl InternalEvent for AwsSqsEventSent<'_> {
         counter!("processed_bytes_total", self.byte_size as u64);
     }
 }
+
+#[derive(Debug)]
+pub struct AwsSqsMessagesReceived {
+    pub count: usize,
+}
+
+impl InternalEvent for AwsSqsMessagesReceived {
+    fn emit_logs(&self) {
+        trace!(message = "Received SQS messages.", count = %self.count);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("sqs_message_receive_succeeded_total", 1);
+        counter!("sqs_message_received_messages_total", self.count as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct AwsSqsReceiveFailed<'a, E> {
+    pub error: &'a E,
+}
+
+impl<'a, E: std::fmt::Display> InternalEvent for AwsSqsReceiveFailed<'a, E> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Failed to fetch SQS messages.",
+            error = %self.error,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("sqs_message_receive_failed_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct AwsSqsEventReceived {
+    pub byte_size: usize,
+}
+
+impl InternalEvent for AwsSqsEventReceived {
+    fn emit_logs(&self) {
+        trace!(message = "Received one event.", internal_log_rate_secs = 10);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("component_received_events_total", 1);
+        counter!("events_in_total", 1);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct AwsSqsMessagesDeleted {
+    pub count: usize,
+}
+
+impl InternalEvent for AwsSqsMessagesDeleted {
+    fn emit_logs(&self) {
+        trace!(message = "Deleted SQS messages.", count = %self.count);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("sqs_message_delete_succeeded_total", self.count as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct AwsSqsMessagesDeleteFailed {
+    pub count: usize,
+    pub error: String,
+}
+
+impl InternalEvent for AwsSqsMessagesDeleteFailed {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Deletion of SQS messages failed.",
+            count = %self.count,
+            error = %self.error,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("sqs_message_delete_failed_total", self.count as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct AwsSqsVisibilityChangeFailed {
+    pub count: usize,
+    pub error: String,
+}
+
+impl InternalEvent for AwsSqsVisibilityChangeFailed {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Changing the visibility timeout of SQS messages failed.",
+            count = %self.count,
+            error = %self.error,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "sqs_message_visibility_change_failed_total",
+            self.count as u64
+        );
+    }
+}
@@ -0,0 +1,56 @@
+use super::InternalEvent;
+use metrics::{counter, gauge};
+use std::time::Duration;
+
+// The values of the `sink_circuit_breaker_state` gauge.
+const CLOSED: f64 = 0.0;
+const HALF_OPEN: f64 = 1.0;
+const OPEN: f64 = 2.0;
+
+#[derive(Debug)]
+pub struct CircuitBreakerOpened {
+    pub reason: &'static str,
+    pub duration: Duration,
+}
+
+impl InternalEvent for CircuitBreakerOpened {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Circuit breaker opened; holding back requests.",
+            reason = %self.reason,
+            duration_secs = %self.duration.as_secs(),
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("sink_circuit_breaker_opened_total", 1, "reason" => self.reason);
+        gauge!("sink_circuit_breaker_state", OPEN);
+    }
+}
+
+#[derive(Debug)]
+pub struct CircuitBreakerHalfOpened;
+
+impl InternalEvent for CircuitBreakerHalfOpened {
+    fn emit_logs(&self) {
+        debug!(message = "Circuit breaker half-opened; probing with a single request.");
+    }
+
+    fn emit_metrics(&self) {
+        gauge!("sink_circuit_breaker_state", HALF_OPEN);
+    }
+}
+
+#[derive(Debug)]
+pub struct CircuitBreakerClosed;
+
+impl InternalEvent for CircuitBreakerClosed {
+    fn emit_logs(&self) {
+        info!(message = "Circuit breaker closed; resuming requests.");
+    }
+
+    fn emit_metrics(&self) {
+        gauge!("sink_circuit_breaker_state", CLOSED);
+    }
+}
@@ -1,5 +1,6 @@
 use super::InternalEvent;
-use metrics::counter;
+use metrics::{counter, histogram};
+use std::time::Duration;
 
 #[derive(Debug)]
 pub struct EventsReceived {
@@ -41,3 +42,16 @@ impl InternalEvent for EventsSent {
         }
     }
 }
+
+/// The time an event spent in a transform, from its receipt to its output. Sinks
+/// measure the time until an event is finalized from a timer attached to it.
+#[derive(Debug)]
+pub struct ComponentLatency {
+    pub latency: Duration,
+}
+
+impl InternalEvent for ComponentLatency {
+    fn emit_metrics(&self) {
+        histogram!("component_latency_seconds", self.latency);
+    }
+}
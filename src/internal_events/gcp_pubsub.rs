@@ -0,0 +1,57 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct GcpPubsubEventReceived {
+    pub byte_size: usize,
+}
+
+impl InternalEvent for GcpPubsubEventReceived {
+    fn emit_logs(&self) {
+        trace!(message = "Received one event.", internal_log_rate_secs = 10);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("component_received_events_total", 1);
+        counter!("events_in_total", 1);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct GcpPubsubReceiveFailed {
+    pub error: crate::Error,
+}
+
+impl InternalEvent for GcpPubsubReceiveFailed {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to pull messages from Pub/Sub.",
+            error = %self.error,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("request_errors_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct GcpPubsubAckFailed {
+    pub error: crate::Error,
+}
+
+impl InternalEvent for GcpPubsubAckFailed {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to acknowledge Pub/Sub messages.",
+            error = %self.error,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("connection_send_ack_errors_total", 1);
+    }
+}
@@ -0,0 +1,48 @@
+use super::InternalEvent;
+use metrics::{counter, gauge, increment_gauge};
+
+fn now_seconds() -> f64 {
+    chrono::Utc::now().timestamp_millis() as f64 / 1000.0
+}
+
+/// A request sent by a sink succeeded, possibly after being retried.
+#[derive(Debug)]
+pub struct SinkRequestSucceeded;
+
+impl InternalEvent for SinkRequestSucceeded {
+    fn emit_metrics(&self) {
+        gauge!("sink_consecutive_failures", 0.0);
+        gauge!("sink_last_success_timestamp_seconds", now_seconds());
+    }
+}
+
+/// An attempt of a sink to send a request failed, whether or not it is retried.
+#[derive(Debug)]
+pub struct SinkRequestFailed;
+
+impl InternalEvent for SinkRequestFailed {
+    fn emit_metrics(&self) {
+        counter!("sink_request_failures_total", 1);
+        increment_gauge!("sink_consecutive_failures", 1.0);
+        gauge!("sink_last_failure_timestamp_seconds", now_seconds());
+    }
+}
+
+#[derive(Debug)]
+pub struct SinkHealthcheckCompleted {
+    pub passed: bool,
+}
+
+impl InternalEvent for SinkHealthcheckCompleted {
+    fn emit_logs(&self) {
+        debug!(message = "Healthcheck completed.", passed = %self.passed);
+    }
+
+    fn emit_metrics(&self) {
+        gauge!(
+            "sink_healthcheck_passed",
+            if self.passed { 1.0 } else { 0.0 }
+        );
+        gauge!("sink_last_healthcheck_timestamp_seconds", now_seconds());
+    }
+}
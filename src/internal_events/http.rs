@@ -61,6 +61,29 @@ impl InternalEvent for HttpEventMissingMessage {
     }
 }
 
+#[derive(Debug)]
+pub struct HttpHeaderValueInvalid<'a> {
+    pub name: &'a str,
+    pub error: http::header::InvalidHeaderValue,
+}
+
+impl<'a> InternalEvent for HttpHeaderValueInvalid<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Rendered header value is invalid; dropping event.",
+            header = %self.name,
+            error = %self.error,
+            internal_log_rate_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1,
+            "error_type" => "render_error");
+        counter!("events_discarded_total", 1);
+    }
+}
+
 #[derive(Debug)]
 pub struct HttpEventEncoded {
     pub byte_size: usize,
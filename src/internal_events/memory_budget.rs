@@ -0,0 +1,18 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct MemoryBudgetExhausted;
+
+impl InternalEvent for MemoryBudgetExhausted {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Memory budget exhausted; waiting for events to be finalized.",
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("memory_budget_waits_total", 1);
+    }
+}
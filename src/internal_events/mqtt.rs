@@ -0,0 +1,127 @@
+use super::InternalEvent;
+use metrics::counter;
+use rumqttc::{ClientError, ConnectionError};
+
+#[derive(Debug)]
+pub struct MqttEventReceived<'a> {
+    pub byte_size: usize,
+    pub topic: &'a str,
+}
+
+impl<'a> InternalEvent for MqttEventReceived<'a> {
+    fn emit_logs(&self) {
+        trace!(
+            message = "Received one event.",
+            topic = %self.topic,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("component_received_events_total", 1);
+        counter!("events_in_total", 1);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct MqttConnectionFailed {
+    pub error: ConnectionError,
+}
+
+impl InternalEvent for MqttConnectionFailed {
+    fn emit_logs(&self) {
+        error!(
+            message = "MQTT connection failed; reconnecting.",
+            error = %self.error,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("connection_errors_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct MqttSubscribeFailed {
+    pub error: ClientError,
+}
+
+impl InternalEvent for MqttSubscribeFailed {
+    fn emit_logs(&self) {
+        error!(message = "Failed to subscribe to MQTT topics.", error = %self.error);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("connection_errors_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct MqttDeliveryFailed<'a> {
+    pub topic: &'a str,
+    pub pkid: u16,
+    pub status: crate::event::BatchStatus,
+}
+
+impl InternalEvent for MqttDeliveryFailed<'_> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Events of MQTT message were not delivered; leaving it unacknowledged.",
+            topic = %self.topic,
+            pkid = %self.pkid,
+            status = ?self.status,
+            internal_log_rate_secs = 10
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct MqttAckFailed {
+    pub error: ClientError,
+}
+
+impl InternalEvent for MqttAckFailed {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to acknowledge MQTT message.",
+            error = %self.error,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("connection_send_ack_errors_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct MqttEventSendSuccess {
+    pub byte_size: usize,
+}
+
+impl InternalEvent for MqttEventSendSuccess {
+    fn emit_logs(&self) {
+        trace!(message = "Processed one event.");
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct MqttEventSendFail {
+    pub error: ClientError,
+}
+
+impl InternalEvent for MqttEventSendFail {
+    fn emit_logs(&self) {
+        error!(message = "Failed to send message.", error = %self.error);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("send_errors_total", 1);
+    }
+}
@@ -0,0 +1,89 @@
+use super::InternalEvent;
+use metrics::counter;
+use std::{fmt::Display, net::SocketAddr};
+
+#[derive(Debug)]
+pub struct NetflowEventsReceived {
+    pub count: usize,
+    pub byte_size: usize,
+}
+
+impl InternalEvent for NetflowEventsReceived {
+    fn emit_logs(&self) {
+        trace!(
+            message = "Received events.",
+            count = self.count,
+            byte_size = self.byte_size,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("component_received_events_total", self.count as u64);
+        counter!("events_in_total", self.count as u64);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct NetflowReceiveFailed {
+    pub error: std::io::Error,
+}
+
+impl InternalEvent for NetflowReceiveFailed {
+    fn emit_logs(&self) {
+        error!(
+            message = "Error receiving datagram.",
+            error = %self.error,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("connection_errors_total", 1, "mode" => "udp");
+    }
+}
+
+#[derive(Debug)]
+pub struct NetflowDecodeFailed<E> {
+    pub exporter: SocketAddr,
+    pub error: E,
+}
+
+impl<E: Display> InternalEvent for NetflowDecodeFailed<E> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Failed to decode datagram, dropping it.",
+            exporter = %self.exporter,
+            error = %self.error,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("decode_errors_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct NetflowTemplateMissed {
+    pub exporter: SocketAddr,
+    pub flow_type: &'static str,
+    pub template_id: u16,
+}
+
+impl InternalEvent for NetflowTemplateMissed {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Dropping data set of unknown template.",
+            exporter = %self.exporter,
+            flow_type = self.flow_type,
+            template_id = self.template_id,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("netflow_template_misses_total", 1, "flow_type" => self.flow_type);
+    }
+}
@@ -0,0 +1,60 @@
+use super::InternalEvent;
+use metrics::counter;
+use std::fmt::Display;
+
+#[derive(Debug)]
+pub struct PostgresCdcEventReceived {
+    pub byte_size: usize,
+}
+
+impl InternalEvent for PostgresCdcEventReceived {
+    fn emit_logs(&self) {
+        trace!(message = "Received one event.", internal_log_rate_secs = 10);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("component_received_events_total", 1);
+        counter!("events_in_total", 1);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct PostgresCdcStreamFailed<E> {
+    pub error: E,
+}
+
+impl<E: Display> InternalEvent for PostgresCdcStreamFailed<E> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Replication stream failed; reconnecting.",
+            error = %self.error,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("connection_errors_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct PostgresCdcDecodeFailed<'a, E> {
+    pub lsn: &'a str,
+    pub error: E,
+}
+
+impl<'a, E: Display> InternalEvent for PostgresCdcDecodeFailed<'a, E> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Failed to decode change; streaming again from the last confirmed transaction.",
+            lsn = %self.lsn,
+            error = %self.error,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("parse_errors_total", 1);
+    }
+}
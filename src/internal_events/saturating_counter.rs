@@ -0,0 +1,21 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct CounterSaturated {
+    pub value: u64,
+}
+
+impl InternalEvent for CounterSaturated {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Counter exceeds the largest integer of events, saturating it.",
+            value = self.value,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("saturated_counters_total", 1);
+    }
+}
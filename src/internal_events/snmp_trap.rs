@@ -0,0 +1,85 @@
+use super::InternalEvent;
+use metrics::counter;
+use std::{fmt::Display, net::SocketAddr};
+
+#[derive(Debug)]
+pub struct SnmpTrapEventReceived {
+    pub byte_size: usize,
+}
+
+impl InternalEvent for SnmpTrapEventReceived {
+    fn emit_logs(&self) {
+        trace!(
+            message = "Received event.",
+            byte_size = self.byte_size,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("component_received_events_total", 1);
+        counter!("events_in_total", 1);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct SnmpTrapReceiveFailed {
+    pub error: std::io::Error,
+}
+
+impl InternalEvent for SnmpTrapReceiveFailed {
+    fn emit_logs(&self) {
+        error!(
+            message = "Error receiving datagram.",
+            error = %self.error,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("connection_errors_total", 1, "mode" => "udp");
+    }
+}
+
+#[derive(Debug)]
+pub struct SnmpTrapDecodeFailed<E> {
+    pub peer: SocketAddr,
+    pub error: E,
+}
+
+impl<E: Display> InternalEvent for SnmpTrapDecodeFailed<E> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Failed to decode message, dropping it.",
+            peer = %self.peer,
+            error = %self.error,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("decode_errors_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct SnmpTrapResponseFailed {
+    pub peer: SocketAddr,
+    pub error: std::io::Error,
+}
+
+impl InternalEvent for SnmpTrapResponseFailed {
+    fn emit_logs(&self) {
+        error!(
+            message = "Error sending response.",
+            peer = %self.peer,
+            error = %self.error,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("connection_send_errors_total", 1, "mode" => "udp");
+    }
+}
@@ -0,0 +1,99 @@
+use super::InternalEvent;
+use metrics::{counter, histogram};
+use std::{fmt::Display, path::Path, time::Instant};
+
+#[derive(Debug)]
+pub struct SqlEventReceived {
+    pub byte_size: usize,
+}
+
+impl InternalEvent for SqlEventReceived {
+    fn emit_logs(&self) {
+        trace!(message = "Received one event.", internal_log_rate_secs = 10);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("component_received_events_total", 1);
+        counter!("events_in_total", 1);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct SqlQueryCompleted {
+    pub rows: usize,
+    pub start: Instant,
+    pub end: Instant,
+}
+
+impl InternalEvent for SqlQueryCompleted {
+    fn emit_logs(&self) {
+        debug!(message = "Query completed.", rows = %self.rows);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("collect_completed_total", 1);
+        histogram!("collect_duration_seconds", self.end - self.start);
+    }
+}
+
+#[derive(Debug)]
+pub struct SqlQueryFailed<E> {
+    pub error: E,
+}
+
+impl<E: Display> InternalEvent for SqlQueryFailed<E> {
+    fn emit_logs(&self) {
+        error!(
+            message = "SQL query failed.",
+            error = %self.error,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("request_errors_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct SqlColumnConversionFailed<'a, E> {
+    pub column: &'a str,
+    pub error: E,
+}
+
+impl<'a, E: Display> InternalEvent for SqlColumnConversionFailed<'a, E> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Failed to convert column, skipping it.",
+            column = %self.column,
+            error = %self.error,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("parse_errors_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct SqlCheckpointFailed<'a> {
+    pub path: &'a Path,
+    pub error: std::io::Error,
+}
+
+impl InternalEvent for SqlCheckpointFailed<'_> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed writing checkpoint.",
+            path = %self.path.display(),
+            error = %self.error,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("checkpoint_write_errors_total", 1);
+    }
+}
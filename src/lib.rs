@@ -32,6 +32,8 @@ pub mod expiring_hash_map;
 pub mod generate;
 #[macro_use]
 pub mod internal_events;
+#[cfg(any(feature = "sources-amqp", feature = "sinks-amqp"))]
+pub(crate) mod amqp;
 #[cfg(feature = "api")]
 pub mod api;
 pub mod app;
@@ -49,6 +51,9 @@ pub mod kafka;
 pub mod kubernetes;
 pub mod line_agg;
 pub mod list;
+pub mod migrate;
+#[cfg(any(feature = "sources-mqtt", feature = "sinks-mqtt"))]
+pub(crate) mod mqtt;
 pub(crate) mod pipeline;
 pub(crate) mod proto;
 pub mod providers;
@@ -0,0 +1,485 @@
+//! Rewrites deprecated transforms into equivalent `remap` transforms.
+
+use crate::{
+    config::{self, CapturedEvent, CapturedEventData, ComponentKey, ConfigBuilder, Format},
+    event::{PathComponent, PathIter, Value},
+};
+use chrono::SecondsFormat;
+use shared::TimeZone;
+use std::{collections::HashMap, fs, path::PathBuf};
+use structopt::StructOpt;
+use toml::value::Table;
+
+#[derive(StructOpt, Debug)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Opts {
+    /// The config file to migrate. The format is detected from the file name.
+    path: PathBuf,
+
+    /// Write the migrated config to this file instead of printing it.
+    #[structopt(short, long)]
+    output: Option<PathBuf>,
+
+    /// Events captured with `vector tap --output`, fed into the generated unit
+    /// tests. Without them, the tests use a single sample event.
+    #[structopt(long)]
+    events: Option<PathBuf>,
+}
+
+/// A deprecated transform rewritten as a `remap` program.
+#[derive(Debug, Default)]
+pub struct Migration {
+    pub source: String,
+    /// Whether the program aborts on events the original transform dropped.
+    pub drop_on_abort: bool,
+    pub timezone: Option<TimeZone>,
+}
+
+impl Migration {
+    pub fn new(source: impl Into<String>) -> Self {
+        Self {
+            source: source.into(),
+            ..Default::default()
+        }
+    }
+
+    pub const fn drop_on_abort(mut self, drop_on_abort: bool) -> Self {
+        self.drop_on_abort = drop_on_abort;
+        self
+    }
+
+    pub const fn timezone(mut self, timezone: Option<TimeZone>) -> Self {
+        self.timezone = timezone;
+        self
+    }
+}
+
+pub async fn cmd(opts: &Opts) -> exitcode::ExitCode {
+    let format = Format::from_path(&opts.path).unwrap_or_default();
+    let mut config = match fs::read_to_string(&opts.path)
+        .map_err(|error| vec![error.to_string()])
+        .and_then(|content| config::format::deserialize::<toml::Value>(&content, Some(format)))
+    {
+        Ok(config) => config,
+        Err(errors) => {
+            error!("Failed to load {:?}:\n{}.", opts.path, errors.join("\n"));
+            return exitcode::CONFIG;
+        }
+    };
+
+    let captured = match &opts.events {
+        Some(path) => match crate::unit_test::read_capture(path) {
+            Ok(captured) => captured,
+            Err(error) => {
+                error!("Failed to read captured events from {:?}: {}.", path, error);
+                return exitcode::NOINPUT;
+            }
+        },
+        None => Vec::new(),
+    };
+
+    let (migrated, warnings) = migrate_transforms(&mut config);
+    for warning in warnings {
+        warn!("{}", warning);
+    }
+
+    if !migrated.is_empty() {
+        match migration_tests(migrated, captured).await {
+            Ok(tests) => {
+                if let Some(table) = config.as_table_mut() {
+                    let existing = table
+                        .entry("tests")
+                        .or_insert_with(|| toml::Value::Array(Vec::new()));
+                    if let Some(existing) = existing.as_array_mut() {
+                        existing.extend(tests);
+                    }
+                }
+            }
+            Err(errors) => {
+                error!(
+                    "Failed to generate tests for the migrated transforms:\n{}.",
+                    errors.join("\n")
+                );
+                return exitcode::CONFIG;
+            }
+        }
+    }
+
+    let migrated = match format {
+        Format::Toml => toml::to_string_pretty(&config).map_err(|error| error.to_string()),
+        Format::Json => serde_json::to_string_pretty(&config).map_err(|error| error.to_string()),
+        Format::Yaml => serde_yaml::to_string(&config).map_err(|error| error.to_string()),
+    };
+    let migrated = match migrated {
+        Ok(migrated) => migrated,
+        Err(error) => {
+            error!("Failed to serialize the migrated config: {}.", error);
+            return exitcode::SOFTWARE;
+        }
+    };
+
+    match &opts.output {
+        Some(path) => {
+            if let Err(error) = fs::write(path, migrated) {
+                error!("Failed to write {:?}: {}.", path, error);
+                return exitcode::CANTCREAT;
+            }
+        }
+        None => println!("{}", migrated),
+    }
+
+    exitcode::OK
+}
+
+/// Replaces deprecated transforms with `remap` transforms, keeping their ids
+/// and inputs. Returns the original configs of the migrated transforms, and
+/// warnings about the ones that couldn't be migrated.
+fn migrate_transforms(config: &mut toml::Value) -> (Table, Vec<String>) {
+    let mut migrated = Table::new();
+    let mut warnings = Vec::new();
+
+    let transforms = match config.get_mut("transforms").and_then(|t| t.as_table_mut()) {
+        Some(transforms) => transforms,
+        None => return (migrated, warnings),
+    };
+
+    for (id, transform) in transforms.iter_mut() {
+        let migration = match migrate_transform(transform) {
+            Some(Ok(migration)) => migration,
+            Some(Err(error)) => {
+                warnings.push(format!("Transform {:?} can't be migrated: {}", id, error));
+                continue;
+            }
+            None => continue,
+        };
+
+        let mut remap = Table::new();
+        remap.insert("type".to_owned(), "remap".into());
+        if let Some(inputs) = transform.get("inputs") {
+            remap.insert("inputs".to_owned(), inputs.clone());
+        }
+        remap.insert("source".to_owned(), migration.source.into());
+        remap.insert("drop_on_abort".to_owned(), migration.drop_on_abort.into());
+        if let Some(timezone) = migration
+            .timezone
+            .and_then(|timezone| toml::Value::try_from(timezone).ok())
+        {
+            remap.insert("timezone".to_owned(), timezone);
+        }
+
+        let original = std::mem::replace(transform, toml::Value::Table(remap));
+        migrated.insert(id.clone(), original);
+    }
+
+    (migrated, warnings)
+}
+
+fn migrate_transform(transform: &toml::Value) -> Option<crate::Result<Migration>> {
+    let mut options = transform.as_table()?.clone();
+    let transform_type = options.remove("type")?;
+    options.remove("inputs");
+    let options = toml::Value::Table(options);
+
+    fn migrate<T: serde::de::DeserializeOwned>(
+        options: toml::Value,
+        migrate: impl FnOnce(T) -> crate::Result<Migration>,
+    ) -> Option<crate::Result<Migration>> {
+        Some(options.try_into().map_err(Into::into).and_then(migrate))
+    }
+
+    match transform_type.as_str()? {
+        #[cfg(feature = "transforms-add_fields")]
+        "add_fields" => migrate(
+            options,
+            |c: crate::transforms::add_fields::AddFieldsConfig| c.migrate(),
+        ),
+        #[cfg(feature = "transforms-coercer")]
+        "coercer" => migrate(options, |c: crate::transforms::coercer::CoercerConfig| {
+            c.migrate()
+        }),
+        #[cfg(feature = "transforms-grok_parser")]
+        "grok_parser" => migrate(
+            options,
+            |c: crate::transforms::grok_parser::GrokParserConfig| c.migrate(),
+        ),
+        #[cfg(feature = "transforms-json_parser")]
+        "json_parser" => migrate(
+            options,
+            |c: crate::transforms::json_parser::JsonParserConfig| c.migrate(),
+        ),
+        #[cfg(feature = "transforms-key_value_parser")]
+        "key_value_parser" => migrate(
+            options,
+            |c: crate::transforms::key_value_parser::KeyValueConfig| c.migrate(),
+        ),
+        #[cfg(feature = "transforms-logfmt_parser")]
+        "logfmt_parser" => migrate(
+            options,
+            |c: crate::transforms::logfmt_parser::LogfmtConfig| c.migrate(),
+        ),
+        #[cfg(feature = "transforms-regex_parser")]
+        "regex_parser" => migrate(
+            options,
+            |c: crate::transforms::regex_parser::RegexParserConfig| c.migrate(),
+        ),
+        #[cfg(feature = "transforms-remove_fields")]
+        "remove_fields" => migrate(
+            options,
+            |c: crate::transforms::remove_fields::RemoveFieldsConfig| c.migrate(),
+        ),
+        #[cfg(feature = "transforms-rename_fields")]
+        "rename_fields" => migrate(
+            options,
+            |c: crate::transforms::rename_fields::RenameFieldsConfig| c.migrate(),
+        ),
+        #[cfg(feature = "transforms-tokenizer")]
+        "tokenizer" => migrate(
+            options,
+            |c: crate::transforms::tokenizer::TokenizerConfig| c.migrate(),
+        ),
+        _ => None,
+    }
+}
+
+/// Generates unit tests recording the outputs of the original transforms, so
+/// running them against the migrated config shows both behave the same.
+async fn migration_tests(
+    migrated: Table,
+    captured: Vec<CapturedEvent>,
+) -> Result<Vec<toml::Value>, Vec<String>> {
+    let mut config = Table::new();
+    config.insert("transforms".to_owned(), toml::Value::Table(migrated));
+    let builder: ConfigBuilder = toml::Value::Table(config)
+        .try_into()
+        .map_err(|error: toml::de::Error| vec![error.to_string()])?;
+
+    // Only events feeding the migrated transforms are useful, and each input
+    // without captured events gets a sample one.
+    let mut inputs = builder
+        .transforms
+        .values()
+        .flat_map(|transform| transform.inputs.iter().cloned())
+        .collect::<Vec<ComponentKey>>();
+    inputs.sort();
+    inputs.dedup();
+    let mut captured = captured
+        .into_iter()
+        .filter(|captured| inputs.contains(&captured.component_id))
+        .collect::<Vec<_>>();
+    for input in inputs {
+        if !captured
+            .iter()
+            .any(|captured| captured.component_id == input)
+        {
+            captured.push(sample_event(input));
+        }
+    }
+
+    config::generate_unit_tests_from_builder(builder, captured)
+        .await?
+        .into_iter()
+        .map(|mut test| {
+            test.name = format!("{} after migrating to remap", test.name);
+            toml::Value::try_from(test).map_err(|error| vec![error.to_string()])
+        })
+        .collect()
+}
+
+fn sample_event(component_id: ComponentKey) -> CapturedEvent {
+    let mut fields = serde_json::Map::new();
+    fields.insert(
+        crate::config::log_schema().message_key().to_owned(),
+        r#"{"key":"value"}"#.into(),
+    );
+
+    CapturedEvent {
+        component_id,
+        event: CapturedEventData::Log(fields),
+    }
+}
+
+/// The VRL path of a field, as used in the configuration of transforms.
+pub fn vrl_path(field: &str) -> crate::Result<String> {
+    let mut path = String::new();
+    for component in PathIter::new(field) {
+        match component {
+            PathComponent::Key(key)
+                if !key.is_empty()
+                    && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') =>
+            {
+                path.push('.');
+                path.push_str(&key);
+            }
+            PathComponent::Key(key) => {
+                path.push('.');
+                path.push_str(&vrl_string(&key));
+            }
+            PathComponent::Index(index) => path.push_str(&format!("[{}]", index)),
+            PathComponent::Invalid => return Err(format!("invalid field {:?}", field).into()),
+        }
+    }
+
+    if path.is_empty() {
+        Err(format!("invalid field {:?}", field).into())
+    } else {
+        Ok(path)
+    }
+}
+
+/// The path of a field of the event, or of the message if none is configured.
+pub fn vrl_field_or_message(field: Option<&str>) -> crate::Result<String> {
+    vrl_path(field.unwrap_or_else(|| crate::config::log_schema().message_key()))
+}
+
+pub fn vrl_string(s: &str) -> String {
+    serde_json::to_string(s).expect("serializing a string can't fail")
+}
+
+pub fn vrl_regex(pattern: &str) -> String {
+    format!("r'{}'", pattern.replace('\'', "\\'"))
+}
+
+pub fn vrl_literal(value: &Value) -> String {
+    match value {
+        Value::Bytes(bytes) => vrl_string(&String::from_utf8_lossy(bytes)),
+        Value::Integer(i) => i.to_string(),
+        Value::Float(f) => format!("{:?}", f),
+        Value::Boolean(b) => b.to_string(),
+        Value::Timestamp(ts) => format!("t'{}'", ts.to_rfc3339_opts(SecondsFormat::AutoSi, true)),
+        Value::Map(map) => format!(
+            "{{{}}}",
+            map.iter()
+                .map(|(key, value)| format!("{}: {}", vrl_string(key), vrl_literal(value)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Value::Array(array) => format!(
+            "[{}]",
+            array.iter().map(vrl_literal).collect::<Vec<_>>().join(", ")
+        ),
+        Value::Null => "null".to_owned(),
+    }
+}
+
+/// Statements converting the fields at `prefix` to the configured types. Like
+/// the original transforms, fields failing to convert are removed.
+pub fn vrl_conversions(prefix: &str, types: &HashMap<String, String>) -> crate::Result<String> {
+    let mut types = types.iter().collect::<Vec<_>>();
+    types.sort();
+
+    let mut statements = Vec::new();
+    for (field, type_name) in types {
+        let path = format!("{}{}", prefix, vrl_path(field)?);
+        let conversion = match type_name.as_str() {
+            "asis" | "bytes" | "string" => continue,
+            "integer" | "int" => format!("to_int({})", path),
+            "float" => format!("to_float({})", path),
+            "bool" | "boolean" => format!("to_bool({})", path),
+            "timestamp" => format!("to_timestamp({})", path),
+            _ if type_name.starts_with("timestamp|") => format!(
+                "parse_timestamp(to_string({}), format: {})",
+                path,
+                vrl_string(&type_name["timestamp|".len()..])
+            ),
+            _ => return Err(format!("unknown conversion {:?}", type_name).into()),
+        };
+
+        statements.push(format!(
+            "if exists({path}) {{\n  converted, err = {conversion}\n  if err == null {{ {path} = converted }} else {{ del({path}) }}\n}}",
+            path = path,
+            conversion = conversion,
+        ));
+    }
+
+    Ok(statements.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paths() {
+        assert_eq!(vrl_path("message").unwrap(), ".message");
+        assert_eq!(vrl_path("a.b[1]").unwrap(), ".a.b[1]");
+        assert_eq!(vrl_path("a-b.c").unwrap(), r#"."a-b".c"#);
+    }
+
+    #[test]
+    fn conversions() {
+        let types = vec![
+            ("count".to_owned(), "int".to_owned()),
+            ("name".to_owned(), "string".to_owned()),
+            ("at".to_owned(), "timestamp|%s".to_owned()),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(
+            vrl_conversions("", &types).unwrap(),
+            indoc::indoc! {r#"
+                if exists(.at) {
+                  converted, err = parse_timestamp(to_string(.at), format: "%s")
+                  if err == null { .at = converted } else { del(.at) }
+                }
+                if exists(.count) {
+                  converted, err = to_int(.count)
+                  if err == null { .count = converted } else { del(.count) }
+                }"#}
+        );
+    }
+
+    #[cfg(all(
+        feature = "sources-stdin",
+        feature = "transforms-json_parser",
+        feature = "transforms-remap"
+    ))]
+    #[tokio::test]
+    async fn migrates_with_equivalence_test() {
+        let mut config: toml::Value = toml::from_str(indoc::indoc! {r#"
+            [sources.in]
+              type = "stdin"
+
+            [transforms.parse]
+              inputs = ["in"]
+              type = "json_parser"
+              drop_invalid = true
+
+            [transforms.other]
+              inputs = ["parse"]
+              type = "remap"
+              source = ".other = true"
+        "#})
+        .unwrap();
+
+        let (migrated, warnings) = migrate_transforms(&mut config);
+        assert!(warnings.is_empty());
+        assert_eq!(migrated.keys().collect::<Vec<_>>(), vec!["parse"]);
+        assert_eq!(
+            config["transforms"]["parse"]["type"].as_str(),
+            Some("remap")
+        );
+        assert_eq!(
+            config["transforms"]["parse"]["inputs"],
+            toml::Value::from(vec!["in"])
+        );
+        assert_eq!(
+            config["transforms"]["other"]["type"].as_str(),
+            Some("remap")
+        );
+
+        let tests = migration_tests(migrated, Vec::new()).await.unwrap();
+        config
+            .as_table_mut()
+            .unwrap()
+            .insert("tests".to_owned(), tests.into());
+
+        // The recorded outputs of the original transform hold for the migrated one.
+        let builder: ConfigBuilder = config.try_into().unwrap();
+        let mut tests = config::build_unit_tests_from_builder(builder)
+            .await
+            .unwrap();
+        assert_eq!(tests.len(), 1);
+        assert_eq!(tests[0].run(), (Vec::<String>::new(), Vec::<String>::new()));
+    }
+}
@@ -0,0 +1,224 @@
+use crate::tls::TlsOptions;
+use rumqttc::{Key, MqttOptions, QoS, TlsConfiguration, Transport};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{
+    convert::TryFrom,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+#[derive(Debug, Snafu)]
+enum MqttError {
+    #[snafu(display("Could not read {:?}: {}", path, source))]
+    ReadFile {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[snafu(display("TLS for MQTT requires a CA certificate in \"tls.ca_file\""))]
+    MissingCaFile,
+    #[snafu(display("\"tls.crt_file\" and \"tls.key_file\" must be set together"))]
+    IncompleteClientCertificate,
+    #[snafu(display("\"tls.{}\" is not supported for MQTT", option))]
+    UnsupportedTlsOption { option: &'static str },
+}
+
+/// Connection settings shared by the `mqtt` source and sink.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub(crate) struct MqttConnectionConfig {
+    pub host: String,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    pub client_id: String,
+    pub user: Option<String>,
+    pub password: Option<String>,
+    #[serde(default = "default_keep_alive_secs")]
+    pub keep_alive_secs: u64,
+    #[serde(default)]
+    pub clean_session: bool,
+    pub tls: Option<TlsOptions>,
+}
+
+const fn default_port() -> u16 {
+    1883
+}
+
+const fn default_keep_alive_secs() -> u64 {
+    60
+}
+
+impl MqttConnectionConfig {
+    pub(crate) fn options(&self) -> crate::Result<MqttOptions> {
+        self.options_for(&self.client_id)
+    }
+
+    /// Builds client options using `client_id` in place of the configured
+    /// one, so short-lived connections such as healthchecks don't take over
+    /// the session of the component itself.
+    pub(crate) fn options_for(&self, client_id: &str) -> crate::Result<MqttOptions> {
+        let mut options = MqttOptions::new(client_id, &self.host, self.port);
+        options
+            .set_keep_alive(Duration::from_secs(self.keep_alive_secs))
+            .set_clean_session(self.clean_session);
+
+        if let Some(user) = &self.user {
+            options.set_credentials(user, self.password.as_deref().unwrap_or_default());
+        }
+
+        if let Some(tls) = &self.tls {
+            options.set_transport(tls_transport(tls)?);
+        }
+
+        Ok(options)
+    }
+}
+
+/// The MQTT client uses rustls, so only the PEM encoded files of
+/// `TlsOptions` can be honored.
+fn tls_transport(tls: &TlsOptions) -> crate::Result<Transport> {
+    if tls.verify_certificate == Some(false) {
+        return Err(MqttError::UnsupportedTlsOption {
+            option: "verify_certificate",
+        }
+        .into());
+    }
+    if tls.verify_hostname == Some(false) {
+        return Err(MqttError::UnsupportedTlsOption {
+            option: "verify_hostname",
+        }
+        .into());
+    }
+    if tls.key_pass.is_some() {
+        return Err(MqttError::UnsupportedTlsOption { option: "key_pass" }.into());
+    }
+
+    let ca = read_file(tls.ca_file.as_ref().ok_or(MqttError::MissingCaFile)?)?;
+
+    let client_auth = match (&tls.crt_file, &tls.key_file) {
+        (Some(crt_file), Some(key_file)) => {
+            let key = read_file(key_file)?;
+            let key = if String::from_utf8_lossy(&key).contains("BEGIN EC PRIVATE KEY") {
+                Key::ECC(key)
+            } else {
+                Key::RSA(key)
+            };
+            Some((read_file(crt_file)?, key))
+        }
+        (None, None) => None,
+        _ => return Err(MqttError::IncompleteClientCertificate.into()),
+    };
+
+    Ok(Transport::tls_with_config(TlsConfiguration::Simple {
+        ca,
+        alpn: None,
+        client_auth,
+    }))
+}
+
+fn read_file(path: &Path) -> crate::Result<Vec<u8>> {
+    std::fs::read(path)
+        .context(ReadFile { path })
+        .map_err(Into::into)
+}
+
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(try_from = "u8", into = "u8")]
+pub(crate) enum MqttQos {
+    AtMostOnce,
+    #[derivative(Default)]
+    AtLeastOnce,
+    ExactlyOnce,
+}
+
+impl TryFrom<u8> for MqttQos {
+    type Error = String;
+
+    fn try_from(qos: u8) -> Result<Self, Self::Error> {
+        match qos {
+            0 => Ok(Self::AtMostOnce),
+            1 => Ok(Self::AtLeastOnce),
+            2 => Ok(Self::ExactlyOnce),
+            _ => Err(format!("invalid QoS {}, expected 0, 1 or 2", qos)),
+        }
+    }
+}
+
+impl From<MqttQos> for u8 {
+    fn from(qos: MqttQos) -> Self {
+        match qos {
+            MqttQos::AtMostOnce => 0,
+            MqttQos::AtLeastOnce => 1,
+            MqttQos::ExactlyOnce => 2,
+        }
+    }
+}
+
+impl From<MqttQos> for QoS {
+    fn from(qos: MqttQos) -> Self {
+        match qos {
+            MqttQos::AtMostOnce => QoS::AtMostOnce,
+            MqttQos::AtLeastOnce => QoS::AtLeastOnce,
+            MqttQos::ExactlyOnce => QoS::ExactlyOnce,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tls::{TEST_PEM_CA_PATH, TEST_PEM_CRT_PATH, TEST_PEM_KEY_PATH};
+
+    #[derive(Debug, Deserialize)]
+    struct Qos {
+        qos: MqttQos,
+    }
+
+    #[test]
+    fn parses_qos() {
+        let config: Qos = toml::from_str("qos = 2").unwrap();
+        assert_eq!(config.qos, MqttQos::ExactlyOnce);
+
+        assert!(toml::from_str::<Qos>("qos = 3").is_err());
+    }
+
+    #[test]
+    fn requires_client_id() {
+        assert!(toml::from_str::<MqttConnectionConfig>(r#"host = "127.0.0.1""#).is_err());
+
+        let config: MqttConnectionConfig = toml::from_str(
+            r#"
+            host = "127.0.0.1"
+            client_id = "vector-telemetry"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.options().unwrap().client_id(), "vector-telemetry");
+    }
+
+    #[test]
+    fn tls_requires_ca_file() {
+        let tls = TlsOptions {
+            crt_file: Some(TEST_PEM_CRT_PATH.into()),
+            key_file: Some(TEST_PEM_KEY_PATH.into()),
+            ..TlsOptions::default()
+        };
+        assert!(tls_transport(&tls).is_err());
+
+        let tls = TlsOptions {
+            ca_file: Some(TEST_PEM_CA_PATH.into()),
+            ..tls
+        };
+        assert!(tls_transport(&tls).is_ok());
+    }
+
+    #[test]
+    fn tls_rejects_disabled_verification() {
+        let tls = TlsOptions {
+            ca_file: Some(TEST_PEM_CA_PATH.into()),
+            verify_certificate: Some(false),
+            ..TlsOptions::default()
+        };
+        assert!(tls_transport(&tls).is_err());
+    }
+}
@@ -1,11 +1,18 @@
-use crate::{internal_events::EventsSent, transforms::FunctionTransform};
-use futures::{channel::mpsc, task::Poll, Sink};
+use crate::{
+    internal_events::{EventsSent, MemoryBudgetExhausted},
+    transforms::FunctionTransform,
+};
+use futures::{channel::mpsc, ready, task::Poll, Sink};
 #[cfg(test)]
 use futures::{Stream, StreamExt};
-use std::{collections::VecDeque, fmt, pin::Pin, task::Context};
+use std::{collections::VecDeque, fmt, pin::Pin, sync::Arc, task::Context};
 #[cfg(test)]
 use vector_core::event::EventStatus;
-use vector_core::{event::Event, ByteSizeOf};
+use vector_core::{
+    event::{BatchNotifier, Event},
+    memory_budget::MemoryBudget,
+    ByteSizeOf,
+};
 
 #[derive(Debug)]
 pub struct ClosedError;
@@ -30,6 +37,10 @@ pub struct Pipeline {
     enqueued: VecDeque<Event>,
     events_outstanding: usize,
     bytes_outstanding: usize,
+    memory_budget: Option<Arc<MemoryBudget>>,
+    // Whether the pipeline is waiting for room in the memory budget, so that each wait is
+    // only reported once.
+    waiting_for_budget: bool,
 }
 
 impl Pipeline {
@@ -84,6 +95,19 @@ impl Sink<Event> for Pipeline {
     type Error = ClosedError;
 
     fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        if let Some(budget) = &self.memory_budget {
+            if budget.poll_available(cx).is_pending() {
+                // The events admitted before the budget ran out can still be sent.
+                ready!(self.try_flush(cx))?;
+                if !self.waiting_for_budget {
+                    self.waiting_for_budget = true;
+                    emit!(MemoryBudgetExhausted);
+                }
+                return Poll::Pending;
+            }
+            self.waiting_for_budget = false;
+        }
+
         if self.enqueued.len() < MAX_ENQUEUED {
             Poll::Ready(Ok(()))
         } else {
@@ -105,6 +129,12 @@ impl Sink<Event> for Pipeline {
             }
             core::mem::swap(&mut new_working_set, &mut working_set);
         }
+        if let Some(budget) = &self.memory_budget {
+            for event in &mut working_set {
+                let reservation = budget.reserve(event.size_of());
+                event.add_batch_notifier(BatchNotifier::with_reservation(reservation));
+            }
+        }
         self.enqueued.extend(working_set);
         Ok(())
     }
@@ -159,8 +189,17 @@ impl Pipeline {
             enqueued: VecDeque::with_capacity(10),
             events_outstanding: 0,
             bytes_outstanding: 0,
+            memory_budget: None,
+            waiting_for_budget: false,
         }
     }
+
+    /// Makes the events sent through this pipeline hold a reservation of `memory_budget`
+    /// until they are finalized, waiting for room in the budget before admitting them.
+    pub fn with_memory_budget(mut self, memory_budget: Option<Arc<MemoryBudget>>) -> Self {
+        self.memory_budget = memory_budget;
+        self
+    }
 }
 
 #[cfg(all(test, feature = "transforms-add_fields", feature = "transforms-filter"))]
@@ -171,9 +210,11 @@ mod test {
         test_util::collect_ready,
         transforms::{add_fields::AddFields, filter::Filter},
     };
-    use futures::SinkExt;
+    use futures::{SinkExt, StreamExt};
     use serde_json::json;
-    use std::convert::TryFrom;
+    use std::{convert::TryFrom, sync::Arc};
+    use tokio_test::{assert_pending, assert_ready_ok, task};
+    use vector_core::memory_budget::MemoryBudget;
 
     const KEYS: [&str; 2] = ["booper", "swooper"];
 
@@ -234,4 +275,23 @@ mod test {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn waits_for_memory_budget() {
+        let budget = MemoryBudget::new(1);
+        let (pipeline, mut receiver) = Pipeline::new_with_buffer(100, vec![]);
+        let mut pipeline = pipeline.with_memory_budget(Some(Arc::clone(&budget)));
+
+        pipeline.send(Event::from("first")).await.unwrap();
+        assert!(budget.used() > 0);
+
+        // The budget is used up until the first event is finalized.
+        let mut send = task::spawn(pipeline.send(Event::from("second")));
+        assert_pending!(send.poll());
+
+        drop(receiver.next().await);
+        assert_eq!(budget.used(), 0);
+        assert!(send.is_woken());
+        assert_ready_ok!(send.poll());
+    }
 }
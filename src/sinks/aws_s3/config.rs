@@ -14,9 +14,10 @@ use crate::{
             encoding::EncodingConfig, BatchConfig, BatchSettings, Compression, Concurrency,
             ServiceBuilderExt, TowerRequestConfig,
         },
-        Healthcheck,
+        Healthcheck, HealthcheckBuilder,
     },
 };
+use futures::FutureExt;
 use rusoto_s3::S3Client;
 use serde::{Deserialize, Serialize};
 use std::convert::TryInto;
@@ -122,6 +123,15 @@ impl SinkConfig for S3SinkConfig {
     fn sink_type(&self) -> &'static str {
         "aws_s3"
     }
+
+    fn build_healthcheck(&self, cx: &SinkContext) -> crate::Result<Option<HealthcheckBuilder>> {
+        let client = self.create_client(&cx.proxy)?;
+        let bucket = self.bucket.clone();
+
+        Ok(Some(Box::new(move || {
+            s3_common::config::healthcheck(bucket.clone(), client.clone()).boxed()
+        })))
+    }
 }
 
 impl S3SinkConfig {
@@ -183,7 +193,7 @@ impl S3SinkConfig {
     }
 
     pub fn build_healthcheck(&self, client: S3Client) -> crate::Result<Healthcheck> {
-        s3_common::config::build_healthcheck(self.bucket.clone(), client)
+        Ok(s3_common::config::healthcheck(self.bucket.clone(), client).boxed())
     }
 
     pub fn create_client(&self, proxy: &ProxyConfig) -> crate::Result<S3Client> {
@@ -100,6 +100,22 @@ impl SinkConfig for ClickhouseConfig {
     fn sink_type(&self) -> &'static str {
         "clickhouse"
     }
+
+    fn build_healthcheck(
+        &self,
+        cx: &SinkContext,
+    ) -> crate::Result<Option<super::HealthcheckBuilder>> {
+        let tls_settings = TlsSettings::from_options(&self.tls)?;
+        let client = HttpClient::new(tls_settings, &cx.proxy)?;
+        let config = ClickhouseConfig {
+            auth: self.auth.choose_one(&self.endpoint.auth)?,
+            ..self.clone()
+        };
+
+        Ok(Some(Box::new(move || {
+            healthcheck(client.clone(), config.clone()).boxed()
+        })))
+    }
 }
 
 #[async_trait::async_trait]
@@ -1,7 +1,9 @@
 use crate::{
     buffers::Acker,
-    config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
-    event::Event,
+    config::{
+        DataType, EncodedTestEvent, GenerateConfig, SinkConfig, SinkContext, SinkDescription,
+    },
+    event::{Event, PathComponent},
     internal_events::{ConsoleEventProcessed, ConsoleFieldNotFound},
     sinks::util::{
         encoding::{EncodingConfig, EncodingConfiguration},
@@ -89,6 +91,18 @@ impl SinkConfig for ConsoleSinkConfig {
     fn sink_type(&self) -> &'static str {
         "console"
     }
+
+    fn referenced_fields(&self) -> Vec<Vec<PathComponent>> {
+        self.encoding.only_fields().clone().unwrap_or_default()
+    }
+
+    fn encode_for_test(&self, event: Event) -> crate::Result<Option<EncodedTestEvent>> {
+        // Each event is written on its own line.
+        Ok(encode_event(event, &self.encoding).map(|mut buf| {
+            buf.push('\n');
+            EncodedTestEvent::new(buf)
+        }))
+    }
 }
 
 fn encode_event(mut event: Event, encoding: &EncodingConfig<Encoding>) -> Option<String> {
@@ -156,6 +170,7 @@ impl StreamSink for WriterSink {
 #[cfg(test)]
 mod test {
     use super::{encode_event, ConsoleSinkConfig, Encoding, EncodingConfig};
+    use crate::config::{EncodedTestEvent, SinkConfig};
     use crate::event::metric::{Metric, MetricKind, MetricValue, StatisticKind};
     use crate::event::{Event, Value};
     use chrono::{offset::TimeZone, Utc};
@@ -175,6 +190,20 @@ mod test {
         );
     }
 
+    #[test]
+    fn encodes_for_test_with_newline() {
+        let config: ConsoleSinkConfig = toml::from_str(
+            r#"
+            target = "stdout"
+            encoding.codec = "text"
+            "#,
+        )
+        .unwrap();
+
+        let encoded = config.encode_for_test(Event::from("foo")).unwrap();
+        assert_eq!(encoded, Some(EncodedTestEvent::new("foo\n")));
+    }
+
     #[test]
     fn encodes_log_events() {
         let mut event = Event::new_empty_log();
@@ -9,7 +9,7 @@ use crate::sinks::util::encoding::EncodingConfigWithDefault;
 use crate::sinks::util::service::ServiceBuilderExt;
 use crate::sinks::util::Concurrency;
 use crate::sinks::util::{BatchConfig, Compression, TowerRequestConfig};
-use crate::sinks::{Healthcheck, VectorSink};
+use crate::sinks::{Healthcheck, HealthcheckBuilder, VectorSink};
 use crate::tls::{MaybeTlsSettings, TlsConfig};
 use futures::FutureExt;
 use indoc::indoc;
@@ -143,6 +143,16 @@ impl SinkConfig for DatadogLogsConfig {
     fn sink_type(&self) -> &'static str {
         "datadog_logs"
     }
+
+    fn build_healthcheck(&self, cx: &SinkContext) -> crate::Result<Option<HealthcheckBuilder>> {
+        let client = self.create_client(&cx.proxy)?;
+        let uri = self.get_uri();
+        let api_key = self.default_api_key.clone();
+
+        Ok(Some(Box::new(move || {
+            healthcheck(client.clone(), uri.clone(), api_key.clone()).boxed()
+        })))
+    }
 }
 
 #[cfg(test)]
@@ -32,6 +32,7 @@ use serde_json::json;
 use snafu::{ResultExt, Snafu};
 use std::collections::{BTreeMap, HashMap};
 use std::convert::TryFrom;
+use std::sync::Arc;
 use vector_core::event::{Event, Value};
 
 /// The field name for the timestamp required by data stream mode
@@ -365,7 +366,7 @@ impl SinkConfig for ElasticSearchConfig {
         let common = ElasticSearchCommon::parse_config(self)?;
         let client = HttpClient::new(common.tls_settings.clone(), cx.proxy())?;
 
-        let healthcheck = common.healthcheck(client.clone()).boxed();
+        let healthcheck = Arc::new(common).healthcheck(client.clone()).boxed();
 
         let common = ElasticSearchCommon::parse_config(self)?;
         let compression = common.compression;
@@ -388,6 +389,7 @@ impl SinkConfig for ElasticSearchConfig {
             cx.acker(),
             ElasticSearchServiceLogic,
         )
+        .ordered_by(self.request.ordering_key.clone())
         .sink_map_err(|error| error!(message = "Fatal elasticsearch sink error.", %error));
 
         Ok((super::VectorSink::Sink(Box::new(sink)), healthcheck))
@@ -400,6 +402,18 @@ impl SinkConfig for ElasticSearchConfig {
     fn sink_type(&self) -> &'static str {
         "elasticsearch"
     }
+
+    fn build_healthcheck(
+        &self,
+        cx: &SinkContext,
+    ) -> crate::Result<Option<super::HealthcheckBuilder>> {
+        let common = Arc::new(ElasticSearchCommon::parse_config(self)?);
+        let client = HttpClient::new(common.tls_settings.clone(), cx.proxy())?;
+
+        Ok(Some(Box::new(move || {
+            Arc::clone(&common).healthcheck(client.clone()).boxed()
+        })))
+    }
 }
 
 #[derive(Debug)]
@@ -696,7 +710,7 @@ impl ElasticSearchCommon {
         request
     }
 
-    async fn healthcheck(self, client: HttpClient) -> crate::Result<()> {
+    async fn healthcheck(self: Arc<Self>, client: HttpClient) -> crate::Result<()> {
         let mut builder = Request::get(format!("{}/_cluster/health", self.base_url));
 
         match &self.credentials {
@@ -1,8 +1,11 @@
 use crate::expiring_hash_map::ExpiringHashMap;
 use crate::{
     buffers::Acker,
-    config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
-    event::Event,
+    config::{
+        log_schema, DataType, EncodedTestEvent, GenerateConfig, SinkConfig, SinkContext,
+        SinkDescription,
+    },
+    event::{Event, PathComponent},
     internal_events::FileOpen,
     internal_events::TemplateRenderingFailed,
     sinks::util::{
@@ -142,6 +145,19 @@ impl SinkConfig for FileSinkConfig {
     fn sink_type(&self) -> &'static str {
         "file"
     }
+
+    fn referenced_fields(&self) -> Vec<Vec<PathComponent>> {
+        self.encoding.only_fields().clone().unwrap_or_default()
+    }
+
+    fn encode_for_test(&self, event: Event) -> crate::Result<Option<EncodedTestEvent>> {
+        let path = self.path.render_string(&event)?;
+
+        let mut bytes = encode_event(&self.encoding, event);
+        bytes.push(b'\n');
+
+        Ok(Some(EncodedTestEvent::new(bytes).with_partition(path)))
+    }
 }
 
 #[derive(Debug)]
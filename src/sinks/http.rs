@@ -1,27 +1,36 @@
 use crate::{
-    config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
-    event::Event,
-    http::{Auth, HttpClient, MaybeAuth},
-    internal_events::{HttpEventEncoded, HttpEventMissingMessage},
+    config::{
+        DataType, EncodedTestEvent, GenerateConfig, SinkConfig, SinkContext, SinkDescription,
+    },
+    event::{Event, PathComponent},
+    http::{Auth, HttpClient, HttpError, MaybeAuth, OAuth2Config, OAuth2Token},
+    internal_events::{
+        HttpEventEncoded, HttpEventMissingMessage, HttpHeaderValueInvalid, TemplateRenderingFailed,
+    },
     sinks::util::{
         buffer::compression::GZIP_DEFAULT,
         encoding::{EncodingConfig, EncodingConfiguration},
-        http::{BatchedHttpSink, HttpSink, RequestConfig},
-        BatchConfig, BatchSettings, Buffer, Compression, TowerRequestConfig, UriSerde,
+        http::{render_ordering_key, HttpRetryLogic, HttpSink, PartitionHttpSink, RequestConfig},
+        retries::{RetryAction, RetryLogic},
+        BatchConfig, BatchSettings, Buffer, Compression, PartitionBuffer, PartitionInnerBuffer,
+        TowerRequestConfig, UriSerde,
     },
+    template::Template,
     tls::{TlsOptions, TlsSettings},
 };
+use bytes::Bytes;
 use flate2::write::GzEncoder;
 use futures::{future, FutureExt, SinkExt};
 use http::{
     header::{self, HeaderName, HeaderValue},
+    uri::PathAndQuery,
     Method, Request, StatusCode, Uri,
 };
 use hyper::Body;
 use indexmap::IndexMap;
-use serde::{Deserialize, Serialize};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use snafu::{ResultExt, Snafu};
-use std::io::Write;
+use std::{convert::TryFrom, fmt, io::Write};
 
 #[derive(Debug, Snafu)]
 enum BuildError {
@@ -35,19 +44,27 @@ enum BuildError {
         value: String,
         source: header::InvalidHeaderValue,
     },
+    #[snafu(display("Only one of `auth` and `oauth2` can be set."))]
+    ConflictingAuth,
+    #[snafu(display("`wrapper` can only be used with the `json` encoding."))]
+    WrapperWithoutJson,
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct HttpSinkConfig {
-    pub uri: UriSerde,
+    pub uri: HttpSinkUri,
     pub method: Option<HttpMethod>,
     pub auth: Option<Auth>,
+    pub oauth2: Option<OAuth2Config>,
     // Deprecated, moved to request.
     pub headers: Option<IndexMap<String, String>>,
     #[serde(default)]
+    pub query: IndexMap<String, String>,
+    #[serde(default)]
     pub compression: Compression,
     pub encoding: EncodingConfig<Encoding>,
+    pub wrapper: Option<WrapperConfig>,
     #[serde(default)]
     pub batch: BatchConfig,
     #[serde(default)]
@@ -61,15 +78,136 @@ fn default_config(e: Encoding) -> HttpSinkConfig {
         uri: Default::default(),
         method: Default::default(),
         auth: Default::default(),
+        oauth2: Default::default(),
         headers: Default::default(),
+        query: Default::default(),
         compression: Default::default(),
         batch: Default::default(),
         encoding: e.into(),
+        wrapper: Default::default(),
         request: Default::default(),
         tls: Default::default(),
     }
 }
 
+/// The URI events are sent to, rendered for each event if it references
+/// event fields.
+#[derive(Clone, Debug)]
+pub enum HttpSinkUri {
+    Static(UriSerde),
+    Template(Template),
+}
+
+impl Default for HttpSinkUri {
+    fn default() -> Self {
+        Self::Static(UriSerde::default())
+    }
+}
+
+impl From<Uri> for HttpSinkUri {
+    fn from(uri: Uri) -> Self {
+        Self::Static(uri.into())
+    }
+}
+
+impl fmt::Display for HttpSinkUri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Static(uri) => uri.fmt(f),
+            Self::Template(template) => template.get_ref().fmt(f),
+        }
+    }
+}
+
+impl Serialize for HttpSinkUri {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for HttpSinkUri {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let uri = String::deserialize(deserializer)?;
+        if uri.contains("{{") {
+            Template::try_from(uri)
+                .map(Self::Template)
+                .map_err(de::Error::custom)
+        } else {
+            uri.parse().map(Self::Static).map_err(de::Error::custom)
+        }
+    }
+}
+
+/// A header or query parameter value, rendered for each event if it
+/// references event fields. Other values are kept as they are, even if they
+/// contain `%` characters.
+#[derive(Clone, Debug)]
+enum TemplatedValue {
+    Static(String),
+    Template(Template),
+}
+
+impl TemplatedValue {
+    fn new(value: &str) -> crate::Result<Self> {
+        if value.contains("{{") {
+            Ok(Self::Template(Template::try_from(value)?))
+        } else {
+            Ok(Self::Static(value.to_owned()))
+        }
+    }
+
+    /// Renders the value for `event`, if it isn't static.
+    fn render(&self, event: &Event, field: &str) -> Option<Option<String>> {
+        match self {
+            Self::Static(_) => Some(None),
+            Self::Template(template) => template
+                .render_string(event)
+                .map_err(|error| {
+                    emit!(TemplateRenderingFailed {
+                        error,
+                        field: Some(field),
+                        drop_event: true,
+                    })
+                })
+                .ok()
+                .map(Some),
+        }
+    }
+
+    fn get<'a>(&'a self, rendered: &'a Option<String>) -> &'a str {
+        match (self, rendered) {
+            (Self::Static(value), _) => value,
+            (Self::Template(_), Some(value)) => value,
+            (Self::Template(template), None) => template.get_ref(),
+        }
+    }
+}
+
+/// Wraps the JSON array of the events of a batch into a document, with the
+/// events under `key` next to the static `fields`.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct WrapperConfig {
+    pub key: String,
+    #[serde(default)]
+    pub fields: serde_json::Map<String, serde_json::Value>,
+}
+
+impl WrapperConfig {
+    /// Returns the bytes written before and after the JSON array of events.
+    fn prefix_and_suffix(&self) -> (Vec<u8>, Vec<u8>) {
+        let mut prefix =
+            serde_json::to_vec(&self.fields).expect("Serializing a JSON map can't fail");
+        prefix.pop(); // remove the closing brace
+        if !self.fields.is_empty() {
+            prefix.push(b',');
+        }
+        serde_json::to_writer(&mut prefix, &self.key).expect("Writing to Vec can't fail");
+        prefix.push(b':');
+        (prefix, b"}".to_vec())
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Derivative)]
 #[serde(rename_all = "snake_case")]
 #[derivative(Default)]
@@ -85,6 +223,21 @@ pub enum HttpMethod {
     Patch,
 }
 
+impl From<&HttpMethod> for Method {
+    fn from(method: &HttpMethod) -> Self {
+        match method {
+            HttpMethod::Get => Method::GET,
+            HttpMethod::Head => Method::HEAD,
+            HttpMethod::Post => Method::POST,
+            HttpMethod::Put => Method::PUT,
+            HttpMethod::Delete => Method::DELETE,
+            HttpMethod::Options => Method::OPTIONS,
+            HttpMethod::Trace => Method::TRACE,
+            HttpMethod::Patch => Method::PATCH,
+        }
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone)]
 #[serde(rename_all = "snake_case")]
 pub enum Encoding {
@@ -123,22 +276,39 @@ impl SinkConfig for HttpSinkConfig {
     ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
         let client = self.build_http_client(&cx)?;
 
+        let mut config = self.clone();
+        if let HttpSinkUri::Static(uri) = &self.uri {
+            config.auth = self.auth.choose_one(&uri.auth)?;
+            config.uri = HttpSinkUri::Static(uri.with_default_parts());
+        }
+        if config.auth.is_some() && config.oauth2.is_some() {
+            return Err(BuildError::ConflictingAuth.into());
+        }
+        if config.wrapper.is_some() && config.encoding.codec() != &Encoding::Json {
+            return Err(BuildError::WrapperWithoutJson.into());
+        }
+
+        config.request.add_old_option(config.headers.take());
+        let has_auth = config.auth.is_some() || config.oauth2.is_some();
+        validate_headers(&config.request.headers, has_auth)?;
+
+        let oauth2 = config
+            .oauth2
+            .as_ref()
+            .map(|oauth2| OAuth2Token::new(oauth2, client.clone()))
+            .transpose()?;
+
         let healthcheck = match cx.healthcheck.uri.clone() {
-            Some(healthcheck_uri) => {
-                healthcheck(healthcheck_uri, self.auth.clone(), client.clone()).boxed()
-            }
+            Some(healthcheck_uri) => healthcheck(
+                healthcheck_uri,
+                self.auth.clone(),
+                oauth2.clone(),
+                client.clone(),
+            )
+            .boxed(),
             None => future::ok(()).boxed(),
         };
 
-        let mut config = HttpSinkConfig {
-            auth: self.auth.choose_one(&self.uri.auth)?,
-            uri: self.uri.with_default_parts(),
-            ..self.clone()
-        };
-
-        config.request.add_old_option(config.headers.take());
-        validate_headers(&config.request.headers, &config.auth)?;
-
         let batch = BatchSettings::default()
             .bytes(bytesize::mib(10u64))
             .timeout(1)
@@ -147,15 +317,21 @@ impl SinkConfig for HttpSinkConfig {
             .request
             .tower
             .unwrap_with(&TowerRequestConfig::default());
-        let sink = BatchedHttpSink::new(
-            config,
-            Buffer::new(batch.size, Compression::None),
+        let ordered = config.request.ordering_key.is_some();
+        let retry_logic = HttpSinkRetryLogic {
+            oauth2: oauth2.clone(),
+        };
+        let sink = PartitionHttpSink::with_retry_logic(
+            GenericHttpSink::new(config, oauth2)?,
+            PartitionBuffer::new(Buffer::new(batch.size, Compression::None)),
+            retry_logic,
             request,
             batch.timeout,
             client,
             cx.acker(),
-        )
-        .sink_map_err(|error| error!(message = "Fatal HTTP sink error.", %error));
+        );
+        let sink = if ordered { sink.ordered() } else { sink };
+        let sink = sink.sink_map_err(|error| error!(message = "Fatal HTTP sink error.", %error));
 
         let sink = super::VectorSink::Sink(Box::new(sink));
 
@@ -169,65 +345,180 @@ impl SinkConfig for HttpSinkConfig {
     fn sink_type(&self) -> &'static str {
         "http"
     }
+
+    fn referenced_fields(&self) -> Vec<Vec<PathComponent>> {
+        self.encoding.only_fields().clone().unwrap_or_default()
+    }
+
+    fn encode_for_test(&self, event: Event) -> crate::Result<Option<EncodedTestEvent>> {
+        Ok(encode_event(&self.encoding, event).map(EncodedTestEvent::new))
+    }
+
+    fn build_healthcheck(
+        &self,
+        cx: &SinkContext,
+    ) -> crate::Result<Option<super::HealthcheckBuilder>> {
+        let uri = match cx.healthcheck.uri.clone() {
+            Some(uri) => uri,
+            None => return Ok(None),
+        };
+        let client = self.build_http_client(cx)?;
+        let oauth2 = self
+            .oauth2
+            .as_ref()
+            .map(|oauth2| OAuth2Token::new(oauth2, client.clone()))
+            .transpose()?;
+        let auth = self.auth.clone();
+
+        Ok(Some(Box::new(move || {
+            healthcheck(uri.clone(), auth.clone(), oauth2.clone(), client.clone()).boxed()
+        })))
+    }
 }
 
-#[async_trait::async_trait]
-impl HttpSink for HttpSinkConfig {
-    type Input = Vec<u8>;
-    type Output = Vec<u8>;
-
-    fn encode_event(&self, mut event: Event) -> Option<Self::Input> {
-        self.encoding.apply_rules(&mut event);
-        let event = event.into_log();
-
-        let body = match &self.encoding.codec() {
-            Encoding::Text => {
-                if let Some(v) = event.get(crate::config::log_schema().message_key()) {
-                    let mut b = v.to_string_lossy().into_bytes();
-                    b.push(b'\n');
-                    b
-                } else {
-                    emit!(HttpEventMissingMessage);
-                    return None;
+/// The batches of events are partitioned by the rendered values of the
+/// templated URI, headers and query parameters, and by the ordering key.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+struct PartitionKey {
+    uri: Option<String>,
+    headers: Vec<Option<String>>,
+    query: Vec<Option<String>>,
+    ordering_key: Option<String>,
+}
+
+struct GenericHttpSink {
+    uri: HttpSinkUri,
+    method: Method,
+    auth: Option<Auth>,
+    oauth2: Option<OAuth2Token>,
+    headers: Vec<(String, TemplatedValue)>,
+    query: Vec<(String, TemplatedValue)>,
+    compression: Compression,
+    encoding: EncodingConfig<Encoding>,
+    wrapper: Option<(Vec<u8>, Vec<u8>)>,
+    ordering_key: Option<Template>,
+}
+
+impl GenericHttpSink {
+    fn new(config: HttpSinkConfig, oauth2: Option<OAuth2Token>) -> crate::Result<Self> {
+        let templated = |values: &IndexMap<String, String>| {
+            values
+                .iter()
+                .map(|(name, value)| Ok((name.clone(), TemplatedValue::new(value)?)))
+                .collect::<crate::Result<Vec<_>>>()
+        };
+
+        Ok(Self {
+            method: config.method.as_ref().unwrap_or(&HttpMethod::Post).into(),
+            headers: templated(&config.request.headers)?,
+            query: templated(&config.query)?,
+            wrapper: config
+                .wrapper
+                .as_ref()
+                .map(WrapperConfig::prefix_and_suffix),
+            ordering_key: config.request.ordering_key,
+            uri: config.uri,
+            auth: config.auth,
+            oauth2,
+            compression: config.compression,
+            encoding: config.encoding,
+        })
+    }
+
+    fn partition_key(&self, event: &Event) -> Option<PartitionKey> {
+        let uri = match &self.uri {
+            HttpSinkUri::Static(_) => None,
+            HttpSinkUri::Template(template) => Some(
+                template
+                    .render_string(event)
+                    .map_err(|error| {
+                        emit!(TemplateRenderingFailed {
+                            error,
+                            field: Some("uri"),
+                            drop_event: true,
+                        })
+                    })
+                    .ok()?,
+            ),
+        };
+        let headers = self
+            .headers
+            .iter()
+            .map(|(name, value)| {
+                let rendered = value.render(event, "request.headers")?;
+                if let Some(rendered) = &rendered {
+                    if let Err(error) = HeaderValue::from_str(rendered) {
+                        emit!(HttpHeaderValueInvalid { name, error });
+                        return None;
+                    }
                 }
-            }
+                Some(rendered)
+            })
+            .collect::<Option<_>>()?;
+        let query = self
+            .query
+            .iter()
+            .map(|(_, value)| value.render(event, "query"))
+            .collect::<Option<_>>()?;
+        let ordering_key = render_ordering_key(self.ordering_key.as_ref(), event);
+
+        Some(PartitionKey {
+            uri,
+            headers,
+            query,
+            ordering_key,
+        })
+    }
 
-            Encoding::Ndjson => {
-                let mut b = serde_json::to_vec(&event)
-                    .map_err(|error| panic!("Unable to encode into JSON: {}", error))
-                    .ok()?;
-                b.push(b'\n');
-                b
+    fn build_uri(&self, key: &PartitionKey) -> crate::Result<(Uri, Option<Auth>)> {
+        let (uri, auth) = match (&self.uri, &key.uri) {
+            (HttpSinkUri::Static(uri), _) => (uri.uri.clone(), self.auth.clone()),
+            (HttpSinkUri::Template(_), Some(uri)) => {
+                let uri = uri.parse::<UriSerde>()?.with_default_parts();
+                let auth = self.auth.choose_one(&uri.auth)?;
+                (uri.uri, auth)
             }
-
-            Encoding::Json => {
-                let mut b = serde_json::to_vec(&event)
-                    .map_err(|error| panic!("Unable to encode into JSON: {}", error))
-                    .ok()?;
-                b.push(b',');
-                b
+            (HttpSinkUri::Template(_), None) => {
+                unreachable!("Templated URIs are rendered for each event.")
             }
         };
+        if self.query.is_empty() {
+            return Ok((uri, auth));
+        }
 
-        emit!(HttpEventEncoded {
-            byte_size: body.len(),
-        });
+        let mut query = url::form_urlencoded::Serializer::new(String::new());
+        for ((name, value), rendered) in self.query.iter().zip(&key.query) {
+            query.append_pair(name, value.get(rendered));
+        }
+        let query = query.finish();
 
-        Some(body)
+        let mut parts = uri.into_parts();
+        let path_and_query = match &parts.path_and_query {
+            Some(path_and_query) => match path_and_query.query() {
+                Some(existing) => format!("{}?{}&{}", path_and_query.path(), existing, query),
+                None => format!("{}?{}", path_and_query.path(), query),
+            },
+            None => format!("/?{}", query),
+        };
+        parts.path_and_query = Some(PathAndQuery::try_from(path_and_query)?);
+        Ok((Uri::from_parts(parts)?, auth))
     }
+}
 
-    async fn build_request(&self, mut body: Self::Output) -> crate::Result<http::Request<Vec<u8>>> {
-        let method = match &self.method.clone().unwrap_or(HttpMethod::Post) {
-            HttpMethod::Get => Method::GET,
-            HttpMethod::Head => Method::HEAD,
-            HttpMethod::Post => Method::POST,
-            HttpMethod::Put => Method::PUT,
-            HttpMethod::Delete => Method::DELETE,
-            HttpMethod::Options => Method::OPTIONS,
-            HttpMethod::Trace => Method::TRACE,
-            HttpMethod::Patch => Method::PATCH,
-        };
-        let uri: Uri = self.uri.uri.clone();
+#[async_trait::async_trait]
+impl HttpSink for GenericHttpSink {
+    type Input = PartitionInnerBuffer<Vec<u8>, PartitionKey>;
+    type Output = PartitionInnerBuffer<Vec<u8>, PartitionKey>;
+
+    fn encode_event(&self, event: Event) -> Option<Self::Input> {
+        let key = self.partition_key(&event)?;
+        let body = encode_event(&self.encoding, event)?;
+        Some(PartitionInnerBuffer::new(body, key))
+    }
+
+    async fn build_request(&self, output: Self::Output) -> crate::Result<http::Request<Vec<u8>>> {
+        let (mut body, key) = output.into_parts();
+        let (uri, auth) = self.build_uri(&key)?;
 
         let ct = match self.encoding.codec() {
             Encoding::Text => "text/plain",
@@ -236,12 +527,18 @@ impl HttpSink for HttpSinkConfig {
                 body.insert(0, b'[');
                 body.pop(); // remove trailing comma from last record
                 body.push(b']');
+                if let Some((prefix, suffix)) = &self.wrapper {
+                    let mut wrapped = prefix.clone();
+                    wrapped.append(&mut body);
+                    wrapped.extend_from_slice(suffix);
+                    body = wrapped;
+                }
                 "application/json"
             }
         };
 
         let mut builder = Request::builder()
-            .method(method)
+            .method(self.method.clone())
             .uri(uri)
             .header("Content-Type", ct);
 
@@ -257,21 +554,92 @@ impl HttpSink for HttpSinkConfig {
             Compression::None => {}
         }
 
-        for (header, value) in self.request.headers.iter() {
-            builder = builder.header(header.as_str(), value.as_str());
+        for ((header, value), rendered) in self.headers.iter().zip(&key.headers) {
+            builder = builder.header(header.as_str(), value.get(rendered));
         }
 
-        let mut request = builder.body(body).unwrap();
+        let mut request = builder.body(body)?;
 
-        if let Some(auth) = &self.auth {
+        if let Some(auth) = &auth {
             auth.apply(&mut request);
         }
+        if let Some(oauth2) = &self.oauth2 {
+            oauth2.apply(&mut request).await?;
+        }
 
         Ok(request)
     }
 }
 
-async fn healthcheck(uri: UriSerde, auth: Option<Auth>, client: HttpClient) -> crate::Result<()> {
+fn encode_event(encoding: &EncodingConfig<Encoding>, mut event: Event) -> Option<Vec<u8>> {
+    encoding.apply_rules(&mut event);
+    let event = event.into_log();
+
+    let body = match &encoding.codec() {
+        Encoding::Text => {
+            if let Some(v) = event.get(crate::config::log_schema().message_key()) {
+                let mut b = v.to_string_lossy().into_bytes();
+                b.push(b'\n');
+                b
+            } else {
+                emit!(HttpEventMissingMessage);
+                return None;
+            }
+        }
+
+        Encoding::Ndjson => {
+            let mut b = serde_json::to_vec(&event)
+                .map_err(|error| panic!("Unable to encode into JSON: {}", error))
+                .ok()?;
+            b.push(b'\n');
+            b
+        }
+
+        Encoding::Json => {
+            let mut b = serde_json::to_vec(&event)
+                .map_err(|error| panic!("Unable to encode into JSON: {}", error))
+                .ok()?;
+            b.push(b',');
+            b
+        }
+    };
+
+    emit!(HttpEventEncoded {
+        byte_size: body.len(),
+    });
+
+    Some(body)
+}
+
+/// Retries like the other HTTP sinks, and invalidates the OAuth2 token when it's
+/// rejected so that the next requests use a new one.
+#[derive(Clone, Debug)]
+struct HttpSinkRetryLogic {
+    oauth2: Option<OAuth2Token>,
+}
+
+impl RetryLogic for HttpSinkRetryLogic {
+    type Error = HttpError;
+    type Response = http::Response<Bytes>;
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        HttpRetryLogic.is_retriable_error(error)
+    }
+
+    fn should_retry_response(&self, response: &Self::Response) -> RetryAction {
+        if let Some(oauth2) = &self.oauth2 {
+            oauth2.check_response_status(response.status());
+        }
+        HttpRetryLogic.should_retry_response(response)
+    }
+}
+
+async fn healthcheck(
+    uri: UriSerde,
+    auth: Option<Auth>,
+    oauth2: Option<OAuth2Token>,
+    client: HttpClient,
+) -> crate::Result<()> {
     let auth = auth.choose_one(&uri.auth)?;
     let uri = uri.with_default_parts();
     let mut request = Request::head(&uri.uri).body(Body::empty()).unwrap();
@@ -279,8 +647,14 @@ async fn healthcheck(uri: UriSerde, auth: Option<Auth>, client: HttpClient) -> c
     if let Some(auth) = auth {
         auth.apply(&mut request);
     }
+    if let Some(oauth2) = &oauth2 {
+        oauth2.apply(&mut request).await?;
+    }
 
     let response = client.send(request).await?;
+    if let Some(oauth2) = &oauth2 {
+        oauth2.check_response_status(response.status());
+    }
 
     match response.status() {
         StatusCode::OK => Ok(()),
@@ -288,14 +662,18 @@ async fn healthcheck(uri: UriSerde, auth: Option<Auth>, client: HttpClient) -> c
     }
 }
 
-fn validate_headers(map: &IndexMap<String, String>, auth: &Option<Auth>) -> crate::Result<()> {
+fn validate_headers(map: &IndexMap<String, String>, has_auth: bool) -> crate::Result<()> {
     for (name, value) in map {
-        if auth.is_some() && name.eq_ignore_ascii_case("Authorization") {
+        if has_auth && name.eq_ignore_ascii_case("Authorization") {
             return Err("Authorization header can not be used with defined auth options".into());
         }
 
         HeaderName::from_bytes(name.as_bytes()).with_context(|| InvalidHeaderName { name })?;
-        HeaderValue::from_bytes(value.as_bytes()).with_context(|| InvalidHeaderValue { value })?;
+        // Templated values are validated once rendered.
+        if !value.contains("{{") {
+            HeaderValue::from_bytes(value.as_bytes())
+                .with_context(|| InvalidHeaderValue { value })?;
+        }
     }
 
     Ok(())
@@ -309,10 +687,7 @@ mod tests {
         config::SinkContext,
         sinks::{
             http::HttpSinkConfig,
-            util::{
-                http::HttpSink,
-                test::{build_test_server, build_test_server_generic, build_test_server_status},
-            },
+            util::test::{build_test_server, build_test_server_generic, build_test_server_status},
         },
         test_util::{next_addr, random_lines_with_stream},
     };
@@ -339,7 +714,7 @@ mod tests {
 
         let mut config = default_config(Encoding::Text);
         config.encoding = encoding;
-        let bytes = config.encode_event(event).unwrap();
+        let bytes = encode_event(&config.encoding, event).unwrap();
 
         assert_eq!(bytes, Vec::from("hello world\n"));
     }
@@ -351,7 +726,7 @@ mod tests {
 
         let mut config = default_config(Encoding::Json);
         config.encoding = encoding;
-        let bytes = config.encode_event(event).unwrap();
+        let bytes = encode_event(&config.encoding, event).unwrap();
 
         #[derive(Deserialize, Debug)]
         #[serde(deny_unknown_fields)]
@@ -376,7 +751,7 @@ mod tests {
         "#;
         let config: HttpSinkConfig = toml::from_str(config).unwrap();
 
-        assert!(super::validate_headers(&config.request.headers, &None).is_ok());
+        assert!(super::validate_headers(&config.request.headers, false).is_ok());
     }
 
     #[test]
@@ -390,7 +765,7 @@ mod tests {
         let config: HttpSinkConfig = toml::from_str(config).unwrap();
 
         assert_downcast_matches!(
-            super::validate_headers(&config.request.headers, &None).unwrap_err(),
+            super::validate_headers(&config.request.headers, false).unwrap_err(),
             BuildError,
             BuildError::InvalidHeaderName { .. }
         );
@@ -643,6 +1018,169 @@ mod tests {
         assert_eq!(input_lines, output_lines);
     }
 
+    #[tokio::test]
+    async fn http_partitions_by_templated_parts() {
+        let in_addr = next_addr();
+        let config = r#"
+        uri = "http://$IN_ADDR/{{ tenant }}/frames"
+        encoding = "ndjson"
+        [query]
+        tenant = "{{ tenant }}"
+        static = "a b"
+        [request.headers]
+        X-Tenant = "{{ tenant }}"
+        "#
+        .replace("$IN_ADDR", &in_addr.to_string());
+        let config: HttpSinkConfig = toml::from_str(&config).unwrap();
+        let (sink, _) = config.build(SinkContext::new_test()).await.unwrap();
+        let (rx, trigger, server) = build_test_server(in_addr);
+        tokio::spawn(server);
+
+        let events = (0..10).map(|i| {
+            let mut event = Event::from(format!("line {}", i));
+            let tenant = if i % 2 == 0 { "even" } else { "odd" };
+            event.as_mut_log().insert("tenant", tenant);
+            event
+        });
+        sink.run(stream::iter(events)).await.unwrap();
+        drop(trigger);
+
+        let mut requests = rx
+            .map(|(parts, body)| {
+                let tenant = parts.headers["X-Tenant"].to_str().unwrap().to_owned();
+                assert_eq!(parts.uri.path(), format!("/{}/frames", tenant));
+                let mut query = url::form_urlencoded::parse(parts.uri.query().unwrap().as_bytes())
+                    .into_owned()
+                    .collect::<Vec<_>>();
+                query.sort();
+                assert_eq!(
+                    query,
+                    vec![
+                        ("static".into(), "a b".into()),
+                        ("tenant".into(), tenant.clone())
+                    ]
+                );
+                let lines = String::from_utf8(body.to_vec()).unwrap().lines().count();
+                (tenant, lines)
+            })
+            .collect::<Vec<_>>()
+            .await;
+        requests.sort();
+        assert_eq!(requests, vec![("even".into(), 5), ("odd".into(), 5)]);
+    }
+
+    #[tokio::test]
+    async fn http_drops_events_with_invalid_rendered_headers() {
+        let in_addr = next_addr();
+        let config = r#"
+        uri = "http://$IN_ADDR/frames"
+        encoding = "text"
+        [request.headers]
+        X-Tenant = "{{ tenant }}"
+        "#
+        .replace("$IN_ADDR", &in_addr.to_string());
+        let config: HttpSinkConfig = toml::from_str(&config).unwrap();
+        let (sink, _) = config.build(SinkContext::new_test()).await.unwrap();
+        let (rx, trigger, server) = build_test_server(in_addr);
+        tokio::spawn(server);
+
+        let events = ["valid", "in\nvalid"].iter().map(|tenant| {
+            let mut event = Event::from(*tenant);
+            event.as_mut_log().insert("tenant", *tenant);
+            event
+        });
+        sink.run(stream::iter(events)).await.unwrap();
+        drop(trigger);
+
+        let requests = rx
+            .map(|(parts, body)| {
+                let tenant = parts.headers["X-Tenant"].to_str().unwrap().to_owned();
+                (tenant, String::from_utf8(body.to_vec()).unwrap())
+            })
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(requests, vec![("valid".into(), "valid\n".into())]);
+    }
+
+    #[tokio::test]
+    async fn http_wraps_json_batches() {
+        let in_addr = next_addr();
+        let config = r#"
+        uri = "http://$IN_ADDR/frames"
+        encoding = "json"
+        [wrapper]
+        key = "events"
+        fields = { source = "vector" }
+        "#
+        .replace("$IN_ADDR", &in_addr.to_string());
+        let config: HttpSinkConfig = toml::from_str(&config).unwrap();
+        let (sink, _) = config.build(SinkContext::new_test()).await.unwrap();
+        let (rx, trigger, server) = build_test_server(in_addr);
+        tokio::spawn(server);
+
+        let (input_lines, events) = random_lines_with_stream(100, 10, None);
+        sink.run(events).await.unwrap();
+        drop(trigger);
+
+        let documents = rx
+            .map(|(_, body)| serde_json::from_slice::<serde_json::Value>(&body).unwrap())
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(documents.len(), 1);
+        assert_eq!(documents[0]["source"], "vector");
+        let output_lines = documents[0]["events"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|event| event["message"].as_str().unwrap().to_owned())
+            .collect::<Vec<_>>();
+        assert_eq!(input_lines, output_lines);
+    }
+
+    #[tokio::test]
+    async fn http_rejects_wrapper_without_json() {
+        let config = r#"
+        uri = "http://127.0.0.1/frames"
+        encoding = "ndjson"
+        wrapper.key = "events"
+        "#;
+        let config: HttpSinkConfig = toml::from_str(config).unwrap();
+
+        assert_downcast_matches!(
+            config.build(SinkContext::new_test()).await.err().unwrap(),
+            BuildError,
+            BuildError::WrapperWithoutJson
+        );
+    }
+
+    #[tokio::test]
+    async fn http_repeats_healthcheck() {
+        let in_addr = next_addr();
+        let config = r#"
+        uri = "http://$IN_ADDR/frames"
+        encoding = "text"
+        "#
+        .replace("$IN_ADDR", &in_addr.to_string());
+        let config: HttpSinkConfig = toml::from_str(&config).unwrap();
+        let healthcheck_uri = format!("http://{}/health", in_addr)
+            .parse::<UriSerde>()
+            .unwrap();
+        let cx = SinkContext::new_test_with_healthcheck(healthcheck_uri.into());
+        let healthcheck = config.build_healthcheck(&cx).unwrap().unwrap();
+        let (rx, trigger, server) = build_test_server(in_addr);
+        tokio::spawn(server);
+
+        healthcheck().await.unwrap();
+        healthcheck().await.unwrap();
+        drop(trigger);
+
+        let requests = rx
+            .map(|(parts, _)| (parts.method, parts.uri.path().to_owned()))
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(requests, vec![(Method::HEAD, "/health".to_owned()); 2]);
+    }
+
     async fn get_received(
         rx: mpsc::Receiver<(Parts, Bytes)>,
         assert_parts: impl Fn(Parts),
@@ -3,7 +3,7 @@ use crate::{
     config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
     sinks::splunk_hec::logs::HecSinkLogsConfig,
     sinks::util::{encoding::EncodingConfig, BatchConfig, Compression, TowerRequestConfig},
-    sinks::{Healthcheck, VectorSink},
+    sinks::{Healthcheck, HealthcheckBuilder, VectorSink},
     template::Template,
     tls::TlsOptions,
 };
@@ -73,6 +73,10 @@ impl SinkConfig for HumioLogsConfig {
     fn sink_type(&self) -> &'static str {
         "humio_logs"
     }
+
+    fn build_healthcheck(&self, cx: &SinkContext) -> crate::Result<Option<HealthcheckBuilder>> {
+        self.build_hec_config().build_healthcheck(cx)
+    }
 }
 
 impl HumioLogsConfig {
@@ -154,6 +154,22 @@ impl SinkConfig for LokiConfig {
     fn sink_type(&self) -> &'static str {
         "loki"
     }
+
+    fn build_healthcheck(
+        &self,
+        cx: &SinkContext,
+    ) -> crate::Result<Option<super::HealthcheckBuilder>> {
+        let tls = TlsSettings::from_options(&self.tls)?;
+        let client = HttpClient::new(tls, cx.proxy())?;
+        let config = LokiConfig {
+            auth: self.auth.choose_one(&self.endpoint.auth)?,
+            ..self.clone()
+        };
+
+        Ok(Some(Box::new(move || {
+            healthcheck(config.clone(), client.clone()).boxed()
+        })))
+    }
 }
 
 struct LokiSink {
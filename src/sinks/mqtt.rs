@@ -0,0 +1,457 @@
+use crate::{
+    buffers::Acker,
+    config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    emit,
+    event::{Event, EventFinalizers, EventStatus},
+    internal_events::{
+        MqttConnectionFailed, MqttEventSendFail, MqttEventSendSuccess, TemplateRenderingFailed,
+    },
+    mqtt::{MqttConnectionConfig, MqttQos},
+    sinks::util::{
+        encoding::{EncodingConfig, EncodingConfiguration},
+        StreamSink,
+    },
+    template::{Template, TemplateParseError},
+};
+use async_trait::async_trait;
+use futures::{stream::BoxStream, FutureExt, StreamExt};
+use rumqttc::{AsyncClient, MqttOptions, Outgoing, Packet};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    convert::TryFrom,
+    time::Duration,
+};
+
+/// Maximum number of messages handed to the client that haven't been
+/// acknowledged by the broker yet.
+const MAX_IN_FLIGHT: u16 = 100;
+
+/// How long to wait before polling a failed connection again, which
+/// makes the client reconnect.
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("invalid topic template: {}", source))]
+    TopicTemplate { source: TemplateParseError },
+}
+
+/**
+ * Code dealing with the SinkConfig struct.
+ */
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MqttSinkConfig {
+    #[serde(flatten)]
+    connection: MqttConnectionConfig,
+    topic: String,
+    #[serde(default)]
+    retain: bool,
+    #[serde(default)]
+    qos: MqttQos,
+    encoding: EncodingConfig<Encoding>,
+}
+
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Encoding {
+    Text,
+    Json,
+}
+
+inventory::submit! {
+    SinkDescription::new::<MqttSinkConfig>("mqtt")
+}
+
+impl GenerateConfig for MqttSinkConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"
+            encoding.codec = "json"
+            host = "127.0.0.1"
+            client_id = "vector"
+            topic = "vector""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "mqtt")]
+impl SinkConfig for MqttSinkConfig {
+    async fn build(
+        &self,
+        cx: SinkContext,
+    ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
+        let sink = MqttSink::new(self, cx.acker())?;
+        let healthcheck = healthcheck(
+            self.connection
+                .options_for(&format!("{}-healthcheck", self.connection.client_id))?,
+        )
+        .boxed();
+        Ok((super::VectorSink::Stream(Box::new(sink)), healthcheck))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "mqtt"
+    }
+}
+
+async fn healthcheck(options: MqttOptions) -> crate::Result<()> {
+    let (client, mut eventloop) = AsyncClient::new(options, 1);
+    loop {
+        if let rumqttc::Event::Incoming(Packet::ConnAck(_)) = eventloop.poll().await? {
+            break;
+        }
+    }
+    // The connection is dropped along with the event loop either way.
+    let _ = client.try_disconnect();
+    Ok(())
+}
+
+/**
+ * Code dealing with the Sink struct.
+ */
+
+pub struct MqttSink {
+    options: MqttOptions,
+    topic: Template,
+    retain: bool,
+    qos: MqttQos,
+    encoding: EncodingConfig<Encoding>,
+    acker: Acker,
+    seq_head: usize,
+    seq_tail: usize,
+    pending_acks: HashSet<usize>,
+}
+
+/// A message handed to the client, tracked until the broker has
+/// acknowledged it.
+struct Pending {
+    seqno: usize,
+    finalizers: EventFinalizers,
+    byte_size: usize,
+}
+
+impl MqttSink {
+    fn new(config: &MqttSinkConfig, acker: Acker) -> crate::Result<Self> {
+        let mut options = config.connection.options()?;
+        options.set_inflight(MAX_IN_FLIGHT);
+
+        Ok(MqttSink {
+            options,
+            topic: Template::try_from(config.topic.clone()).context(TopicTemplate)?,
+            retain: config.retain,
+            qos: config.qos,
+            encoding: config.encoding.clone(),
+            acker,
+            seq_head: 0,
+            seq_tail: 0,
+            pending_acks: HashSet::new(),
+        })
+    }
+
+    fn publish(&mut self, client: &AsyncClient, mut event: Event, queued: &mut VecDeque<Pending>) {
+        let seqno = self.seq_head;
+        self.seq_head += 1;
+
+        let topic = match self.topic.render_string(&event) {
+            Ok(topic) => topic,
+            Err(error) => {
+                emit!(TemplateRenderingFailed {
+                    error,
+                    field: Some("topic"),
+                    drop_event: true,
+                });
+                self.ack(seqno);
+                return;
+            }
+        };
+
+        let finalizers = event.metadata_mut().take_finalizers();
+        let payload = encode_event(event, &self.encoding);
+        let byte_size = payload.len();
+
+        match client.try_publish(topic, self.qos.into(), self.retain, payload) {
+            Ok(()) => queued.push_back(Pending {
+                seqno,
+                finalizers,
+                byte_size,
+            }),
+            Err(error) => {
+                emit!(MqttEventSendFail { error });
+                finalizers.update_status(EventStatus::Errored);
+                self.ack(seqno);
+            }
+        }
+    }
+
+    fn complete(&mut self, pending: Pending) {
+        emit!(MqttEventSendSuccess {
+            byte_size: pending.byte_size,
+        });
+        pending.finalizers.update_status(EventStatus::Delivered);
+        self.ack(pending.seqno);
+    }
+
+    /// The broker may acknowledge messages out of order, while events must
+    /// be acked in the order of the input stream.
+    fn ack(&mut self, seqno: usize) {
+        self.pending_acks.insert(seqno);
+
+        let mut num_to_ack = 0;
+        while self.pending_acks.remove(&self.seq_tail) {
+            num_to_ack += 1;
+            self.seq_tail += 1
+        }
+        self.acker.ack(num_to_ack);
+    }
+}
+
+#[async_trait]
+impl StreamSink for MqttSink {
+    async fn run(&mut self, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        // The request channel is as large as the number of messages allowed
+        // in flight, so publishing to it never fails for lack of capacity.
+        let (client, mut eventloop) =
+            AsyncClient::new(self.options.clone(), MAX_IN_FLIGHT as usize);
+
+        let mut input = input.fuse();
+        let mut input_done = false;
+        // Messages handed to the client that weren't written to the
+        // connection yet, in publishing order.
+        let mut queued = VecDeque::new();
+        // Messages written to the connection, by packet identifier, waiting
+        // for the broker to acknowledge them.
+        let mut in_flight = HashMap::new();
+
+        while !(input_done && queued.is_empty() && in_flight.is_empty()) {
+            let has_capacity = queued.len() + in_flight.len() < MAX_IN_FLIGHT as usize;
+
+            tokio::select! {
+                event = input.next(), if !input_done && has_capacity => match event {
+                    Some(event) => self.publish(&client, event, &mut queued),
+                    None => input_done = true,
+                },
+                notification = eventloop.poll() => match notification {
+                    Ok(rumqttc::Event::Outgoing(Outgoing::Publish(pkid))) => {
+                        // Messages retransmitted after a reconnection keep
+                        // their packet identifier.
+                        if !in_flight.contains_key(&pkid) {
+                            if let Some(pending) = queued.pop_front() {
+                                // QoS 0 messages are never acknowledged.
+                                if self.qos == MqttQos::AtMostOnce {
+                                    self.complete(pending);
+                                } else {
+                                    in_flight.insert(pkid, pending);
+                                }
+                            }
+                        }
+                    }
+                    Ok(rumqttc::Event::Incoming(Packet::PubAck(ack))) => {
+                        if let Some(pending) = in_flight.remove(&ack.pkid) {
+                            self.complete(pending);
+                        }
+                    }
+                    Ok(rumqttc::Event::Incoming(Packet::PubComp(comp))) => {
+                        if let Some(pending) = in_flight.remove(&comp.pkid) {
+                            self.complete(pending);
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(error) => {
+                        emit!(MqttConnectionFailed { error });
+                        tokio::time::sleep(RECONNECT_DELAY).await;
+                    }
+                },
+            }
+        }
+
+        let _ = client.try_disconnect();
+        Ok(())
+    }
+}
+
+fn encode_event(mut event: Event, encoding: &EncodingConfig<Encoding>) -> String {
+    encoding.apply_rules(&mut event);
+
+    match encoding.codec() {
+        Encoding::Json => serde_json::to_string(event.as_log()).unwrap(),
+        Encoding::Text => event
+            .as_log()
+            .get(crate::config::log_schema().message_key())
+            .map(|v| v.to_string_lossy())
+            .unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::Value;
+    use std::sync::atomic::Ordering;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<MqttSinkConfig>();
+    }
+
+    #[test]
+    fn acks_in_input_order() {
+        let config: MqttSinkConfig = toml::from_str(
+            r#"
+            encoding.codec = "json"
+            host = "127.0.0.1"
+            client_id = "vector"
+            topic = "sensors"
+            "#,
+        )
+        .unwrap();
+        let (acker, ack_counter) = Acker::new_for_testing();
+        let mut sink = MqttSink::new(&config, acker).unwrap();
+
+        sink.ack(1);
+        sink.ack(3);
+        assert_eq!(ack_counter.load(Ordering::Relaxed), 0);
+        sink.ack(0);
+        assert_eq!(ack_counter.load(Ordering::Relaxed), 2);
+        sink.ack(2);
+        assert_eq!(ack_counter.load(Ordering::Relaxed), 4);
+    }
+
+    #[test]
+    fn encodes_raw_logs() {
+        let event = Event::from("foo");
+        assert_eq!(
+            "foo",
+            encode_event(event, &EncodingConfig::from(Encoding::Text))
+        );
+    }
+
+    #[test]
+    fn encodes_log_events() {
+        let mut event = Event::new_empty_log();
+        let log = event.as_mut_log();
+        log.insert("x", Value::from("23"));
+        log.insert("z", Value::from(25));
+        log.insert("a", Value::from("0"));
+
+        let encoded = encode_event(event, &EncodingConfig::from(Encoding::Json));
+        let expected = r#"{"a":"0","x":"23","z":25}"#;
+        assert_eq!(encoded, expected);
+    }
+
+    #[test]
+    fn rejects_invalid_topic_template() {
+        let config: MqttSinkConfig = toml::from_str(
+            r#"
+            encoding.codec = "json"
+            host = "127.0.0.1"
+            client_id = "vector"
+            topic = "sensors/%E"
+            "#,
+        )
+        .unwrap();
+        assert!(MqttSink::new(&config, Acker::Null).is_err());
+    }
+}
+
+#[cfg(feature = "mqtt-integration-tests")]
+#[cfg(test)]
+mod integration_tests {
+    use super::*;
+    use crate::{
+        event::{BatchNotifier, BatchStatus},
+        test_util::{random_lines_with_stream, random_string, trace_init},
+    };
+    use rumqttc::QoS;
+    use std::sync::atomic::Ordering;
+
+    fn mqtt_host() -> String {
+        std::env::var("MQTT_HOST").unwrap_or_else(|_| "127.0.0.1".into())
+    }
+
+    async fn publishes_messages(qos: u8) {
+        trace_init();
+
+        let topic = format!("test-{}", random_string(10));
+        let config: MqttSinkConfig = toml::from_str(&format!(
+            r#"
+            encoding.codec = "text"
+            host = "{}"
+            client_id = "vector-{}"
+            topic = "{}/{{{{ sensor }}}}"
+            qos = {}
+            "#,
+            mqtt_host(),
+            random_string(10),
+            topic,
+            qos
+        ))
+        .unwrap();
+
+        // Establish the consumer subscription.
+        let options =
+            MqttOptions::new(format!("consumer-{}", random_string(10)), mqtt_host(), 1883);
+        let (consumer, mut consumer_loop) = AsyncClient::new(options, 10);
+        consumer
+            .subscribe(format!("{}/#", topic), QoS::ExactlyOnce)
+            .await
+            .unwrap();
+        loop {
+            if let rumqttc::Event::Incoming(Packet::SubAck(_)) = consumer_loop.poll().await.unwrap()
+            {
+                break;
+            }
+        }
+
+        // Publish events.
+        let (acker, ack_counter) = Acker::new_for_testing();
+        let mut sink = MqttSink::new(&config, acker).unwrap();
+        let num_events = 100;
+        let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+        let (input, events) = random_lines_with_stream(100, num_events, Some(batch));
+        let events = events.map(|mut event| {
+            event.as_mut_log().insert("sensor", "temperature");
+            event
+        });
+        sink.run(Box::pin(events)).await.unwrap();
+
+        let mut output = Vec::new();
+        while output.len() < num_events {
+            let notification = tokio::time::timeout(Duration::from_secs(5), consumer_loop.poll())
+                .await
+                .expect("Timed out waiting for messages")
+                .unwrap();
+            if let rumqttc::Event::Incoming(Packet::Publish(publish)) = notification {
+                assert_eq!(publish.topic, format!("{}/temperature", topic));
+                output.push(String::from_utf8_lossy(&publish.payload).to_string());
+            }
+        }
+
+        assert_eq!(output, input);
+        assert_eq!(ack_counter.load(Ordering::Relaxed), num_events);
+        assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+    }
+
+    #[tokio::test]
+    async fn publishes_messages_at_most_once() {
+        publishes_messages(0).await;
+    }
+
+    #[tokio::test]
+    async fn publishes_messages_at_least_once() {
+        publishes_messages(1).await;
+    }
+
+    #[tokio::test]
+    async fn publishes_messages_exactly_once() {
+        publishes_messages(2).await;
+    }
+}
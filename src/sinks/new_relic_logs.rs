@@ -151,15 +151,22 @@ impl NewRelicLogsConfig {
             ..self.request
         };
 
-        let request = RequestConfig { tower, headers };
+        let request = RequestConfig {
+            tower,
+            headers,
+            ordering_key: None,
+        };
 
         Ok(HttpSinkConfig {
             uri: uri.into(),
             method: Some(HttpMethod::Post),
             auth: None,
+            oauth2: None,
             headers: None,
+            query: IndexMap::new(),
             compression: self.compression,
             encoding: EncodingConfig::<Encoding>::from(self.encoding.clone()).into_encoding(),
+            wrapper: None,
             batch,
             request,
             tls: None,
@@ -211,7 +218,7 @@ mod tests {
         let http_config = nr_config.create_config().unwrap();
 
         assert_eq!(
-            format!("{}", http_config.uri.uri),
+            format!("{}", http_config.uri),
             "https://log-api.newrelic.com/log/v1".to_string()
         );
         assert_eq!(http_config.method, Some(HttpMethod::Post));
@@ -245,7 +252,7 @@ mod tests {
         let http_config = nr_config.create_config().unwrap();
 
         assert_eq!(
-            format!("{}", http_config.uri.uri),
+            format!("{}", http_config.uri),
             "https://log-api.eu.newrelic.com/log/v1".to_string()
         );
         assert_eq!(http_config.method, Some(HttpMethod::Post));
@@ -283,7 +290,7 @@ mod tests {
         let http_config = nr_config.create_config().unwrap();
 
         assert_eq!(
-            format!("{}", http_config.uri.uri),
+            format!("{}", http_config.uri),
             "https://log-api.eu.newrelic.com/log/v1".to_string()
         );
         assert_eq!(http_config.method, Some(HttpMethod::Post));
@@ -2,8 +2,6 @@ use crate::config::ProxyConfig;
 use crate::rusoto;
 use crate::rusoto::{AwsAuthentication, RegionOrEndpoint};
 use crate::sinks::util::retries::RetryLogic;
-use crate::sinks::Healthcheck;
-use futures::FutureExt;
 use http::StatusCode;
 use rusoto_core::RusotoError;
 use rusoto_s3::{HeadBucketRequest, PutObjectError, S3Client, S3};
@@ -87,29 +85,25 @@ pub enum HealthcheckError {
     UnknownStatus { status: StatusCode },
 }
 
-pub fn build_healthcheck(bucket: String, client: S3Client) -> crate::Result<Healthcheck> {
-    let healthcheck = async move {
-        let req = client
-            .head_bucket(HeadBucketRequest {
-                bucket: bucket.clone(),
-                expected_bucket_owner: None,
-            })
-            .await;
-
-        match req {
-            Ok(_) => Ok(()),
-            Err(error) => Err(match error {
-                RusotoError::Unknown(resp) => match resp.status {
-                    StatusCode::FORBIDDEN => HealthcheckError::InvalidCredentials.into(),
-                    StatusCode::NOT_FOUND => HealthcheckError::UnknownBucket { bucket }.into(),
-                    status => HealthcheckError::UnknownStatus { status }.into(),
-                },
-                error => error.into(),
-            }),
-        }
-    };
-
-    Ok(healthcheck.boxed())
+pub async fn healthcheck(bucket: String, client: S3Client) -> crate::Result<()> {
+    let req = client
+        .head_bucket(HeadBucketRequest {
+            bucket: bucket.clone(),
+            expected_bucket_owner: None,
+        })
+        .await;
+
+    match req {
+        Ok(_) => Ok(()),
+        Err(error) => Err(match error {
+            RusotoError::Unknown(resp) => match resp.status {
+                StatusCode::FORBIDDEN => HealthcheckError::InvalidCredentials.into(),
+                StatusCode::NOT_FOUND => HealthcheckError::UnknownBucket { bucket }.into(),
+                status => HealthcheckError::UnknownStatus { status }.into(),
+            },
+            error => error.into(),
+        }),
+    }
 }
 
 pub fn create_client(
@@ -9,7 +9,7 @@ use crate::{
     sinks::util::service::TowerRequestConfig,
     sinks::util::{BatchConfig, BatchSettings, Buffer},
     sinks::UriParseError,
-    sinks::{Healthcheck, VectorSink},
+    sinks::{Healthcheck, HealthcheckBuilder, VectorSink},
     tls::{TlsOptions, TlsSettings},
 };
 use futures::{FutureExt, SinkExt};
@@ -71,6 +71,25 @@ where
     Ok((VectorSink::Sink(Box::new(sink)), healthcheck))
 }
 
+/// Builds the healthcheck of the sink so that it can be repeated while the sink runs.
+pub fn build_healthcheck(
+    tls_options: &Option<TlsOptions>,
+    proxy_config: &ProxyConfig,
+    endpoint: &str,
+    token: &str,
+) -> crate::Result<HealthcheckBuilder> {
+    validate_host(endpoint)?;
+
+    let tls_settings = TlsSettings::from_options(tls_options)?;
+    let client = HttpClient::new(tls_settings, proxy_config)?;
+    let endpoint = endpoint.to_string();
+    let token = token.to_string();
+
+    Ok(Box::new(move || {
+        healthcheck(endpoint.clone(), token.clone(), client.clone()).boxed()
+    }))
+}
+
 pub async fn build_request(
     endpoint: &str,
     token: &str,
@@ -269,6 +288,25 @@ mod tests {
         assert!(healthcheck.await.is_ok())
     }
 
+    #[tokio::test]
+    async fn test_build_healthcheck_repeats_healthcheck() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/services/collector/health/1.0"))
+            .and(header("Authorization", "Splunk token"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let healthcheck =
+            build_healthcheck(&None, &ProxyConfig::default(), &mock_server.uri(), "token").unwrap();
+
+        assert!(healthcheck().await.is_ok());
+        assert!(healthcheck().await.is_ok());
+    }
+
     #[tokio::test]
     async fn test_build_sink_healthcheck_400_response_returns_error() {
         let mock_server = MockServer::start().await;
@@ -8,7 +8,7 @@ use crate::{
         http::HttpSink,
         BatchConfig, Compression, TowerRequestConfig,
     },
-    sinks::{Healthcheck, VectorSink},
+    sinks::{Healthcheck, HealthcheckBuilder, VectorSink},
     template::Template,
     tls::TlsOptions,
 };
@@ -103,6 +103,10 @@ impl SinkConfig for HecSinkLogsConfig {
     fn sink_type(&self) -> &'static str {
         "splunk_hec_logs"
     }
+
+    fn build_healthcheck(&self, cx: &SinkContext) -> crate::Result<Option<HealthcheckBuilder>> {
+        conn::build_healthcheck(&self.tls, cx.proxy(), &self.endpoint, &self.token).map(Some)
+    }
 }
 
 // Add a compatibility alias to avoid breaking existing configs
@@ -127,6 +131,10 @@ impl SinkConfig for HecSinkCompatConfig {
     fn sink_type(&self) -> &'static str {
         "splunk_hec"
     }
+
+    fn build_healthcheck(&self, cx: &SinkContext) -> crate::Result<Option<HealthcheckBuilder>> {
+        self.config.build_healthcheck(cx)
+    }
 }
 
 #[async_trait::async_trait]
@@ -5,7 +5,7 @@ use crate::{
     internal_events::{SplunkEventEncodeError, SplunkEventSent},
     sinks::splunk_hec::conn,
     sinks::util::{encode_namespace, http::HttpSink, BatchConfig, Compression, TowerRequestConfig},
-    sinks::{Healthcheck, VectorSink},
+    sinks::{Healthcheck, HealthcheckBuilder, VectorSink},
     template::Template,
     tls::TlsOptions,
 };
@@ -135,6 +135,10 @@ impl SinkConfig for HecSinkMetricsConfig {
     fn sink_type(&self) -> &'static str {
         "splunk_hec_metrics"
     }
+
+    fn build_healthcheck(&self, cx: &SinkContext) -> crate::Result<Option<HealthcheckBuilder>> {
+        conn::build_healthcheck(&self.tls, cx.proxy(), &self.endpoint, &self.token).map(Some)
+    }
 }
 
 #[async_trait::async_trait]
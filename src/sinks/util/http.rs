@@ -1,13 +1,15 @@
 use super::{
     retries::{RetryAction, RetryLogic},
     sink::{self, ServiceLogic},
-    Batch, EncodedEvent, Partition, TowerBatchedSink, TowerPartitionSink, TowerRequestConfig,
-    TowerRequestSettings,
+    Batch, EncodedEvent, OrderingKey, Partition, TowerBatchedSink, TowerPartitionSink,
+    TowerRequestConfig, TowerRequestSettings,
 };
 use crate::{
     buffers::Acker,
     event::Event,
     http::{HttpClient, HttpError},
+    internal_events::TemplateRenderingFailed,
+    template::Template,
 };
 use bytes::{Buf, Bytes};
 use futures::{future::BoxFuture, ready, Sink};
@@ -26,6 +28,7 @@ use std::{
     time::Duration,
 };
 use tower::Service;
+use tracing_futures::Instrument;
 
 #[async_trait::async_trait]
 pub trait HttpSink: Send + Sync + 'static {
@@ -72,7 +75,8 @@ pub struct BatchedHttpSink<
     // An empty slot is needed to buffer an item where we encoded it but
     // the inner sink is applying back pressure. This trick is used in the `WithFlatMap`
     // sink combinator. https://docs.rs/futures/0.1.29/src/futures/sink/with_flat_map.rs.html#20
-    slot: Option<EncodedEvent<B::Input>>,
+    slot: Option<(OrderingKey, EncodedEvent<B::Input>)>,
+    ordering_key: Option<Template>,
 }
 
 impl<T, B> BatchedHttpSink<T, B>
@@ -126,7 +130,10 @@ where
         let request_builder =
             move |b| -> BoxFuture<'static, crate::Result<http::Request<Vec<u8>>>> {
                 let sink = Arc::clone(&sink1);
-                Box::pin(async move { sink.build_request(b).await })
+                Box::pin(
+                    async move { sink.build_request(b).await }
+                        .instrument(debug_span!("build_request")),
+                )
             };
 
         let svc = HttpBatchService::new(client, request_builder);
@@ -143,7 +150,18 @@ where
             sink,
             inner,
             slot: None,
+            ordering_key: None,
+        }
+    }
+
+    /// Delivers the events rendering the same `ordering_key` in order, see
+    /// the ordering of `BatchSink`.
+    pub fn ordered_by(mut self, ordering_key: Option<Template>) -> Self {
+        if ordering_key.is_some() {
+            self.inner.ordered();
         }
+        self.ordering_key = ordering_key;
+        self
     }
 }
 
@@ -174,9 +192,10 @@ where
     }
 
     fn start_send(self: Pin<&mut Self>, mut event: Event) -> Result<(), Self::Error> {
+        let key = render_ordering_key(self.ordering_key.as_ref(), &event);
         let finalizers = event.metadata_mut().take_finalizers();
         if let Some(item) = self.sink.encode_event(event) {
-            *self.project().slot = Some(EncodedEvent { item, finalizers });
+            *self.project().slot = Some((key, EncodedEvent { item, finalizers }));
         }
 
         Ok(())
@@ -186,7 +205,8 @@ where
         let mut this = self.project();
         if this.slot.is_some() {
             ready!(this.inner.as_mut().poll_ready(cx))?;
-            this.inner.as_mut().start_send(this.slot.take().unwrap())?;
+            let (key, item) = this.slot.take().unwrap();
+            this.inner.as_mut().start_send_keyed(item, key)?;
         }
 
         this.inner.poll_flush(cx)
@@ -272,7 +292,10 @@ where
         let request_builder =
             move |b| -> BoxFuture<'static, crate::Result<http::Request<Vec<u8>>>> {
                 let sink = Arc::clone(&sink1);
-                Box::pin(async move { sink.build_request(b).await })
+                Box::pin(
+                    async move { sink.build_request(b).await }
+                        .instrument(debug_span!("build_request")),
+                )
             };
 
         let svc = HttpBatchService::new(client, request_builder);
@@ -455,6 +478,9 @@ pub struct RequestConfig {
     pub tower: TowerRequestConfig,
     #[serde(default)]
     pub headers: IndexMap<String, String>,
+    /// Events rendering the same key are delivered in order, even with a
+    /// concurrency above one.
+    pub ordering_key: Option<Template>,
 }
 
 impl RequestConfig {
@@ -466,11 +492,35 @@ impl RequestConfig {
     }
 }
 
+/// Renders the `request.ordering_key` of an event. The event is delivered
+/// without ordering when the template fails to render.
+pub fn render_ordering_key(ordering_key: Option<&Template>, event: &Event) -> Option<String> {
+    ordering_key.and_then(|template| {
+        template
+            .render_string(event)
+            .map_err(|error| {
+                emit!(TemplateRenderingFailed {
+                    error,
+                    field: Some("request.ordering_key"),
+                    drop_event: false,
+                })
+            })
+            .ok()
+    })
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::{config::ProxyConfig, sinks::util::service::Concurrency, test_util::next_addr};
-    use futures::{future::ready, StreamExt};
+    use crate::{
+        config::ProxyConfig,
+        sinks::util::{
+            service::Concurrency, BatchSettings, Buffer, Compression, PartitionBuffer,
+            PartitionInnerBuffer,
+        },
+        test_util::next_addr,
+    };
+    use futures::{future::ready, SinkExt, StreamExt};
     use hyper::{
         service::{make_service_fn, service_fn},
         Response, Server, Uri,
@@ -548,6 +598,113 @@ mod test {
         assert_eq!(body.unwrap(), "hello");
     }
 
+    #[tokio::test]
+    async fn util_http_partition_sink_sends_batches_per_partition() {
+        let addr = next_addr();
+        let uri = format!("http://{}:{}/", addr.ip(), addr.port())
+            .parse::<Uri>()
+            .unwrap();
+
+        let (tx, rx) = futures::channel::mpsc::channel(10);
+
+        let new_service = make_service_fn(move |_| {
+            let tx = tx.clone();
+
+            let svc = service_fn(move |req: http::Request<Body>| {
+                let mut tx = tx.clone();
+
+                async move {
+                    let path = req.uri().path().to_string();
+                    let mut body = hyper::body::aggregate(req.into_body())
+                        .await
+                        .map_err(|error| format!("error: {}", error))?;
+                    let string = String::from_utf8(body.copy_to_bytes(body.remaining()).to_vec())
+                        .map_err(|_| "Wasn't UTF-8".to_string())?;
+                    tx.try_send((path, string))
+                        .map_err(|_| "Send error".to_string())?;
+
+                    Ok::<_, crate::Error>(Response::new(Body::from("")))
+                }
+            });
+
+            async move { Ok::<_, std::convert::Infallible>(svc) }
+        });
+
+        tokio::spawn(async move {
+            if let Err(error) = Server::bind(&addr).serve(new_service).await {
+                eprintln!("Server error: {}", error);
+            }
+        });
+
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+        let (acker, _) = Acker::new_for_testing();
+        let client = HttpClient::new(None, &ProxyConfig::default()).unwrap();
+        let request = TowerRequestConfig::default().unwrap_with(&TowerRequestConfig::default());
+        let batch = BatchSettings::default().bytes(9999).events(10).timeout(1);
+        let sink = PartitionHttpSink::new(
+            PartitionedSink { uri },
+            PartitionBuffer::new(Buffer::new(batch.size, Compression::None)),
+            request,
+            batch.timeout,
+            client,
+            acker,
+        )
+        .ordered();
+        let mut sink = Box::pin(sink);
+
+        let events = [("a", "1"), ("b", "2"), ("a", "3")]
+            .iter()
+            .map(|(key, message)| {
+                let mut event = Event::from(*message);
+                event.as_mut_log().insert("key", *key);
+                Ok(event)
+            })
+            .collect::<Vec<_>>();
+        sink.send_all(&mut futures::stream::iter(events))
+            .await
+            .unwrap();
+        sink.close().await.unwrap();
+
+        let mut output = rx.take(2).collect::<Vec<_>>().await;
+        output.sort();
+        assert_eq!(
+            output,
+            vec![
+                ("/a".to_string(), "1\n3\n".to_string()),
+                ("/b".to_string(), "2\n".to_string()),
+            ]
+        );
+    }
+
+    struct PartitionedSink {
+        uri: Uri,
+    }
+
+    #[async_trait::async_trait]
+    impl HttpSink for PartitionedSink {
+        type Input = PartitionInnerBuffer<Vec<u8>, String>;
+        type Output = PartitionInnerBuffer<Vec<u8>, String>;
+
+        fn encode_event(&self, event: Event) -> Option<Self::Input> {
+            let log = event.into_log();
+            let key = log.get("key")?.to_string_lossy();
+            let mut body = log.get("message")?.as_bytes().to_vec();
+            body.push(b'\n');
+            Some(PartitionInnerBuffer::new(body, key))
+        }
+
+        async fn build_request(
+            &self,
+            output: Self::Output,
+        ) -> crate::Result<http::Request<Vec<u8>>> {
+            let (body, key) = output.into_parts();
+            http::Request::post(format!("{}{}", self.uri, key))
+                .body(body)
+                .map_err(Into::into)
+        }
+    }
+
     #[test]
     fn alias_in_flight_limit_works() {
         let cfg = toml::from_str::<RequestConfig>("in_flight_limit = 10")
@@ -1,4 +1,7 @@
-use crate::Error;
+use crate::{
+    internal_events::{SinkRequestFailed, SinkRequestSucceeded},
+    Error,
+};
 use futures::FutureExt;
 use std::{
     cmp,
@@ -98,24 +101,32 @@ where
             Ok(response) => {
                 if self.remaining_attempts == 0 {
                     error!("Retries exhausted; dropping the request.");
+                    emit!(SinkRequestFailed);
                     return None;
                 }
 
                 match self.logic.should_retry_response(response) {
                     RetryAction::Retry(reason) => {
                         warn!(message = "Retrying after response.", reason = %reason);
+                        emit!(SinkRequestFailed);
                         Some(self.build_retry())
                     }
 
                     RetryAction::DontRetry(reason) => {
                         error!(message = "Not retriable; dropping the request.", reason = ?reason);
+                        emit!(SinkRequestFailed);
                         None
                     }
 
-                    RetryAction::Successful => None,
+                    RetryAction::Successful => {
+                        emit!(SinkRequestSucceeded);
+                        None
+                    }
                 }
             }
             Err(error) => {
+                emit!(SinkRequestFailed);
+
                 if self.remaining_attempts == 0 {
                     error!(message = "Retries exhausted; dropping the request.", %error);
                     return None;
@@ -3,6 +3,8 @@ use crate::sinks::util::adaptive_concurrency::{
     AdaptiveConcurrencyLimit, AdaptiveConcurrencyLimitLayer, AdaptiveConcurrencySettings,
 };
 use crate::sinks::util::retries::{FixedRetryPolicy, RetryLogic};
+use crate::sinks::util::service::circuit_breaker::CircuitBreakerLayer;
+pub use crate::sinks::util::service::circuit_breaker::{CircuitBreaker, CircuitBreakerSettings};
 pub use crate::sinks::util::service::concurrency::{concurrency_is_none, Concurrency};
 pub use crate::sinks::util::service::map::Map;
 use crate::sinks::util::service::map::MapLayer;
@@ -19,10 +21,13 @@ use tower::{
     Service, ServiceBuilder,
 };
 
+mod circuit_breaker;
 mod concurrency;
 mod map;
 
-pub type Svc<S, L> = RateLimit<AdaptiveConcurrencyLimit<Retry<FixedRetryPolicy<L>, Timeout<S>>, L>>;
+pub type Svc<S, L> = RateLimit<
+    AdaptiveConcurrencyLimit<Retry<FixedRetryPolicy<L>, CircuitBreaker<Timeout<S>, L>>, L>,
+>;
 pub type TowerBatchedSink<S, B, RL, SL> = BatchSink<Svc<S, RL>, B, SL>;
 pub type TowerPartitionSink<S, B, RL, K, SL> = PartitionBatchSink<Svc<S, RL>, B, K, SL>;
 
@@ -78,6 +83,8 @@ pub struct TowerRequestConfig {
     pub retry_initial_backoff_secs: Option<u64>, // 1
     #[serde(default)]
     pub adaptive_concurrency: AdaptiveConcurrencySettings,
+    #[serde(default)]
+    pub circuit_breaker: CircuitBreakerSettings,
 }
 
 pub const CONCURRENCY_DEFAULT: Concurrency = Concurrency::None;
@@ -100,6 +107,7 @@ impl Default for TowerRequestConfig {
             retry_max_duration_secs: Some(RETRY_MAX_DURATION_SECONDS_DEFAULT),
             retry_initial_backoff_secs: Some(RETRY_INITIAL_BACKOFF_SECONDS_DEFAULT),
             adaptive_concurrency: AdaptiveConcurrencySettings::default(),
+            circuit_breaker: CircuitBreakerSettings::default(),
         }
     }
 }
@@ -116,6 +124,7 @@ impl TowerRequestConfig {
             retry_max_duration_secs: Some(RETRY_MAX_DURATION_SECONDS_DEFAULT),
             retry_initial_backoff_secs: Some(RETRY_INITIAL_BACKOFF_SECONDS_DEFAULT),
             adaptive_concurrency: AdaptiveConcurrencySettings::const_default(),
+            circuit_breaker: CircuitBreakerSettings::const_default(),
         }
     }
 
@@ -156,6 +165,7 @@ impl TowerRequestConfig {
                     .unwrap_or(RETRY_INITIAL_BACKOFF_SECONDS_DEFAULT),
             ),
             adaptive_concurrency: self.adaptive_concurrency,
+            circuit_breaker: self.circuit_breaker,
         }
     }
 
@@ -184,6 +194,7 @@ pub struct TowerRequestSettings {
     pub retry_max_duration_secs: Duration,
     pub retry_initial_backoff_secs: Duration,
     pub adaptive_concurrency: AdaptiveConcurrencySettings,
+    pub circuit_breaker: CircuitBreakerSettings,
 }
 
 impl TowerRequestSettings {
@@ -269,9 +280,10 @@ impl TowerRequestSettings {
             .layer(AdaptiveConcurrencyLimitLayer::new(
                 self.concurrency,
                 self.adaptive_concurrency,
-                retry_logic,
+                retry_logic.clone(),
             ))
             .retry(policy)
+            .layer(CircuitBreakerLayer::new(self.circuit_breaker, retry_logic))
             .timeout(self.timeout)
             .service(service)
     }
@@ -305,6 +317,10 @@ where
                 self.settings.rate_limit_duration,
             )
             .retry(policy)
+            .layer(CircuitBreakerLayer::new(
+                self.settings.circuit_breaker,
+                self.retry_logic.clone(),
+            ))
             .timeout(self.settings.timeout)
             .service(inner);
 
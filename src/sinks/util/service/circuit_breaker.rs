@@ -0,0 +1,501 @@
+//! Holds back the requests to a downstream service that keeps failing, until it recovers.
+//!
+//! The circuit opens after a number of consecutive failed requests, or once the ratio of
+//! failed requests over a window of the latest ones reaches a threshold. While it is open,
+//! the service isn't ready, so that requests and their retries wait instead of hitting the
+//! downstream service. Once the open duration elapsed, the circuit is half-open: a single
+//! probe request is let through, closing the circuit if it succeeds, or opening it again
+//! otherwise.
+
+use crate::{
+    internal_events::{CircuitBreakerClosed, CircuitBreakerHalfOpened, CircuitBreakerOpened},
+    sinks::util::retries::{RetryAction, RetryLogic},
+};
+use futures::ready;
+use pin_project::pin_project;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+use tokio::time::{sleep_until, Instant, Sleep};
+use tower::{Layer, Service};
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CircuitBreakerSettings {
+    pub enabled: bool,
+    /// Number of consecutive failed requests opening the circuit, `0` to disable.
+    pub consecutive_failures: usize,
+    /// Ratio of failed requests, among the latest `window` ones, opening the circuit.
+    pub error_rate: Option<f64>,
+    pub window: usize,
+    /// Seconds the circuit stays open before being probed.
+    pub open_secs: u64,
+}
+
+impl CircuitBreakerSettings {
+    pub const fn const_default() -> Self {
+        Self {
+            enabled: false,
+            consecutive_failures: 5,
+            error_rate: None,
+            window: 100,
+            open_secs: 30,
+        }
+    }
+}
+
+impl Default for CircuitBreakerSettings {
+    fn default() -> Self {
+        CircuitBreakerSettings::const_default()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Circuit {
+    Closed,
+    /// Open until the given instant
+    Open(Instant),
+    /// Half-open, and whether the probe request was let through
+    HalfOpen {
+        probing: bool,
+    },
+}
+
+#[derive(Debug)]
+struct Breaker {
+    settings: CircuitBreakerSettings,
+    circuit: Circuit,
+    consecutive_failures: usize,
+    /// Whether each of the latest requests failed, oldest first
+    outcomes: VecDeque<bool>,
+    /// Tasks waiting for the probe of the half-open circuit to complete
+    waiters: Vec<Waker>,
+}
+
+impl Breaker {
+    fn new(settings: CircuitBreakerSettings) -> Self {
+        Self {
+            settings,
+            circuit: Circuit::Closed,
+            consecutive_failures: 0,
+            outcomes: VecDeque::new(),
+            waiters: Vec::new(),
+        }
+    }
+
+    fn reset(&mut self, circuit: Circuit) {
+        self.circuit = circuit;
+        self.consecutive_failures = 0;
+        self.outcomes.clear();
+        for waker in self.waiters.drain(..) {
+            waker.wake();
+        }
+    }
+
+    fn open(&mut self, reason: &'static str) {
+        let duration = Duration::from_secs(self.settings.open_secs);
+        self.reset(Circuit::Open(Instant::now() + duration));
+        emit!(CircuitBreakerOpened { reason, duration });
+    }
+
+    fn close(&mut self) {
+        self.reset(Circuit::Closed);
+        emit!(CircuitBreakerClosed);
+    }
+
+    /// Lets another request probe the half-open circuit, as the previous probe was dropped
+    /// before completing.
+    fn release_probe(&mut self) {
+        if self.circuit == (Circuit::HalfOpen { probing: true }) {
+            self.reset(Circuit::HalfOpen { probing: false });
+        }
+    }
+
+    fn record_probe(&mut self, failed: bool) {
+        if failed {
+            self.open("probe_failed");
+        } else {
+            self.close();
+        }
+    }
+
+    fn record(&mut self, failed: bool) {
+        // Requests sent before the circuit opened may complete while it isn't closed
+        // anymore, and don't change its state.
+        if !self.settings.enabled || self.circuit != Circuit::Closed {
+            return;
+        }
+
+        self.consecutive_failures = if failed {
+            self.consecutive_failures + 1
+        } else {
+            0
+        };
+        if self.outcomes.len() >= self.settings.window {
+            self.outcomes.pop_front();
+        }
+        self.outcomes.push_back(failed);
+
+        let error_rate = (self.outcomes.len() >= self.settings.window).then(|| {
+            self.outcomes.iter().filter(|failed| **failed).count() as f64
+                / self.outcomes.len() as f64
+        });
+
+        if self.settings.consecutive_failures > 0
+            && self.consecutive_failures >= self.settings.consecutive_failures
+        {
+            self.open("consecutive_failures");
+        } else if let (Some(threshold), Some(error_rate)) = (self.settings.error_rate, error_rate) {
+            if error_rate >= threshold {
+                self.open("error_rate");
+            }
+        }
+    }
+}
+
+/// Permit to send the probe of a half-open circuit, released if dropped before the probe
+/// completed.
+#[derive(Debug)]
+struct Probe {
+    breaker: Arc<Mutex<Breaker>>,
+    completed: bool,
+}
+
+impl Drop for Probe {
+    fn drop(&mut self) {
+        if !self.completed {
+            self.breaker
+                .lock()
+                .expect("Circuit breaker mutex is poisoned")
+                .release_probe();
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct CircuitBreakerLayer<L> {
+    settings: CircuitBreakerSettings,
+    logic: L,
+}
+
+impl<L> CircuitBreakerLayer<L> {
+    pub(crate) const fn new(settings: CircuitBreakerSettings, logic: L) -> Self {
+        Self { settings, logic }
+    }
+}
+
+impl<S, L: Clone> Layer<S> for CircuitBreakerLayer<L> {
+    type Service = CircuitBreaker<S, L>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        CircuitBreaker::new(inner, self.settings, self.logic.clone())
+    }
+}
+
+/// Service holding back requests while its circuit is open. Its clones share the same
+/// circuit.
+#[derive(Debug)]
+pub struct CircuitBreaker<S, L> {
+    inner: S,
+    logic: L,
+    breaker: Arc<Mutex<Breaker>>,
+    /// Wakes this handle once the open circuit can be probed
+    sleep: Option<Pin<Box<Sleep>>>,
+    /// Acquired when this handle is ready to send the probe
+    probe: Option<Probe>,
+}
+
+impl<S, L> CircuitBreaker<S, L> {
+    pub(crate) fn new(inner: S, settings: CircuitBreakerSettings, logic: L) -> Self {
+        Self {
+            inner,
+            logic,
+            breaker: Arc::new(Mutex::new(Breaker::new(settings))),
+            sleep: None,
+            probe: None,
+        }
+    }
+
+    fn poll_circuit(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        if self.probe.is_some() {
+            return Poll::Ready(());
+        }
+
+        loop {
+            let mut breaker = self
+                .breaker
+                .lock()
+                .expect("Circuit breaker mutex is poisoned");
+            let circuit = breaker.circuit;
+            match circuit {
+                Circuit::Closed => return Poll::Ready(()),
+                Circuit::Open(until) if Instant::now() >= until => {
+                    breaker.circuit = Circuit::HalfOpen { probing: false };
+                    emit!(CircuitBreakerHalfOpened);
+                }
+                Circuit::Open(until) => {
+                    drop(breaker);
+                    let sleep = self
+                        .sleep
+                        .get_or_insert_with(|| Box::pin(sleep_until(until)));
+                    if sleep.deadline() != until {
+                        sleep.as_mut().reset(until);
+                    }
+                    ready!(sleep.as_mut().poll(cx));
+                }
+                Circuit::HalfOpen { probing: false } => {
+                    breaker.circuit = Circuit::HalfOpen { probing: true };
+                    self.probe = Some(Probe {
+                        breaker: Arc::clone(&self.breaker),
+                        completed: false,
+                    });
+                    return Poll::Ready(());
+                }
+                Circuit::HalfOpen { probing: true } => {
+                    if !breaker.waiters.iter().any(|w| w.will_wake(cx.waker())) {
+                        breaker.waiters.push(cx.waker().clone());
+                    }
+                    return Poll::Pending;
+                }
+            }
+        }
+    }
+}
+
+impl<S: Clone, L: Clone> Clone for CircuitBreaker<S, L> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            logic: self.logic.clone(),
+            breaker: Arc::clone(&self.breaker),
+            sleep: None,
+            probe: None,
+        }
+    }
+}
+
+impl<S, L, Request> Service<Request> for CircuitBreaker<S, L>
+where
+    S: Service<Request>,
+    S::Error: Into<crate::Error>,
+    L: RetryLogic<Response = S::Response>,
+{
+    type Response = S::Response;
+    type Error = crate::Error;
+    type Future = ResponseFuture<S::Future, L>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        ready!(self.poll_circuit(cx));
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        ResponseFuture {
+            inner: self.inner.call(request),
+            logic: self.logic.clone(),
+            breaker: Arc::clone(&self.breaker),
+            probe: self.probe.take(),
+        }
+    }
+}
+
+/// Future for the `CircuitBreaker` service, recording whether the request failed.
+#[pin_project]
+#[derive(Debug)]
+pub struct ResponseFuture<F, L> {
+    #[pin]
+    inner: F,
+    logic: L,
+    breaker: Arc<Mutex<Breaker>>,
+    probe: Option<Probe>,
+}
+
+impl<F, L, E> Future for ResponseFuture<F, L>
+where
+    F: Future<Output = Result<L::Response, E>>,
+    L: RetryLogic,
+    E: Into<crate::Error>,
+{
+    type Output = Result<L::Response, crate::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let future = self.project();
+        let output = ready!(future.inner.poll(cx)).map_err(Into::into);
+
+        // Requests are failed when they would be retried. Errors of unexpected types, such
+        // as timeouts, are failures too.
+        let failed = match &output {
+            Ok(response) => matches!(
+                future.logic.should_retry_response(response),
+                RetryAction::Retry(_)
+            ),
+            Err(error) => error
+                .downcast_ref::<L::Error>()
+                .map_or(true, |error| future.logic.is_retriable_error(error)),
+        };
+
+        // The probe is marked completed before locking, as releasing it would lock too.
+        let is_probe = future.probe.take().map_or(false, |mut probe| {
+            probe.completed = true;
+            true
+        });
+        let mut breaker = future
+            .breaker
+            .lock()
+            .expect("Circuit breaker mutex is poisoned");
+        if is_probe {
+            breaker.record_probe(failed);
+        } else {
+            breaker.record(failed);
+        }
+
+        Poll::Ready(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::future;
+    use std::{
+        io,
+        sync::atomic::{AtomicBool, Ordering},
+    };
+    use tokio_test::{assert_pending, assert_ready_ok, task};
+
+    #[derive(Clone, Debug)]
+    struct RetryErrors;
+
+    impl RetryLogic for RetryErrors {
+        type Error = io::Error;
+        type Response = ();
+
+        fn is_retriable_error(&self, _: &Self::Error) -> bool {
+            true
+        }
+    }
+
+    fn service(
+        settings: CircuitBreakerSettings,
+    ) -> (
+        CircuitBreaker<impl Service<(), Response = (), Error = io::Error> + Clone, RetryErrors>,
+        Arc<AtomicBool>,
+    ) {
+        let failing = Arc::new(AtomicBool::new(true));
+        let service = {
+            let failing = Arc::clone(&failing);
+            tower::service_fn(move |_: ()| {
+                future::ready(if failing.load(Ordering::Relaxed) {
+                    Err(io::Error::new(io::ErrorKind::Other, "down"))
+                } else {
+                    Ok(())
+                })
+            })
+        };
+        (CircuitBreaker::new(service, settings, RetryErrors), failing)
+    }
+
+    /// Sends a request once the service is ready, returning whether it succeeded.
+    async fn send<S>(service: &mut S) -> bool
+    where
+        S: Service<(), Response = (), Error = crate::Error>,
+    {
+        assert_ready_ok!(task::spawn(()).enter(|cx, _| service.poll_ready(cx)));
+        service.call(()).await.is_ok()
+    }
+
+    #[tokio::test]
+    async fn opens_after_consecutive_failures_then_probes() {
+        tokio::time::pause();
+        let (mut service, failing) = service(CircuitBreakerSettings {
+            enabled: true,
+            consecutive_failures: 2,
+            ..CircuitBreakerSettings::default()
+        });
+        let mut other = service.clone();
+
+        assert!(!send(&mut service).await);
+        assert!(!send(&mut service).await);
+
+        let mut task = task::spawn(());
+        assert_pending!(task.enter(|cx, _| service.poll_ready(cx)));
+
+        tokio::time::advance(Duration::from_secs(30)).await;
+        assert!(task.is_woken());
+
+        // A single request probes the half-open circuit.
+        assert_ready_ok!(task.enter(|cx, _| service.poll_ready(cx)));
+        let mut other_task = task::spawn(());
+        assert_pending!(other_task.enter(|cx, _| other.poll_ready(cx)));
+
+        failing.store(false, Ordering::Relaxed);
+        assert!(service.call(()).await.is_ok());
+
+        assert!(other_task.is_woken());
+        assert_ready_ok!(other_task.enter(|cx, _| other.poll_ready(cx)));
+    }
+
+    #[tokio::test]
+    async fn opens_on_error_rate_and_reopens_on_failed_probe() {
+        tokio::time::pause();
+        let (mut service, failing) = service(CircuitBreakerSettings {
+            enabled: true,
+            consecutive_failures: 0,
+            error_rate: Some(0.5),
+            window: 4,
+            open_secs: 10,
+        });
+
+        for failed in &[true, false, true] {
+            failing.store(*failed, Ordering::Relaxed);
+            assert_eq!(send(&mut service).await, !failed);
+        }
+        failing.store(false, Ordering::Relaxed);
+        assert!(send(&mut service).await);
+
+        let mut task = task::spawn(());
+        assert_pending!(task.enter(|cx, _| service.poll_ready(cx)));
+
+        tokio::time::advance(Duration::from_secs(10)).await;
+        failing.store(true, Ordering::Relaxed);
+        assert!(!send(&mut service).await);
+        assert_pending!(task.enter(|cx, _| service.poll_ready(cx)));
+    }
+
+    #[tokio::test]
+    async fn dropped_probe_is_released() {
+        tokio::time::pause();
+        let (mut service, _) = service(CircuitBreakerSettings {
+            enabled: true,
+            consecutive_failures: 1,
+            ..CircuitBreakerSettings::default()
+        });
+        let mut other = service.clone();
+
+        assert!(!send(&mut service).await);
+        tokio::time::advance(Duration::from_secs(30)).await;
+
+        assert_ready_ok!(task::spawn(()).enter(|cx, _| service.poll_ready(cx)));
+        let mut other_task = task::spawn(());
+        assert_pending!(other_task.enter(|cx, _| other.poll_ready(cx)));
+
+        drop(service.call(()));
+        assert!(other_task.is_woken());
+        assert_ready_ok!(other_task.enter(|cx, _| other.poll_ready(cx)));
+    }
+
+    #[tokio::test]
+    async fn disabled_never_opens() {
+        let (mut service, _) = service(CircuitBreakerSettings::default());
+
+        for _ in 0..10 {
+            assert!(!send(&mut service).await);
+        }
+    }
+}
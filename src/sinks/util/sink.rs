@@ -80,6 +80,15 @@ pub use vector_core::sink::StreamSink;
 /// batches have been acked. This means if sequential requests r1, r2,
 /// and r3 are dispatched and r2 and r3 complete, all events contained
 /// in all requests will not be acked until r1 has completed.
+///
+/// # Ordering
+///
+/// With a request concurrency above one, batches may reach the service out
+/// of order. Once `ordered` is called, events sent with `start_send_keyed`
+/// are batched separately for each ordering key, and a batch is only sent
+/// once the previous request with the same key has completed, including
+/// all of its retries. Batches with different keys are still sent
+/// concurrently.
 #[pin_project]
 #[derive(Debug)]
 pub struct BatchSink<S, B, L>
@@ -89,13 +98,17 @@ where
 {
     #[pin]
     inner: PartitionBatchSink<
-        Map<S, PartitionInnerBuffer<B::Output, ()>, B::Output>,
-        PartitionBuffer<B, ()>,
-        (),
+        Map<S, PartitionInnerBuffer<B::Output, OrderingKey>, B::Output>,
+        PartitionBuffer<B, OrderingKey>,
+        OrderingKey,
         L,
     >,
 }
 
+/// The key of the events that must be delivered in order, events without a
+/// key being ordered together.
+pub type OrderingKey = Option<String>;
+
 impl<S, B> BatchSink<S, B, StdServiceLogic<S::Response>>
 where
     S: Service<B::Output>,
@@ -126,12 +139,31 @@ where
         logic: SL,
     ) -> Self {
         let service = ServiceBuilder::new()
-            .map(|req: PartitionInnerBuffer<B::Output, ()>| req.into_parts().0)
+            .map(|req: PartitionInnerBuffer<B::Output, OrderingKey>| req.into_parts().0)
             .service(service);
         let batch = PartitionBuffer::new(batch);
         let inner = PartitionBatchSink::new_with_logic(service, batch, timeout, acker, logic);
         Self { inner }
     }
+
+    /// Enforces the ordering of requests per ordering key.
+    pub fn ordered(&mut self) {
+        self.inner.ordered();
+    }
+
+    /// Like `start_send`, but batches the event with the others sharing its
+    /// ordering key.
+    pub fn start_send_keyed(
+        self: Pin<&mut Self>,
+        item: EncodedEvent<B::Input>,
+        key: OrderingKey,
+    ) -> crate::Result<()> {
+        let EncodedEvent { item, finalizers } = item;
+        self.project().inner.start_send(EncodedEvent {
+            item: PartitionInnerBuffer::new(item, key),
+            finalizers,
+        })
+    }
 }
 
 #[cfg(test)]
@@ -161,11 +193,7 @@ where
     }
 
     fn start_send(self: Pin<&mut Self>, item: EncodedEvent<B::Input>) -> Result<(), Self::Error> {
-        let EncodedEvent { item, finalizers } = item;
-        self.project().inner.start_send(EncodedEvent {
-            item: PartitionInnerBuffer::new(item, ()),
-            finalizers,
-        })
+        self.start_send_keyed(item, None)
     }
 
     fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
@@ -1154,6 +1182,61 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn batch_sink_ordering_per_key() {
+        let (acker, _) = Acker::new_for_testing();
+        let sent_requests = Arc::new(Mutex::new(Vec::new()));
+
+        let mut delay = true;
+        let svc = tower::service_fn(|req: Vec<(usize, usize)>| {
+            let sent_requests = Arc::clone(&sent_requests);
+            if delay {
+                delay = false;
+                sleep(Duration::from_secs(1))
+                    .map(move |_| {
+                        sent_requests.lock().unwrap().push(req);
+                        Result::<_, std::io::Error>::Ok(())
+                    })
+                    .boxed()
+            } else {
+                sent_requests.lock().unwrap().push(req);
+                future::ok::<_, std::io::Error>(()).boxed()
+            }
+        });
+
+        let batch = BatchSettings::default().bytes(9999).events(10);
+        let mut sink = BatchSink::new(svc, VecBuffer::new(batch.size), TIMEOUT, acker);
+        sink.ordered();
+        let mut sink = Box::pin(sink);
+
+        let input = (0..20)
+            .into_iter()
+            .map(|i| (0, i))
+            .chain((0..20).into_iter().map(|i| (1, i)));
+        for item in input {
+            future::poll_fn(|cx| sink.as_mut().poll_ready(cx))
+                .await
+                .unwrap();
+            sink.as_mut()
+                .start_send_keyed(EncodedEvent::new(item), Some(item.0.to_string()))
+                .unwrap();
+        }
+        sink.close().await.unwrap();
+
+        // Only the first request of the '0' key is delayed, which delays the second one
+        // with the same key but not the requests of the '1' key.
+        let output = sent_requests.lock().unwrap();
+        assert_eq!(
+            &*output,
+            &vec![
+                (0..10).into_iter().map(|i| (1, i)).collect::<Vec<_>>(),
+                (10..20).into_iter().map(|i| (1, i)).collect(),
+                (0..10).into_iter().map(|i| (0, i)).collect(),
+                (10..20).into_iter().map(|i| (0, i)).collect(),
+            ]
+        );
+    }
+
     #[derive(Debug, PartialEq, Eq, Ord, PartialOrd)]
     enum Partitions {
         A,
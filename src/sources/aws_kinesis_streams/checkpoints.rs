@@ -0,0 +1,165 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+/// The read position of a single shard.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub(super) struct ShardCheckpoint {
+    /// The sequence number of the last record whose events were delivered.
+    pub(super) sequence_number: Option<String>,
+    /// Set once every record of a closed shard has been delivered.
+    #[serde(default)]
+    pub(super) finished: bool,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct State {
+    version: u32,
+    shards: HashMap<String, ShardCheckpoint>,
+}
+
+const STATE_VERSION: u32 = 1;
+
+/// Tracks the checkpoints of all shards of a stream and persists them to a
+/// JSON file, so reading resumes where it left off after a restart.
+#[derive(Debug)]
+pub(super) struct Checkpoints {
+    path: PathBuf,
+    tmp_path: PathBuf,
+    shards: Mutex<HashMap<String, ShardCheckpoint>>,
+}
+
+impl Checkpoints {
+    pub(super) fn load(path: PathBuf) -> io::Result<Self> {
+        let shards = match fs::read(&path) {
+            Ok(data) => {
+                let state: State = serde_json::from_slice(&data)
+                    .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+                state.shards
+            }
+            Err(error) if error.kind() == io::ErrorKind::NotFound => HashMap::new(),
+            Err(error) => return Err(error),
+        };
+
+        Ok(Self {
+            tmp_path: path.with_extension("json.tmp"),
+            path,
+            shards: Mutex::new(shards),
+        })
+    }
+
+    pub(super) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub(super) fn get(&self, shard_id: &str) -> Option<ShardCheckpoint> {
+        self.shards.lock().unwrap().get(shard_id).cloned()
+    }
+
+    pub(super) fn contains(&self, shard_id: &str) -> bool {
+        self.shards.lock().unwrap().contains_key(shard_id)
+    }
+
+    pub(super) fn is_finished(&self, shard_id: &str) -> bool {
+        self.shards
+            .lock()
+            .unwrap()
+            .get(shard_id)
+            .map_or(false, |checkpoint| checkpoint.finished)
+    }
+
+    /// Advances the checkpoint of a shard. A missing sequence number keeps
+    /// the previous one, which happens when a closed shard yields no more
+    /// records.
+    pub(super) fn update(&self, shard_id: &str, sequence_number: Option<String>, finished: bool) {
+        let mut shards = self.shards.lock().unwrap();
+        let checkpoint = shards.entry(shard_id.to_owned()).or_default();
+        if sequence_number.is_some() {
+            checkpoint.sequence_number = sequence_number;
+        }
+        checkpoint.finished |= finished;
+    }
+
+    /// Forgets shards that no longer exist in the stream, so the file does
+    /// not keep growing as the stream is resharded.
+    pub(super) fn retain(&self, mut exists: impl FnMut(&str) -> bool) {
+        self.shards
+            .lock()
+            .unwrap()
+            .retain(|shard_id, _| exists(shard_id));
+    }
+
+    /// Writes the checkpoints to a temporary file first, then moves it in
+    /// place, so a crash never leaves a partially written file behind.
+    pub(super) fn persist(&self) -> io::Result<()> {
+        let state = State {
+            version: STATE_VERSION,
+            shards: self.shards.lock().unwrap().clone(),
+        };
+        let data = serde_json::to_vec(&state)?;
+        fs::write(&self.tmp_path, data)?;
+        fs::rename(&self.tmp_path, &self.path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn persists_and_loads_checkpoints() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("stream.json");
+
+        let checkpoints = Checkpoints::load(path.clone()).unwrap();
+        assert_eq!(checkpoints.get("shard-0"), None);
+        checkpoints.update("shard-0", Some("1".into()), false);
+        checkpoints.update("shard-1", Some("2".into()), false);
+        checkpoints.update("shard-1", None, true);
+        checkpoints.persist().unwrap();
+
+        let checkpoints = Checkpoints::load(path).unwrap();
+        assert_eq!(
+            checkpoints.get("shard-0"),
+            Some(ShardCheckpoint {
+                sequence_number: Some("1".into()),
+                finished: false,
+            })
+        );
+        assert_eq!(
+            checkpoints.get("shard-1"),
+            Some(ShardCheckpoint {
+                sequence_number: Some("2".into()),
+                finished: true,
+            })
+        );
+    }
+
+    #[test]
+    fn forgets_removed_shards() {
+        let dir = tempdir().unwrap();
+        let checkpoints = Checkpoints::load(dir.path().join("stream.json")).unwrap();
+        checkpoints.update("shard-0", Some("1".into()), true);
+        checkpoints.update("shard-1", Some("2".into()), false);
+
+        checkpoints.retain(|shard_id| shard_id == "shard-1");
+
+        assert!(!checkpoints.contains("shard-0"));
+        assert!(checkpoints.contains("shard-1"));
+    }
+
+    #[test]
+    fn rejects_invalid_file() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("stream.json");
+        fs::write(&path, "not json").unwrap();
+
+        let error = Checkpoints::load(path).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,149 @@
+//! De-aggregation of records produced by the Kinesis Producer Library (KPL).
+//!
+//! The KPL packs many user records into a single Kinesis record, formatted
+//! as a magic number, followed by a protobuf encoded `AggregatedRecord`, and
+//! the MD5 digest of that protobuf message.
+//!
+//! See <https://github.com/awslabs/amazon-kinesis-producer/blob/master/aggregation-format.md>.
+
+use bytes::Bytes;
+use md5::{Digest, Md5};
+use prost::Message;
+
+const MAGIC: [u8; 4] = [0xF3, 0x89, 0x9A, 0xC2];
+const DIGEST_SIZE: usize = 16;
+
+#[derive(Clone, PartialEq, Message)]
+struct AggregatedRecord {
+    #[prost(string, repeated, tag = "1")]
+    partition_key_table: Vec<String>,
+    #[prost(string, repeated, tag = "2")]
+    explicit_hash_key_table: Vec<String>,
+    #[prost(message, repeated, tag = "3")]
+    records: Vec<Record>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct Record {
+    #[prost(uint64, required, tag = "1")]
+    partition_key_index: u64,
+    #[prost(uint64, optional, tag = "2")]
+    explicit_hash_key_index: Option<u64>,
+    #[prost(bytes = "vec", required, tag = "3")]
+    data: Vec<u8>,
+}
+
+/// Splits a Kinesis record into the user records it contains, along with
+/// their partition keys. Records that were not aggregated by the KPL are
+/// returned as is.
+pub(super) fn deaggregate(partition_key: String, data: Bytes) -> Vec<(String, Bytes)> {
+    match parse(&data) {
+        Some(AggregatedRecord {
+            partition_key_table,
+            records,
+            ..
+        }) => records
+            .into_iter()
+            .map(|record| {
+                let partition_key = partition_key_table
+                    .get(record.partition_key_index as usize)
+                    .cloned()
+                    .unwrap_or_else(|| partition_key.clone());
+                (partition_key, Bytes::from(record.data))
+            })
+            .collect(),
+        None => vec![(partition_key, data)],
+    }
+}
+
+fn parse(data: &[u8]) -> Option<AggregatedRecord> {
+    let body = data.strip_prefix(&MAGIC)?;
+    if body.len() < DIGEST_SIZE {
+        return None;
+    }
+
+    let (message, digest) = body.split_at(body.len() - DIGEST_SIZE);
+    if Md5::digest(message).as_slice() != digest {
+        return None;
+    }
+
+    AggregatedRecord::decode(message).ok()
+}
+
+#[cfg(test)]
+pub(super) fn aggregate(records: &[(&str, &str)]) -> Bytes {
+    let mut partition_key_table = Vec::<String>::new();
+    let records = records
+        .iter()
+        .map(|(partition_key, data)| {
+            let index = match partition_key_table
+                .iter()
+                .position(|key| key == partition_key)
+            {
+                Some(index) => index,
+                None => {
+                    partition_key_table.push(partition_key.to_string());
+                    partition_key_table.len() - 1
+                }
+            };
+            Record {
+                partition_key_index: index as u64,
+                explicit_hash_key_index: None,
+                data: data.as_bytes().to_vec(),
+            }
+        })
+        .collect();
+    let mut message = Vec::new();
+    AggregatedRecord {
+        partition_key_table,
+        explicit_hash_key_table: Vec::new(),
+        records,
+    }
+    .encode(&mut message)
+    .unwrap();
+
+    let mut data = MAGIC.to_vec();
+    data.extend_from_slice(&message);
+    data.extend_from_slice(Md5::digest(&message).as_slice());
+    data.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deaggregates_kpl_records() {
+        let data = aggregate(&[("a", "foo"), ("b", "bar"), ("a", "baz")]);
+
+        let records = deaggregate("outer".into(), data);
+
+        assert_eq!(
+            records,
+            vec![
+                ("a".into(), Bytes::from("foo")),
+                ("b".into(), Bytes::from("bar")),
+                ("a".into(), Bytes::from("baz")),
+            ]
+        );
+    }
+
+    #[test]
+    fn passes_through_plain_records() {
+        let records = deaggregate("key".into(), Bytes::from("foo"));
+
+        assert_eq!(records, vec![("key".into(), Bytes::from("foo"))]);
+    }
+
+    #[test]
+    fn passes_through_records_with_invalid_digest() {
+        let mut data = aggregate(&[("a", "foo")]).to_vec();
+        let last = data.len() - 1;
+        data[last] ^= 0xFF;
+        let data = Bytes::from(data);
+
+        let records = deaggregate("key".into(), data.clone());
+
+        assert_eq!(records, vec![("key".into(), data)]);
+    }
+}
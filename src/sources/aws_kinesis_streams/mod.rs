@@ -0,0 +1,857 @@
+use crate::{
+    codecs::{self, DecodingConfig},
+    config::{
+        log_schema, DataType, GenerateConfig, SourceConfig, SourceContext, SourceDescription,
+    },
+    event::{BatchNotifier, BatchStatus, Event, Value},
+    internal_events::{
+        AwsKinesisStreamsBatchFailed, AwsKinesisStreamsCheckpointFailed,
+        AwsKinesisStreamsEventReceived, AwsKinesisStreamsRequestFailed,
+    },
+    rusoto::{self, AwsAuthentication, RegionOrEndpoint},
+    shutdown::ShutdownSignal,
+    sources::util::finalizer::OrderedFinalizer,
+    Pipeline,
+};
+use bytes::Bytes;
+use checkpoints::Checkpoints;
+use chrono::{TimeZone, Utc};
+use deaggregation::deaggregate;
+use futures::{future::Shared, stream, FutureExt, SinkExt, StreamExt};
+use rusoto_core::RusotoError;
+use rusoto_kinesis::{
+    GetRecordsError, GetRecordsInput, GetShardIteratorInput, Kinesis, KinesisClient,
+    ListShardsError, ListShardsInput, Record, Shard,
+};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{
+    collections::HashSet,
+    convert::TryInto,
+    io,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::sync::mpsc;
+use tokio_util::codec::FramedRead;
+use tracing::Instrument;
+
+mod checkpoints;
+mod deaggregation;
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Could not load checkpoints from {:?}: {}", path, source))]
+    LoadCheckpoints { path: PathBuf, source: io::Error },
+}
+
+#[derive(Derivative, Clone, Debug, Deserialize, Serialize)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub struct KinesisSourceConfig {
+    pub stream_name: String,
+    #[serde(flatten)]
+    pub region: RegionOrEndpoint,
+    #[serde(default)]
+    pub auth: AwsAuthentication,
+    #[serde(default)]
+    pub start_position: StartPosition,
+    #[serde(default = "default_poll_interval_ms")]
+    #[derivative(Default(value = "default_poll_interval_ms()"))]
+    pub poll_interval_ms: u64,
+    #[serde(default = "default_shard_refresh_secs")]
+    #[derivative(Default(value = "default_shard_refresh_secs()"))]
+    pub shard_refresh_secs: u64,
+    // restricted to u32 for safe conversion to i64 later
+    #[serde(default = "default_max_records")]
+    #[derivative(Default(value = "default_max_records()"))]
+    pub max_records: u32,
+    pub data_dir: Option<PathBuf>,
+    #[serde(flatten)]
+    pub decoding: DecodingConfig,
+}
+
+/// Where to start reading shards that have no checkpoint yet.
+#[derive(Derivative, Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum StartPosition {
+    /// The oldest record still retained in the shard.
+    TrimHorizon,
+    /// Records added to the shard after the source started.
+    #[derivative(Default)]
+    Latest,
+}
+
+impl StartPosition {
+    const fn iterator_type(self) -> &'static str {
+        match self {
+            Self::TrimHorizon => "TRIM_HORIZON",
+            Self::Latest => "LATEST",
+        }
+    }
+}
+
+const fn default_poll_interval_ms() -> u64 {
+    1000
+}
+
+const fn default_shard_refresh_secs() -> u64 {
+    10
+}
+
+const fn default_max_records() -> u32 {
+    1000
+}
+
+inventory::submit! {
+    SourceDescription::new::<KinesisSourceConfig>("aws_kinesis_streams")
+}
+
+impl GenerateConfig for KinesisSourceConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"stream_name = "my-stream"
+            region = "us-east-1""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "aws_kinesis_streams")]
+impl SourceConfig for KinesisSourceConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let data_dir = cx
+            .globals
+            // source are only global, name can be used for subdir
+            .resolve_and_make_data_subdir(self.data_dir.as_ref(), cx.key.id())?;
+        // Shard ids are only unique within a stream, so checkpoints are kept
+        // per stream in case the configured stream changes.
+        let path = data_dir.join(format!("{}.json", self.stream_name));
+        let checkpoints = Checkpoints::load(path.clone()).context(LoadCheckpoints { path })?;
+
+        let region = (&self.region).try_into()?;
+        let client = rusoto::client(&cx.proxy)?;
+        let creds = self.auth.build(&region, None)?;
+
+        let state = Arc::new(State {
+            client: KinesisClient::new_with(client, creds, region),
+            stream_name: self.stream_name.clone(),
+            poll_interval: Duration::from_millis(self.poll_interval_ms),
+            max_records: self.max_records.into(),
+            decoder: self.decoding.build()?,
+            checkpoints,
+        });
+
+        Ok(Box::pin(run(
+            state,
+            self.start_position,
+            Duration::from_secs(self.shard_refresh_secs),
+            cx.out,
+            cx.shutdown,
+            cx.acknowledgements,
+        )))
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn source_type(&self) -> &'static str {
+        "aws_kinesis_streams"
+    }
+
+    fn decoding(&self) -> Option<&DecodingConfig> {
+        Some(&self.decoding)
+    }
+}
+
+struct State {
+    client: KinesisClient,
+    stream_name: String,
+    poll_interval: Duration,
+    max_records: i64,
+    decoder: codecs::Decoder,
+    checkpoints: Checkpoints,
+}
+
+impl State {
+    fn advance(&self, progress: ShardProgress) {
+        self.checkpoints.update(
+            &progress.shard_id,
+            progress.sequence_number,
+            progress.finished,
+        );
+        if let Err(error) = self.checkpoints.persist() {
+            emit!(AwsKinesisStreamsCheckpointFailed {
+                path: self.checkpoints.path(),
+                error,
+            });
+        }
+    }
+
+    /// Advances the checkpoint of a shard only if its batch was delivered.
+    /// Once a batch fails, none of the batches read after it by the same
+    /// reader are checkpointed, so the shard is read again from there.
+    fn finalize(&self, status: BatchStatus, progress: ShardProgress) {
+        if progress.failed.load(Ordering::Relaxed) {
+            return;
+        }
+        match status {
+            BatchStatus::Delivered => self.advance(progress),
+            status => {
+                progress.failed.store(true, Ordering::Relaxed);
+                emit!(AwsKinesisStreamsBatchFailed {
+                    shard_id: &progress.shard_id,
+                    status,
+                });
+            }
+        }
+    }
+}
+
+/// The position a shard reader reached with a batch of records, recorded
+/// once the events of that batch are acknowledged.
+#[derive(Debug)]
+struct ShardProgress {
+    shard_id: String,
+    sequence_number: Option<String>,
+    finished: bool,
+    /// Set once a batch of the reader that produced this progress failed.
+    failed: Arc<AtomicBool>,
+}
+
+async fn run(
+    state: Arc<State>,
+    start_position: StartPosition,
+    shard_refresh_interval: Duration,
+    out: Pipeline,
+    shutdown: ShutdownSignal,
+    acknowledgements: bool,
+) -> Result<(), ()> {
+    let mut shutdown = shutdown.shared();
+    let finalizer = acknowledgements.then(|| {
+        let state = Arc::clone(&state);
+        Arc::new(OrderedFinalizer::new(
+            shutdown.clone(),
+            move |status, progress: ShardProgress| state.finalize(status, progress),
+        ))
+    });
+
+    let (done_tx, mut done_rx) = mpsc::unbounded_channel();
+    let mut running = HashSet::new();
+    let mut refresh = tokio::time::interval(shard_refresh_interval);
+    loop {
+        tokio::select! {
+            _ = &mut shutdown => break,
+            Some(shard_id) = done_rx.recv() => {
+                running.remove(&shard_id);
+            }
+            _ = refresh.tick() => {
+                let shards = match list_shards(&state).await {
+                    Ok(shards) => shards,
+                    Err(error) => {
+                        emit!(AwsKinesisStreamsRequestFailed {
+                            operation: "ListShards",
+                            shard_id: None,
+                            error: &error,
+                        });
+                        continue;
+                    }
+                };
+
+                let listed = shards
+                    .iter()
+                    .map(|shard| shard.shard_id.as_str())
+                    .collect::<HashSet<_>>();
+                state.checkpoints.retain(|shard_id| listed.contains(shard_id));
+
+                for (shard_id, start_position) in
+                    readable_shards(&shards, &running, &state.checkpoints, start_position)
+                {
+                    debug!(message = "Reading shard.", shard_id = %shard_id);
+                    running.insert(shard_id.clone());
+                    let reader = ShardReader {
+                        state: Arc::clone(&state),
+                        sequence_number: state
+                            .checkpoints
+                            .get(&shard_id)
+                            .and_then(|checkpoint| checkpoint.sequence_number),
+                        shard_id,
+                        start_position,
+                        out: out.clone(),
+                        shutdown: shutdown.clone(),
+                        finalizer: finalizer.clone(),
+                        failed: Arc::default(),
+                    };
+                    tokio::spawn(reader.run(done_tx.clone()).in_current_span());
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn list_shards(state: &State) -> Result<Vec<Shard>, RusotoError<ListShardsError>> {
+    let mut shards = Vec::new();
+    let mut next_token = None;
+    loop {
+        let output = state
+            .client
+            .list_shards(ListShardsInput {
+                // The stream name must not be given along with a pagination token.
+                stream_name: next_token.is_none().then(|| state.stream_name.clone()),
+                next_token: next_token.take(),
+                ..Default::default()
+            })
+            .await?;
+        shards.extend(output.shards.unwrap_or_default());
+
+        match output.next_token {
+            Some(token) => next_token = Some(token),
+            None => return Ok(shards),
+        }
+    }
+}
+
+/// Picks the shards that should start being read, along with where to start
+/// reading them when they have no checkpoint yet.
+///
+/// After resharding, child shards are only read once every record of their
+/// parents has been delivered, so records with the same partition key keep
+/// their order. Parents which are no longer listed have expired from the
+/// stream, and do not hold back their children.
+fn readable_shards(
+    shards: &[Shard],
+    running: &HashSet<String>,
+    checkpoints: &Checkpoints,
+    start_position: StartPosition,
+) -> Vec<(String, StartPosition)> {
+    let listed = shards
+        .iter()
+        .map(|shard| shard.shard_id.as_str())
+        .collect::<HashSet<_>>();
+
+    shards
+        .iter()
+        .filter(|shard| {
+            !running.contains(&shard.shard_id) && !checkpoints.is_finished(&shard.shard_id)
+        })
+        .filter_map(|shard| {
+            let parents = shard
+                .parent_shard_id
+                .iter()
+                .chain(shard.adjacent_parent_shard_id.iter());
+            let parents_done = parents
+                .clone()
+                .all(|parent| !listed.contains(parent.as_str()) || checkpoints.is_finished(parent));
+            if !parents_done {
+                return None;
+            }
+
+            // Children of shards we read records from are read from their
+            // beginning, so nothing written after the resharding is missed.
+            let parents_read = parents.any(|parent| {
+                checkpoints
+                    .get(parent)
+                    .map_or(false, |checkpoint| checkpoint.sequence_number.is_some())
+            });
+            let start_position = if parents_read {
+                StartPosition::TrimHorizon
+            } else {
+                start_position
+            };
+
+            Some((shard.shard_id.clone(), start_position))
+        })
+        .collect()
+}
+
+struct ShardReader {
+    state: Arc<State>,
+    shard_id: String,
+    start_position: StartPosition,
+    /// The sequence number of the last record read from the shard.
+    sequence_number: Option<String>,
+    out: Pipeline,
+    shutdown: Shared<ShutdownSignal>,
+    finalizer: Option<Arc<OrderedFinalizer<ShardProgress>>>,
+    failed: Arc<AtomicBool>,
+}
+
+impl ShardReader {
+    async fn run(mut self, done: mpsc::UnboundedSender<String>) {
+        if self.read().await.is_ok() {
+            debug!(message = "Finished reading closed shard.", shard_id = %self.shard_id);
+        }
+        let _ = done.send(self.shard_id);
+    }
+
+    /// Reads the shard until it is closed, until the source shuts down, or
+    /// until a batch fails to be delivered. In the last case the shard is
+    /// read again from its checkpoint on the next shard refresh.
+    async fn read(&mut self) -> Result<(), ()> {
+        let mut shard_iterator = self.shard_iterator().await?;
+        loop {
+            if self.failed.load(Ordering::Relaxed) {
+                return Err(());
+            }
+
+            let result = tokio::select! {
+                _ = self.shutdown.clone() => return Err(()),
+                result = self.state.client.get_records(GetRecordsInput {
+                    shard_iterator: shard_iterator.clone(),
+                    limit: Some(self.state.max_records),
+                }) => result,
+            };
+
+            let output = match result {
+                Ok(output) => output,
+                Err(RusotoError::Service(GetRecordsError::ExpiredIterator(_))) => {
+                    shard_iterator = self.shard_iterator().await?;
+                    continue;
+                }
+                Err(error) => {
+                    emit!(AwsKinesisStreamsRequestFailed {
+                        operation: "GetRecords",
+                        shard_id: Some(&self.shard_id),
+                        error: &error,
+                    });
+                    self.sleep(self.state.poll_interval).await?;
+                    continue;
+                }
+            };
+
+            let has_records = !output.records.is_empty();
+            // A shard without a next iterator has been closed by resharding,
+            // and all of its records have been read.
+            let finished = output.next_shard_iterator.is_none();
+            if has_records || finished {
+                self.handle_records(output.records, finished).await?;
+            }
+
+            match output.next_shard_iterator {
+                Some(next_shard_iterator) => shard_iterator = next_shard_iterator,
+                None => return Ok(()),
+            }
+            if !has_records {
+                self.sleep(self.state.poll_interval).await?;
+            }
+        }
+    }
+
+    async fn shard_iterator(&self) -> Result<String, ()> {
+        let (shard_iterator_type, starting_sequence_number) = match &self.sequence_number {
+            Some(sequence_number) => ("AFTER_SEQUENCE_NUMBER", Some(sequence_number.clone())),
+            None => (self.start_position.iterator_type(), None),
+        };
+
+        loop {
+            let result = tokio::select! {
+                _ = self.shutdown.clone() => return Err(()),
+                result = self.state.client.get_shard_iterator(GetShardIteratorInput {
+                    stream_name: self.state.stream_name.clone(),
+                    shard_id: self.shard_id.clone(),
+                    shard_iterator_type: shard_iterator_type.into(),
+                    starting_sequence_number: starting_sequence_number.clone(),
+                    ..Default::default()
+                }) => result,
+            };
+
+            match result.map(|output| output.shard_iterator) {
+                Ok(Some(shard_iterator)) => return Ok(shard_iterator),
+                Ok(None) => emit!(AwsKinesisStreamsRequestFailed {
+                    operation: "GetShardIterator",
+                    shard_id: Some(&self.shard_id),
+                    error: &"No shard iterator returned.",
+                }),
+                Err(error) => emit!(AwsKinesisStreamsRequestFailed {
+                    operation: "GetShardIterator",
+                    shard_id: Some(&self.shard_id),
+                    error: &error,
+                }),
+            }
+            self.sleep(self.state.poll_interval).await?;
+        }
+    }
+
+    async fn handle_records(&mut self, records: Vec<Record>, finished: bool) -> Result<(), ()> {
+        if let Some(record) = records.last() {
+            self.sequence_number = Some(record.sequence_number.clone());
+        }
+
+        let (batch, receiver) = match &self.finalizer {
+            Some(_) => {
+                let (batch, receiver) = BatchNotifier::new_with_receiver();
+                (Some(batch), Some(receiver))
+            }
+            None => (None, None),
+        };
+
+        let mut events = Vec::new();
+        for record in records {
+            events.extend(decode_record(record, &self.shard_id, self.state.decoder.clone()).await);
+        }
+
+        let mut stream = stream::iter(events).map(|event| {
+            Ok(match &batch {
+                Some(batch) => event.with_batch_notifier(batch),
+                None => event,
+            })
+        });
+        if let Err(error) = self.out.send_all(&mut stream).await {
+            error!(message = "Error sending to sink.", %error);
+            return Err(());
+        }
+        drop(batch);
+
+        let progress = ShardProgress {
+            shard_id: self.shard_id.clone(),
+            sequence_number: self.sequence_number.clone(),
+            finished,
+            failed: Arc::clone(&self.failed),
+        };
+        match (&self.finalizer, receiver) {
+            (Some(finalizer), Some(receiver)) => finalizer.add(progress, receiver),
+            _ => self.state.advance(progress),
+        }
+
+        Ok(())
+    }
+
+    async fn sleep(&self, duration: Duration) -> Result<(), ()> {
+        tokio::select! {
+            _ = self.shutdown.clone() => Err(()),
+            _ = tokio::time::sleep(duration) => Ok(()),
+        }
+    }
+}
+
+async fn decode_record(record: Record, shard_id: &str, decoder: codecs::Decoder) -> Vec<Event> {
+    let timestamp = record
+        .approximate_arrival_timestamp
+        .map(|timestamp| Utc.timestamp_millis((timestamp * 1000.0) as i64))
+        .unwrap_or_else(Utc::now);
+    let sequence_number = Value::from(record.sequence_number);
+    let shard_id = Value::from(shard_id);
+
+    let mut events = Vec::new();
+    for (partition_key, data) in deaggregate(record.partition_key, record.data) {
+        emit!(AwsKinesisStreamsEventReceived {
+            byte_size: data.len(),
+        });
+
+        let partition_key = Value::from(partition_key);
+        let mut stream = FramedRead::new(data.as_ref(), decoder.clone());
+        while let Some(result) = stream.next().await {
+            match result {
+                Ok((decoded, _)) => {
+                    for mut event in decoded {
+                        if let Event::Log(ref mut log) = event {
+                            log.insert(
+                                log_schema().source_type_key(),
+                                Bytes::from("aws_kinesis_streams"),
+                            );
+                            log.insert(log_schema().timestamp_key(), timestamp);
+                            log.insert("partition_key", partition_key.clone());
+                            log.insert("sequence_number", sequence_number.clone());
+                            log.insert("shard_id", shard_id.clone());
+                        }
+                        events.push(event);
+                    }
+                }
+                Err(error) => {
+                    // Error is logged by `crate::codecs::Decoder`, no further handling
+                    // is needed here.
+                    if !error.can_continue() {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::EventStatus;
+    use tempfile::{tempdir, TempDir};
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<KinesisSourceConfig>();
+    }
+
+    #[tokio::test]
+    async fn decodes_aggregated_record() {
+        let record = Record {
+            approximate_arrival_timestamp: Some(1636106400.123),
+            data: deaggregation::aggregate(&[("a", "foo\nbar"), ("b", "baz")]),
+            partition_key: "outer".into(),
+            sequence_number: "42".into(),
+            ..Default::default()
+        };
+
+        let events = decode_record(
+            record,
+            "shardId-000000000000",
+            DecodingConfig::default().build().unwrap(),
+        )
+        .await;
+
+        let messages = events
+            .iter()
+            .map(|event| {
+                let log = event.as_log();
+                assert_eq!(log["sequence_number"], "42".into());
+                assert_eq!(log["shard_id"], "shardId-000000000000".into());
+                assert_eq!(
+                    log[log_schema().timestamp_key()],
+                    Utc.timestamp_millis(1636106400123).into()
+                );
+                assert_eq!(
+                    log[log_schema().source_type_key()],
+                    "aws_kinesis_streams".into()
+                );
+                (
+                    log["partition_key"].to_string_lossy(),
+                    log[log_schema().message_key()].to_string_lossy(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            messages,
+            vec![
+                ("a".into(), "foo".into()),
+                ("a".into(), "bar".into()),
+                ("b".into(), "baz".into()),
+            ]
+        );
+    }
+
+    fn shard(shard_id: &str, parents: &[&str]) -> Shard {
+        Shard {
+            shard_id: shard_id.into(),
+            parent_shard_id: parents.first().map(|parent| parent.to_string()),
+            adjacent_parent_shard_id: parents.get(1).map(|parent| parent.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn reads_children_after_parents() {
+        let dir = tempdir().unwrap();
+        let checkpoints = Checkpoints::load(dir.path().join("stream.json")).unwrap();
+        let shards = vec![
+            shard("parent", &[]),
+            shard("child-0", &["parent"]),
+            shard("child-1", &["parent"]),
+            shard("merged", &["child-0", "expired"]),
+        ];
+
+        let readable = readable_shards(
+            &shards,
+            &HashSet::new(),
+            &checkpoints,
+            StartPosition::Latest,
+        );
+        assert_eq!(readable, vec![("parent".into(), StartPosition::Latest)]);
+
+        checkpoints.update("parent", Some("1".into()), true);
+        let running = vec!["child-1".to_string()].into_iter().collect();
+        let readable = readable_shards(&shards, &running, &checkpoints, StartPosition::Latest);
+        assert_eq!(
+            readable,
+            vec![("child-0".into(), StartPosition::TrimHorizon)]
+        );
+
+        checkpoints.update("child-0", None, true);
+        let readable = readable_shards(&shards, &running, &checkpoints, StartPosition::Latest);
+        assert_eq!(readable, vec![("merged".into(), StartPosition::Latest)]);
+    }
+}
+
+#[cfg(feature = "aws-kinesis-streams-integration-tests")]
+#[cfg(test)]
+mod integration_tests {
+    use super::*;
+    use crate::{
+        event::EventStatus,
+        test_util::{collect_n_stream, random_string, trace_init},
+    };
+    use rusoto_core::Region;
+    use rusoto_kinesis::{CreateStreamInput, PutRecordInput};
+    use stream_cancel::{Trigger, Tripwire};
+    use tempfile::tempdir;
+
+    const ENDPOINT: &str = "http://localhost:4566";
+
+    fn client() -> KinesisClient {
+        KinesisClient::new(Region::Custom {
+            name: "localstack".into(),
+            endpoint: ENDPOINT.into(),
+        })
+    }
+
+    async fn create_stream() -> String {
+        let stream_name = format!("test-{}", random_string(10).to_lowercase());
+        client()
+            .create_stream(CreateStreamInput {
+                stream_name: stream_name.clone(),
+                shard_count: 1,
+            })
+            .await
+            .unwrap();
+        // Wait for localstack to persist the stream, otherwise it returns
+        // ResourceNotFound errors.
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        stream_name
+    }
+
+    async fn put_record(stream_name: &str, data: Bytes) {
+        client()
+            .put_record(PutRecordInput {
+                stream_name: stream_name.into(),
+                partition_key: "key".into(),
+                data,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+    }
+
+    async fn run_source(
+        stream_name: &str,
+        data_dir: &std::path::Path,
+    ) -> (
+        impl futures::Stream<Item = Event> + Unpin,
+        Trigger,
+        Tripwire,
+    ) {
+        let config: KinesisSourceConfig = toml::from_str(&format!(
+            r#"
+            stream_name = "{}"
+            endpoint = "{}"
+            start_position = "trim_horizon"
+            poll_interval_ms = 100
+            data_dir = "{}"
+            "#,
+            stream_name,
+            ENDPOINT,
+            data_dir.display()
+        ))
+        .unwrap();
+
+        let (tx, rx) = Pipeline::new_test_finalize(EventStatus::Delivered);
+        let (trigger, shutdown, shutdown_done) = ShutdownSignal::new_wired();
+        let mut cx = SourceContext::new_test(tx);
+        cx.shutdown = shutdown;
+        cx.acknowledgements = true;
+        tokio::spawn(config.build(cx).await.unwrap());
+        (rx, trigger, shutdown_done)
+    }
+
+    #[tokio::test]
+    async fn reads_records_and_resumes_from_checkpoint() {
+        trace_init();
+
+        let stream_name = create_stream().await;
+        let data_dir = tempdir().unwrap();
+        put_record(&stream_name, Bytes::from("plain")).await;
+        put_record(
+            &stream_name,
+            deaggregation::aggregate(&[("a", "aggregated 1"), ("b", "aggregated 2")]),
+        )
+        .await;
+
+        let (mut rx, trigger, shutdown_done) = run_source(&stream_name, data_dir.path()).await;
+        let events = collect_n_stream(&mut rx, 3).await;
+        let messages = events
+            .iter()
+            .map(|event| event.as_log()[log_schema().message_key()].to_string_lossy())
+            .collect::<Vec<_>>();
+        assert_eq!(messages, vec!["plain", "aggregated 1", "aggregated 2"]);
+        assert_eq!(events[2].as_log()["partition_key"], "b".into());
+
+        // Shutting down waits for the finalizer to write the checkpoint.
+        drop(trigger);
+        shutdown_done.await;
+
+        // A new source only reads the records added since the checkpoint.
+        put_record(&stream_name, Bytes::from("new")).await;
+        let (mut rx, _trigger, _) = run_source(&stream_name, data_dir.path()).await;
+        let events = collect_n_stream(&mut rx, 1).await;
+        assert_eq!(events[0].as_log()[log_schema().message_key()], "new".into());
+    }
+
+    async fn finalize_records(status: EventStatus) -> (TempDir, Arc<State>, ShardReader) {
+        let dir = tempdir().unwrap();
+        let state = Arc::new(State {
+            client: KinesisClient::new(rusoto_core::Region::UsEast1),
+            stream_name: "stream".into(),
+            poll_interval: Duration::from_millis(10),
+            max_records: 100,
+            decoder: DecodingConfig::default().build().unwrap(),
+            checkpoints: Checkpoints::load(dir.path().join("stream.json")).unwrap(),
+        });
+        let shutdown = ShutdownSignal::noop().shared();
+        let finalizer = {
+            let state = Arc::clone(&state);
+            OrderedFinalizer::new(shutdown.clone(), move |status, progress: ShardProgress| {
+                state.finalize(status, progress)
+            })
+        };
+        let (out, mut rx) = Pipeline::new_test_finalize(status);
+        let mut reader = ShardReader {
+            state: Arc::clone(&state),
+            shard_id: "shardId-000000000000".into(),
+            start_position: StartPosition::TrimHorizon,
+            sequence_number: None,
+            out,
+            shutdown,
+            finalizer: Some(Arc::new(finalizer)),
+            failed: Arc::default(),
+        };
+
+        let record = Record {
+            data: Bytes::from("foo"),
+            partition_key: "key".into(),
+            sequence_number: "42".into(),
+            ..Default::default()
+        };
+        reader.handle_records(vec![record], false).await.unwrap();
+        let event = rx.next().await.unwrap();
+        assert_eq!(event.as_log()[log_schema().message_key()], "foo".into());
+        drop(event);
+
+        // Let the finalizer task receive the status of the batch.
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        (dir, state, reader)
+    }
+
+    #[tokio::test]
+    async fn checkpoints_delivered_records() {
+        let (_dir, state, reader) = finalize_records(EventStatus::Delivered).await;
+
+        let checkpoint = state.checkpoints.get("shardId-000000000000").unwrap();
+        assert_eq!(checkpoint.sequence_number, Some("42".into()));
+        assert!(!reader.failed.load(Ordering::Relaxed));
+    }
+
+    #[tokio::test]
+    async fn does_not_checkpoint_failed_records() {
+        let (_dir, state, reader) = finalize_records(EventStatus::Failed).await;
+
+        assert!(state.checkpoints.get("shardId-000000000000").is_none());
+        assert!(reader.failed.load(Ordering::Relaxed));
+    }
+}
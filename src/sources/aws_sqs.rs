@@ -0,0 +1,625 @@
+use crate::{
+    codecs::{self, DecodingConfig},
+    config::{
+        log_schema, DataType, GenerateConfig, SourceConfig, SourceContext, SourceDescription,
+    },
+    event::{BatchNotifier, BatchStatus, BatchStatusReceiver, Event, Value},
+    internal_events::{
+        AwsSqsEventReceived, AwsSqsMessagesDeleteFailed, AwsSqsMessagesDeleted,
+        AwsSqsMessagesReceived, AwsSqsReceiveFailed, AwsSqsVisibilityChangeFailed,
+    },
+    rusoto::{self, AwsAuthentication, RegionOrEndpoint},
+    shutdown::ShutdownSignal,
+    Pipeline,
+};
+use bytes::Bytes;
+use chrono::{TimeZone, Utc};
+use futures::{stream, SinkExt, StreamExt};
+use rusoto_sqs::{
+    ChangeMessageVisibilityBatchRequest, ChangeMessageVisibilityBatchRequestEntry,
+    DeleteMessageBatchRequest, DeleteMessageBatchRequestEntry, Message, ReceiveMessageRequest, Sqs,
+    SqsClient,
+};
+use serde::{Deserialize, Serialize};
+use std::{cmp, collections::BTreeMap, convert::TryInto, panic, sync::Arc, time::Duration};
+use tokio_util::codec::FramedRead;
+use tracing::Instrument;
+
+/// The maximum number of messages a single receive call can return.
+const MAX_NUMBER_OF_MESSAGES: i64 = 10;
+
+#[derive(Derivative, Clone, Debug, Deserialize, Serialize)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub struct AwsSqsConfig {
+    #[serde(flatten)]
+    pub region: RegionOrEndpoint,
+    #[serde(default)]
+    pub auth: AwsAuthentication,
+
+    pub queue_url: String,
+
+    // restricted to u32 for safe conversion to i64 later
+    #[serde(default = "default_poll_secs")]
+    #[derivative(Default(value = "default_poll_secs()"))]
+    pub poll_secs: u32,
+
+    // restricted to u32 for safe conversion to i64 later
+    #[serde(default = "default_visibility_timeout_secs")]
+    #[derivative(Default(value = "default_visibility_timeout_secs()"))]
+    pub visibility_timeout_secs: u32,
+
+    #[serde(default = "default_true")]
+    #[derivative(Default(value = "default_true()"))]
+    pub delete_message: bool,
+
+    // number of tasks spawned for running the receive loop
+    #[serde(default = "default_client_concurrency")]
+    #[derivative(Default(value = "default_client_concurrency()"))]
+    pub client_concurrency: u32,
+
+    #[serde(flatten)]
+    pub decoding: DecodingConfig,
+}
+
+const fn default_poll_secs() -> u32 {
+    15
+}
+
+const fn default_visibility_timeout_secs() -> u32 {
+    300
+}
+
+const fn default_true() -> bool {
+    true
+}
+
+fn default_client_concurrency() -> u32 {
+    cmp::max(1, num_cpus::get() as u32)
+}
+
+inventory::submit! {
+    SourceDescription::new::<AwsSqsConfig>("aws_sqs")
+}
+
+impl GenerateConfig for AwsSqsConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"queue_url = "https://sqs.us-east-2.amazonaws.com/123456789012/MyQueue"
+            region = "us-east-2""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "aws_sqs")]
+impl SourceConfig for AwsSqsConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let region = (&self.region).try_into()?;
+        let client = rusoto::client(&cx.proxy)?;
+        let creds = self.auth.build(&region, None)?;
+
+        let state = Arc::new(State {
+            client: SqsClient::new_with(client, creds, region),
+            queue_url: self.queue_url.clone(),
+            poll_secs: self.poll_secs.into(),
+            visibility_timeout_secs: self.visibility_timeout_secs.into(),
+            delete_message: self.delete_message,
+            decoder: self.decoding.build()?,
+        });
+
+        Ok(Box::pin(run(
+            state,
+            self.client_concurrency,
+            cx.out,
+            cx.shutdown,
+            cx.acknowledgements,
+        )))
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn source_type(&self) -> &'static str {
+        "aws_sqs"
+    }
+
+    fn decoding(&self) -> Option<&DecodingConfig> {
+        Some(&self.decoding)
+    }
+}
+
+struct State {
+    client: SqsClient,
+    queue_url: String,
+    poll_secs: i64,
+    visibility_timeout_secs: i64,
+    delete_message: bool,
+    decoder: codecs::Decoder,
+}
+
+async fn run(
+    state: Arc<State>,
+    client_concurrency: u32,
+    out: Pipeline,
+    shutdown: ShutdownSignal,
+    acknowledgements: bool,
+) -> Result<(), ()> {
+    let mut handles = Vec::new();
+    for _ in 0..client_concurrency {
+        let consumer = Consumer {
+            state: Arc::clone(&state),
+            out: out.clone(),
+            shutdown: shutdown.clone(),
+            acknowledgements,
+        };
+        handles.push(tokio::spawn(consumer.run().in_current_span()));
+    }
+
+    // Wait for all of the consumers to finish. If any one of them panics, we
+    // resume that panic here to properly shutdown Vector.
+    for handle in handles {
+        if let Err(error) = handle.await {
+            if error.is_panic() {
+                panic::resume_unwind(error.into_panic());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+struct Consumer {
+    state: Arc<State>,
+    out: Pipeline,
+    shutdown: ShutdownSignal,
+    acknowledgements: bool,
+}
+
+impl Consumer {
+    async fn run(mut self) {
+        loop {
+            let messages = tokio::select! {
+                _ = &mut self.shutdown => break,
+                messages = receive_messages(&self.state) => messages,
+            };
+            if messages.is_empty() {
+                continue;
+            }
+
+            if self.handle_messages(messages).await.is_err() {
+                break;
+            }
+        }
+    }
+
+    async fn handle_messages(&mut self, messages: Vec<Message>) -> Result<(), ()> {
+        let (batch, receiver) = BatchNotifier::new_with_receiver();
+        let mut receipt_handles = Vec::with_capacity(messages.len());
+        let mut events = Vec::new();
+        for message in messages {
+            if let Some(receipt_handle) = message.receipt_handle.clone() {
+                receipt_handles.push(receipt_handle);
+            }
+            events.extend(decode_message(message, self.state.decoder.clone()).await);
+        }
+
+        let acknowledgements = self.acknowledgements;
+        let mut stream = stream::iter(events).map(|event| {
+            Ok(if acknowledgements {
+                event.with_batch_notifier(&batch)
+            } else {
+                event
+            })
+        });
+        if let Err(error) = self.out.send_all(&mut stream).await {
+            error!(message = "Error sending to sink.", %error);
+            return Err(());
+        }
+        drop(batch);
+
+        if self.acknowledgements {
+            let state = Arc::clone(&self.state);
+            tokio::spawn(finalize(state, receipt_handles, receiver).in_current_span());
+        } else if self.state.delete_message {
+            delete_messages(&self.state, &receipt_handles).await;
+        }
+
+        Ok(())
+    }
+}
+
+async fn receive_messages(state: &State) -> Vec<Message> {
+    let result = state
+        .client
+        .receive_message(ReceiveMessageRequest {
+            queue_url: state.queue_url.clone(),
+            max_number_of_messages: Some(MAX_NUMBER_OF_MESSAGES),
+            wait_time_seconds: Some(state.poll_secs),
+            visibility_timeout: Some(state.visibility_timeout_secs),
+            attribute_names: Some(vec!["SentTimestamp".into()]),
+            message_attribute_names: Some(vec!["All".into()]),
+            ..Default::default()
+        })
+        .await;
+
+    match result {
+        Ok(result) => {
+            let messages = result.messages.unwrap_or_default();
+            emit!(AwsSqsMessagesReceived {
+                count: messages.len(),
+            });
+            messages
+        }
+        Err(error) => {
+            emit!(AwsSqsReceiveFailed { error: &error });
+            // Avoid spinning on persistent errors, such as a missing queue.
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            Vec::new()
+        }
+    }
+}
+
+/// Keeps extending the visibility timeout of messages until their events
+/// are finalized, then deletes them if the events were delivered, or makes
+/// them visible again right away otherwise.
+async fn finalize(
+    state: Arc<State>,
+    receipt_handles: Vec<String>,
+    mut receiver: BatchStatusReceiver,
+) {
+    let period = Duration::from_secs(cmp::max(1, state.visibility_timeout_secs as u64 / 2));
+    let mut extend = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+    let status = loop {
+        tokio::select! {
+            status = &mut receiver => break status,
+            _ = extend.tick() => {
+                change_visibility(&state, &receipt_handles, state.visibility_timeout_secs).await;
+            }
+        }
+    };
+
+    match status {
+        BatchStatus::Delivered => {
+            if state.delete_message {
+                delete_messages(&state, &receipt_handles).await;
+            }
+        }
+        BatchStatus::Errored | BatchStatus::Failed => {
+            change_visibility(&state, &receipt_handles, 0).await
+        }
+    }
+}
+
+async fn delete_messages(state: &State, receipt_handles: &[String]) {
+    let entries = receipt_handles
+        .iter()
+        .enumerate()
+        .map(|(id, receipt_handle)| DeleteMessageBatchRequestEntry {
+            id: id.to_string(),
+            receipt_handle: receipt_handle.clone(),
+        })
+        .collect();
+
+    let result = state
+        .client
+        .delete_message_batch(DeleteMessageBatchRequest {
+            queue_url: state.queue_url.clone(),
+            entries,
+        })
+        .await;
+
+    match result {
+        Ok(result) => {
+            // Batch deletes can have partial successes/failures, so we have to
+            // check for both cases and emit accordingly.
+            if !result.successful.is_empty() {
+                emit!(AwsSqsMessagesDeleted {
+                    count: result.successful.len(),
+                });
+            }
+            if !result.failed.is_empty() {
+                emit!(AwsSqsMessagesDeleteFailed {
+                    count: result.failed.len(),
+                    error: batch_errors(result.failed.iter().map(|entry| &entry.code)),
+                });
+            }
+        }
+        Err(error) => emit!(AwsSqsMessagesDeleteFailed {
+            count: receipt_handles.len(),
+            error: error.to_string(),
+        }),
+    }
+}
+
+async fn change_visibility(state: &State, receipt_handles: &[String], timeout_secs: i64) {
+    let entries = receipt_handles
+        .iter()
+        .enumerate()
+        .map(
+            |(id, receipt_handle)| ChangeMessageVisibilityBatchRequestEntry {
+                id: id.to_string(),
+                receipt_handle: receipt_handle.clone(),
+                visibility_timeout: Some(timeout_secs),
+            },
+        )
+        .collect();
+
+    let result = state
+        .client
+        .change_message_visibility_batch(ChangeMessageVisibilityBatchRequest {
+            queue_url: state.queue_url.clone(),
+            entries,
+        })
+        .await;
+
+    match result {
+        Ok(result) if !result.failed.is_empty() => emit!(AwsSqsVisibilityChangeFailed {
+            count: result.failed.len(),
+            error: batch_errors(result.failed.iter().map(|entry| &entry.code)),
+        }),
+        Ok(_) => {}
+        Err(error) => emit!(AwsSqsVisibilityChangeFailed {
+            count: receipt_handles.len(),
+            error: error.to_string(),
+        }),
+    }
+}
+
+fn batch_errors<'a>(codes: impl Iterator<Item = &'a String>) -> String {
+    codes.cloned().collect::<Vec<_>>().join(", ")
+}
+
+async fn decode_message(message: Message, decoder: codecs::Decoder) -> Vec<Event> {
+    let body = message.body.unwrap_or_default();
+    emit!(AwsSqsEventReceived {
+        byte_size: body.len(),
+    });
+
+    let timestamp = message
+        .attributes
+        .as_ref()
+        .and_then(|attributes| attributes.get("SentTimestamp"))
+        .and_then(|timestamp| timestamp.parse::<i64>().ok())
+        .map(|timestamp| Utc.timestamp_millis(timestamp))
+        .unwrap_or_else(Utc::now);
+    let message_id = message.message_id.map(Value::from);
+    let attributes = message
+        .message_attributes
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|(name, attribute)| {
+            let value = match (attribute.string_value, attribute.binary_value) {
+                (Some(value), _) => Value::from(value),
+                (None, Some(value)) => Value::from(value),
+                (None, None) => return None,
+            };
+            Some((name, value))
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    let mut events = Vec::new();
+    let mut stream = FramedRead::new(body.as_bytes(), decoder);
+    while let Some(result) = stream.next().await {
+        match result {
+            Ok((decoded, _)) => {
+                for mut event in decoded {
+                    if let Event::Log(ref mut log) = event {
+                        log.insert(log_schema().source_type_key(), Bytes::from("aws_sqs"));
+                        log.insert(log_schema().timestamp_key(), timestamp);
+                        if let Some(message_id) = &message_id {
+                            log.insert("message_id", message_id.clone());
+                        }
+                        log.insert("message_attributes", Value::from(attributes.clone()));
+                    }
+                    events.push(event);
+                }
+            }
+            Err(error) => {
+                // Error is logged by `crate::codecs::Decoder`, no further handling
+                // is needed here.
+                if !error.can_continue() {
+                    break;
+                }
+            }
+        }
+    }
+
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusoto_sqs::MessageAttributeValue;
+    use std::collections::HashMap;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<AwsSqsConfig>();
+    }
+
+    #[tokio::test]
+    async fn decodes_message_with_attributes() {
+        let mut attributes = HashMap::new();
+        attributes.insert("SentTimestamp".to_string(), "1636106400123".to_string());
+        let mut message_attributes = HashMap::new();
+        message_attributes.insert(
+            "sensor".to_string(),
+            MessageAttributeValue {
+                data_type: "String".into(),
+                string_value: Some("kitchen".into()),
+                ..Default::default()
+            },
+        );
+        let message = Message {
+            body: Some("foo\nbar".into()),
+            message_id: Some("1".into()),
+            attributes: Some(attributes),
+            message_attributes: Some(message_attributes),
+            ..Default::default()
+        };
+
+        let events = decode_message(message, DecodingConfig::default().build().unwrap()).await;
+
+        assert_eq!(events.len(), 2);
+        let log = events[1].as_log();
+        assert_eq!(log[log_schema().message_key()], "bar".into());
+        assert_eq!(log["message_id"], "1".into());
+        assert_eq!(log["message_attributes.sensor"], "kitchen".into());
+        assert_eq!(
+            log[log_schema().timestamp_key()],
+            Utc.timestamp_millis(1636106400123).into()
+        );
+        assert_eq!(log[log_schema().source_type_key()], "aws_sqs".into());
+    }
+}
+
+#[cfg(feature = "aws-sqs-integration-tests")]
+#[cfg(test)]
+mod integration_tests {
+    use super::*;
+    use crate::{
+        event::EventStatus,
+        test_util::{collect_n_stream, random_string, trace_init},
+    };
+    use rusoto_core::Region;
+    use rusoto_sqs::{
+        CreateQueueRequest, GetQueueAttributesRequest, MessageAttributeValue, SendMessageRequest,
+    };
+    use std::collections::HashMap;
+
+    const ENDPOINT: &str = "http://localhost:4566";
+
+    fn client() -> SqsClient {
+        SqsClient::new(Region::Custom {
+            name: "localstack".into(),
+            endpoint: ENDPOINT.into(),
+        })
+    }
+
+    async fn create_queue() -> String {
+        client()
+            .create_queue(CreateQueueRequest {
+                queue_name: format!("test-{}", random_string(10).to_lowercase()),
+                ..Default::default()
+            })
+            .await
+            .unwrap()
+            .queue_url
+            .unwrap()
+    }
+
+    async fn send_messages(queue_url: &str, messages: &[String]) {
+        let client = client();
+        for message in messages {
+            let mut attributes = HashMap::new();
+            attributes.insert(
+                "sensor".to_string(),
+                MessageAttributeValue {
+                    data_type: "String".into(),
+                    string_value: Some("kitchen".into()),
+                    ..Default::default()
+                },
+            );
+            client
+                .send_message(SendMessageRequest {
+                    queue_url: queue_url.into(),
+                    message_body: message.clone(),
+                    message_attributes: Some(attributes),
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+        }
+    }
+
+    async fn queue_size(queue_url: &str) -> usize {
+        let attributes = client()
+            .get_queue_attributes(GetQueueAttributesRequest {
+                queue_url: queue_url.into(),
+                attribute_names: Some(vec![
+                    "ApproximateNumberOfMessages".into(),
+                    "ApproximateNumberOfMessagesNotVisible".into(),
+                ]),
+            })
+            .await
+            .unwrap()
+            .attributes
+            .unwrap();
+        attributes
+            .values()
+            .map(|count| count.parse::<usize>().unwrap())
+            .sum()
+    }
+
+    async fn consume_messages(
+        status: EventStatus,
+    ) -> (
+        String,
+        Vec<String>,
+        impl futures::Stream<Item = Event> + Unpin,
+    ) {
+        trace_init();
+
+        let queue_url = create_queue().await;
+        let config: AwsSqsConfig = toml::from_str(&format!(
+            r#"
+            queue_url = "{}"
+            endpoint = "{}"
+            poll_secs = 1
+            client_concurrency = 2
+            "#,
+            queue_url, ENDPOINT
+        ))
+        .unwrap();
+
+        let (tx, mut rx) = Pipeline::new_test_finalize(status);
+        let mut cx = SourceContext::new_test(tx);
+        cx.acknowledgements = true;
+        tokio::spawn(config.build(cx).await.unwrap());
+
+        let messages = (0..10)
+            .map(|i| format!("message {}", i))
+            .collect::<Vec<_>>();
+        send_messages(&queue_url, &messages).await;
+
+        let events = collect_n_stream(&mut rx, messages.len()).await;
+        let mut received = events
+            .iter()
+            .map(|event| {
+                let log = event.as_log();
+                assert_eq!(log["message_attributes.sensor"], "kitchen".into());
+                log[log_schema().message_key()].to_string_lossy()
+            })
+            .collect::<Vec<_>>();
+        received.sort();
+        assert_eq!(received, messages);
+
+        (queue_url, messages, rx)
+    }
+
+    #[tokio::test]
+    async fn deletes_delivered_messages() {
+        let (queue_url, _, _rx) = consume_messages(EventStatus::Delivered).await;
+
+        // Give the finalizers time to delete the messages.
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        assert_eq!(queue_size(&queue_url).await, 0);
+    }
+
+    #[tokio::test]
+    async fn redelivers_errored_messages() {
+        let (_, messages, mut rx) = consume_messages(EventStatus::Errored).await;
+
+        let redelivered = tokio::time::timeout(
+            Duration::from_secs(10),
+            collect_n_stream(&mut rx, messages.len()),
+        )
+        .await
+        .expect("Messages were not redelivered");
+        assert_eq!(redelivered.len(), messages.len());
+    }
+}
@@ -1,7 +1,7 @@
 use super::util::finalizer::OrderedFinalizer;
 use super::util::{EncodingConfig, MultilineConfig};
 use crate::{
-    config::{log_schema, DataType, SourceConfig, SourceContext, SourceDescription},
+    config::{log_schema, schema, DataType, SourceConfig, SourceContext, SourceDescription},
     encoding_transcode::{Decoder, Encoder},
     event::{BatchNotifier, Event, LogEvent},
     internal_events::{FileEventReceived, FileOpen, FileSourceInternalEventsEmitter},
@@ -28,6 +28,7 @@ use std::convert::TryInto;
 use std::path::PathBuf;
 use std::time::Duration;
 use tokio::task::spawn_blocking;
+use vrl::prelude::TypeDef;
 
 #[derive(Debug, Snafu)]
 enum BuildError {
@@ -239,6 +240,28 @@ impl SourceConfig for FileConfig {
         DataType::Log
     }
 
+    fn output_schema(&self) -> TypeDef {
+        let host_key = self
+            .host_key
+            .as_deref()
+            .unwrap_or_else(|| log_schema().host_key());
+
+        schema::log_fields(
+            vec![
+                (log_schema().message_key(), TypeDef::new().bytes()),
+                (log_schema().timestamp_key(), TypeDef::new().timestamp()),
+                (log_schema().source_type_key(), TypeDef::new().bytes()),
+                (host_key, TypeDef::new().bytes()),
+            ]
+            .into_iter()
+            .chain(
+                self.file_key
+                    .as_deref()
+                    .map(|file_key| (file_key, TypeDef::new().bytes())),
+            ),
+        )
+    }
+
     fn source_type(&self) -> &'static str {
         "file"
     }
@@ -316,7 +339,7 @@ pub fn file_source(
     let shutdown = shutdown.shared();
     let finalizer = acknowledgements.then(|| {
         let checkpoints = checkpointer.view();
-        OrderedFinalizer::new(shutdown.clone(), move |entry: FinalizerEntry| {
+        OrderedFinalizer::new(shutdown.clone(), move |_status, entry: FinalizerEntry| {
             checkpoints.update(entry.file_id, entry.offset)
         })
     });
@@ -493,6 +516,20 @@ mod tests {
         crate::test_util::test_generate_config::<FileConfig>();
     }
 
+    #[test]
+    fn output_schema_contains_file_key() {
+        let config = FileConfig {
+            file_key: Some("source".to_owned()),
+            ..Default::default()
+        };
+        let output = config.output_schema();
+        let never_exists = |path| output.path_never_exists(&schema::field_lookup(path).unwrap());
+
+        assert!(!never_exists("message"));
+        assert!(!never_exists("source"));
+        assert!(never_exists("file"));
+    }
+
     fn test_default_file_config(dir: &tempfile::TempDir) -> file::FileConfig {
         file::FileConfig {
             fingerprint: FingerprintConfig::Checksum {
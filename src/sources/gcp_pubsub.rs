@@ -0,0 +1,576 @@
+use crate::{
+    codecs::{self, DecodingConfig},
+    config::{
+        log_schema, DataType, GenerateConfig, SourceConfig, SourceContext, SourceDescription,
+    },
+    event::{BatchNotifier, BatchStatus, BatchStatusReceiver, Event, Value},
+    http::{HttpClient, HttpError},
+    internal_events::{GcpPubsubAckFailed, GcpPubsubEventReceived, GcpPubsubReceiveFailed},
+    shutdown::ShutdownSignal,
+    sinks::gcp::{GcpAuthConfig, GcpCredentials},
+    tls::{TlsOptions, TlsSettings},
+    Pipeline,
+};
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::{stream, SinkExt, StreamExt};
+use goauth::scopes::Scope;
+use http::{Request, StatusCode, Uri};
+use hyper::Body;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::json;
+use snafu::{ResultExt, Snafu};
+use std::{collections::BTreeMap, time::Duration};
+use tokio_util::codec::FramedRead;
+
+#[derive(Debug, Snafu)]
+enum PubsubError {
+    #[snafu(display("Invalid Pub/Sub URI: {}", source))]
+    InvalidUri { source: http::uri::InvalidUri },
+    #[snafu(display("Pub/Sub request failed: {}", source))]
+    SendRequest { source: HttpError },
+    #[snafu(display("Failed to read Pub/Sub response: {}", source))]
+    ReadBody { source: hyper::Error },
+    #[snafu(display("Pub/Sub request failed with status {}", status))]
+    UnexpectedStatus { status: StatusCode },
+    #[snafu(display("Failed to parse Pub/Sub response: {}", source))]
+    ParseResponse { source: serde_json::Error },
+    #[snafu(display("Invalid base64 message data: {}", source))]
+    DecodeData { source: base64::DecodeError },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct PubsubConfig {
+    pub project: String,
+    pub subscription: String,
+    pub endpoint: Option<String>,
+    #[serde(default)]
+    pub skip_authentication: bool,
+    #[serde(flatten)]
+    pub auth: GcpAuthConfig,
+    pub tls: Option<TlsOptions>,
+    #[serde(default = "default_ack_deadline_secs")]
+    pub ack_deadline_secs: u64,
+    #[serde(default = "default_max_messages")]
+    pub max_messages: usize,
+    #[serde(default = "default_retry_delay_secs")]
+    pub retry_delay_secs: u64,
+    #[serde(flatten)]
+    pub decoding: DecodingConfig,
+}
+
+const fn default_ack_deadline_secs() -> u64 {
+    60
+}
+
+const fn default_max_messages() -> usize {
+    1000
+}
+
+const fn default_retry_delay_secs() -> u64 {
+    1
+}
+
+inventory::submit! {
+    SourceDescription::new::<PubsubConfig>("gcp_pubsub")
+}
+
+impl GenerateConfig for PubsubConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"
+            project = "my-project"
+            subscription = "my-subscription"
+            credentials_path = "/path/to/credentials.json""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "gcp_pubsub")]
+impl SourceConfig for PubsubConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        // We only need to load the credentials if we are not targeting an emulator.
+        let creds = if self.skip_authentication {
+            None
+        } else {
+            self.auth.make_credentials(Scope::PubSub).await?
+        };
+        if let Some(creds) = &creds {
+            creds.spawn_regenerate_token();
+        }
+
+        let endpoint = self
+            .endpoint
+            .as_deref()
+            .unwrap_or("https://pubsub.googleapis.com");
+        let client = PubsubClient {
+            client: HttpClient::new(TlsSettings::from_options(&self.tls)?, &cx.proxy)?,
+            uri_base: format!(
+                "{}/v1/projects/{}/subscriptions/{}",
+                endpoint, self.project, self.subscription
+            ),
+            api_key: self.auth.api_key.clone(),
+            creds,
+        };
+        // Validate the URI once, so requests can't fail on it later.
+        client.uri(":pull").context(InvalidUri)?;
+
+        Ok(Box::pin(pubsub_source(
+            client,
+            self.decoding.build()?,
+            self.ack_deadline_secs,
+            self.max_messages,
+            Duration::from_secs(self.retry_delay_secs),
+            cx.shutdown,
+            cx.out,
+            cx.acknowledgements,
+        )))
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn source_type(&self) -> &'static str {
+        "gcp_pubsub"
+    }
+
+    fn decoding(&self) -> Option<&DecodingConfig> {
+        Some(&self.decoding)
+    }
+}
+
+#[derive(Clone)]
+struct PubsubClient {
+    client: HttpClient,
+    uri_base: String,
+    api_key: Option<String>,
+    creds: Option<GcpCredentials>,
+}
+
+impl PubsubClient {
+    fn uri(&self, action: &str) -> Result<Uri, http::uri::InvalidUri> {
+        let mut uri = format!("{}{}", self.uri_base, action);
+        if let Some(key) = &self.api_key {
+            uri = format!("{}?key={}", uri, key);
+        }
+        uri.parse()
+    }
+
+    async fn request<T: DeserializeOwned>(
+        &self,
+        action: &str,
+        body: serde_json::Value,
+    ) -> Result<T, PubsubError> {
+        let mut request = Request::post(self.uri(action).context(InvalidUri)?)
+            .header("Content-Type", "application/json")
+            .body(Body::from(serde_json::to_vec(&body).unwrap()))
+            .unwrap();
+        if let Some(creds) = &self.creds {
+            creds.apply(&mut request);
+        }
+
+        let response = self.client.send(request).await.context(SendRequest)?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(PubsubError::UnexpectedStatus { status });
+        }
+        let body = hyper::body::to_bytes(response.into_body())
+            .await
+            .context(ReadBody)?;
+        serde_json::from_slice(&body).context(ParseResponse)
+    }
+
+    async fn pull(&self, max_messages: usize) -> Result<Vec<ReceivedMessage>, PubsubError> {
+        let response: PullResponse = self
+            .request(":pull", json!({ "maxMessages": max_messages }))
+            .await?;
+        Ok(response.received_messages)
+    }
+
+    async fn acknowledge(&self, ack_ids: &[String]) -> Result<(), PubsubError> {
+        self.request::<serde_json::Value>(":acknowledge", json!({ "ackIds": ack_ids }))
+            .await
+            .map(|_| ())
+    }
+
+    async fn modify_ack_deadline(
+        &self,
+        ack_ids: &[String],
+        ack_deadline_secs: u64,
+    ) -> Result<(), PubsubError> {
+        self.request::<serde_json::Value>(
+            ":modifyAckDeadline",
+            json!({ "ackIds": ack_ids, "ackDeadlineSeconds": ack_deadline_secs }),
+        )
+        .await
+        .map(|_| ())
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PullResponse {
+    #[serde(default)]
+    received_messages: Vec<ReceivedMessage>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReceivedMessage {
+    ack_id: String,
+    message: PubsubMessage,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PubsubMessage {
+    #[serde(default)]
+    data: String,
+    #[serde(default)]
+    attributes: BTreeMap<String, String>,
+    message_id: String,
+    publish_time: Option<DateTime<Utc>>,
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn pubsub_source(
+    client: PubsubClient,
+    decoder: codecs::Decoder,
+    ack_deadline_secs: u64,
+    max_messages: usize,
+    retry_delay: Duration,
+    mut shutdown: ShutdownSignal,
+    mut out: Pipeline,
+    acknowledgements: bool,
+) -> Result<(), ()> {
+    loop {
+        let result = tokio::select! {
+            _ = &mut shutdown => break,
+            result = client.pull(max_messages) => result,
+        };
+
+        let messages = match result {
+            Ok(messages) => messages,
+            Err(error) => {
+                emit!(GcpPubsubReceiveFailed {
+                    error: error.into()
+                });
+                tokio::time::sleep(retry_delay).await;
+                continue;
+            }
+        };
+        if messages.is_empty() {
+            continue;
+        }
+
+        let (batch, receiver) = BatchNotifier::new_with_receiver();
+        let mut ack_ids = Vec::with_capacity(messages.len());
+        let mut events = Vec::new();
+        for received in messages {
+            match decode_message(received.message, decoder.clone()).await {
+                Ok(decoded) => events.extend(decoded),
+                // Messages that can't be decoded would fail the same way
+                // when redelivered, so they are acknowledged all the same.
+                Err(error) => emit!(GcpPubsubReceiveFailed {
+                    error: error.into()
+                }),
+            }
+            ack_ids.push(received.ack_id);
+        }
+
+        let mut stream = stream::iter(events).map(|event| {
+            Ok(if acknowledgements {
+                event.with_batch_notifier(&batch)
+            } else {
+                event
+            })
+        });
+        if let Err(error) = out.send_all(&mut stream).await {
+            error!(message = "Error sending to sink.", %error);
+            return Err(());
+        }
+        drop(batch);
+
+        if acknowledgements {
+            tokio::spawn(finalize(
+                client.clone(),
+                ack_ids,
+                receiver,
+                ack_deadline_secs,
+            ));
+        } else if let Err(error) = client.acknowledge(&ack_ids).await {
+            emit!(GcpPubsubAckFailed {
+                error: error.into()
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Keeps extending the ack deadline of messages until their events are
+/// finalized, then acknowledges them if the events were delivered, or makes
+/// them available for redelivery right away otherwise.
+async fn finalize(
+    client: PubsubClient,
+    ack_ids: Vec<String>,
+    mut receiver: BatchStatusReceiver,
+    ack_deadline_secs: u64,
+) {
+    // The first tick fires right away, which takes over from the deadline
+    // configured on the subscription.
+    let mut extend = tokio::time::interval(Duration::from_secs((ack_deadline_secs / 2).max(1)));
+    let status = loop {
+        tokio::select! {
+            status = &mut receiver => break status,
+            _ = extend.tick() => {
+                if let Err(error) = client.modify_ack_deadline(&ack_ids, ack_deadline_secs).await {
+                    emit!(GcpPubsubAckFailed {
+                        error: error.into()
+                    });
+                }
+            }
+        }
+    };
+
+    let result = match status {
+        BatchStatus::Delivered => client.acknowledge(&ack_ids).await,
+        BatchStatus::Errored | BatchStatus::Failed => client.modify_ack_deadline(&ack_ids, 0).await,
+    };
+    if let Err(error) = result {
+        emit!(GcpPubsubAckFailed {
+            error: error.into()
+        });
+    }
+}
+
+async fn decode_message(
+    message: PubsubMessage,
+    decoder: codecs::Decoder,
+) -> Result<Vec<Event>, PubsubError> {
+    let data = base64::decode(&message.data).context(DecodeData)?;
+    emit!(GcpPubsubEventReceived {
+        byte_size: data.len(),
+    });
+
+    let timestamp = message.publish_time.unwrap_or_else(Utc::now);
+    let attributes = message
+        .attributes
+        .into_iter()
+        .map(|(key, value)| (key, Value::from(value)))
+        .collect::<BTreeMap<_, _>>();
+    let mut events = Vec::new();
+
+    let mut stream = FramedRead::new(data.as_slice(), decoder);
+    while let Some(result) = stream.next().await {
+        match result {
+            Ok((decoded, _)) => {
+                for mut event in decoded {
+                    if let Event::Log(ref mut log) = event {
+                        log.insert(log_schema().source_type_key(), Bytes::from("gcp_pubsub"));
+                        log.insert(log_schema().timestamp_key(), timestamp);
+                        log.insert("message_id", Value::from(message.message_id.clone()));
+                        log.insert("attributes", Value::from(attributes.clone()));
+                    }
+                    events.push(event);
+                }
+            }
+            Err(error) => {
+                // Error is logged by `crate::codecs::Decoder`, no further handling
+                // is needed here.
+                if !error.can_continue() {
+                    break;
+                }
+            }
+        }
+    }
+
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<PubsubConfig>();
+    }
+
+    #[tokio::test]
+    async fn decodes_message_with_attributes() {
+        let response: PullResponse = serde_json::from_str(
+            r#"{
+                "receivedMessages": [{
+                    "ackId": "ack-1",
+                    "message": {
+                        "data": "Zm9vCmJhcg==",
+                        "attributes": { "sensor": "kitchen" },
+                        "messageId": "1",
+                        "publishTime": "2021-11-05T10:00:00.123Z"
+                    }
+                }]
+            }"#,
+        )
+        .unwrap();
+        let message = response.received_messages.into_iter().next().unwrap();
+        assert_eq!(message.ack_id, "ack-1");
+
+        let events = decode_message(message.message, DecodingConfig::default().build().unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(events.len(), 2);
+        let log = events[1].as_log();
+        assert_eq!(log[log_schema().message_key()], "bar".into());
+        assert_eq!(log["message_id"], "1".into());
+        assert_eq!(log["attributes.sensor"], "kitchen".into());
+        assert_eq!(
+            log[log_schema().timestamp_key()],
+            "2021-11-05T10:00:00.123Z"
+                .parse::<DateTime<Utc>>()
+                .unwrap()
+                .into()
+        );
+    }
+
+    #[test]
+    fn parses_empty_pull_response() {
+        let response: PullResponse = serde_json::from_str("{}").unwrap();
+        assert!(response.received_messages.is_empty());
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "gcp-pubsub-integration-tests")]
+mod integration_tests {
+    use super::*;
+    use crate::{
+        event::EventStatus,
+        test_util::{collect_n_stream, random_string, trace_init},
+    };
+    use reqwest::{Client, Method};
+    use serde_json::Value as JsonValue;
+
+    const EMULATOR_HOST: &str = "http://localhost:8681";
+    const PROJECT: &str = "testproject";
+
+    async fn request(method: Method, path: &str, json: JsonValue) -> JsonValue {
+        let url = format!("{}/v1/projects/{}/{}", EMULATOR_HOST, PROJECT, path);
+        Client::new()
+            .request(method.clone(), &url)
+            .json(&json)
+            .send()
+            .await
+            .unwrap_or_else(|_| panic!("Sending {} request to {} failed", method, url))
+            .json()
+            .await
+            .expect("Invalid response")
+    }
+
+    async fn create_topic_subscription() -> (String, String) {
+        let topic = format!("topic-{}", random_string(10));
+        let subscription = format!("subscription-{}", random_string(10));
+        request(Method::PUT, &format!("topics/{}", topic), json!({})).await;
+        request(
+            Method::PUT,
+            &format!("subscriptions/{}", subscription),
+            json!({
+                "topic": format!("projects/{}/topics/{}", PROJECT, topic),
+                "ackDeadlineSeconds": 10,
+            }),
+        )
+        .await;
+        (topic, subscription)
+    }
+
+    async fn publish(topic: &str, messages: &[String]) {
+        let messages = messages
+            .iter()
+            .map(|message| {
+                json!({
+                    "data": base64::encode(message),
+                    "attributes": { "sensor": "kitchen" },
+                })
+            })
+            .collect::<Vec<_>>();
+        request(
+            Method::POST,
+            &format!("topics/{}:publish", topic),
+            json!({ "messages": messages }),
+        )
+        .await;
+    }
+
+    async fn consume_messages(
+        status: EventStatus,
+    ) -> (impl futures::Stream<Item = Event> + Unpin, Vec<String>) {
+        trace_init();
+
+        let (topic, subscription) = create_topic_subscription().await;
+        let config: PubsubConfig = toml::from_str(&format!(
+            r#"
+            project = "{}"
+            subscription = "{}"
+            endpoint = "{}"
+            skip_authentication = true
+            ack_deadline_secs = 10
+            "#,
+            PROJECT, subscription, EMULATOR_HOST
+        ))
+        .unwrap();
+
+        let (tx, mut rx) = Pipeline::new_test_finalize(status);
+        let mut cx = SourceContext::new_test(tx);
+        cx.acknowledgements = true;
+        tokio::spawn(config.build(cx).await.unwrap());
+
+        let messages = (0..10)
+            .map(|i| format!("message {}", i))
+            .collect::<Vec<_>>();
+        publish(&topic, &messages).await;
+
+        assert_messages(collect_n_stream(&mut rx, messages.len()).await, &messages);
+        (rx, messages)
+    }
+
+    fn assert_messages(events: Vec<Event>, messages: &[String]) {
+        let mut received = events
+            .iter()
+            .map(|event| {
+                let log = event.as_log();
+                assert_eq!(log["attributes.sensor"], "kitchen".into());
+                log[log_schema().message_key()].to_string_lossy()
+            })
+            .collect::<Vec<_>>();
+        received.sort();
+        assert_eq!(received, messages);
+    }
+
+    #[tokio::test]
+    async fn acknowledges_delivered_messages() {
+        let (mut rx, _) = consume_messages(EventStatus::Delivered).await;
+
+        // Acknowledged messages are not redelivered once the ack deadline
+        // has passed.
+        let redelivered = tokio::time::timeout(Duration::from_secs(15), rx.next()).await;
+        assert!(redelivered.is_err());
+    }
+
+    #[tokio::test]
+    async fn redelivers_errored_messages() {
+        let (mut rx, messages) = consume_messages(EventStatus::Errored).await;
+
+        let redelivered = tokio::time::timeout(
+            Duration::from_secs(5),
+            collect_n_stream(&mut rx, messages.len()),
+        )
+        .await
+        .expect("Messages were not redelivered");
+        assert_messages(redelivered, &messages);
+    }
+}
@@ -144,6 +144,10 @@ impl SourceConfig for SimpleHttpConfig {
         "http"
     }
 
+    fn decoding(&self) -> Option<&DecodingConfig> {
+        Some(&self.decoding)
+    }
+
     fn resources(&self) -> Vec<Resource> {
         vec![Resource::tcp(self.address)]
     }
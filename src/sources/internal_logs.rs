@@ -1,5 +1,5 @@
 use crate::{
-    config::{log_schema, DataType, SourceConfig, SourceContext, SourceDescription},
+    config::{log_schema, schema, DataType, SourceConfig, SourceContext, SourceDescription},
     event::Event,
     shutdown::ShutdownSignal,
     trace, Pipeline,
@@ -7,6 +7,7 @@ use crate::{
 use futures::{stream, SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
 use tokio::sync::broadcast::error::RecvError;
+use vrl::prelude::TypeDef;
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
@@ -39,6 +40,31 @@ impl SourceConfig for InternalLogsConfig {
         DataType::Log
     }
 
+    fn output_schema(&self) -> TypeDef {
+        let host_key = self
+            .host_key
+            .as_deref()
+            .unwrap_or_else(|| log_schema().host_key());
+        let pid_key = self.pid_key.as_deref().unwrap_or("pid");
+
+        // The fields of the internal events are added as they are.
+        schema::open_log_fields(vec![
+            ("message", TypeDef::new().bytes()),
+            ("timestamp", TypeDef::new().timestamp()),
+            (
+                "metadata",
+                schema::log_fields(vec![
+                    ("kind", TypeDef::new().bytes().add_null()),
+                    ("level", TypeDef::new().bytes()),
+                    ("module_path", TypeDef::new().bytes().add_null()),
+                    ("target", TypeDef::new().bytes()),
+                ]),
+            ),
+            (host_key, TypeDef::new().bytes()),
+            (pid_key, TypeDef::new().integer()),
+        ])
+    }
+
     fn source_type(&self) -> &'static str {
         "internal_logs"
     }
@@ -140,6 +166,34 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn attributes_logs_to_components() {
+        let test_id: u8 = rand::random();
+        trace::init(false, false, "debug");
+        trace::reset_early_buffer();
+
+        let rx = start_source().await;
+
+        error_span!(
+            "sink",
+            component_kind = "sink",
+            component_id = "out",
+            component_type = "http",
+        )
+        .in_scope(|| error_span!("request").in_scope(|| error!(message = "Failed.", %test_id)));
+
+        sleep(Duration::from_millis(1)).await;
+        let mut events = collect_ready(rx).await;
+        let test_id = Value::from(test_id.to_string());
+        events.retain(|event| event.as_log().get("test_id") == Some(&test_id));
+
+        assert_eq!(events.len(), 1);
+        let log = events[0].as_log();
+        assert_eq!(log["component_kind"], "sink".into());
+        assert_eq!(log["component_id"], "out".into());
+        assert_eq!(log["component_type"], "http".into());
+    }
+
     async fn start_source() -> mpsc::Receiver<Event> {
         let (tx, rx) = Pipeline::new_test();
 
@@ -0,0 +1,173 @@
+use crate::{
+    config::{log_schema, DataType, SourceConfig, SourceContext, SourceDescription},
+    event::Event,
+    shutdown::ShutdownSignal,
+    trace, Pipeline,
+};
+use futures::SinkExt;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::error::RecvError;
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct InternalTracesConfig {
+    host_key: Option<String>,
+    pid_key: Option<String>,
+}
+
+inventory::submit! {
+    SourceDescription::new::<InternalTracesConfig>("internal_traces")
+}
+
+impl_generate_config_from_default!(InternalTracesConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "internal_traces")]
+impl SourceConfig for InternalTracesConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let host_key = self
+            .host_key
+            .as_deref()
+            .unwrap_or_else(|| log_schema().host_key())
+            .to_owned();
+        let pid_key = self.pid_key.as_deref().unwrap_or("pid").to_owned();
+
+        Ok(Box::pin(run(host_key, pid_key, cx.out, cx.shutdown)))
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn source_type(&self) -> &'static str {
+        "internal_traces"
+    }
+}
+
+async fn run(
+    host_key: String,
+    pid_key: String,
+    out: Pipeline,
+    mut shutdown: ShutdownSignal,
+) -> Result<(), ()> {
+    let mut out = out.sink_map_err(|error| error!(message = "Error sending span.", %error));
+    let mut rx = trace::subscribe_spans();
+
+    let hostname = crate::get_hostname();
+    let pid = std::process::id();
+
+    // Note: This loop, or anything called within it, MUST NOT create
+    // any spans, as that could cause an infinite loop since it receives
+    // all such spans.
+    loop {
+        tokio::select! {
+            receive = rx.recv() => {
+                match receive {
+                    Ok(mut log) => {
+                        if let Ok(hostname) = &hostname {
+                            log.insert(host_key.as_str(), hostname.to_owned());
+                        }
+                        log.insert(pid_key.as_str(), pid);
+                        out.send(Event::from(log)).await?
+                    }
+                    Err(RecvError::Lagged(_)) => (),
+                    Err(RecvError::Closed) => break,
+                }
+            }
+            _ = &mut shutdown => break,
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{event::Event, test_util::collect_ready, trace};
+    use futures::channel::mpsc;
+    use tokio::time::{sleep, Duration};
+    use vector_core::event::Value;
+
+    #[test]
+    fn generates_config() {
+        crate::test_util::test_generate_config::<InternalTracesConfig>();
+    }
+
+    #[tokio::test]
+    async fn receives_spans() {
+        let test_id: u8 = rand::random();
+        trace::init(false, false, "debug");
+
+        let rx = start_source().await;
+
+        {
+            let component = error_span!(
+                "transform",
+                component_kind = "transform",
+                component_id = "remap",
+                component_type = "remap",
+                %test_id,
+            );
+            let _component = component.enter();
+            let child = error_span!("vrl", %test_id);
+            let _child = child.enter();
+        }
+
+        sleep(Duration::from_millis(1)).await;
+        let mut events = collect_ready(rx).await;
+        let test_id = Value::from(test_id.to_string());
+        events.retain(|event| event.as_log().get("fields.test_id") == Some(&test_id));
+
+        assert_eq!(events.len(), 4);
+
+        let kinds = events
+            .iter()
+            .map(|event| {
+                let log = event.as_log();
+                (
+                    log["kind"].to_string_lossy(),
+                    log["span.name"].to_string_lossy(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                ("start".to_owned(), "transform".to_owned()),
+                ("start".to_owned(), "vrl".to_owned()),
+                ("end".to_owned(), "vrl".to_owned()),
+                ("end".to_owned(), "transform".to_owned()),
+            ]
+        );
+
+        for event in &events {
+            let log = event.as_log();
+            // The component context is inherited by nested spans.
+            assert_eq!(log["component_kind"], "transform".into());
+            assert_eq!(log["component_id"], "remap".into());
+        }
+        assert_eq!(
+            events[1].as_log()["span.parent_id"],
+            events[0].as_log()["span.id"]
+        );
+
+        let seconds = |key: &str| match events[3].as_log()[key] {
+            Value::Float(seconds) => seconds,
+            ref value => panic!("{} isn't a float: {:?}", key, value),
+        };
+        assert!(seconds("busy_seconds") <= seconds("duration_seconds"));
+    }
+
+    async fn start_source() -> mpsc::Receiver<Event> {
+        let (tx, rx) = Pipeline::new_test();
+
+        let source = InternalTracesConfig::default()
+            .build(SourceContext::new_test(tx))
+            .await
+            .unwrap();
+        tokio::spawn(source);
+        sleep(Duration::from_millis(1)).await;
+        rx
+    }
+}
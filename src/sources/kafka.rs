@@ -2,7 +2,7 @@ use super::util::finalizer::OrderedFinalizer;
 use crate::{
     codecs::{self, DecodingConfig},
     config::{log_schema, DataType, SourceConfig, SourceContext, SourceDescription},
-    event::{BatchNotifier, Event, Value},
+    event::{BatchNotifier, BatchStatus, Event, Value},
     internal_events::{KafkaEventFailed, KafkaEventReceived, KafkaOffsetUpdateFailed},
     kafka::{KafkaAuthConfig, KafkaStatisticsContext},
     shutdown::ShutdownSignal,
@@ -139,6 +139,10 @@ impl SourceConfig for KafkaSourceConfig {
     fn source_type(&self) -> &'static str {
         "kafka"
     }
+
+    fn decoding(&self) -> Option<&DecodingConfig> {
+        Some(&self.decoding)
+    }
 }
 
 async fn kafka_source(
@@ -290,8 +294,10 @@ impl<'a> From<BorrowedMessage<'a>> for FinalizerEntry {
     }
 }
 
-fn mark_done(consumer: Arc<StreamConsumer<KafkaStatisticsContext>>) -> impl Fn(FinalizerEntry) {
-    move |entry| {
+fn mark_done(
+    consumer: Arc<StreamConsumer<KafkaStatisticsContext>>,
+) -> impl Fn(BatchStatus, FinalizerEntry) {
+    move |_status, entry| {
         // Would like to use `consumer.store_offset` here, but types don't allow it.
         let mut tpl = TopicPartitionList::new();
         tpl.add_partition(&entry.topic, entry.partition)
@@ -0,0 +1,367 @@
+use super::util::finalizer::OrderedFinalizer;
+use crate::{
+    codecs::{self, DecodingConfig},
+    config::{
+        log_schema, DataType, GenerateConfig, SourceConfig, SourceContext, SourceDescription,
+    },
+    event::{BatchNotifier, BatchStatus, Event, Value},
+    internal_events::{
+        MqttAckFailed, MqttConnectionFailed, MqttDeliveryFailed, MqttEventReceived,
+        MqttSubscribeFailed,
+    },
+    mqtt::{MqttConnectionConfig, MqttQos},
+    shutdown::ShutdownSignal,
+    Pipeline,
+};
+use bytes::Bytes;
+use chrono::Utc;
+use futures::{stream, FutureExt, SinkExt, StreamExt};
+use rumqttc::{AsyncClient, EventLoop, Packet, Publish, SubscribeFilter};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio_util::codec::FramedRead;
+
+/// Capacity of the channel of requests (subscriptions, acks) to the client.
+const REQUEST_CAPACITY: usize = 1024;
+
+/// How long to wait before polling a failed connection again, which
+/// makes the client reconnect.
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MqttSourceConfig {
+    #[serde(flatten)]
+    connection: MqttConnectionConfig,
+    topics: Vec<String>,
+    #[serde(default)]
+    qos: MqttQos,
+    #[serde(default = "default_topic_key")]
+    topic_key: String,
+    #[serde(flatten)]
+    decoding: DecodingConfig,
+}
+
+fn default_topic_key() -> String {
+    "topic".into()
+}
+
+inventory::submit! {
+    SourceDescription::new::<MqttSourceConfig>("mqtt")
+}
+
+impl GenerateConfig for MqttSourceConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"
+            host = "127.0.0.1"
+            client_id = "vector"
+            topics = ["vector/#"]"#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "mqtt")]
+impl SourceConfig for MqttSourceConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let mut options = self.connection.options()?;
+        // With acknowledgements enabled the broker is only acknowledged once
+        // the events of a message have been delivered.
+        options.set_manual_acks(cx.acknowledgements);
+        let (client, eventloop) = AsyncClient::new(options, REQUEST_CAPACITY);
+
+        Ok(Box::pin(mqtt_source(
+            client,
+            eventloop,
+            self.topics.clone(),
+            self.qos,
+            self.topic_key.clone(),
+            self.decoding.build()?,
+            cx.shutdown,
+            cx.out,
+            cx.acknowledgements,
+        )))
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn source_type(&self) -> &'static str {
+        "mqtt"
+    }
+
+    fn decoding(&self) -> Option<&DecodingConfig> {
+        Some(&self.decoding)
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn mqtt_source(
+    client: AsyncClient,
+    mut eventloop: EventLoop,
+    topics: Vec<String>,
+    qos: MqttQos,
+    topic_key: String,
+    decoder: codecs::Decoder,
+    shutdown: ShutdownSignal,
+    mut out: Pipeline,
+    acknowledgements: bool,
+) -> Result<(), ()> {
+    let mut shutdown = shutdown.shared();
+    let finalizer = acknowledgements
+        .then(|| OrderedFinalizer::new(shutdown.clone(), mark_done(client.clone())));
+
+    loop {
+        let notification = tokio::select! {
+            _ = &mut shutdown => break,
+            notification = eventloop.poll() => notification,
+        };
+
+        let publish = match notification {
+            Ok(rumqttc::Event::Incoming(Packet::ConnAck(_))) => {
+                // Subscribe on every (re)connection, as a clean session does
+                // not keep subscriptions across connections.
+                let filters = topics
+                    .iter()
+                    .map(|topic| SubscribeFilter::new(topic.clone(), qos.into()));
+                if let Err(error) = client.try_subscribe_many(filters) {
+                    emit!(MqttSubscribeFailed { error });
+                    return Err(());
+                }
+                continue;
+            }
+            Ok(rumqttc::Event::Incoming(Packet::Publish(publish))) => publish,
+            Ok(_) => continue,
+            Err(error) => {
+                emit!(MqttConnectionFailed { error });
+                tokio::time::sleep(RECONNECT_DELAY).await;
+                continue;
+            }
+        };
+
+        emit!(MqttEventReceived {
+            byte_size: publish.payload.len(),
+            topic: &publish.topic,
+        });
+
+        let events = decode_message(&publish, decoder.clone(), &topic_key).await;
+
+        match &finalizer {
+            Some(finalizer) => {
+                let (batch, receiver) = BatchNotifier::new_with_receiver();
+                let mut stream =
+                    stream::iter(events).map(|event| Ok(event.with_batch_notifier(&batch)));
+                match out.send_all(&mut stream).await {
+                    Err(error) => error!(message = "Error sending to sink.", %error),
+                    Ok(_) => finalizer.add(without_payload(publish), receiver),
+                }
+            }
+            None => {
+                let mut stream = stream::iter(events).map(Ok);
+                if let Err(error) = out.send_all(&mut stream).await {
+                    error!(message = "Error sending to sink.", %error);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn decode_message(
+    publish: &Publish,
+    decoder: codecs::Decoder,
+    topic_key: &str,
+) -> Vec<Event> {
+    let now = Utc::now();
+    let mut events = Vec::new();
+
+    let mut stream = FramedRead::new(publish.payload.as_ref(), decoder);
+    while let Some(result) = stream.next().await {
+        match result {
+            Ok((decoded, _)) => {
+                for mut event in decoded {
+                    if let Event::Log(ref mut log) = event {
+                        log.insert(log_schema().source_type_key(), Bytes::from("mqtt"));
+                        log.insert(log_schema().timestamp_key(), now);
+                        log.insert(topic_key, Value::from(publish.topic.clone()));
+                    }
+                    events.push(event);
+                }
+            }
+            Err(error) => {
+                // Error is logged by `crate::codecs::Decoder`, no further handling
+                // is needed here.
+                if !error.can_continue() {
+                    break;
+                }
+            }
+        }
+    }
+
+    events
+}
+
+/// Acknowledging only needs the packet identifier and QoS of a message, so
+/// the payload isn't kept around until the events are delivered.
+fn without_payload(publish: Publish) -> Publish {
+    Publish {
+        payload: Bytes::new(),
+        ..publish
+    }
+}
+
+/// MQTT has no negative acknowledgement, so messages whose events were not
+/// delivered are left unacknowledged. The broker sends them again when the
+/// session is resumed, which requires `clean_session` to be disabled.
+fn mark_done(client: AsyncClient) -> impl Fn(BatchStatus, Publish) {
+    move |status, publish| match status {
+        BatchStatus::Delivered => {
+            if let Err(error) = client.try_ack(&publish) {
+                emit!(MqttAckFailed { error });
+            }
+        }
+        BatchStatus::Errored | BatchStatus::Failed => emit!(MqttDeliveryFailed {
+            topic: &publish.topic,
+            pkid: publish.pkid,
+            status,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<MqttSourceConfig>();
+    }
+
+    #[tokio::test]
+    async fn decodes_message_with_topic() {
+        let config: MqttSourceConfig = toml::from_str(
+            r#"
+            host = "127.0.0.1"
+            client_id = "vector"
+            topics = ["sensors/+"]
+            "#,
+        )
+        .unwrap();
+        let publish = Publish::new("sensors/1", rumqttc::QoS::AtLeastOnce, "21.5\n22.0");
+
+        let events = decode_message(&publish, config.decoding.build().unwrap(), "topic").await;
+
+        assert_eq!(events.len(), 2);
+        let log = events[0].as_log();
+        assert_eq!(log[log_schema().message_key()], "21.5".into());
+        assert_eq!(log["topic"], "sensors/1".into());
+        assert_eq!(log[log_schema().source_type_key()], "mqtt".into());
+    }
+
+    #[test]
+    fn acknowledges_delivered_messages_only() {
+        let (client, eventloop) =
+            AsyncClient::new(rumqttc::MqttOptions::new("test", "127.0.0.1", 1883), 10);
+        let done = mark_done(client);
+        let mut publish = Publish::new("sensors/1", rumqttc::QoS::AtLeastOnce, "21.5");
+        publish.pkid = 1;
+        let publish = without_payload(publish);
+
+        done(BatchStatus::Errored, publish.clone());
+        done(BatchStatus::Failed, publish.clone());
+        assert!(eventloop.requests_rx.try_recv().is_err());
+
+        done(BatchStatus::Delivered, publish);
+        assert!(matches!(
+            eventloop.requests_rx.try_recv(),
+            Ok(rumqttc::Request::PubAck(ack)) if ack.pkid == 1
+        ));
+    }
+}
+
+#[cfg(feature = "mqtt-integration-tests")]
+#[cfg(test)]
+mod integration_tests {
+    use super::*;
+    use crate::{
+        event::EventStatus,
+        test_util::{collect_n, random_string, trace_init},
+    };
+    use rumqttc::{MqttOptions, QoS};
+
+    fn mqtt_host() -> String {
+        std::env::var("MQTT_HOST").unwrap_or_else(|_| "127.0.0.1".into())
+    }
+
+    async fn publish(topic: &str, messages: &[String]) {
+        let options = MqttOptions::new(
+            format!("publisher-{}", random_string(10)),
+            mqtt_host(),
+            1883,
+        );
+        let (client, mut eventloop) = AsyncClient::new(options, messages.len() + 1);
+        for message in messages {
+            client
+                .publish(topic, QoS::AtLeastOnce, false, message.clone())
+                .await
+                .unwrap();
+        }
+
+        let mut acked = 0;
+        while acked < messages.len() {
+            if let rumqttc::Event::Incoming(Packet::PubAck(_)) = eventloop.poll().await.unwrap() {
+                acked += 1;
+            }
+        }
+    }
+
+    async fn consume_messages(acknowledgements: bool) {
+        trace_init();
+
+        let topic = format!("test-{}", random_string(10));
+        let config: MqttSourceConfig = toml::from_str(&format!(
+            r#"
+            host = "{}"
+            client_id = "vector-{}"
+            topics = ["{}/#"]
+            "#,
+            mqtt_host(),
+            random_string(10),
+            topic
+        ))
+        .unwrap();
+
+        let (tx, rx) = Pipeline::new_test_finalize(EventStatus::Delivered);
+        let mut cx = SourceContext::new_test(tx);
+        cx.acknowledgements = acknowledgements;
+        tokio::spawn(config.build(cx).await.unwrap());
+
+        // Give the source time to connect and subscribe.
+        tokio::time::sleep(Duration::from_secs(1)).await;
+
+        let messages = (0..10)
+            .map(|i| format!("message {}", i))
+            .collect::<Vec<_>>();
+        publish(&format!("{}/sensor", topic), &messages).await;
+
+        let events = collect_n(rx, messages.len()).await;
+        for (event, message) in events.iter().zip(&messages) {
+            let log = event.as_log();
+            assert_eq!(log[log_schema().message_key()], message.as_str().into());
+            assert_eq!(log["topic"], format!("{}/sensor", topic).into());
+        }
+    }
+
+    #[tokio::test]
+    async fn consumes_messages_with_acknowledgements() {
+        consume_messages(true).await;
+    }
+
+    #[tokio::test]
+    async fn consumes_messages_without_acknowledgements() {
+        consume_messages(false).await;
+    }
+}
@@ -0,0 +1,272 @@
+use crate::{
+    config::{
+        log_schema, DataType, GenerateConfig, Resource, SourceConfig, SourceContext,
+        SourceDescription,
+    },
+    event::Event,
+    internal_events::{
+        NetflowDecodeFailed, NetflowEventsReceived, NetflowReceiveFailed, NetflowTemplateMissed,
+    },
+    shutdown::ShutdownSignal,
+    udp, Pipeline,
+};
+use bytes::Bytes;
+use futures::{stream, SinkExt, StreamExt};
+use reader::{DecodeError, Reader};
+use serde::{Deserialize, Serialize};
+use std::{net::SocketAddr, time::Duration};
+use template::{Decoded, TemplateCache};
+use tokio::{net::UdpSocket, time};
+
+mod netflow_v5;
+mod reader;
+mod sflow;
+mod template;
+
+/// The largest payload of a UDP datagram.
+const MAX_DATAGRAM_SIZE: usize = 65_507;
+
+/// Templates which are not refreshed are already ignored, this only frees
+/// the memory they take.
+const TEMPLATE_SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+struct NetflowConfig {
+    address: SocketAddr,
+    #[serde(default = "default_template_expiry_secs")]
+    template_expiry_secs: u64,
+    receive_buffer_bytes: Option<usize>,
+}
+
+const fn default_template_expiry_secs() -> u64 {
+    1800
+}
+
+inventory::submit! {
+    SourceDescription::new::<NetflowConfig>("netflow")
+}
+
+impl GenerateConfig for NetflowConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(r#"address = "0.0.0.0:2055""#).unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "netflow")]
+impl SourceConfig for NetflowConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        Ok(Box::pin(netflow(self.clone(), cx.shutdown, cx.out)))
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn source_type(&self) -> &'static str {
+        "netflow"
+    }
+
+    fn resources(&self) -> Vec<Resource> {
+        vec![Resource::udp(self.address)]
+    }
+}
+
+async fn netflow(
+    config: NetflowConfig,
+    mut shutdown: ShutdownSignal,
+    out: Pipeline,
+) -> Result<(), ()> {
+    let mut out = out.sink_map_err(|error| error!(message = "Error sending event.", %error));
+
+    let socket = UdpSocket::bind(&config.address).await.map_err(|error| {
+        error!(
+            message = "Failed to bind to UDP listener socket.",
+            address = %config.address,
+            %error
+        )
+    })?;
+    if let Some(receive_buffer_bytes) = config.receive_buffer_bytes {
+        if let Err(error) = udp::set_receive_buffer_size(&socket, receive_buffer_bytes) {
+            warn!(message = "Failed configuring receive buffer size on UDP socket.", %error);
+        }
+    }
+    info!(message = "Listening.", address = %config.address);
+
+    let mut templates = TemplateCache::new(Duration::from_secs(config.template_expiry_secs));
+    let mut sweep = time::interval(TEMPLATE_SWEEP_INTERVAL);
+    let mut buf = vec![0; MAX_DATAGRAM_SIZE];
+    loop {
+        let (size, exporter) = tokio::select! {
+            _ = &mut shutdown => break,
+            _ = sweep.tick() => {
+                templates.expire();
+                continue;
+            }
+            result = socket.recv_from(&mut buf) => match result {
+                Ok(received) => received,
+                Err(error) => {
+                    emit!(NetflowReceiveFailed { error });
+                    continue;
+                }
+            },
+        };
+
+        let (flow_type, decoded) = match decode(&buf[..size], exporter, &mut templates) {
+            Ok(decoded) => decoded,
+            Err(error) => {
+                emit!(NetflowDecodeFailed { exporter, error });
+                continue;
+            }
+        };
+        for template_id in decoded.missed_templates {
+            emit!(NetflowTemplateMissed {
+                exporter,
+                flow_type,
+                template_id,
+            });
+        }
+        if decoded.events.is_empty() {
+            continue;
+        }
+
+        emit!(NetflowEventsReceived {
+            count: decoded.events.len(),
+            byte_size: size,
+        });
+        let exporter_address = exporter.ip().to_string();
+        let mut stream = stream::iter(decoded.events).map(|mut log| {
+            log.insert(log_schema().source_type_key(), Bytes::from("netflow"));
+            log.insert("flow_type", flow_type);
+            log.insert("exporter_address", exporter_address.clone());
+            Ok(Event::from(log))
+        });
+        out.send_all(&mut stream).await?;
+    }
+
+    Ok(())
+}
+
+/// Decodes a datagram of any of the supported protocols, which are told
+/// apart by their leading version number.
+fn decode(
+    data: &[u8],
+    exporter: SocketAddr,
+    templates: &mut TemplateCache,
+) -> Result<(&'static str, Decoded), DecodeError> {
+    let from_events = |events| Decoded {
+        events,
+        ..Decoded::default()
+    };
+
+    let mut reader = Reader::new(data);
+    Ok(match reader.u16()? {
+        5 => ("netflow_v5", from_events(netflow_v5::decode(data)?)),
+        9 => (
+            "netflow_v9",
+            template::decode_v9(data, exporter, templates)?,
+        ),
+        10 => ("ipfix", template::decode_ipfix(data, exporter, templates)?),
+        // sFlow starts with a 32 bits version.
+        0 if reader.u16()? == 5 => ("sflow_v5", from_events(sflow::decode(data)?)),
+        version => {
+            return Err(DecodeError::UnsupportedVersion {
+                version: version.into(),
+            })
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{collect_n, next_addr, trace_init};
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<NetflowConfig>();
+    }
+
+    #[test]
+    fn rejects_unsupported_versions() {
+        let mut templates = TemplateCache::new(Duration::from_secs(60));
+        let exporter = "192.0.2.1:2055".parse().unwrap();
+
+        assert_eq!(
+            decode(&[0, 7, 0, 0], exporter, &mut templates).unwrap_err(),
+            DecodeError::UnsupportedVersion { version: 7 }
+        );
+        assert_eq!(
+            decode(&[0, 0, 0, 4], exporter, &mut templates).unwrap_err(),
+            DecodeError::UnsupportedVersion { version: 0 }
+        );
+    }
+
+    #[tokio::test]
+    async fn receives_flows() {
+        trace_init();
+
+        let address = next_addr();
+        let config: NetflowConfig = toml::from_str(&format!(r#"address = "{}""#, address)).unwrap();
+        let (tx, rx) = Pipeline::new_test();
+        tokio::spawn(config.build(SourceContext::new_test(tx)).await.unwrap());
+        // Give the source time to bind.
+        time::sleep(Duration::from_millis(100)).await;
+
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let packets = vec![
+            netflow_v5::tests::packet(&[([10, 0, 0, 1], [10, 0, 0, 2], 3, 180)]),
+            template::tests::ipfix_packet(&[
+                template::tests::set(2, &template::tests::template(256, &[(8, 4), (2, 8)])),
+                template::tests::set(256, &[10, 0, 0, 3, 0, 0, 0, 0, 0, 0, 0, 9]),
+            ]),
+            sflow::tests::datagram(&[(1, sflow::tests::flow_sample())]),
+        ];
+        for packet in &packets {
+            socket.send_to(packet, address).await.unwrap();
+        }
+
+        let events = collect_n(rx, 3).await;
+
+        let flow_types = events
+            .iter()
+            .map(|event| event.as_log()["flow_type"].to_string_lossy())
+            .collect::<Vec<_>>();
+        assert_eq!(flow_types, vec!["netflow_v5", "ipfix", "sflow_v5"]);
+        for event in &events {
+            let log = event.as_log();
+            assert_eq!(log["exporter_address"], "127.0.0.1".into());
+            assert_eq!(log[log_schema().source_type_key()], "netflow".into());
+        }
+        assert_eq!(events[1].as_log()["packet_delta_count"], 9.into());
+    }
+
+    #[test]
+    fn decodes_after_template_miss() {
+        let mut templates = TemplateCache::new(Duration::from_secs(60));
+        let exporter = "192.0.2.1:2055".parse().unwrap();
+        let data_set = template::tests::set(256, &[10, 0, 0, 3]);
+
+        let (_, decoded) = decode(
+            &template::tests::v9_packet(&[data_set.clone()]),
+            exporter,
+            &mut templates,
+        )
+        .unwrap();
+        assert_eq!(decoded.missed_templates, vec![256]);
+
+        let (flow_type, decoded) = decode(
+            &template::tests::v9_packet(&[
+                template::tests::set(0, &template::tests::template(256, &[(8, 4)])),
+                data_set,
+            ]),
+            exporter,
+            &mut templates,
+        )
+        .unwrap();
+        assert_eq!(flow_type, "netflow_v9");
+        assert_eq!(decoded.events.len(), 1);
+        assert_eq!(decoded.events[0]["source_ipv4_address"], "10.0.0.3".into());
+    }
+}
@@ -0,0 +1,144 @@
+//! Decoding of NetFlow v5 packets, made of a fixed header followed by fixed
+//! size flow records.
+
+use super::reader::{DecodeError, Reader};
+use crate::{config::log_schema, event::LogEvent, sources::util::saturating_counter};
+use chrono::{Duration, TimeZone, Utc};
+
+const HEADER_LEN: usize = 24;
+const RECORD_LEN: usize = 48;
+
+pub(super) fn decode(data: &[u8]) -> Result<Vec<LogEvent>, DecodeError> {
+    let mut reader = Reader::new(data);
+    let _version = reader.u16()?;
+    let count = reader.u16()? as usize;
+    let sys_uptime = reader.u32()?;
+    let unix_secs = reader.u32()?;
+    let unix_nsecs = reader.u32()?;
+    let flow_sequence = reader.u32()?;
+    let engine_type = reader.u8()?;
+    let engine_id = reader.u8()?;
+    let sampling = reader.u16()?;
+    if data.len() < HEADER_LEN + count * RECORD_LEN {
+        return Err(DecodeError::Truncated);
+    }
+
+    let export_time = Utc
+        .timestamp_opt(i64::from(unix_secs), unix_nsecs)
+        .single()
+        .unwrap_or_else(Utc::now);
+    // Flow times are given in milliseconds of uptime of the exporter.
+    let uptime_to_timestamp = |uptime: u32| {
+        export_time - Duration::milliseconds(i64::from(sys_uptime) - i64::from(uptime))
+    };
+
+    (0..count)
+        .map(|_| {
+            let mut log = LogEvent::default();
+            log.insert(log_schema().timestamp_key(), export_time);
+            log.insert("sequence_number", flow_sequence);
+            log.insert("engine_type", engine_type);
+            log.insert("engine_id", engine_id);
+            log.insert("sampling_interval", sampling & 0x3FFF);
+
+            log.insert("source_ipv4_address", reader.ipv4()?.to_string());
+            log.insert("destination_ipv4_address", reader.ipv4()?.to_string());
+            log.insert("ip_next_hop_ipv4_address", reader.ipv4()?.to_string());
+            log.insert("ingress_interface", reader.u16()?);
+            log.insert("egress_interface", reader.u16()?);
+            log.insert(
+                "packet_delta_count",
+                saturating_counter(reader.u32()?.into()),
+            );
+            log.insert(
+                "octet_delta_count",
+                saturating_counter(reader.u32()?.into()),
+            );
+            log.insert(
+                "flow_start_milliseconds",
+                uptime_to_timestamp(reader.u32()?),
+            );
+            log.insert("flow_end_milliseconds", uptime_to_timestamp(reader.u32()?));
+            log.insert("source_transport_port", reader.u16()?);
+            log.insert("destination_transport_port", reader.u16()?);
+            reader.skip(1)?;
+            log.insert("tcp_control_bits", reader.u8()?);
+            log.insert("protocol_identifier", reader.u8()?);
+            log.insert("ip_class_of_service", reader.u8()?);
+            log.insert("bgp_source_as_number", reader.u16()?);
+            log.insert("bgp_destination_as_number", reader.u16()?);
+            log.insert("source_ipv4_prefix_length", reader.u8()?);
+            log.insert("destination_ipv4_prefix_length", reader.u8()?);
+            reader.skip(2)?;
+            Ok(log)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+    use crate::event::Value;
+
+    /// Builds a packet with a record per flow of `(source, destination,
+    /// packets, bytes)`.
+    pub(crate) fn packet(flows: &[([u8; 4], [u8; 4], u32, u32)]) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(&5u16.to_be_bytes());
+        data.extend_from_slice(&(flows.len() as u16).to_be_bytes());
+        data.extend_from_slice(&60_000u32.to_be_bytes());
+        data.extend_from_slice(&1_636_106_400u32.to_be_bytes());
+        data.extend_from_slice(&0u32.to_be_bytes());
+        data.extend_from_slice(&42u32.to_be_bytes());
+        data.extend_from_slice(&[0, 1]);
+        data.extend_from_slice(&(0x4000u16 | 100).to_be_bytes());
+        for (source, destination, packets, bytes) in flows {
+            data.extend_from_slice(source);
+            data.extend_from_slice(destination);
+            data.extend_from_slice(&[0; 4]);
+            data.extend_from_slice(&[0, 1, 0, 2]);
+            data.extend_from_slice(&packets.to_be_bytes());
+            data.extend_from_slice(&bytes.to_be_bytes());
+            data.extend_from_slice(&50_000u32.to_be_bytes());
+            data.extend_from_slice(&59_000u32.to_be_bytes());
+            data.extend_from_slice(&[0xC3, 0x50, 0x01, 0xBB]);
+            data.extend_from_slice(&[0, 0x18, 6, 0]);
+            data.extend_from_slice(&[0; 12]);
+        }
+        data
+    }
+
+    #[test]
+    fn decodes_records() {
+        let data = packet(&[
+            ([10, 0, 0, 1], [10, 0, 0, 2], 3, 180),
+            ([10, 0, 0, 2], [10, 0, 0, 1], 2, 120),
+        ]);
+
+        let events = decode(&data).unwrap();
+
+        assert_eq!(events.len(), 2);
+        let log = &events[0];
+        assert_eq!(log["source_ipv4_address"], "10.0.0.1".into());
+        assert_eq!(log["destination_ipv4_address"], "10.0.0.2".into());
+        assert_eq!(log["packet_delta_count"], 3.into());
+        assert_eq!(log["octet_delta_count"], 180.into());
+        assert_eq!(log["source_transport_port"], 50000.into());
+        assert_eq!(log["destination_transport_port"], 443.into());
+        assert_eq!(log["protocol_identifier"], 6.into());
+        assert_eq!(log["sequence_number"], 42.into());
+        assert_eq!(log["sampling_interval"], 100.into());
+        assert_eq!(
+            log["flow_start_milliseconds"],
+            Value::from(Utc.timestamp(1_636_106_390, 0))
+        );
+        assert_eq!(events[1]["source_ipv4_address"], "10.0.0.2".into());
+    }
+
+    #[test]
+    fn rejects_truncated_packets() {
+        let data = packet(&[([10, 0, 0, 1], [10, 0, 0, 2], 3, 180)]);
+
+        assert_eq!(decode(&data[..data.len() - 1]), Err(DecodeError::Truncated));
+    }
+}
@@ -0,0 +1,94 @@
+use snafu::Snafu;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+#[derive(Debug, PartialEq, Snafu)]
+pub(super) enum DecodeError {
+    #[snafu(display("packet is truncated"))]
+    Truncated,
+    #[snafu(display("unsupported version {}", version))]
+    UnsupportedVersion { version: u32 },
+    #[snafu(display("invalid length {} of set {}", length, id))]
+    InvalidSetLength { id: u16, length: u16 },
+    #[snafu(display("unsupported address type {}", address_type))]
+    UnsupportedAddressType { address_type: u32 },
+}
+
+/// Reads big endian values from a packet.
+#[derive(Clone, Copy)]
+pub(super) struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    pub(super) const fn new(data: &'a [u8]) -> Self {
+        Self(data)
+    }
+
+    pub(super) const fn remaining(&self) -> usize {
+        self.0.len()
+    }
+
+    pub(super) fn bytes(&mut self, len: usize) -> Result<&'a [u8], DecodeError> {
+        if self.0.len() < len {
+            return Err(DecodeError::Truncated);
+        }
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(bytes)
+    }
+
+    /// Splits off the next `len` bytes into their own reader.
+    pub(super) fn sub(&mut self, len: usize) -> Result<Reader<'a>, DecodeError> {
+        self.bytes(len).map(Reader)
+    }
+
+    pub(super) fn skip(&mut self, len: usize) -> Result<(), DecodeError> {
+        self.bytes(len).map(|_| ())
+    }
+
+    pub(super) fn u8(&mut self) -> Result<u8, DecodeError> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    pub(super) fn u16(&mut self) -> Result<u16, DecodeError> {
+        let bytes = self.bytes(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    pub(super) fn u32(&mut self) -> Result<u32, DecodeError> {
+        let bytes = self.bytes(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    pub(super) fn u64(&mut self) -> Result<u64, DecodeError> {
+        Ok(u64::from(self.u32()?) << 32 | u64::from(self.u32()?))
+    }
+
+    pub(super) fn ipv4(&mut self) -> Result<Ipv4Addr, DecodeError> {
+        let bytes = self.bytes(4)?;
+        Ok(Ipv4Addr::new(bytes[0], bytes[1], bytes[2], bytes[3]))
+    }
+
+    pub(super) fn ipv6(&mut self) -> Result<Ipv6Addr, DecodeError> {
+        let mut octets = [0; 16];
+        octets.copy_from_slice(self.bytes(16)?);
+        Ok(Ipv6Addr::from(octets))
+    }
+}
+
+/// Reads an unsigned integer of up to 8 bytes, in network byte order.
+pub(super) fn unsigned(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(0, |value, byte| value << 8 | u64::from(*byte))
+}
+
+pub(super) fn format_mac(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+pub(super) fn format_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
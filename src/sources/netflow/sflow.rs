@@ -0,0 +1,366 @@
+//! Decoding of sFlow v5 datagrams, made of flow samples of packets and
+//! counter samples of interfaces.
+//!
+//! See <https://sflow.org/sflow_version_5.txt>.
+
+use super::reader::{format_mac, DecodeError, Reader};
+use crate::{config::log_schema, event::LogEvent, sources::util::saturating_counter};
+use chrono::Utc;
+
+pub(super) fn decode(data: &[u8]) -> Result<Vec<LogEvent>, DecodeError> {
+    let mut reader = Reader::new(data);
+    let _version = reader.u32()?;
+    let agent_address = match reader.u32()? {
+        1 => reader.ipv4()?.to_string(),
+        2 => reader.ipv6()?.to_string(),
+        address_type => return Err(DecodeError::UnsupportedAddressType { address_type }),
+    };
+    let sub_agent_id = reader.u32()?;
+    let sequence_number = reader.u32()?;
+    let uptime = reader.u32()?;
+    let sample_count = reader.u32()?;
+
+    let now = Utc::now();
+    let mut events = Vec::new();
+    for _ in 0..sample_count {
+        let format = reader.u32()?;
+        let length = reader.u32()? as usize;
+        let mut sample = reader.sub(length)?;
+
+        let mut log = LogEvent::default();
+        log.insert(log_schema().timestamp_key(), now);
+        log.insert("agent_address", agent_address.clone());
+        log.insert("sub_agent_id", sub_agent_id);
+        log.insert("sequence_number", sequence_number);
+        log.insert("uptime", uptime);
+
+        // Samples of other enterprises than sFlow are skipped.
+        match format {
+            1 => {
+                log.insert("sample_type", "flow");
+                log.insert("sample_sequence_number", sample.u32()?);
+                let source_id = sample.u32()?;
+                log.insert("source_id_type", source_id >> 24);
+                log.insert("source_id_index", source_id & 0x00FF_FFFF);
+                log.insert("sampling_rate", sample.u32()?);
+                log.insert("sample_pool", sample.u32()?);
+                log.insert("drops", sample.u32()?);
+                log.insert("ingress_interface", sample.u32()? & 0x3FFF_FFFF);
+                log.insert("egress_interface", sample.u32()? & 0x3FFF_FFFF);
+                decode_records(&mut sample, &mut log, decode_flow_record)?;
+            }
+            2 => {
+                log.insert("sample_type", "counter");
+                log.insert("sample_sequence_number", sample.u32()?);
+                let source_id = sample.u32()?;
+                log.insert("source_id_type", source_id >> 24);
+                log.insert("source_id_index", source_id & 0x00FF_FFFF);
+                decode_records(&mut sample, &mut log, decode_counter_record)?;
+            }
+            3 => {
+                log.insert("sample_type", "flow");
+                log.insert("sample_sequence_number", sample.u32()?);
+                log.insert("source_id_type", sample.u32()?);
+                log.insert("source_id_index", sample.u32()?);
+                log.insert("sampling_rate", sample.u32()?);
+                log.insert("sample_pool", sample.u32()?);
+                log.insert("drops", sample.u32()?);
+                sample.skip(4)?;
+                log.insert("ingress_interface", sample.u32()?);
+                sample.skip(4)?;
+                log.insert("egress_interface", sample.u32()?);
+                decode_records(&mut sample, &mut log, decode_flow_record)?;
+            }
+            4 => {
+                log.insert("sample_type", "counter");
+                log.insert("sample_sequence_number", sample.u32()?);
+                log.insert("source_id_type", sample.u32()?);
+                log.insert("source_id_index", sample.u32()?);
+                decode_records(&mut sample, &mut log, decode_counter_record)?;
+            }
+            _ => continue,
+        }
+        events.push(log);
+    }
+
+    Ok(events)
+}
+
+fn decode_records(
+    sample: &mut Reader,
+    log: &mut LogEvent,
+    decode_record: fn(u32, Reader, &mut LogEvent) -> Result<(), DecodeError>,
+) -> Result<(), DecodeError> {
+    let record_count = sample.u32()?;
+    for _ in 0..record_count {
+        let format = sample.u32()?;
+        let length = sample.u32()? as usize;
+        decode_record(format, sample.sub(length)?, log)?;
+    }
+    Ok(())
+}
+
+fn decode_flow_record(
+    format: u32,
+    mut record: Reader,
+    log: &mut LogEvent,
+) -> Result<(), DecodeError> {
+    match format {
+        // Raw packet header
+        1 => {
+            let protocol = record.u32()?;
+            log.insert("header_protocol", protocol);
+            log.insert("frame_length", record.u32()?);
+            log.insert("stripped", record.u32()?);
+            let length = record.u32()? as usize;
+            let header = Reader::new(record.bytes(length)?);
+            // Only Ethernet headers are decoded. The header is cut at the
+            // sampled length, so it is decoded as far as it goes.
+            if protocol == 1 {
+                let _ = decode_ethernet(header, log);
+            }
+        }
+        // Ethernet frame data
+        2 => {
+            log.insert("frame_length", record.u32()?);
+            log.insert("source_mac_address", format_mac(record.bytes(6)?));
+            record.skip(2)?;
+            log.insert("destination_mac_address", format_mac(record.bytes(6)?));
+            record.skip(2)?;
+            log.insert("ethernet_type", record.u32()?);
+        }
+        // IPv4 data
+        3 => {
+            log.insert("ip_total_length", record.u32()?);
+            log.insert("protocol_identifier", record.u32()?);
+            log.insert("source_ipv4_address", record.ipv4()?.to_string());
+            log.insert("destination_ipv4_address", record.ipv4()?.to_string());
+            log.insert("source_transport_port", record.u32()?);
+            log.insert("destination_transport_port", record.u32()?);
+            log.insert("tcp_control_bits", record.u32()?);
+            log.insert("ip_class_of_service", record.u32()?);
+        }
+        // IPv6 data
+        4 => {
+            log.insert("ip_total_length", record.u32()?);
+            log.insert("protocol_identifier", record.u32()?);
+            log.insert("source_ipv6_address", record.ipv6()?.to_string());
+            log.insert("destination_ipv6_address", record.ipv6()?.to_string());
+            log.insert("source_transport_port", record.u32()?);
+            log.insert("destination_transport_port", record.u32()?);
+            log.insert("tcp_control_bits", record.u32()?);
+            log.insert("ip_class_of_service", record.u32()?);
+        }
+        // Extended switch data
+        1001 => {
+            log.insert("source_vlan", record.u32()?);
+            log.insert("source_priority", record.u32()?);
+            log.insert("destination_vlan", record.u32()?);
+            log.insert("destination_priority", record.u32()?);
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn decode_counter_record(
+    format: u32,
+    mut record: Reader,
+    log: &mut LogEvent,
+) -> Result<(), DecodeError> {
+    // Generic interface counters
+    if format == 1 {
+        log.insert("if_index", record.u32()?);
+        log.insert("if_type", record.u32()?);
+        log.insert("if_speed", saturating_counter(record.u64()?));
+        log.insert("if_direction", record.u32()?);
+        log.insert("if_status", record.u32()?);
+        log.insert("if_in_octets", saturating_counter(record.u64()?));
+        for name in &[
+            "if_in_ucast_pkts",
+            "if_in_multicast_pkts",
+            "if_in_broadcast_pkts",
+            "if_in_discards",
+            "if_in_errors",
+            "if_in_unknown_protos",
+        ] {
+            log.insert(*name, record.u32()?);
+        }
+        log.insert("if_out_octets", saturating_counter(record.u64()?));
+        for name in &[
+            "if_out_ucast_pkts",
+            "if_out_multicast_pkts",
+            "if_out_broadcast_pkts",
+            "if_out_discards",
+            "if_out_errors",
+            "if_promiscuous_mode",
+        ] {
+            log.insert(*name, record.u32()?);
+        }
+    }
+    Ok(())
+}
+
+fn decode_ethernet(mut header: Reader, log: &mut LogEvent) -> Result<(), DecodeError> {
+    log.insert("destination_mac_address", format_mac(header.bytes(6)?));
+    log.insert("source_mac_address", format_mac(header.bytes(6)?));
+    let mut ethernet_type = header.u16()?;
+    if ethernet_type == 0x8100 {
+        log.insert("vlan_id", header.u16()? & 0x0FFF);
+        ethernet_type = header.u16()?;
+    }
+    log.insert("ethernet_type", ethernet_type);
+
+    let protocol = match ethernet_type {
+        0x0800 => {
+            let header_length = (header.u8()? & 0x0F) as usize * 4;
+            log.insert("ip_class_of_service", header.u8()?);
+            log.insert("ip_total_length", header.u16()?);
+            header.skip(4)?;
+            log.insert("ip_ttl", header.u8()?);
+            let protocol = header.u8()?;
+            header.skip(2)?;
+            log.insert("source_ipv4_address", header.ipv4()?.to_string());
+            log.insert("destination_ipv4_address", header.ipv4()?.to_string());
+            header.skip(header_length.saturating_sub(20))?;
+            protocol
+        }
+        0x86DD => {
+            let first = header.u32()?;
+            log.insert("ip_class_of_service", (first >> 20) & 0xFF);
+            log.insert("ip_total_length", u32::from(header.u16()?) + 40);
+            let protocol = header.u8()?;
+            log.insert("ip_ttl", header.u8()?);
+            log.insert("source_ipv6_address", header.ipv6()?.to_string());
+            log.insert("destination_ipv6_address", header.ipv6()?.to_string());
+            protocol
+        }
+        _ => return Ok(()),
+    };
+    log.insert("protocol_identifier", protocol);
+
+    // TCP and UDP
+    if protocol == 6 || protocol == 17 {
+        log.insert("source_transport_port", header.u16()?);
+        log.insert("destination_transport_port", header.u16()?);
+        if protocol == 6 {
+            header.skip(9)?;
+            log.insert("tcp_control_bits", header.u8()?);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+
+    /// Builds a datagram of an IPv4 agent with the given samples, each of
+    /// their format and contents.
+    pub(crate) fn datagram(samples: &[(u32, Vec<u8>)]) -> Vec<u8> {
+        let mut data = Vec::new();
+        for value in &[5, 1] {
+            data.extend_from_slice(&u32::to_be_bytes(*value));
+        }
+        data.extend_from_slice(&[192, 0, 2, 10]);
+        for value in &[0, 9, 120_000, samples.len() as u32] {
+            data.extend_from_slice(&u32::to_be_bytes(*value));
+        }
+        for (format, sample) in samples {
+            data.extend_from_slice(&format.to_be_bytes());
+            data.extend_from_slice(&(sample.len() as u32).to_be_bytes());
+            data.extend_from_slice(sample);
+        }
+        data
+    }
+
+    /// Builds a flow sample with a raw packet header record of a TCP packet.
+    pub(crate) fn flow_sample() -> Vec<u8> {
+        let mut packet = Vec::new();
+        packet.extend_from_slice(&[0x00, 0x11, 0x22, 0x33, 0x44, 0x55]);
+        packet.extend_from_slice(&[0x66, 0x77, 0x88, 0x99, 0xAA, 0xBB]);
+        packet.extend_from_slice(&[0x08, 0x00]);
+        packet.extend_from_slice(&[0x45, 0, 0, 60, 0, 0, 0x40, 0, 64, 6, 0, 0]);
+        packet.extend_from_slice(&[10, 0, 0, 1, 10, 0, 0, 2]);
+        packet.extend_from_slice(&[0xC3, 0x50, 0x01, 0xBB, 0, 0, 0, 0, 0, 0, 0, 0, 0x50, 0x02]);
+        packet.extend_from_slice(&[0, 0]);
+
+        let mut record = Vec::new();
+        for value in &[1, 74, 4, packet.len() as u32] {
+            record.extend_from_slice(&u32::to_be_bytes(*value));
+        }
+        record.extend_from_slice(&packet);
+
+        let mut sample = Vec::new();
+        for value in &[3, 5, 512, 1_024_000, 0, 5, 6, 1, 1, record.len() as u32] {
+            sample.extend_from_slice(&u32::to_be_bytes(*value));
+        }
+        sample.extend_from_slice(&record);
+        sample
+    }
+
+    #[test]
+    fn decodes_flow_samples() {
+        let data = datagram(&[(1, flow_sample())]);
+
+        let events = decode(&data).unwrap();
+
+        assert_eq!(events.len(), 1);
+        let log = &events[0];
+        assert_eq!(log["agent_address"], "192.0.2.10".into());
+        assert_eq!(log["sample_type"], "flow".into());
+        assert_eq!(log["sampling_rate"], 512.into());
+        assert_eq!(log["ingress_interface"], 5.into());
+        assert_eq!(log["frame_length"], 74.into());
+        assert_eq!(log["source_mac_address"], "66:77:88:99:aa:bb".into());
+        assert_eq!(log["source_ipv4_address"], "10.0.0.1".into());
+        assert_eq!(log["destination_ipv4_address"], "10.0.0.2".into());
+        assert_eq!(log["protocol_identifier"], 6.into());
+        assert_eq!(log["source_transport_port"], 50000.into());
+        assert_eq!(log["destination_transport_port"], 443.into());
+        assert_eq!(log["tcp_control_bits"], 2.into());
+    }
+
+    #[test]
+    fn decodes_counter_samples() {
+        let mut record = Vec::new();
+        for value in &[7, 6] {
+            record.extend_from_slice(&u32::to_be_bytes(*value));
+        }
+        record.extend_from_slice(&10_000_000_000u64.to_be_bytes());
+        for value in &[1, 3] {
+            record.extend_from_slice(&u32::to_be_bytes(*value));
+        }
+        record.extend_from_slice(&123_456u64.to_be_bytes());
+        record.extend_from_slice(&[0; 24]);
+        record.extend_from_slice(&654_321u64.to_be_bytes());
+        record.extend_from_slice(&[0; 24]);
+        let mut sample = Vec::new();
+        for value in &[1, 7, 1, 1, record.len() as u32] {
+            sample.extend_from_slice(&u32::to_be_bytes(*value));
+        }
+        sample.extend_from_slice(&record);
+        let data = datagram(&[(2, sample), (0x1234_5678, vec![0; 4])]);
+
+        let events = decode(&data).unwrap();
+
+        assert_eq!(events.len(), 1);
+        let log = &events[0];
+        assert_eq!(log["sample_type"], "counter".into());
+        assert_eq!(log["if_index"], 7.into());
+        assert_eq!(log["if_speed"], 10_000_000_000i64.into());
+        assert_eq!(log["if_in_octets"], 123_456.into());
+        assert_eq!(log["if_out_octets"], 654_321.into());
+    }
+
+    #[test]
+    fn rejects_unknown_address_types() {
+        let mut data = datagram(&[]);
+        data[7] = 3;
+
+        assert_eq!(
+            decode(&data),
+            Err(DecodeError::UnsupportedAddressType { address_type: 3 })
+        );
+    }
+}
@@ -0,0 +1,739 @@
+//! Decoding of the template based NetFlow v9 and IPFIX packets.
+//!
+//! Exporters describe the layout of their data records with templates, sent
+//! periodically in the same packets as the records. Templates are cached per
+//! exporter and observation domain, and expire when not refreshed.
+//!
+//! See <https://www.ietf.org/rfc/rfc3954.txt> and <https://www.ietf.org/rfc/rfc7011.txt>.
+
+use super::reader::{format_hex, format_mac, unsigned, DecodeError, Reader};
+use crate::{
+    config::log_schema,
+    event::{LogEvent, Value},
+    sources::util::saturating_counter,
+};
+use chrono::{offset::LocalResult, DateTime, TimeZone, Utc};
+use std::{
+    collections::HashMap,
+    net::{Ipv4Addr, Ipv6Addr, SocketAddr},
+    time::{Duration, Instant},
+};
+
+const NETFLOW_V9: u16 = 9;
+const IPFIX: u16 = 10;
+
+/// Length of variable length fields of IPFIX templates.
+const VARIABLE_LENGTH: u16 = 65535;
+
+/// Seconds between the NTP epoch (1900-01-01) and the Unix epoch.
+const NTP_EPOCH_OFFSET: u64 = 2_208_988_800;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+struct TemplateKey {
+    exporter: SocketAddr,
+    version: u16,
+    /// The source ID of NetFlow v9, or the observation domain ID of IPFIX.
+    domain: u32,
+    id: u16,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Template {
+    fields: Vec<TemplateField>,
+    /// The number of leading scope fields of options templates.
+    scope_field_count: usize,
+}
+
+impl Template {
+    /// The smallest length of a record, with variable length fields taking
+    /// at least their one byte length.
+    fn min_record_length(&self) -> usize {
+        self.fields
+            .iter()
+            .map(|field| match field.length {
+                VARIABLE_LENGTH => 1,
+                length => length as usize,
+            })
+            .sum()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct TemplateField {
+    id: u16,
+    enterprise: Option<u32>,
+    length: u16,
+}
+
+/// Templates received from exporters.
+pub(super) struct TemplateCache {
+    templates: HashMap<TemplateKey, (Template, Instant)>,
+    expiry: Duration,
+}
+
+impl TemplateCache {
+    pub(super) fn new(expiry: Duration) -> Self {
+        Self {
+            templates: HashMap::new(),
+            expiry,
+        }
+    }
+
+    fn insert(&mut self, key: TemplateKey, template: Template) {
+        self.templates.insert(key, (template, Instant::now()));
+    }
+
+    fn remove(&mut self, key: &TemplateKey) {
+        self.templates.remove(key);
+    }
+
+    fn get(&self, key: &TemplateKey) -> Option<&Template> {
+        self.templates
+            .get(key)
+            .filter(|(_, received)| received.elapsed() < self.expiry)
+            .map(|(template, _)| template)
+    }
+
+    /// Drops the templates which were not refreshed in time.
+    pub(super) fn expire(&mut self) {
+        let expiry = self.expiry;
+        self.templates
+            .retain(|_, (_, received)| received.elapsed() < expiry);
+    }
+
+    #[cfg(test)]
+    fn count(&self) -> usize {
+        self.templates.count()
+    }
+}
+
+/// The records of a packet, along with the IDs of the templates of data sets
+/// which could not be decoded as their template is unknown.
+#[derive(Debug, Default)]
+pub(super) struct Decoded {
+    pub(super) events: Vec<LogEvent>,
+    pub(super) missed_templates: Vec<u16>,
+}
+
+pub(super) fn decode_v9(
+    data: &[u8],
+    exporter: SocketAddr,
+    templates: &mut TemplateCache,
+) -> Result<Decoded, DecodeError> {
+    let mut reader = Reader::new(data);
+    let _version = reader.u16()?;
+    let _count = reader.u16()?;
+    let _sys_uptime = reader.u32()?;
+    let export_time = seconds(reader.u32()?);
+    let sequence_number = reader.u32()?;
+    let source_id = reader.u32()?;
+
+    let header = |log: &mut LogEvent| {
+        log.insert(log_schema().timestamp_key(), export_time);
+        log.insert("sequence_number", sequence_number);
+        log.insert("source_id", source_id);
+    };
+    let key = |id| TemplateKey {
+        exporter,
+        version: NETFLOW_V9,
+        domain: source_id,
+        id,
+    };
+
+    let mut decoded = Decoded::default();
+    while reader.remaining() >= 4 {
+        let (id, mut set) = next_set(&mut reader)?;
+        match id {
+            0 => {
+                while set.remaining() >= 4 {
+                    let template_id = set.u16()?;
+                    let field_count = set.u16()?;
+                    let fields = (0..field_count)
+                        .map(|_| v9_field(&mut set))
+                        .collect::<Result<_, _>>()?;
+                    templates.insert(
+                        key(template_id),
+                        Template {
+                            fields,
+                            scope_field_count: 0,
+                        },
+                    );
+                }
+            }
+            1 => {
+                // Options templates are padded to 4 bytes.
+                while set.remaining() >= 6 {
+                    let template_id = set.u16()?;
+                    let scope_count = set.u16()? as usize / 4;
+                    let option_count = set.u16()? as usize / 4;
+                    let fields = (0..scope_count + option_count)
+                        .map(|_| v9_field(&mut set))
+                        .collect::<Result<_, _>>()?;
+                    templates.insert(
+                        key(template_id),
+                        Template {
+                            fields,
+                            scope_field_count: scope_count,
+                        },
+                    );
+                }
+            }
+            2..=255 => {}
+            template_id => match templates.get(&key(template_id)) {
+                Some(template) => {
+                    decode_records(set, template, NETFLOW_V9, &header, &mut decoded.events)?
+                }
+                None => decoded.missed_templates.push(template_id),
+            },
+        }
+    }
+
+    Ok(decoded)
+}
+
+pub(super) fn decode_ipfix(
+    data: &[u8],
+    exporter: SocketAddr,
+    templates: &mut TemplateCache,
+) -> Result<Decoded, DecodeError> {
+    let mut reader = Reader::new(data);
+    let _version = reader.u16()?;
+    let length = reader.u16()? as usize;
+    let export_time = seconds(reader.u32()?);
+    let sequence_number = reader.u32()?;
+    let observation_domain_id = reader.u32()?;
+    // The message length excludes any padding of the datagram.
+    let mut reader = reader.sub(length.saturating_sub(16))?;
+
+    let header = |log: &mut LogEvent| {
+        log.insert(log_schema().timestamp_key(), export_time);
+        log.insert("sequence_number", sequence_number);
+        log.insert("observation_domain_id", observation_domain_id);
+    };
+    let key = |id| TemplateKey {
+        exporter,
+        version: IPFIX,
+        domain: observation_domain_id,
+        id,
+    };
+
+    let mut decoded = Decoded::default();
+    while reader.remaining() >= 4 {
+        let (id, mut set) = next_set(&mut reader)?;
+        match id {
+            2 | 3 => {
+                while set.remaining() >= 4 {
+                    let template_id = set.u16()?;
+                    let field_count = set.u16()?;
+                    // Templates without fields withdraw the template.
+                    if field_count == 0 {
+                        templates.remove(&key(template_id));
+                        continue;
+                    }
+                    let scope_field_count = match id {
+                        3 => set.u16()? as usize,
+                        _ => 0,
+                    };
+                    let fields = (0..field_count)
+                        .map(|_| ipfix_field(&mut set))
+                        .collect::<Result<_, _>>()?;
+                    templates.insert(
+                        key(template_id),
+                        Template {
+                            fields,
+                            scope_field_count,
+                        },
+                    );
+                }
+            }
+            0..=255 => {}
+            template_id => match templates.get(&key(template_id)) {
+                Some(template) => {
+                    decode_records(set, template, IPFIX, &header, &mut decoded.events)?
+                }
+                None => decoded.missed_templates.push(template_id),
+            },
+        }
+    }
+
+    Ok(decoded)
+}
+
+/// Reads the header of the next set, and returns its ID and contents.
+fn next_set<'a>(reader: &mut Reader<'a>) -> Result<(u16, Reader<'a>), DecodeError> {
+    let id = reader.u16()?;
+    let length = reader.u16()?;
+    if length < 4 {
+        return Err(DecodeError::InvalidSetLength { id, length });
+    }
+    Ok((id, reader.sub(length as usize - 4)?))
+}
+
+fn v9_field(reader: &mut Reader) -> Result<TemplateField, DecodeError> {
+    Ok(TemplateField {
+        id: reader.u16()?,
+        enterprise: None,
+        length: reader.u16()?,
+    })
+}
+
+fn ipfix_field(reader: &mut Reader) -> Result<TemplateField, DecodeError> {
+    let id = reader.u16()?;
+    let length = reader.u16()?;
+    // The enterprise bit is followed by the private enterprise number.
+    let enterprise = if id & 0x8000 != 0 {
+        Some(reader.u32()?)
+    } else {
+        None
+    };
+    Ok(TemplateField {
+        id: id & 0x7FFF,
+        enterprise,
+        length,
+    })
+}
+
+fn decode_records(
+    mut set: Reader,
+    template: &Template,
+    version: u16,
+    header: &impl Fn(&mut LogEvent),
+    events: &mut Vec<LogEvent>,
+) -> Result<(), DecodeError> {
+    let min_length = template.min_record_length();
+    // Anything shorter than a record is padding.
+    while min_length > 0 && set.remaining() >= min_length {
+        let mut log = LogEvent::default();
+        header(&mut log);
+        log.insert(
+            "record_type",
+            match template.scope_field_count {
+                0 => "flow",
+                _ => "options",
+            },
+        );
+
+        for (index, field) in template.fields.iter().enumerate() {
+            let length = match field.length {
+                VARIABLE_LENGTH if version == IPFIX => match set.u8()? {
+                    255 => set.u16()? as usize,
+                    length => length as usize,
+                },
+                length => length as usize,
+            };
+            let bytes = set.bytes(length)?;
+
+            let scope = index < template.scope_field_count;
+            let (name, value) = match field.enterprise {
+                Some(enterprise) => (
+                    format!("enterprise_{}_{}", enterprise, field.id),
+                    raw_value(bytes),
+                ),
+                None if scope && version == NETFLOW_V9 => {
+                    (v9_scope_name(field.id), raw_value(bytes))
+                }
+                None => match element(field.id) {
+                    Some((name, kind)) => (name.to_owned(), element_value(kind, bytes)),
+                    None => (format!("field_{}", field.id), raw_value(bytes)),
+                },
+            };
+            log.insert_flat(name, value);
+        }
+        events.push(log);
+    }
+
+    Ok(())
+}
+
+fn v9_scope_name(id: u16) -> String {
+    match id {
+        1 => "scope_system".to_owned(),
+        2 => "scope_interface".to_owned(),
+        3 => "scope_line_card".to_owned(),
+        4 => "scope_cache".to_owned(),
+        5 => "scope_template".to_owned(),
+        id => format!("scope_{}", id),
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Kind {
+    Unsigned,
+    Ipv4,
+    Ipv6,
+    Mac,
+    String,
+    Seconds,
+    Milliseconds,
+    /// A timestamp in the NTP format, used for microseconds and nanoseconds.
+    Ntp,
+}
+
+/// Returns the name and type of the information elements of the IANA
+/// registry commonly sent by exporters. The first 127 elements share their
+/// IDs with the field types of NetFlow v9.
+///
+/// See <https://www.iana.org/assignments/ipfix/ipfix.xhtml>.
+const fn element(id: u16) -> Option<(&'static str, Kind)> {
+    use Kind::*;
+
+    Some(match id {
+        1 => ("octet_delta_count", Unsigned),
+        2 => ("packet_delta_count", Unsigned),
+        3 => ("delta_flow_count", Unsigned),
+        4 => ("protocol_identifier", Unsigned),
+        5 => ("ip_class_of_service", Unsigned),
+        6 => ("tcp_control_bits", Unsigned),
+        7 => ("source_transport_port", Unsigned),
+        8 => ("source_ipv4_address", Ipv4),
+        9 => ("source_ipv4_prefix_length", Unsigned),
+        10 => ("ingress_interface", Unsigned),
+        11 => ("destination_transport_port", Unsigned),
+        12 => ("destination_ipv4_address", Ipv4),
+        13 => ("destination_ipv4_prefix_length", Unsigned),
+        14 => ("egress_interface", Unsigned),
+        15 => ("ip_next_hop_ipv4_address", Ipv4),
+        16 => ("bgp_source_as_number", Unsigned),
+        17 => ("bgp_destination_as_number", Unsigned),
+        18 => ("bgp_next_hop_ipv4_address", Ipv4),
+        19 => ("post_mcast_packet_delta_count", Unsigned),
+        20 => ("post_mcast_octet_delta_count", Unsigned),
+        21 => ("flow_end_sys_up_time", Unsigned),
+        22 => ("flow_start_sys_up_time", Unsigned),
+        23 => ("post_octet_delta_count", Unsigned),
+        24 => ("post_packet_delta_count", Unsigned),
+        25 => ("minimum_ip_total_length", Unsigned),
+        26 => ("maximum_ip_total_length", Unsigned),
+        27 => ("source_ipv6_address", Ipv6),
+        28 => ("destination_ipv6_address", Ipv6),
+        29 => ("source_ipv6_prefix_length", Unsigned),
+        30 => ("destination_ipv6_prefix_length", Unsigned),
+        31 => ("flow_label_ipv6", Unsigned),
+        32 => ("icmp_type_code_ipv4", Unsigned),
+        33 => ("igmp_type", Unsigned),
+        34 => ("sampling_interval", Unsigned),
+        35 => ("sampling_algorithm", Unsigned),
+        36 => ("flow_active_timeout", Unsigned),
+        37 => ("flow_idle_timeout", Unsigned),
+        38 => ("engine_type", Unsigned),
+        39 => ("engine_id", Unsigned),
+        40 => ("exported_octet_total_count", Unsigned),
+        41 => ("exported_message_total_count", Unsigned),
+        42 => ("exported_flow_record_total_count", Unsigned),
+        44 => ("source_ipv4_prefix", Ipv4),
+        45 => ("destination_ipv4_prefix", Ipv4),
+        46 => ("mpls_top_label_type", Unsigned),
+        47 => ("mpls_top_label_ipv4_address", Ipv4),
+        48 => ("sampler_id", Unsigned),
+        49 => ("sampler_mode", Unsigned),
+        50 => ("sampler_random_interval", Unsigned),
+        52 => ("minimum_ttl", Unsigned),
+        53 => ("maximum_ttl", Unsigned),
+        54 => ("fragment_identification", Unsigned),
+        55 => ("post_ip_class_of_service", Unsigned),
+        56 => ("source_mac_address", Mac),
+        57 => ("post_destination_mac_address", Mac),
+        58 => ("vlan_id", Unsigned),
+        59 => ("post_vlan_id", Unsigned),
+        60 => ("ip_version", Unsigned),
+        61 => ("flow_direction", Unsigned),
+        62 => ("ip_next_hop_ipv6_address", Ipv6),
+        63 => ("bgp_next_hop_ipv6_address", Ipv6),
+        64 => ("ipv6_extension_headers", Unsigned),
+        80 => ("destination_mac_address", Mac),
+        81 => ("post_source_mac_address", Mac),
+        82 => ("interface_name", String),
+        83 => ("interface_description", String),
+        85 => ("octet_total_count", Unsigned),
+        86 => ("packet_total_count", Unsigned),
+        88 => ("fragment_offset", Unsigned),
+        89 => ("forwarding_status", Unsigned),
+        94 => ("application_description", String),
+        96 => ("application_name", String),
+        136 => ("flow_end_reason", Unsigned),
+        138 => ("observation_point_id", Unsigned),
+        139 => ("icmp_type_code_ipv6", Unsigned),
+        148 => ("flow_id", Unsigned),
+        150 => ("flow_start_seconds", Seconds),
+        151 => ("flow_end_seconds", Seconds),
+        152 => ("flow_start_milliseconds", Milliseconds),
+        153 => ("flow_end_milliseconds", Milliseconds),
+        154 => ("flow_start_microseconds", Ntp),
+        155 => ("flow_end_microseconds", Ntp),
+        156 => ("flow_start_nanoseconds", Ntp),
+        157 => ("flow_end_nanoseconds", Ntp),
+        160 => ("system_init_time_milliseconds", Milliseconds),
+        176 => ("icmp_type_ipv4", Unsigned),
+        177 => ("icmp_code_ipv4", Unsigned),
+        178 => ("icmp_type_ipv6", Unsigned),
+        179 => ("icmp_code_ipv6", Unsigned),
+        180 => ("udp_source_port", Unsigned),
+        181 => ("udp_destination_port", Unsigned),
+        182 => ("tcp_source_port", Unsigned),
+        183 => ("tcp_destination_port", Unsigned),
+        225 => ("post_nat_source_ipv4_address", Ipv4),
+        226 => ("post_nat_destination_ipv4_address", Ipv4),
+        227 => ("post_napt_source_transport_port", Unsigned),
+        228 => ("post_napt_destination_transport_port", Unsigned),
+        230 => ("nat_event", Unsigned),
+        231 => ("initiator_octets", Unsigned),
+        232 => ("responder_octets", Unsigned),
+        233 => ("firewall_event", Unsigned),
+        234 => ("ingress_vrf_id", Unsigned),
+        235 => ("egress_vrf_id", Unsigned),
+        239 => ("biflow_direction", Unsigned),
+        281 => ("post_nat_source_ipv6_address", Ipv6),
+        282 => ("post_nat_destination_ipv6_address", Ipv6),
+        323 => ("observation_time_milliseconds", Milliseconds),
+        _ => return None,
+    })
+}
+
+fn element_value(kind: Kind, bytes: &[u8]) -> Value {
+    match (kind, bytes.len()) {
+        (Kind::Unsigned, 1..=8) => saturating_counter(unsigned(bytes)),
+        (Kind::Ipv4, 4) => Ipv4Addr::new(bytes[0], bytes[1], bytes[2], bytes[3])
+            .to_string()
+            .into(),
+        (Kind::Ipv6, 16) => {
+            let mut octets = [0; 16];
+            octets.copy_from_slice(bytes);
+            Ipv6Addr::from(octets).to_string().into()
+        }
+        (Kind::Mac, 6) => format_mac(bytes).into(),
+        (Kind::String, _) => String::from_utf8_lossy(bytes).trim_end_matches('\0').into(),
+        (Kind::Seconds, 4) => seconds(unsigned(bytes) as u32).into(),
+        (Kind::Milliseconds, 8) => match Utc.timestamp_millis_opt(unsigned(bytes) as i64) {
+            LocalResult::Single(timestamp) => timestamp.into(),
+            _ => saturating_counter(unsigned(bytes)),
+        },
+        (Kind::Ntp, 8) => {
+            let seconds = unsigned(&bytes[..4]).saturating_sub(NTP_EPOCH_OFFSET);
+            let nanos = (unsigned(&bytes[4..]) * 1_000_000_000) >> 32;
+            Utc.timestamp(seconds as i64, nanos as u32).into()
+        }
+        // Elements may be sent with a reduced size, or a different one than
+        // registered, in which case they are kept as is.
+        (Kind::Seconds, 1..=8) | (Kind::Milliseconds, 1..=8) => saturating_counter(unsigned(bytes)),
+        _ => format_hex(bytes).into(),
+    }
+}
+
+/// Converts a field of unknown type, as an integer when it fits in one.
+fn raw_value(bytes: &[u8]) -> Value {
+    match bytes.len() {
+        1..=8 => saturating_counter(unsigned(bytes)),
+        _ => format_hex(bytes).into(),
+    }
+}
+
+fn seconds(seconds: u32) -> DateTime<Utc> {
+    Utc.timestamp(i64::from(seconds), 0)
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+
+    /// Builds a set of the given ID and contents.
+    pub(crate) fn set(id: u16, contents: &[u8]) -> Vec<u8> {
+        let mut set = Vec::new();
+        set.extend_from_slice(&id.to_be_bytes());
+        set.extend_from_slice(&(contents.len() as u16 + 4).to_be_bytes());
+        set.extend_from_slice(contents);
+        set
+    }
+
+    /// Builds a template of the given ID and `(field type, length)` fields.
+    pub(crate) fn template(id: u16, fields: &[(u16, u16)]) -> Vec<u8> {
+        let mut template = Vec::new();
+        template.extend_from_slice(&id.to_be_bytes());
+        template.extend_from_slice(&(fields.len() as u16).to_be_bytes());
+        for (id, length) in fields {
+            template.extend_from_slice(&id.to_be_bytes());
+            template.extend_from_slice(&length.to_be_bytes());
+        }
+        template
+    }
+
+    pub(crate) fn v9_packet(sets: &[Vec<u8>]) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(&9u16.to_be_bytes());
+        data.extend_from_slice(&(sets.len() as u16).to_be_bytes());
+        data.extend_from_slice(&60_000u32.to_be_bytes());
+        data.extend_from_slice(&1_636_106_400u32.to_be_bytes());
+        data.extend_from_slice(&7u32.to_be_bytes());
+        data.extend_from_slice(&1u32.to_be_bytes());
+        data.extend(sets.iter().flatten());
+        data
+    }
+
+    pub(crate) fn ipfix_packet(sets: &[Vec<u8>]) -> Vec<u8> {
+        let length = 16 + sets.iter().map(Vec::len).sum::<usize>();
+        let mut data = Vec::new();
+        data.extend_from_slice(&10u16.to_be_bytes());
+        data.extend_from_slice(&(length as u16).to_be_bytes());
+        data.extend_from_slice(&1_636_106_400u32.to_be_bytes());
+        data.extend_from_slice(&7u32.to_be_bytes());
+        data.extend_from_slice(&3u32.to_be_bytes());
+        data.extend(sets.iter().flatten());
+        data
+    }
+
+    fn exporter() -> SocketAddr {
+        "192.0.2.1:2055".parse().unwrap()
+    }
+
+    fn flow(source: [u8; 4], destination: [u8; 4], bytes: u32) -> Vec<u8> {
+        let mut record = Vec::new();
+        record.extend_from_slice(&source);
+        record.extend_from_slice(&destination);
+        record.extend_from_slice(&bytes.to_be_bytes());
+        record.push(17);
+        record
+    }
+
+    const FLOW_TEMPLATE: &[(u16, u16)] = &[(8, 4), (12, 4), (1, 4), (4, 1)];
+
+    #[test]
+    fn decodes_v9_records_with_template() {
+        let mut templates = TemplateCache::new(Duration::from_secs(60));
+        let records = [
+            flow([10, 0, 0, 1], [10, 0, 0, 2], 1500),
+            flow([10, 0, 0, 3], [10, 0, 0, 4], 40),
+        ]
+        .concat();
+        let data = v9_packet(&[
+            set(0, &template(256, FLOW_TEMPLATE)),
+            // Padded to 4 bytes.
+            set(256, &[records, vec![0, 0]].concat()),
+        ]);
+
+        let decoded = decode_v9(&data, exporter(), &mut templates).unwrap();
+
+        assert!(decoded.missed_templates.is_empty());
+        assert_eq!(decoded.events.len(), 2);
+        let log = &decoded.events[0];
+        assert_eq!(log["source_ipv4_address"], "10.0.0.1".into());
+        assert_eq!(log["destination_ipv4_address"], "10.0.0.2".into());
+        assert_eq!(log["octet_delta_count"], 1500.into());
+        assert_eq!(log["protocol_identifier"], 17.into());
+        assert_eq!(log["record_type"], "flow".into());
+        assert_eq!(log["source_id"], 1.into());
+        assert_eq!(decoded.events[1]["octet_delta_count"], 40.into());
+    }
+
+    #[test]
+    fn caches_templates_per_exporter() {
+        let mut templates = TemplateCache::new(Duration::from_secs(60));
+        decode_v9(
+            &v9_packet(&[set(0, &template(256, FLOW_TEMPLATE))]),
+            exporter(),
+            &mut templates,
+        )
+        .unwrap();
+        let data = v9_packet(&[set(256, &flow([10, 0, 0, 1], [10, 0, 0, 2], 1500))]);
+
+        let decoded = decode_v9(&data, exporter(), &mut templates).unwrap();
+        assert_eq!(decoded.events.len(), 1);
+
+        let other = "192.0.2.2:2055".parse().unwrap();
+        let decoded = decode_v9(&data, other, &mut templates).unwrap();
+        assert!(decoded.events.is_empty());
+        assert_eq!(decoded.missed_templates, vec![256]);
+    }
+
+    #[test]
+    fn expires_templates() {
+        let mut templates = TemplateCache::new(Duration::from_secs(0));
+        let data = v9_packet(&[
+            set(0, &template(256, FLOW_TEMPLATE)),
+            set(256, &flow([10, 0, 0, 1], [10, 0, 0, 2], 1500)),
+        ]);
+
+        let decoded = decode_v9(&data, exporter(), &mut templates).unwrap();
+
+        assert_eq!(decoded.missed_templates, vec![256]);
+        templates.expire();
+        assert_eq!(templates.count(), 0);
+    }
+
+    #[test]
+    fn decodes_v9_options_records() {
+        let mut templates = TemplateCache::new(Duration::from_secs(60));
+        let mut options_template = Vec::new();
+        options_template.extend_from_slice(&257u16.to_be_bytes());
+        options_template.extend_from_slice(&4u16.to_be_bytes());
+        options_template.extend_from_slice(&4u16.to_be_bytes());
+        options_template.extend_from_slice(&[0, 1, 0, 4, 0, 34, 0, 4, 0, 0]);
+        let data = v9_packet(&[
+            set(1, &options_template),
+            set(257, &[0, 0, 0, 1, 0, 0, 0, 100]),
+        ]);
+
+        let decoded = decode_v9(&data, exporter(), &mut templates).unwrap();
+
+        let log = &decoded.events[0];
+        assert_eq!(log["record_type"], "options".into());
+        assert_eq!(log["scope_system"], 1.into());
+        assert_eq!(log["sampling_interval"], 100.into());
+    }
+
+    #[test]
+    fn decodes_ipfix_variable_length_and_enterprise_fields() {
+        let mut templates = TemplateCache::new(Duration::from_secs(60));
+        let mut template = template(300, &[(8, 4), (96, VARIABLE_LENGTH)]);
+        template[3] = 4;
+        template.extend_from_slice(&[0x80, 0x01, 0, 2, 0, 0, 0x0B, 0x3B]);
+        template.extend_from_slice(&[0, 152, 0, 8]);
+        let mut record = vec![10, 0, 0, 1, 4];
+        record.extend_from_slice(b"http");
+        record.extend_from_slice(&[0, 42]);
+        record.extend_from_slice(&1_636_106_400_123u64.to_be_bytes());
+        let data = ipfix_packet(&[set(2, &template), set(300, &record)]);
+
+        let decoded = decode_ipfix(&data, exporter(), &mut templates).unwrap();
+
+        assert_eq!(decoded.events.len(), 1);
+        let log = &decoded.events[0];
+        assert_eq!(log["source_ipv4_address"], "10.0.0.1".into());
+        assert_eq!(log["application_name"], "http".into());
+        assert_eq!(log["enterprise_2875_1"], 42.into());
+        assert_eq!(
+            log["flow_start_milliseconds"],
+            Utc.timestamp_millis(1_636_106_400_123).into()
+        );
+        assert_eq!(log["observation_domain_id"], 3.into());
+    }
+
+    #[test]
+    fn withdraws_ipfix_templates() {
+        let mut templates = TemplateCache::new(Duration::from_secs(60));
+        decode_ipfix(
+            &ipfix_packet(&[set(2, &template(256, FLOW_TEMPLATE))]),
+            exporter(),
+            &mut templates,
+        )
+        .unwrap();
+        assert_eq!(templates.count(), 1);
+
+        decode_ipfix(
+            &ipfix_packet(&[set(2, &template(256, &[]))]),
+            exporter(),
+            &mut templates,
+        )
+        .unwrap();
+        assert_eq!(templates.count(), 0);
+    }
+
+    #[test]
+    fn rejects_invalid_set_lengths() {
+        let mut templates = TemplateCache::new(Duration::from_secs(60));
+        let data = v9_packet(&[vec![1, 0, 0, 2]]);
+
+        assert_eq!(
+            decode_v9(&data, exporter(), &mut templates).unwrap_err(),
+            DecodeError::InvalidSetLength { id: 256, length: 2 }
+        );
+    }
+}
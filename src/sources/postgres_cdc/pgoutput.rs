@@ -0,0 +1,413 @@
+//! Decoding of messages of the `pgoutput` logical decoding plugin, protocol
+//! version 1.
+//!
+//! See <https://www.postgresql.org/docs/current/protocol-logicalrep-message-formats.html>.
+
+use bytes::{Buf, Bytes};
+use snafu::Snafu;
+
+#[derive(Debug, PartialEq, Snafu)]
+pub(super) enum DecodeError {
+    #[snafu(display("message is truncated"))]
+    Truncated,
+    #[snafu(display("unknown message type {:?}", *tag as char))]
+    UnknownMessage { tag: u8 },
+    #[snafu(display("unknown tuple type {:?}", *tag as char))]
+    UnknownTuple { tag: u8 },
+    #[snafu(display("unknown tuple column type {:?}", *tag as char))]
+    UnknownTupleColumn { tag: u8 },
+    #[snafu(display("string is not valid UTF-8"))]
+    InvalidString,
+}
+
+#[derive(Debug, PartialEq)]
+pub(super) enum Message {
+    Begin(Begin),
+    Commit(Commit),
+    Origin,
+    Relation(Relation),
+    Type,
+    Insert {
+        relation_id: u32,
+        new: Tuple,
+    },
+    Update {
+        relation_id: u32,
+        old: Option<Tuple>,
+        new: Tuple,
+    },
+    Delete {
+        relation_id: u32,
+        old: Tuple,
+    },
+    Truncate {
+        relation_ids: Vec<u32>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct Begin {
+    pub(super) final_lsn: u64,
+    /// Microseconds since 2000-01-01.
+    pub(super) commit_timestamp: i64,
+    pub(super) xid: u32,
+}
+
+#[derive(Debug, PartialEq)]
+pub(super) struct Commit {
+    /// The end of the commit record, from where decoding resumes once the
+    /// transaction is confirmed.
+    pub(super) end_lsn: u64,
+}
+
+#[derive(Debug, PartialEq)]
+pub(super) struct Relation {
+    pub(super) id: u32,
+    pub(super) namespace: String,
+    pub(super) name: String,
+    pub(super) columns: Vec<Column>,
+}
+
+#[derive(Debug, PartialEq)]
+pub(super) struct Column {
+    pub(super) name: String,
+    pub(super) type_oid: u32,
+}
+
+pub(super) type Tuple = Vec<TupleValue>;
+
+#[derive(Debug, PartialEq)]
+pub(super) enum TupleValue {
+    Null,
+    /// A TOASTed value which was not changed, and is not sent.
+    UnchangedToast,
+    /// The value in its text representation.
+    Text(Bytes),
+}
+
+pub(super) fn decode(data: Bytes) -> Result<Message, DecodeError> {
+    let mut reader = Reader::new(data);
+    let message = match reader.u8()? {
+        b'B' => Message::Begin(Begin {
+            final_lsn: reader.u64()?,
+            commit_timestamp: reader.i64()?,
+            xid: reader.u32()?,
+        }),
+        b'C' => {
+            let _flags = reader.u8()?;
+            let _commit_lsn = reader.u64()?;
+            Message::Commit(Commit {
+                end_lsn: reader.u64()?,
+            })
+        }
+        b'O' => Message::Origin,
+        b'Y' => Message::Type,
+        b'R' => {
+            let id = reader.u32()?;
+            let namespace = reader.string()?;
+            let name = reader.string()?;
+            let _replica_identity = reader.u8()?;
+            let count = reader.u16()?;
+            let columns = (0..count)
+                .map(|_| -> Result<_, DecodeError> {
+                    let _flags = reader.u8()?;
+                    let name = reader.string()?;
+                    let type_oid = reader.u32()?;
+                    let _type_modifier = reader.u32()?;
+                    Ok(Column { name, type_oid })
+                })
+                .collect::<Result<_, _>>()?;
+            Message::Relation(Relation {
+                id,
+                namespace,
+                name,
+                columns,
+            })
+        }
+        b'I' => {
+            let relation_id = reader.u32()?;
+            reader.expect_tuple(b'N')?;
+            Message::Insert {
+                relation_id,
+                new: reader.tuple()?,
+            }
+        }
+        b'U' => {
+            let relation_id = reader.u32()?;
+            // The old tuple is only sent when the replica identity changed,
+            // or the table uses `REPLICA IDENTITY FULL`.
+            let old = match reader.u8()? {
+                b'K' | b'O' => {
+                    let old = reader.tuple()?;
+                    reader.expect_tuple(b'N')?;
+                    Some(old)
+                }
+                b'N' => None,
+                tag => return Err(DecodeError::UnknownTuple { tag }),
+            };
+            Message::Update {
+                relation_id,
+                old,
+                new: reader.tuple()?,
+            }
+        }
+        b'D' => {
+            let relation_id = reader.u32()?;
+            match reader.u8()? {
+                b'K' | b'O' => {}
+                tag => return Err(DecodeError::UnknownTuple { tag }),
+            }
+            Message::Delete {
+                relation_id,
+                old: reader.tuple()?,
+            }
+        }
+        b'T' => {
+            let count = reader.u32()?;
+            let _options = reader.u8()?;
+            let relation_ids = (0..count).map(|_| reader.u32()).collect::<Result<_, _>>()?;
+            Message::Truncate { relation_ids }
+        }
+        tag => return Err(DecodeError::UnknownMessage { tag }),
+    };
+
+    Ok(message)
+}
+
+/// Reads the big-endian values of messages, failing on truncated ones.
+pub(super) struct Reader(Bytes);
+
+impl Reader {
+    pub(super) const fn new(data: Bytes) -> Self {
+        Self(data)
+    }
+
+    /// The data left to read.
+    #[allow(clippy::missing_const_for_fn)] // const cannot run destructor
+    pub(super) fn rest(self) -> Bytes {
+        self.0
+    }
+
+    fn ensure(&self, len: usize) -> Result<(), DecodeError> {
+        if self.0.remaining() < len {
+            Err(DecodeError::Truncated)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub(super) fn u8(&mut self) -> Result<u8, DecodeError> {
+        self.ensure(1)?;
+        Ok(self.0.get_u8())
+    }
+
+    pub(super) fn u16(&mut self) -> Result<u16, DecodeError> {
+        self.ensure(2)?;
+        Ok(self.0.get_u16())
+    }
+
+    pub(super) fn u32(&mut self) -> Result<u32, DecodeError> {
+        self.ensure(4)?;
+        Ok(self.0.get_u32())
+    }
+
+    pub(super) fn u64(&mut self) -> Result<u64, DecodeError> {
+        self.ensure(8)?;
+        Ok(self.0.get_u64())
+    }
+
+    pub(super) fn i64(&mut self) -> Result<i64, DecodeError> {
+        self.ensure(8)?;
+        Ok(self.0.get_i64())
+    }
+
+    pub(super) fn bytes(&mut self, len: usize) -> Result<Bytes, DecodeError> {
+        self.ensure(len)?;
+        Ok(self.0.split_to(len))
+    }
+
+    /// Reads a null terminated string.
+    pub(super) fn string(&mut self) -> Result<String, DecodeError> {
+        let len = self
+            .0
+            .iter()
+            .position(|byte| *byte == 0)
+            .ok_or(DecodeError::Truncated)?;
+        let string = self.bytes(len)?;
+        self.0.advance(1);
+        String::from_utf8(string.to_vec()).map_err(|_| DecodeError::InvalidString)
+    }
+
+    fn expect_tuple(&mut self, expected: u8) -> Result<(), DecodeError> {
+        match self.u8()? {
+            tag if tag == expected => Ok(()),
+            tag => Err(DecodeError::UnknownTuple { tag }),
+        }
+    }
+
+    fn tuple(&mut self) -> Result<Tuple, DecodeError> {
+        let count = self.u16()?;
+        (0..count)
+            .map(|_| -> Result<_, DecodeError> {
+                Ok(match self.u8()? {
+                    b'n' => TupleValue::Null,
+                    b'u' => TupleValue::UnchangedToast,
+                    b't' => {
+                        let len = self.u32()? as usize;
+                        TupleValue::Text(self.bytes(len)?)
+                    }
+                    tag => return Err(DecodeError::UnknownTupleColumn { tag }),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+    use bytes::{BufMut, BytesMut};
+
+    pub(crate) fn relation(id: u32, namespace: &str, name: &str, columns: &[(&str, u32)]) -> Bytes {
+        let mut buf = BytesMut::new();
+        buf.put_u8(b'R');
+        buf.put_u32(id);
+        put_string(&mut buf, namespace);
+        put_string(&mut buf, name);
+        buf.put_u8(b'd');
+        buf.put_u16(columns.len() as u16);
+        for (name, type_oid) in columns {
+            buf.put_u8(1);
+            put_string(&mut buf, name);
+            buf.put_u32(*type_oid);
+            buf.put_i32(-1);
+        }
+        buf.freeze()
+    }
+
+    pub(crate) fn begin(final_lsn: u64, commit_timestamp: i64, xid: u32) -> Bytes {
+        let mut buf = BytesMut::new();
+        buf.put_u8(b'B');
+        buf.put_u64(final_lsn);
+        buf.put_i64(commit_timestamp);
+        buf.put_u32(xid);
+        buf.freeze()
+    }
+
+    pub(crate) fn commit(commit_lsn: u64, end_lsn: u64) -> Bytes {
+        let mut buf = BytesMut::new();
+        buf.put_u8(b'C');
+        buf.put_u8(0);
+        buf.put_u64(commit_lsn);
+        buf.put_u64(end_lsn);
+        buf.put_i64(0);
+        buf.freeze()
+    }
+
+    pub(crate) fn update(
+        relation_id: u32,
+        old: Option<&[Option<&str>]>,
+        new: &[Option<&str>],
+    ) -> Bytes {
+        let mut buf = BytesMut::new();
+        buf.put_u8(b'U');
+        buf.put_u32(relation_id);
+        if let Some(old) = old {
+            buf.put_u8(b'O');
+            put_tuple(&mut buf, old);
+        }
+        buf.put_u8(b'N');
+        put_tuple(&mut buf, new);
+        buf.freeze()
+    }
+
+    fn put_string(buf: &mut BytesMut, string: &str) {
+        buf.put_slice(string.as_bytes());
+        buf.put_u8(0);
+    }
+
+    fn put_tuple(buf: &mut BytesMut, values: &[Option<&str>]) {
+        buf.put_u16(values.len() as u16);
+        for value in values {
+            match value {
+                Some(value) => {
+                    buf.put_u8(b't');
+                    buf.put_u32(value.len() as u32);
+                    buf.put_slice(value.as_bytes());
+                }
+                None => buf.put_u8(b'n'),
+            }
+        }
+    }
+
+    #[test]
+    fn decodes_transaction() {
+        assert_eq!(
+            decode(begin(0x16B3748, 42, 7)).unwrap(),
+            Message::Begin(Begin {
+                final_lsn: 0x16B3748,
+                commit_timestamp: 42,
+                xid: 7,
+            })
+        );
+        assert_eq!(
+            decode(commit(0x16B3748, 0x16B3778)).unwrap(),
+            Message::Commit(Commit { end_lsn: 0x16B3778 })
+        );
+    }
+
+    #[test]
+    fn decodes_relation() {
+        let data = relation(16384, "public", "users", &[("id", 20), ("name", 25)]);
+
+        assert_eq!(
+            decode(data).unwrap(),
+            Message::Relation(Relation {
+                id: 16384,
+                namespace: "public".into(),
+                name: "users".into(),
+                columns: vec![
+                    Column {
+                        name: "id".into(),
+                        type_oid: 20,
+                    },
+                    Column {
+                        name: "name".into(),
+                        type_oid: 25,
+                    },
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn decodes_update_with_old_tuple() {
+        let data = update(16384, Some(&[Some("1"), Some("alice")]), &[Some("1"), None]);
+
+        assert_eq!(
+            decode(data).unwrap(),
+            Message::Update {
+                relation_id: 16384,
+                old: Some(vec![
+                    TupleValue::Text(Bytes::from("1")),
+                    TupleValue::Text(Bytes::from("alice")),
+                ]),
+                new: vec![TupleValue::Text(Bytes::from("1")), TupleValue::Null],
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_messages() {
+        let data = update(16384, None, &[Some("1")]);
+
+        assert_eq!(
+            decode(data.slice(..data.len() - 1)),
+            Err(DecodeError::Truncated)
+        );
+        assert_eq!(
+            decode(Bytes::from("X")),
+            Err(DecodeError::UnknownMessage { tag: b'X' })
+        );
+    }
+}
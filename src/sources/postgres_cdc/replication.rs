@@ -0,0 +1,526 @@
+//! A minimal client of the streaming replication protocol, which
+//! `tokio-postgres` does not implement, to stream the changes of a logical
+//! replication slot.
+//!
+//! See <https://www.postgresql.org/docs/current/protocol-replication.html>.
+
+use super::pgoutput::{DecodeError, Reader};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use chrono::{TimeZone, Utc};
+use openssl::error::ErrorStack;
+use postgres_openssl::{MakeTlsConnector, TlsStream};
+use postgres_protocol::{
+    authentication::{self, sasl},
+    message::frontend,
+};
+use snafu::{ResultExt, Snafu};
+use std::{convert::TryFrom, io};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    net::TcpStream,
+};
+use tokio_postgres::{
+    config::Host,
+    tls::{MakeTlsConnect, TlsConnect},
+    Config,
+};
+
+const DEFAULT_PORT: u16 = 5432;
+
+#[derive(Debug, Snafu)]
+pub(super) enum ReplicationError {
+    #[snafu(display("no host to connect to"))]
+    NoHost,
+    #[snafu(display("connection failed: {}", source))]
+    Io { source: io::Error },
+    #[snafu(display("failed to create tls connector: {}", source))]
+    TlsConnector { source: ErrorStack },
+    #[snafu(display("tls handshake failed: {}", source))]
+    TlsHandshake {
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    #[snafu(display("server does not support tls"))]
+    TlsUnsupported,
+    #[snafu(display("a user is required"))]
+    MissingUser,
+    #[snafu(display("server requested a password, but none was configured"))]
+    MissingPassword,
+    #[snafu(display("unsupported authentication method {}", code))]
+    UnsupportedAuthentication { code: u32 },
+    #[snafu(display("authentication failed: {}", source))]
+    Authentication { source: io::Error },
+    #[snafu(display("{}: {} ({})", severity, message, code))]
+    Server {
+        severity: String,
+        code: String,
+        message: String,
+    },
+    #[snafu(display("unexpected message {:?}", *tag as char))]
+    UnexpectedMessage { tag: u8 },
+    #[snafu(display("invalid message: {}", source))]
+    InvalidMessage { source: DecodeError },
+    #[snafu(display("connection closed by server"))]
+    Closed,
+}
+
+pub(super) enum ReplicationMessage {
+    /// A message of the output plugin, decoded from the WAL at `wal_start`.
+    XLogData { wal_start: u64, data: Bytes },
+    /// Sent periodically, with the position the WAL was sent up to.
+    Keepalive { wal_end: u64, reply_requested: bool },
+}
+
+trait Socket: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<S: AsyncRead + AsyncWrite + Unpin + Send> Socket for S {}
+
+pub(super) struct ReplicationStream {
+    socket: Box<dyn Socket>,
+    /// Data read from the socket that doesn't form a whole message yet.
+    buf: BytesMut,
+}
+
+/// Opens a replication connection to the first host of `config` that
+/// accepts it.
+pub(super) async fn connect(
+    config: &Config,
+    tls: Option<MakeTlsConnector>,
+) -> Result<ReplicationStream, ReplicationError> {
+    let ports = config.get_ports();
+    let mut error = ReplicationError::NoHost;
+    for (index, host) in config.get_hosts().iter().enumerate() {
+        // Either a single port applies to every host, or there is one per host.
+        let port = ports
+            .get(index)
+            .or_else(|| ports.first())
+            .copied()
+            .unwrap_or(DEFAULT_PORT);
+        match connect_host(config, host, port, tls.clone()).await {
+            Ok(stream) => return Ok(stream),
+            Err(host_error) => error = host_error,
+        }
+    }
+    Err(error)
+}
+
+async fn connect_host(
+    config: &Config,
+    host: &Host,
+    port: u16,
+    tls: Option<MakeTlsConnector>,
+) -> Result<ReplicationStream, ReplicationError> {
+    let mut stream = match host {
+        Host::Tcp(hostname) => {
+            let socket = TcpStream::connect((hostname.as_str(), port))
+                .await
+                .context(Io)?;
+            socket.set_nodelay(true).context(Io)?;
+            match tls {
+                Some(tls) => ReplicationStream::new(start_tls(socket, hostname, tls).await?),
+                None => ReplicationStream::new(socket),
+            }
+        }
+        #[cfg(unix)]
+        Host::Unix(path) => {
+            let path = path.join(format!(".s.PGSQL.{}", port));
+            ReplicationStream::new(tokio::net::UnixStream::connect(path).await.context(Io)?)
+        }
+    };
+    stream.startup(config).await?;
+    Ok(stream)
+}
+
+async fn start_tls(
+    mut socket: TcpStream,
+    hostname: &str,
+    mut tls: MakeTlsConnector,
+) -> Result<TlsStream<TcpStream>, ReplicationError> {
+    let mut buf = BytesMut::new();
+    frontend::ssl_request(&mut buf);
+    socket.write_all(&buf).await.context(Io)?;
+    if socket.read_u8().await.context(Io)? != b'S' {
+        return Err(ReplicationError::TlsUnsupported);
+    }
+
+    let connector =
+        MakeTlsConnect::<TcpStream>::make_tls_connect(&mut tls, hostname).context(TlsConnector)?;
+    connector.connect(socket).await.context(TlsHandshake)
+}
+
+impl ReplicationStream {
+    fn new(socket: impl AsyncRead + AsyncWrite + Unpin + Send + 'static) -> Self {
+        Self {
+            socket: Box::new(socket),
+            buf: BytesMut::new(),
+        }
+    }
+
+    /// Starts a session, and authenticates with the password of `config`.
+    async fn startup(&mut self, config: &Config) -> Result<(), ReplicationError> {
+        let user = config.get_user().ok_or(ReplicationError::MissingUser)?;
+        // Values are sent in the text format of the session, so timestamps
+        // are parsed reliably.
+        let mut parameters = vec![
+            ("user", user),
+            ("replication", "database"),
+            ("client_encoding", "UTF8"),
+            ("DateStyle", "ISO"),
+            ("TimeZone", "UTC"),
+        ];
+        if let Some(dbname) = config.get_dbname() {
+            parameters.push(("database", dbname));
+        }
+        if let Some(application_name) = config.get_application_name() {
+            parameters.push(("application_name", application_name));
+        }
+        let mut buf = BytesMut::new();
+        frontend::startup_message(parameters, &mut buf).context(Io)?;
+        self.send(&buf).await?;
+
+        let password = config.get_password();
+        let mut scram = None;
+        loop {
+            let (tag, body) = self.next_frame().await?;
+            match tag {
+                b'R' => {
+                    let mut reader = Reader::new(body);
+                    let mut buf = BytesMut::new();
+                    match reader.u32().context(InvalidMessage)? {
+                        // AuthenticationOk
+                        0 => continue,
+                        // AuthenticationCleartextPassword
+                        3 => {
+                            let password = password.ok_or(ReplicationError::MissingPassword)?;
+                            frontend::password_message(password, &mut buf).context(Io)?;
+                        }
+                        // AuthenticationMD5Password
+                        5 => {
+                            let password = password.ok_or(ReplicationError::MissingPassword)?;
+                            let mut salt = [0; 4];
+                            salt.copy_from_slice(&reader.bytes(4).context(InvalidMessage)?);
+                            let hash = authentication::md5_hash(user.as_bytes(), password, salt);
+                            frontend::password_message(hash.as_bytes(), &mut buf).context(Io)?;
+                        }
+                        // AuthenticationSASL, for which servers always offer
+                        // SCRAM-SHA-256.
+                        10 => {
+                            let password = password.ok_or(ReplicationError::MissingPassword)?;
+                            let client = sasl::ScramSha256::new(
+                                password,
+                                sasl::ChannelBinding::unsupported(),
+                            );
+                            frontend::sasl_initial_response(
+                                sasl::SCRAM_SHA_256,
+                                client.message(),
+                                &mut buf,
+                            )
+                            .context(Io)?;
+                            scram = Some(client);
+                        }
+                        // AuthenticationSASLContinue
+                        11 => {
+                            let client = scram
+                                .as_mut()
+                                .ok_or(ReplicationError::UnexpectedMessage { tag })?;
+                            client.update(&reader.rest()).context(Authentication)?;
+                            frontend::sasl_response(client.message(), &mut buf).context(Io)?;
+                        }
+                        // AuthenticationSASLFinal
+                        12 => {
+                            scram
+                                .take()
+                                .ok_or(ReplicationError::UnexpectedMessage { tag })?
+                                .finish(&reader.rest())
+                                .context(Authentication)?;
+                            continue;
+                        }
+                        code => return Err(ReplicationError::UnsupportedAuthentication { code }),
+                    }
+                    self.send(&buf).await?;
+                }
+                // ReadyForQuery
+                b'Z' => return Ok(()),
+                b'E' => return Err(server_error(body)),
+                // ParameterStatus, BackendKeyData and NoticeResponse
+                b'S' | b'K' | b'N' => {}
+                tag => return Err(ReplicationError::UnexpectedMessage { tag }),
+            }
+        }
+    }
+
+    /// Starts streaming the changes of the publication from the slot, from
+    /// `lsn` or the last position confirmed to the slot, whichever is later.
+    pub(super) async fn start(
+        &mut self,
+        slot_name: &str,
+        publication: &str,
+        lsn: u64,
+    ) -> Result<(), ReplicationError> {
+        let query = format!(
+            "START_REPLICATION SLOT {} LOGICAL {} (proto_version '1', publication_names {})",
+            quote_identifier(slot_name),
+            super::format_lsn(lsn),
+            quote_literal(&quote_identifier(publication)),
+        );
+        let mut buf = BytesMut::new();
+        frontend::query(&query, &mut buf).context(Io)?;
+        self.send(&buf).await?;
+
+        loop {
+            let (tag, body) = self.next_frame().await?;
+            match tag {
+                // CopyBothResponse
+                b'W' => return Ok(()),
+                b'E' => return Err(server_error(body)),
+                b'N' => {}
+                tag => return Err(ReplicationError::UnexpectedMessage { tag }),
+            }
+        }
+    }
+
+    /// Reads the next message of the stream, or `None` once the server ended
+    /// it. Cancelling it doesn't lose any data.
+    pub(super) async fn next_message(
+        &mut self,
+    ) -> Result<Option<ReplicationMessage>, ReplicationError> {
+        loop {
+            let (tag, body) = self.next_frame().await?;
+            match tag {
+                // CopyData
+                b'd' => return parse_copy_data(body).map(Some),
+                // CopyDone
+                b'c' => return Ok(None),
+                b'E' => return Err(server_error(body)),
+                b'N' => {}
+                tag => return Err(ReplicationError::UnexpectedMessage { tag }),
+            }
+        }
+    }
+
+    /// Reports `lsn` as written, flushed and applied, which confirms the
+    /// changes up to it to the slot.
+    pub(super) async fn send_status(&mut self, lsn: u64) -> Result<(), ReplicationError> {
+        let mut buf = BytesMut::new();
+        buf.put_u8(b'd');
+        buf.put_i32(4 + 34);
+        buf.put_u8(b'r');
+        buf.put_u64(lsn);
+        buf.put_u64(lsn);
+        buf.put_u64(lsn);
+        buf.put_i64(postgres_now());
+        // No reply requested.
+        buf.put_u8(0);
+        self.send(&buf).await
+    }
+
+    async fn send(&mut self, buf: &[u8]) -> Result<(), ReplicationError> {
+        self.socket.write_all(buf).await.context(Io)?;
+        self.socket.flush().await.context(Io)
+    }
+
+    /// Reads the next message as its tag and body. Only `read_buf` awaits,
+    /// which is cancellation safe.
+    async fn next_frame(&mut self) -> Result<(u8, Bytes), ReplicationError> {
+        loop {
+            if let Some(frame) = parse_frame(&mut self.buf)? {
+                return Ok(frame);
+            }
+            if self.socket.read_buf(&mut self.buf).await.context(Io)? == 0 {
+                return Err(ReplicationError::Closed);
+            }
+        }
+    }
+}
+
+fn parse_frame(buf: &mut BytesMut) -> Result<Option<(u8, Bytes)>, ReplicationError> {
+    if buf.len() < 5 {
+        return Ok(None);
+    }
+    // The length includes itself, but not the tag.
+    let len = i32::from_be_bytes([buf[1], buf[2], buf[3], buf[4]]);
+    let len = usize::try_from(len).ok().filter(|len| *len >= 4).ok_or(
+        ReplicationError::InvalidMessage {
+            source: DecodeError::Truncated,
+        },
+    )?;
+    if buf.len() < len + 1 {
+        buf.reserve(len + 1 - buf.len());
+        return Ok(None);
+    }
+
+    let mut frame = buf.split_to(len + 1).freeze();
+    let tag = frame.get_u8();
+    frame.advance(4);
+    Ok(Some((tag, frame)))
+}
+
+fn parse_copy_data(body: Bytes) -> Result<ReplicationMessage, ReplicationError> {
+    let mut reader = Reader::new(body);
+    let message = match reader.u8().context(InvalidMessage)? {
+        b'w' => {
+            let wal_start = reader.u64().context(InvalidMessage)?;
+            let _wal_end = reader.u64().context(InvalidMessage)?;
+            let _send_time = reader.i64().context(InvalidMessage)?;
+            ReplicationMessage::XLogData {
+                wal_start,
+                data: reader.rest(),
+            }
+        }
+        b'k' => {
+            let wal_end = reader.u64().context(InvalidMessage)?;
+            let _send_time = reader.i64().context(InvalidMessage)?;
+            ReplicationMessage::Keepalive {
+                wal_end,
+                reply_requested: reader.u8().context(InvalidMessage)? == 1,
+            }
+        }
+        tag => return Err(ReplicationError::UnexpectedMessage { tag }),
+    };
+    Ok(message)
+}
+
+/// Extracts the severity, code and message fields of an `ErrorResponse`.
+fn server_error(body: Bytes) -> ReplicationError {
+    let mut reader = Reader::new(body);
+    let (mut severity, mut code, mut message) = (String::new(), String::new(), String::new());
+    while let Ok(field) = reader.u8() {
+        if field == 0 {
+            break;
+        }
+        let value = match reader.string() {
+            Ok(value) => value,
+            Err(_) => break,
+        };
+        match field {
+            b'S' => severity = value,
+            b'C' => code = value,
+            b'M' => message = value,
+            _ => {}
+        }
+    }
+    ReplicationError::Server {
+        severity,
+        code,
+        message,
+    }
+}
+
+fn quote_identifier(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+fn quote_literal(literal: &str) -> String {
+    format!("'{}'", literal.replace('\'', "''"))
+}
+
+/// Microseconds since 2000-01-01.
+fn postgres_now() -> i64 {
+    (Utc::now() - Utc.timestamp(super::POSTGRES_EPOCH_SECS, 0))
+        .num_microseconds()
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+    use tokio::io::DuplexStream;
+
+    /// Returns a stream already streaming changes, and the server side of its
+    /// connection.
+    pub(crate) fn stream() -> (ReplicationStream, DuplexStream) {
+        let (client, server) = tokio::io::duplex(64 * 1024);
+        (ReplicationStream::new(client), server)
+    }
+
+    pub(crate) fn xlog_data(wal_start: u64, data: Bytes) -> Bytes {
+        let mut body = BytesMut::new();
+        body.put_u8(b'w');
+        body.put_u64(wal_start);
+        body.put_u64(wal_start);
+        body.put_i64(0);
+        body.put_slice(&data);
+        frame(b'd', &body)
+    }
+
+    pub(crate) fn keepalive(wal_end: u64, reply_requested: bool) -> Bytes {
+        let mut body = BytesMut::new();
+        body.put_u8(b'k');
+        body.put_u64(wal_end);
+        body.put_i64(0);
+        body.put_u8(reply_requested as u8);
+        frame(b'd', &body)
+    }
+
+    fn frame(tag: u8, body: &[u8]) -> Bytes {
+        let mut buf = BytesMut::new();
+        buf.put_u8(tag);
+        buf.put_i32(4 + body.len() as i32);
+        buf.put_slice(body);
+        buf.freeze()
+    }
+
+    #[tokio::test]
+    async fn reads_messages() {
+        let (mut stream, mut server) = stream();
+        let data = xlog_data(0x16B3748, Bytes::from("B"));
+        // Messages split across reads are put back together.
+        server.write_all(&data[..10]).await.unwrap();
+        server.write_all(&data[10..]).await.unwrap();
+        server.write_all(&keepalive(0x16B3800, true)).await.unwrap();
+        server.write_all(&frame(b'c', &[])).await.unwrap();
+
+        assert!(matches!(
+            stream.next_message().await.unwrap(),
+            Some(ReplicationMessage::XLogData { wal_start: 0x16B3748, data }) if data == "B"
+        ));
+        assert!(matches!(
+            stream.next_message().await.unwrap(),
+            Some(ReplicationMessage::Keepalive {
+                wal_end: 0x16B3800,
+                reply_requested: true,
+            })
+        ));
+        assert!(stream.next_message().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn reads_server_errors() {
+        let (mut stream, mut server) = stream();
+        server
+            .write_all(&frame(
+                b'E',
+                b"SERROR\0C55006\0Mreplication slot \"vector\" is active\0\0",
+            ))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            stream.next_message().await.err().unwrap().to_string(),
+            "ERROR: replication slot \"vector\" is active (55006)"
+        );
+    }
+
+    #[tokio::test]
+    async fn sends_status_updates() {
+        let (mut stream, mut server) = stream();
+        stream.send_status(0x16B3748).await.unwrap();
+
+        let mut buf = [0; 39];
+        server.read_exact(&mut buf).await.unwrap();
+        let mut buf = &buf[..];
+        assert_eq!(buf.get_u8(), b'd');
+        assert_eq!(buf.get_i32(), 38);
+        assert_eq!(buf.get_u8(), b'r');
+        for _ in 0..3 {
+            assert_eq!(buf.get_u64(), 0x16B3748);
+        }
+    }
+
+    #[test]
+    fn quotes_identifiers() {
+        assert_eq!(
+            quote_literal(&quote_identifier(r#"it's "vector""#)),
+            r#"'"it''s ""vector"""'"#
+        );
+    }
+}
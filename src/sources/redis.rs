@@ -0,0 +1,724 @@
+use super::util::finalizer::OrderedFinalizer;
+use crate::{
+    codecs::{self, DecodingConfig},
+    config::{
+        log_schema, DataType, GenerateConfig, SourceConfig, SourceContext, SourceDescription,
+    },
+    event::{BatchNotifier, BatchStatus, Event, Value},
+    internal_events::{RedisAckFailed, RedisEventReceived, RedisReceiveEventFailed},
+    shutdown::ShutdownSignal,
+    Pipeline,
+};
+use bytes::Bytes;
+use chrono::Utc;
+use futures::{stream, SinkExt, StreamExt};
+use redis::{
+    aio::{ConnectionManager, PubSub},
+    streams::{StreamReadOptions, StreamReadReply},
+    AsyncCommands, RedisError, RedisResult,
+};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::time::Duration;
+use tokio_util::codec::FramedRead;
+
+/// How long to wait before retrying after a failed read or a lost
+/// connection.
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Creating Redis client failed: {}", source))]
+    RedisCreateFailed { source: RedisError },
+    #[snafu(display("Creating Redis consumer group failed: {}", source))]
+    CreateGroupFailed { source: RedisError },
+}
+
+#[derive(Copy, Clone, Debug, Derivative, Deserialize, Serialize)]
+#[derivative(Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DataTypeConfig {
+    #[derivative(Default)]
+    List,
+    Channel,
+    Stream,
+}
+
+#[derive(Copy, Clone, Debug, Default, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub struct ListOption {
+    #[serde(default)]
+    method: Method,
+}
+
+#[derive(Copy, Clone, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Method {
+    #[derivative(Default)]
+    LPop,
+    RPop,
+}
+
+#[derive(Copy, Clone, Debug, Default, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub struct ChannelOption {
+    #[serde(default)]
+    pattern: bool,
+}
+
+#[derive(Clone, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(default, rename_all = "lowercase")]
+pub struct StreamOption {
+    #[derivative(Default(value = "default_group()"))]
+    group: String,
+    #[derivative(Default(value = "default_consumer()"))]
+    consumer: String,
+    #[derivative(Default(value = "default_field()"))]
+    field: String,
+    #[derivative(Default(value = "100"))]
+    batch_size: usize,
+    #[derivative(Default(value = "true"))]
+    create_group: bool,
+}
+
+fn default_group() -> String {
+    "vector".into()
+}
+
+fn default_consumer() -> String {
+    "vector".into()
+}
+
+fn default_field() -> String {
+    "message".into()
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RedisSourceConfig {
+    #[serde(default)]
+    data_type: DataTypeConfig,
+    #[serde(alias = "list")]
+    list_option: Option<ListOption>,
+    #[serde(alias = "channel")]
+    channel_option: Option<ChannelOption>,
+    #[serde(alias = "stream")]
+    stream_option: Option<StreamOption>,
+    url: String,
+    key: String,
+    redis_key: Option<String>,
+    #[serde(flatten)]
+    decoding: DecodingConfig,
+}
+
+inventory::submit! {
+    SourceDescription::new::<RedisSourceConfig>("redis")
+}
+
+impl GenerateConfig for RedisSourceConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"
+            url = "redis://127.0.0.1:6379/0"
+            key = "vector"
+            data_type = "list"
+            list.method = "lpop"
+            redis_key = "redis_key"
+            "#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "redis")]
+impl SourceConfig for RedisSourceConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        if self.key.is_empty() {
+            return Err("`key` cannot be empty.".into());
+        }
+        let client = redis::Client::open(self.url.as_str()).context(RedisCreateFailed)?;
+
+        let handler = InputHandler {
+            decoder: self.decoding.build()?,
+            redis_key: self.redis_key.clone(),
+            out: cx.out,
+        };
+
+        Ok(match self.data_type {
+            DataTypeConfig::List => {
+                let conn = client
+                    .get_tokio_connection_manager()
+                    .await
+                    .context(RedisCreateFailed)?;
+                let method = self.list_option.unwrap_or_default().method;
+                Box::pin(list_source(
+                    conn,
+                    self.key.clone(),
+                    method,
+                    handler,
+                    cx.shutdown,
+                ))
+            }
+            DataTypeConfig::Channel => {
+                let pattern = self.channel_option.unwrap_or_default().pattern;
+                Box::pin(channel_source(
+                    client,
+                    self.key.clone(),
+                    pattern,
+                    handler,
+                    cx.shutdown,
+                ))
+            }
+            DataTypeConfig::Stream => {
+                let option = self.stream_option.clone().unwrap_or_default();
+                // Reads block the connection they are made on, so
+                // acknowledgements are sent on a connection of their own.
+                let mut conn = client
+                    .get_tokio_connection_manager()
+                    .await
+                    .context(RedisCreateFailed)?;
+                let ack_conn = client
+                    .get_tokio_connection_manager()
+                    .await
+                    .context(RedisCreateFailed)?;
+                if option.create_group {
+                    create_group(&mut conn, &self.key, &option.group)
+                        .await
+                        .context(CreateGroupFailed)?;
+                }
+                Box::pin(stream_source(
+                    conn,
+                    ack_conn,
+                    self.key.clone(),
+                    option,
+                    handler,
+                    cx.shutdown,
+                    cx.acknowledgements,
+                ))
+            }
+        })
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn source_type(&self) -> &'static str {
+        "redis"
+    }
+
+    fn decoding(&self) -> Option<&DecodingConfig> {
+        Some(&self.decoding)
+    }
+}
+
+/// Creates the consumer group, along with the stream if it doesn't exist
+/// yet. A group that already exists is left as is.
+async fn create_group(conn: &mut ConnectionManager, key: &str, group: &str) -> RedisResult<()> {
+    match conn
+        .xgroup_create_mkstream::<_, _, _, ()>(key, group, "$")
+        .await
+    {
+        Err(error) if error.code() == Some("BUSYGROUP") => Ok(()),
+        result => result,
+    }
+}
+
+struct InputHandler {
+    decoder: codecs::Decoder,
+    redis_key: Option<String>,
+    out: Pipeline,
+}
+
+impl InputHandler {
+    async fn handle(
+        &mut self,
+        key: &str,
+        payload: &[u8],
+        batch: Option<&BatchNotifier>,
+    ) -> Result<(), ()> {
+        emit!(RedisEventReceived {
+            byte_size: payload.len(),
+        });
+
+        let events = decode_payload(payload, self.decoder.clone(), key, &self.redis_key).await;
+        let mut stream = stream::iter(events).map(|event| {
+            Ok(match batch {
+                Some(batch) => event.with_batch_notifier(batch),
+                None => event,
+            })
+        });
+        self.out
+            .send_all(&mut stream)
+            .await
+            .map_err(|error| error!(message = "Error sending to sink.", %error))
+    }
+}
+
+async fn decode_payload(
+    payload: &[u8],
+    decoder: codecs::Decoder,
+    key: &str,
+    redis_key: &Option<String>,
+) -> Vec<Event> {
+    let now = Utc::now();
+    let mut events = Vec::new();
+
+    let mut stream = FramedRead::new(payload, decoder);
+    while let Some(result) = stream.next().await {
+        match result {
+            Ok((decoded, _)) => {
+                for mut event in decoded {
+                    if let Event::Log(ref mut log) = event {
+                        log.insert(log_schema().source_type_key(), Bytes::from("redis"));
+                        log.insert(log_schema().timestamp_key(), now);
+                        if let Some(redis_key) = redis_key {
+                            log.insert(redis_key.as_str(), Value::from(key.to_owned()));
+                        }
+                    }
+                    events.push(event);
+                }
+            }
+            Err(error) => {
+                // Error is logged by `crate::codecs::Decoder`, no further handling
+                // is needed here.
+                if !error.can_continue() {
+                    break;
+                }
+            }
+        }
+    }
+
+    events
+}
+
+async fn list_source(
+    mut conn: ConnectionManager,
+    key: String,
+    method: Method,
+    mut handler: InputHandler,
+    mut shutdown: ShutdownSignal,
+) -> Result<(), ()> {
+    loop {
+        let result: RedisResult<(String, Vec<u8>)> = tokio::select! {
+            _ = &mut shutdown => break,
+            result = pop(&mut conn, &key, method) => result,
+        };
+
+        match result {
+            Ok((key, payload)) => handler.handle(&key, &payload, None).await?,
+            Err(error) => {
+                emit!(RedisReceiveEventFailed { error });
+                tokio::time::sleep(RETRY_DELAY).await;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn pop(
+    conn: &mut ConnectionManager,
+    key: &str,
+    method: Method,
+) -> RedisResult<(String, Vec<u8>)> {
+    // A timeout of zero blocks until an element is available.
+    match method {
+        Method::LPop => conn.blpop(key, 0).await,
+        Method::RPop => conn.brpop(key, 0).await,
+    }
+}
+
+async fn channel_source(
+    client: redis::Client,
+    key: String,
+    pattern: bool,
+    mut handler: InputHandler,
+    mut shutdown: ShutdownSignal,
+) -> Result<(), ()> {
+    loop {
+        let subscription = tokio::select! {
+            _ = &mut shutdown => break,
+            subscription = subscribe(&client, &key, pattern) => subscription,
+        };
+
+        match subscription {
+            Ok(mut pubsub) => {
+                let mut messages = pubsub.on_message();
+                loop {
+                    let message = tokio::select! {
+                        _ = &mut shutdown => return Ok(()),
+                        message = messages.next() => message,
+                    };
+                    match message {
+                        Some(message) => {
+                            handler
+                                .handle(
+                                    message.get_channel_name(),
+                                    message.get_payload_bytes(),
+                                    None,
+                                )
+                                .await?
+                        }
+                        None => break,
+                    }
+                }
+                warn!(message = "Redis connection closed; resubscribing.");
+            }
+            Err(error) => emit!(RedisReceiveEventFailed { error }),
+        }
+
+        tokio::time::sleep(RETRY_DELAY).await;
+    }
+
+    Ok(())
+}
+
+async fn subscribe(client: &redis::Client, key: &str, pattern: bool) -> RedisResult<PubSub> {
+    let mut pubsub = client.get_async_connection().await?.into_pubsub();
+    if pattern {
+        pubsub.psubscribe(key).await?;
+    } else {
+        pubsub.subscribe(key).await?;
+    }
+    Ok(pubsub)
+}
+
+async fn stream_source(
+    mut conn: ConnectionManager,
+    ack_conn: ConnectionManager,
+    key: String,
+    option: StreamOption,
+    mut handler: InputHandler,
+    shutdown: ShutdownSignal,
+    acknowledgements: bool,
+) -> Result<(), ()> {
+    let mut shutdown = shutdown.shared();
+    let finalizer = acknowledgements.then(|| {
+        OrderedFinalizer::new(
+            shutdown.clone(),
+            ack_entries(ack_conn, key.clone(), option.group.clone()),
+        )
+    });
+
+    let mut options = StreamReadOptions::default()
+        .group(&option.group, &option.consumer)
+        .count(option.batch_size)
+        .block(0);
+    if !acknowledgements {
+        options = options.noack();
+    }
+
+    // Entries delivered to this consumer but never acknowledged, for
+    // example because Vector stopped before they were delivered, are read
+    // again first. Reading from an ID past the last pending entry read so
+    // far makes sure each of them is only read once.
+    let mut read_pending = acknowledgements;
+    let mut last_pending_id = "0".to_string();
+
+    loop {
+        let id = if read_pending {
+            last_pending_id.as_str()
+        } else {
+            ">"
+        };
+        let result = tokio::select! {
+            _ = &mut shutdown => break,
+            result = read_entries(&mut conn, &key, id, &options) => result,
+        };
+
+        let reply = match result {
+            Ok(reply) => reply,
+            Err(error) => {
+                emit!(RedisReceiveEventFailed { error });
+                tokio::time::sleep(RETRY_DELAY).await;
+                continue;
+            }
+        };
+
+        let entries = reply
+            .into_iter()
+            .flat_map(|reply| reply.keys)
+            .flat_map(|key| key.ids)
+            .collect::<Vec<_>>();
+        match entries.last() {
+            Some(entry) if read_pending => last_pending_id = entry.id.clone(),
+            None if read_pending => read_pending = false,
+            _ => {}
+        }
+        if entries.is_empty() {
+            continue;
+        }
+
+        let (batch, receiver) = match &finalizer {
+            Some(_) => {
+                let (batch, receiver) = BatchNotifier::new_with_receiver();
+                (Some(batch), Some(receiver))
+            }
+            None => (None, None),
+        };
+        let mut ids = Vec::with_capacity(entries.len());
+        for entry in entries {
+            match entry.get::<Vec<u8>>(&option.field) {
+                Some(payload) => handler.handle(&key, &payload, batch.as_ref()).await?,
+                None => warn!(
+                    message = "Stream entry has no payload field; skipping.",
+                    id = %entry.id,
+                    field = %option.field,
+                    rate_limit_secs = 10,
+                ),
+            }
+            ids.push(entry.id);
+        }
+        drop(batch);
+
+        if let (Some(finalizer), Some(receiver)) = (&finalizer, receiver) {
+            finalizer.add(ids, receiver);
+        }
+    }
+
+    Ok(())
+}
+
+async fn read_entries(
+    conn: &mut ConnectionManager,
+    key: &str,
+    id: &str,
+    options: &StreamReadOptions,
+) -> RedisResult<Option<StreamReadReply>> {
+    conn.xread_options(&[key], &[id], options).await
+}
+
+fn ack_entries(
+    conn: ConnectionManager,
+    key: String,
+    group: String,
+) -> impl Fn(BatchStatus, Vec<String>) + Send + 'static {
+    move |status, ids| {
+        // Entries of batches that were not delivered stay pending, so they
+        // are read again when the source restarts.
+        if status != BatchStatus::Delivered {
+            return;
+        }
+        let mut conn = conn.clone();
+        let key = key.clone();
+        let group = group.clone();
+        tokio::spawn(async move {
+            if let Err(error) = conn.xack::<_, _, _, usize>(&key, &group, &ids).await {
+                emit!(RedisAckFailed { error });
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<RedisSourceConfig>();
+    }
+
+    #[test]
+    fn parses_stream_options() {
+        let config: RedisSourceConfig = toml::from_str(
+            r#"
+            url = "redis://127.0.0.1:6379/0"
+            key = "vector"
+            data_type = "stream"
+            stream.group = "tier2"
+            "#,
+        )
+        .unwrap();
+        let option = config.stream_option.unwrap();
+        assert_eq!(option.group, "tier2");
+        assert_eq!(option.consumer, "vector");
+        assert_eq!(option.field, "message");
+        assert_eq!(option.batch_size, 100);
+        assert!(option.create_group);
+    }
+
+    #[tokio::test]
+    async fn decodes_payload_with_key() {
+        let events = decode_payload(
+            b"foo\nbar",
+            DecodingConfig::default().build().unwrap(),
+            "vector",
+            &Some("redis_key".into()),
+        )
+        .await;
+
+        assert_eq!(events.len(), 2);
+        let log = events[1].as_log();
+        assert_eq!(log[log_schema().message_key()], "bar".into());
+        assert_eq!(log["redis_key"], "vector".into());
+        assert_eq!(log[log_schema().source_type_key()], "redis".into());
+    }
+}
+
+#[cfg(feature = "redis-integration-tests")]
+#[cfg(test)]
+mod integration_tests {
+    use super::*;
+    use crate::{
+        event::EventStatus,
+        test_util::{collect_n, random_string, trace_init},
+    };
+
+    const REDIS_SERVER: &str = "redis://127.0.0.1:6379/0";
+
+    async fn connect() -> ConnectionManager {
+        redis::Client::open(REDIS_SERVER)
+            .unwrap()
+            .get_tokio_connection_manager()
+            .await
+            .unwrap()
+    }
+
+    async fn start_source(
+        config: &str,
+        acknowledgements: bool,
+        status: EventStatus,
+    ) -> impl futures::Stream<Item = Event> + Unpin {
+        let config: RedisSourceConfig = toml::from_str(config).unwrap();
+        let (tx, rx) = Pipeline::new_test_finalize(status);
+        let mut cx = SourceContext::new_test(tx);
+        cx.acknowledgements = acknowledgements;
+        tokio::spawn(config.build(cx).await.unwrap());
+        rx
+    }
+
+    fn messages() -> Vec<String> {
+        (0..10).map(|i| format!("message {}", i)).collect()
+    }
+
+    fn assert_messages(events: &[Event], messages: &[String], key: &str) {
+        for (event, message) in events.iter().zip(messages) {
+            let log = event.as_log();
+            assert_eq!(log[log_schema().message_key()], message.as_str().into());
+            assert_eq!(log["redis_key"], key.into());
+        }
+    }
+
+    #[tokio::test]
+    async fn consumes_list() {
+        trace_init();
+
+        let key = format!("test-{}", random_string(10));
+        let mut conn = connect().await;
+        let messages = messages();
+        for message in &messages {
+            conn.rpush::<_, _, ()>(&key, message).await.unwrap();
+        }
+
+        let rx = start_source(
+            &format!(
+                r#"
+                url = "{}"
+                key = "{}"
+                redis_key = "redis_key"
+                "#,
+                REDIS_SERVER, key
+            ),
+            false,
+            EventStatus::Delivered,
+        )
+        .await;
+
+        let events = collect_n(rx, messages.len()).await;
+        assert_messages(&events, &messages, &key);
+        let len: usize = conn.llen(&key).await.unwrap();
+        assert_eq!(len, 0);
+    }
+
+    #[tokio::test]
+    async fn consumes_channel() {
+        trace_init();
+
+        let channel = format!("test-{}", random_string(10));
+        let rx = start_source(
+            &format!(
+                r#"
+                url = "{}"
+                key = "{}.*"
+                data_type = "channel"
+                channel.pattern = true
+                redis_key = "redis_key"
+                "#,
+                REDIS_SERVER, channel
+            ),
+            false,
+            EventStatus::Delivered,
+        )
+        .await;
+
+        // Give the source time to subscribe.
+        tokio::time::sleep(Duration::from_secs(1)).await;
+
+        let mut conn = connect().await;
+        let messages = messages();
+        for message in &messages {
+            conn.publish::<_, _, ()>(format!("{}.sensor", channel), message)
+                .await
+                .unwrap();
+        }
+
+        let events = collect_n(rx, messages.len()).await;
+        assert_messages(&events, &messages, &format!("{}.sensor", channel));
+    }
+
+    /// Consumes entries of a new stream and returns the number of entries
+    /// left pending once their events are finalized with `status`.
+    async fn consume_stream(status: EventStatus) -> usize {
+        let key = format!("test-{}", random_string(10));
+        let rx = start_source(
+            &format!(
+                r#"
+                url = "{}"
+                key = "{}"
+                data_type = "stream"
+                redis_key = "redis_key"
+                "#,
+                REDIS_SERVER, key
+            ),
+            true,
+            status,
+        )
+        .await;
+
+        let mut conn = connect().await;
+        let messages = messages();
+        for message in &messages {
+            conn.xadd::<_, _, _, _, ()>(&key, "*", &[("message", message)])
+                .await
+                .unwrap();
+        }
+
+        let events = collect_n(rx, messages.len()).await;
+        assert_messages(&events, &messages, &key);
+
+        // Give the finalizer time to acknowledge the entries.
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        let pending: redis::streams::StreamPendingReply =
+            conn.xpending(&key, "vector").await.unwrap();
+        pending.count()
+    }
+
+    #[tokio::test]
+    async fn consumes_and_acknowledges_stream() {
+        trace_init();
+
+        assert_eq!(consume_stream(EventStatus::Delivered).await, 0);
+    }
+
+    #[tokio::test]
+    async fn does_not_acknowledge_failed_stream_entries() {
+        trace_init();
+
+        assert_eq!(consume_stream(EventStatus::Failed).await, messages().len());
+    }
+}
@@ -0,0 +1,357 @@
+//! Decoding and encoding of the subset of the ASN.1 Basic Encoding Rules
+//! used by SNMP messages.
+
+use snafu::Snafu;
+use std::{fmt, str::FromStr};
+
+pub(super) const INTEGER: u8 = 0x02;
+pub(super) const OCTET_STRING: u8 = 0x04;
+pub(super) const NULL: u8 = 0x05;
+pub(super) const OBJECT_IDENTIFIER: u8 = 0x06;
+pub(super) const SEQUENCE: u8 = 0x30;
+pub(super) const IP_ADDRESS: u8 = 0x40;
+pub(super) const COUNTER32: u8 = 0x41;
+pub(super) const GAUGE32: u8 = 0x42;
+pub(super) const TIME_TICKS: u8 = 0x43;
+pub(super) const OPAQUE: u8 = 0x44;
+pub(super) const COUNTER64: u8 = 0x46;
+pub(super) const NO_SUCH_OBJECT: u8 = 0x80;
+pub(super) const NO_SUCH_INSTANCE: u8 = 0x81;
+pub(super) const END_OF_MIB_VIEW: u8 = 0x82;
+
+#[derive(Debug, PartialEq, Snafu)]
+pub(super) enum BerError {
+    #[snafu(display("value is truncated"))]
+    Truncated,
+    #[snafu(display("unsupported length encoding"))]
+    InvalidLength,
+    #[snafu(display("expected tag {:#04x}, found {:#04x}", expected, actual))]
+    UnexpectedTag { expected: u8, actual: u8 },
+    #[snafu(display("integer does not fit in 64 bits"))]
+    IntegerOverflow,
+    #[snafu(display("invalid object identifier"))]
+    InvalidOid,
+}
+
+/// An object identifier, such as `1.3.6.1.2.1.1.3.0`.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub(super) struct Oid(pub(super) Vec<u32>);
+
+impl fmt::Display for Oid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, arc) in self.0.iter().enumerate() {
+            if index > 0 {
+                f.write_str(".")?;
+            }
+            write!(f, "{}", arc)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Oid {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.trim_start_matches('.')
+            .split('.')
+            .map(str::parse)
+            .collect::<Result<_, _>>()
+            .map(Oid)
+    }
+}
+
+/// Reads values from an encoded message, keeping track of their offset in
+/// the whole message.
+#[derive(Clone, Copy, Debug)]
+pub(super) struct Reader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub(super) const fn new(data: &'a [u8]) -> Self {
+        Self { data, offset: 0 }
+    }
+
+    pub(super) const fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// The offset of the next value in the whole message.
+    pub(super) const fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Reads the next value, and returns its tag and a reader of its contents.
+    pub(super) fn read(&mut self) -> Result<(u8, Reader<'a>), BerError> {
+        let (&tag, rest) = self.data.split_first().ok_or(BerError::Truncated)?;
+        let (&first, mut rest) = rest.split_first().ok_or(BerError::Truncated)?;
+        let length = if first < 0x80 {
+            first as usize
+        } else {
+            let count = (first & 0x7F) as usize;
+            if count == 0 || count > 4 || rest.len() < count {
+                return Err(BerError::InvalidLength);
+            }
+            let (bytes, remaining) = rest.split_at(count);
+            rest = remaining;
+            bytes
+                .iter()
+                .fold(0, |length, byte| length << 8 | *byte as usize)
+        };
+        if rest.len() < length {
+            return Err(BerError::Truncated);
+        }
+
+        let header_length = self.data.len() - rest.len();
+        let contents = Reader {
+            data: &rest[..length],
+            offset: self.offset + header_length,
+        };
+        self.data = &rest[length..];
+        self.offset += header_length + length;
+        Ok((tag, contents))
+    }
+
+    /// Reads the next value, which must be of the given tag.
+    pub(super) fn expect(&mut self, expected: u8) -> Result<Reader<'a>, BerError> {
+        match self.read()? {
+            (tag, contents) if tag == expected => Ok(contents),
+            (actual, _) => Err(BerError::UnexpectedTag { expected, actual }),
+        }
+    }
+
+    pub(super) fn sequence(&mut self) -> Result<Reader<'a>, BerError> {
+        self.expect(SEQUENCE)
+    }
+
+    pub(super) fn integer(&mut self) -> Result<i64, BerError> {
+        decode_integer(self.expect(INTEGER)?.data)
+    }
+
+    pub(super) fn octet_string(&mut self) -> Result<&'a [u8], BerError> {
+        Ok(self.expect(OCTET_STRING)?.data)
+    }
+
+    pub(super) fn oid(&mut self) -> Result<Oid, BerError> {
+        decode_oid(self.expect(OBJECT_IDENTIFIER)?.data)
+    }
+
+    /// The remaining bytes.
+    pub(super) const fn bytes(&self) -> &'a [u8] {
+        self.data
+    }
+}
+
+pub(super) fn decode_integer(bytes: &[u8]) -> Result<i64, BerError> {
+    match bytes.first() {
+        None => Ok(0),
+        Some(_) if bytes.len() > 8 => Err(BerError::IntegerOverflow),
+        // Sign extended from the leading bit.
+        Some(first) => Ok(bytes[1..]
+            .iter()
+            .fold(i64::from(*first as i8), |value, byte| {
+                value << 8 | i64::from(*byte)
+            })),
+    }
+}
+
+pub(super) fn decode_unsigned(bytes: &[u8]) -> Result<u64, BerError> {
+    // Values with the leading bit set are prefixed with a zero byte.
+    let bytes = match bytes {
+        [0, rest @ ..] => rest,
+        bytes => bytes,
+    };
+    if bytes.len() > 8 {
+        return Err(BerError::IntegerOverflow);
+    }
+    Ok(bytes
+        .iter()
+        .fold(0, |value, byte| value << 8 | u64::from(*byte)))
+}
+
+pub(super) fn decode_oid(bytes: &[u8]) -> Result<Oid, BerError> {
+    let mut arcs = Vec::new();
+    let mut value: u32 = 0;
+    for (index, byte) in bytes.iter().enumerate() {
+        value = value
+            .checked_mul(128)
+            .map(|value| value | u32::from(byte & 0x7F))
+            .ok_or(BerError::InvalidOid)?;
+        if byte & 0x80 != 0 {
+            if index == bytes.len() - 1 {
+                return Err(BerError::InvalidOid);
+            }
+            continue;
+        }
+        // The first value holds the first two arcs.
+        if arcs.is_empty() {
+            let first = (value / 40).min(2);
+            arcs.push(first);
+            arcs.push(value - first * 40);
+        } else {
+            arcs.push(value);
+        }
+        value = 0;
+    }
+    if arcs.is_empty() {
+        return Err(BerError::InvalidOid);
+    }
+    Ok(Oid(arcs))
+}
+
+pub(super) fn encode(tag: u8, contents: &[u8]) -> Vec<u8> {
+    let mut encoded = vec![tag];
+    let length = contents.len();
+    if length < 0x80 {
+        encoded.push(length as u8);
+    } else {
+        let bytes = (length as u32).to_be_bytes();
+        let skip = bytes.iter().take_while(|byte| **byte == 0).count();
+        encoded.push(0x80 | (4 - skip) as u8);
+        encoded.extend_from_slice(&bytes[skip..]);
+    }
+    encoded.extend_from_slice(contents);
+    encoded
+}
+
+/// The length of the tag and length of an encoded value.
+pub(super) fn header_length(encoded: &[u8]) -> usize {
+    match encoded[1] {
+        length if length < 0x80 => 2,
+        length => 2 + (length & 0x7F) as usize,
+    }
+}
+
+pub(super) fn encode_sequence(values: &[Vec<u8>]) -> Vec<u8> {
+    encode(SEQUENCE, &values.concat())
+}
+
+pub(super) fn encode_integer(value: i64) -> Vec<u8> {
+    let bytes = value.to_be_bytes();
+    // Leading bytes which only extend the sign of the next one are dropped.
+    let skip = (0..7)
+        .take_while(|index| {
+            let (byte, next) = (bytes[*index], bytes[*index + 1]);
+            (byte == 0 && next & 0x80 == 0) || (byte == 0xFF && next & 0x80 != 0)
+        })
+        .count();
+    encode(INTEGER, &bytes[skip..])
+}
+
+pub(super) fn encode_unsigned(tag: u8, value: u64) -> Vec<u8> {
+    let bytes = value.to_be_bytes();
+    let skip = bytes[..7].iter().take_while(|byte| **byte == 0).count();
+    let mut contents = bytes[skip..].to_vec();
+    if contents[0] & 0x80 != 0 {
+        contents.insert(0, 0);
+    }
+    encode(tag, &contents)
+}
+
+pub(super) fn encode_octet_string(value: &[u8]) -> Vec<u8> {
+    encode(OCTET_STRING, value)
+}
+
+pub(super) fn encode_oid(oid: &Oid) -> Vec<u8> {
+    let mut contents = Vec::new();
+    let (first, rest) = match oid.0.as_slice() {
+        [first, second, rest @ ..] => (first * 40 + second, rest),
+        [first] => (first * 40, &[][..]),
+        [] => (0, &[][..]),
+    };
+    for arc in std::iter::once(&first).chain(rest) {
+        let mut groups = vec![(arc & 0x7F) as u8];
+        let mut arc = arc >> 7;
+        while arc > 0 {
+            groups.push((arc & 0x7F) as u8 | 0x80);
+            arc >>= 7;
+        }
+        contents.extend(groups.iter().rev());
+    }
+    encode(OBJECT_IDENTIFIER, &contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_integers() {
+        for value in &[0, 1, 127, 128, 255, 256, -1, -128, -129, i64::MAX, i64::MIN] {
+            let encoded = encode_integer(*value);
+            assert_eq!(Reader::new(&encoded).integer(), Ok(*value), "{}", value);
+        }
+        assert_eq!(encode_integer(128), vec![INTEGER, 2, 0, 128]);
+        assert_eq!(encode_integer(-129), vec![INTEGER, 2, 0xFF, 0x7F]);
+    }
+
+    #[test]
+    fn round_trips_unsigned() {
+        for value in &[0, 127, 128, u64::from(u32::MAX), u64::MAX] {
+            let encoded = encode_unsigned(COUNTER64, *value);
+            let (tag, contents) = Reader::new(&encoded).read().unwrap();
+            assert_eq!(tag, COUNTER64);
+            assert_eq!(decode_unsigned(contents.bytes()), Ok(*value));
+        }
+    }
+
+    #[test]
+    fn round_trips_oids() {
+        let oid = "1.3.6.1.4.1.2021.13.990".parse::<Oid>().unwrap();
+        let encoded = encode_oid(&oid);
+
+        assert_eq!(
+            encoded,
+            vec![
+                OBJECT_IDENTIFIER,
+                10,
+                0x2B,
+                6,
+                1,
+                4,
+                1,
+                0x8F,
+                0x65,
+                13,
+                0x87,
+                0x5E
+            ]
+        );
+        let decoded = Reader::new(&encoded).oid().unwrap();
+        assert_eq!(decoded, oid);
+        assert_eq!(decoded.to_string(), "1.3.6.1.4.1.2021.13.990");
+    }
+
+    #[test]
+    fn reads_long_lengths_and_offsets() {
+        let contents = vec![0x41; 300];
+        let encoded = encode_sequence(&[encode_integer(1), encode_octet_string(&contents)]);
+        assert_eq!(&encoded[..4], &[SEQUENCE, 0x82, 0x01, 0x33]);
+        assert_eq!(header_length(&encoded), 4);
+
+        let mut sequence = Reader::new(&encoded).sequence().unwrap();
+        assert_eq!(sequence.integer(), Ok(1));
+        let (_, string) = sequence.read().unwrap();
+        assert_eq!(string.offset(), 4 + 3 + 4);
+        assert_eq!(string.bytes(), contents.as_slice());
+        assert!(sequence.is_empty());
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        assert_eq!(
+            Reader::new(&[INTEGER, 2, 0]).integer(),
+            Err(BerError::Truncated)
+        );
+        assert_eq!(
+            Reader::new(&[OCTET_STRING, 0]).integer(),
+            Err(BerError::UnexpectedTag {
+                expected: INTEGER,
+                actual: OCTET_STRING,
+            })
+        );
+        assert_eq!(decode_oid(&[0x2B, 0x86]), Err(BerError::InvalidOid));
+    }
+}
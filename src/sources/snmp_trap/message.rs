@@ -0,0 +1,808 @@
+//! Decoding of SNMP notifications, and encoding of the responses to informs.
+//!
+//! See <https://tools.ietf.org/html/rfc1157>, <https://tools.ietf.org/html/rfc3416>
+//! and <https://tools.ietf.org/html/rfc3412>.
+
+use super::{
+    ber::{self, BerError, Oid, Reader},
+    usm::{Engine, User},
+};
+use snafu::Snafu;
+use std::{convert::TryFrom, net::Ipv4Addr};
+
+const VERSION_1: i64 = 0;
+const VERSION_2C: i64 = 1;
+const VERSION_3: i64 = 3;
+
+const RESPONSE: u8 = 0xA2;
+const TRAP_V1: u8 = 0xA4;
+const INFORM: u8 = 0xA6;
+const TRAP_V2: u8 = 0xA7;
+const REPORT: u8 = 0xA8;
+
+const FLAG_AUTH: u8 = 0x01;
+const FLAG_PRIV: u8 = 0x02;
+const USER_BASED_SECURITY_MODEL: i64 = 3;
+
+/// The largest message the source accepts, which is the largest payload of
+/// a UDP datagram.
+pub(super) const MAX_MESSAGE_SIZE: usize = 65_507;
+
+#[derive(Debug, PartialEq, Snafu)]
+pub(super) enum DecodeError {
+    #[snafu(display("invalid encoding: {}", source))]
+    Ber { source: BerError },
+    #[snafu(display("unsupported SNMP version {}", version))]
+    UnsupportedVersion { version: i64 },
+    #[snafu(display("unsupported PDU type {:#04x}", tag))]
+    UnsupportedPdu { tag: u8 },
+    #[snafu(display("invalid IP address"))]
+    InvalidAddress,
+    #[snafu(display("unsupported security model {}", model))]
+    UnsupportedSecurityModel { model: i64 },
+    #[snafu(display("unknown community"))]
+    UnknownCommunity,
+    #[snafu(display("unknown user {:?}", user))]
+    UnknownUser { user: String },
+    #[snafu(display("security level is not supported by user {:?}", user))]
+    UnsupportedSecurityLevel { user: String },
+    #[snafu(display("authentication failed for user {:?}", user))]
+    AuthenticationFailed { user: String },
+    #[snafu(display("decryption failed for user {:?}", user))]
+    DecryptionFailed { user: String },
+}
+
+impl From<BerError> for DecodeError {
+    fn from(source: BerError) -> Self {
+        DecodeError::Ber { source }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub(super) enum Incoming {
+    Message(Message),
+    /// A SNMPv3 message sent to discover the engine ID of the source, before
+    /// sending it informs.
+    Discovery {
+        msg_id: i64,
+        request_id: i64,
+        user: Vec<u8>,
+    },
+}
+
+#[derive(Debug, PartialEq)]
+pub(super) struct Message {
+    pub(super) security: Security,
+    pub(super) pdu: Pdu,
+}
+
+#[derive(Debug, PartialEq)]
+pub(super) enum Security {
+    V1 {
+        community: Vec<u8>,
+    },
+    V2c {
+        community: Vec<u8>,
+    },
+    V3 {
+        msg_id: i64,
+        flags: u8,
+        user: Vec<u8>,
+        engine_id: Vec<u8>,
+        context_engine_id: Vec<u8>,
+        context_name: Vec<u8>,
+    },
+}
+
+#[derive(Debug, PartialEq)]
+pub(super) enum Pdu {
+    TrapV1 {
+        enterprise: Oid,
+        agent_address: Ipv4Addr,
+        generic_trap: i64,
+        specific_trap: i64,
+        timestamp: u64,
+        varbinds: Vec<VarBind>,
+    },
+    TrapV2 {
+        request_id: i64,
+        varbinds: Vec<VarBind>,
+    },
+    Inform {
+        request_id: i64,
+        varbinds: Vec<VarBind>,
+    },
+}
+
+impl Pdu {
+    pub(super) fn varbinds(&self) -> &[VarBind] {
+        match self {
+            Self::TrapV1 { varbinds, .. }
+            | Self::TrapV2 { varbinds, .. }
+            | Self::Inform { varbinds, .. } => varbinds,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct VarBind {
+    pub(super) oid: Oid,
+    pub(super) value: VarValue,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(super) enum VarValue {
+    Integer(i64),
+    OctetString(Vec<u8>),
+    Null,
+    Oid(Oid),
+    IpAddress(Ipv4Addr),
+    Counter32(u64),
+    Gauge32(u64),
+    TimeTicks(u64),
+    Opaque(Vec<u8>),
+    Counter64(u64),
+    NoSuchObject,
+    NoSuchInstance,
+    EndOfMibView,
+}
+
+impl VarBind {
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, DecodeError> {
+        let mut varbind = reader.sequence()?;
+        let oid = varbind.oid()?;
+        let (tag, contents) = varbind.read()?;
+        let bytes = contents.bytes();
+        let value = match tag {
+            ber::INTEGER => VarValue::Integer(ber::decode_integer(bytes)?),
+            ber::OCTET_STRING => VarValue::OctetString(bytes.to_vec()),
+            ber::NULL => VarValue::Null,
+            ber::OBJECT_IDENTIFIER => VarValue::Oid(ber::decode_oid(bytes)?),
+            ber::IP_ADDRESS => VarValue::IpAddress(decode_address(bytes)?),
+            ber::COUNTER32 => VarValue::Counter32(ber::decode_unsigned(bytes)?),
+            ber::GAUGE32 => VarValue::Gauge32(ber::decode_unsigned(bytes)?),
+            ber::TIME_TICKS => VarValue::TimeTicks(ber::decode_unsigned(bytes)?),
+            ber::OPAQUE => VarValue::Opaque(bytes.to_vec()),
+            ber::COUNTER64 => VarValue::Counter64(ber::decode_unsigned(bytes)?),
+            ber::NO_SUCH_OBJECT => VarValue::NoSuchObject,
+            ber::NO_SUCH_INSTANCE => VarValue::NoSuchInstance,
+            ber::END_OF_MIB_VIEW => VarValue::EndOfMibView,
+            // Values of unknown types are kept as their raw bytes.
+            _ => VarValue::Opaque(bytes.to_vec()),
+        };
+        Ok(Self { oid, value })
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let value = match &self.value {
+            VarValue::Integer(value) => ber::encode_integer(*value),
+            VarValue::OctetString(value) => ber::encode_octet_string(value),
+            VarValue::Null => ber::encode(ber::NULL, &[]),
+            VarValue::Oid(value) => ber::encode_oid(value),
+            VarValue::IpAddress(value) => ber::encode(ber::IP_ADDRESS, &value.octets()),
+            VarValue::Counter32(value) => ber::encode_unsigned(ber::COUNTER32, *value),
+            VarValue::Gauge32(value) => ber::encode_unsigned(ber::GAUGE32, *value),
+            VarValue::TimeTicks(value) => ber::encode_unsigned(ber::TIME_TICKS, *value),
+            VarValue::Opaque(value) => ber::encode(ber::OPAQUE, value),
+            VarValue::Counter64(value) => ber::encode_unsigned(ber::COUNTER64, *value),
+            VarValue::NoSuchObject => ber::encode(ber::NO_SUCH_OBJECT, &[]),
+            VarValue::NoSuchInstance => ber::encode(ber::NO_SUCH_INSTANCE, &[]),
+            VarValue::EndOfMibView => ber::encode(ber::END_OF_MIB_VIEW, &[]),
+        };
+        ber::encode_sequence(&[ber::encode_oid(&self.oid), value])
+    }
+}
+
+/// The header of a SNMPv3 message, up to its scoped PDU.
+struct Header<'a> {
+    msg_id: i64,
+    flags: u8,
+    engine_id: &'a [u8],
+    boots: u32,
+    time: u32,
+    user: &'a [u8],
+    auth_params: Reader<'a>,
+    priv_params: &'a [u8],
+}
+
+impl<'a> Header<'a> {
+    fn decode(message: &mut Reader<'a>) -> Result<Self, DecodeError> {
+        let mut global = message.sequence()?;
+        let msg_id = global.integer()?;
+        let _max_size = global.integer()?;
+        let flags = global.octet_string()?.first().copied().unwrap_or(0);
+        let model = global.integer()?;
+        if model != USER_BASED_SECURITY_MODEL {
+            return Err(DecodeError::UnsupportedSecurityModel { model });
+        }
+
+        let mut usm = message.expect(ber::OCTET_STRING)?.sequence()?;
+        Ok(Self {
+            msg_id,
+            flags,
+            engine_id: usm.octet_string()?,
+            boots: u32::try_from(usm.integer()?).unwrap_or(0),
+            time: u32::try_from(usm.integer()?).unwrap_or(0),
+            user: usm.octet_string()?,
+            auth_params: usm.expect(ber::OCTET_STRING)?,
+            priv_params: usm.octet_string()?,
+        })
+    }
+}
+
+/// Decodes a message, and authenticates and decrypts it if it is of
+/// SNMPv3.
+pub(super) fn decode(
+    data: &[u8],
+    engine: &Engine,
+    communities: Option<&[String]>,
+) -> Result<Incoming, DecodeError> {
+    let mut message = Reader::new(data).sequence()?;
+    let version = message.integer()?;
+    let security = match version {
+        VERSION_1 | VERSION_2C => {
+            let community = message.octet_string()?;
+            if let Some(communities) = communities {
+                if !communities
+                    .iter()
+                    .any(|allowed| allowed.as_bytes() == community)
+                {
+                    return Err(DecodeError::UnknownCommunity);
+                }
+            }
+            let community = community.to_vec();
+            if version == VERSION_1 {
+                Security::V1 { community }
+            } else {
+                Security::V2c { community }
+            }
+        }
+        VERSION_3 => return decode_v3(data, message, engine),
+        version => return Err(DecodeError::UnsupportedVersion { version }),
+    };
+
+    Ok(Incoming::Message(Message {
+        security,
+        pdu: decode_pdu(&mut message)?,
+    }))
+}
+
+fn decode_v3(
+    data: &[u8],
+    mut message: Reader<'_>,
+    engine: &Engine,
+) -> Result<Incoming, DecodeError> {
+    let header = Header::decode(&mut message)?;
+
+    // Discovery messages are neither authenticated nor encrypted.
+    if header.engine_id.is_empty() {
+        let mut scoped = message.sequence()?;
+        let _context_engine_id = scoped.octet_string()?;
+        let _context_name = scoped.octet_string()?;
+        let (_, mut pdu) = scoped.read()?;
+        return Ok(Incoming::Discovery {
+            msg_id: header.msg_id,
+            request_id: pdu.integer()?,
+            user: header.user.to_vec(),
+        });
+    }
+
+    let user_name = String::from_utf8_lossy(header.user).into_owned();
+    let user = engine
+        .user(header.user)
+        .ok_or_else(|| DecodeError::UnknownUser {
+            user: user_name.clone(),
+        })?;
+    let unsupported = || DecodeError::UnsupportedSecurityLevel {
+        user: user_name.clone(),
+    };
+
+    if header.flags & FLAG_AUTH != 0 {
+        let auth = user.auth().ok_or_else(unsupported)?;
+        // The HMAC is computed over the message with zeroed authentication
+        // parameters.
+        let mut zeroed = data.to_vec();
+        let start = header.auth_params.offset();
+        let mac = header.auth_params.bytes();
+        zeroed[start..start + mac.len()]
+            .iter_mut()
+            .for_each(|byte| *byte = 0);
+        if !auth.verify(header.engine_id, &zeroed, mac).unwrap_or(false) {
+            return Err(DecodeError::AuthenticationFailed {
+                user: user_name.clone(),
+            });
+        }
+    } else if header.flags & FLAG_PRIV != 0 {
+        return Err(unsupported());
+    }
+
+    let decrypted;
+    let mut scoped = if header.flags & FLAG_PRIV != 0 {
+        let privacy = user.privacy().ok_or_else(unsupported)?;
+        let encrypted = message.octet_string()?;
+        decrypted = privacy
+            .decrypt(
+                header.engine_id,
+                header.boots,
+                header.time,
+                header.priv_params,
+                encrypted,
+            )
+            .map_err(|_| DecodeError::DecryptionFailed {
+                user: user_name.clone(),
+            })?;
+        // Encrypted data may be padded after the scoped PDU.
+        Reader::new(&decrypted).sequence()?
+    } else {
+        message.sequence()?
+    };
+    let context_engine_id = scoped.octet_string()?.to_vec();
+    let context_name = scoped.octet_string()?.to_vec();
+    let pdu = decode_pdu(&mut scoped)?;
+
+    // The source is authoritative for the informs it receives, which must be
+    // sent to its engine ID.
+    if let Pdu::Inform { request_id, .. } = pdu {
+        if header.engine_id != engine.id.as_slice() {
+            return Ok(Incoming::Discovery {
+                msg_id: header.msg_id,
+                request_id,
+                user: header.user.to_vec(),
+            });
+        }
+    }
+
+    Ok(Incoming::Message(Message {
+        security: Security::V3 {
+            msg_id: header.msg_id,
+            flags: header.flags,
+            user: header.user.to_vec(),
+            engine_id: header.engine_id.to_vec(),
+            context_engine_id,
+            context_name,
+        },
+        pdu,
+    }))
+}
+
+fn decode_pdu(reader: &mut Reader<'_>) -> Result<Pdu, DecodeError> {
+    let (tag, mut pdu) = reader.read()?;
+    let decode_varbinds = |pdu: &mut Reader<'_>| {
+        let mut list = pdu.sequence()?;
+        let mut varbinds = Vec::new();
+        while !list.is_empty() {
+            varbinds.push(VarBind::decode(&mut list)?);
+        }
+        Ok::<_, DecodeError>(varbinds)
+    };
+
+    match tag {
+        TRAP_V1 => Ok(Pdu::TrapV1 {
+            enterprise: pdu.oid()?,
+            agent_address: decode_address(pdu.expect(ber::IP_ADDRESS)?.bytes())?,
+            generic_trap: pdu.integer()?,
+            specific_trap: pdu.integer()?,
+            timestamp: ber::decode_unsigned(pdu.expect(ber::TIME_TICKS)?.bytes())?,
+            varbinds: decode_varbinds(&mut pdu)?,
+        }),
+        TRAP_V2 | INFORM => {
+            let request_id = pdu.integer()?;
+            let _error_status = pdu.integer()?;
+            let _error_index = pdu.integer()?;
+            let varbinds = decode_varbinds(&mut pdu)?;
+            Ok(if tag == INFORM {
+                Pdu::Inform {
+                    request_id,
+                    varbinds,
+                }
+            } else {
+                Pdu::TrapV2 {
+                    request_id,
+                    varbinds,
+                }
+            })
+        }
+        tag => Err(DecodeError::UnsupportedPdu { tag }),
+    }
+}
+
+fn decode_address(bytes: &[u8]) -> Result<Ipv4Addr, DecodeError> {
+    <[u8; 4]>::try_from(bytes)
+        .map(Ipv4Addr::from)
+        .map_err(|_| DecodeError::InvalidAddress)
+}
+
+/// Encodes the response to a message, which is only sent for informs.
+pub(super) fn encode_response(message: &Message, engine: &Engine) -> Option<Vec<u8>> {
+    let (request_id, varbinds) = match &message.pdu {
+        Pdu::Inform {
+            request_id,
+            varbinds,
+        } => (*request_id, varbinds),
+        _ => return None,
+    };
+    let pdu = encode_pdu(RESPONSE, request_id, varbinds);
+
+    match &message.security {
+        Security::V1 { .. } => None,
+        Security::V2c { community } => Some(ber::encode_sequence(&[
+            ber::encode_integer(VERSION_2C),
+            ber::encode_octet_string(community),
+            pdu,
+        ])),
+        Security::V3 {
+            msg_id,
+            flags,
+            user,
+            context_engine_id,
+            context_name,
+            ..
+        } => {
+            let scoped = ber::encode_sequence(&[
+                ber::encode_octet_string(context_engine_id),
+                ber::encode_octet_string(context_name),
+                pdu,
+            ]);
+            encode_v3(engine, *msg_id, *flags, user, engine.user(user), scoped)
+        }
+    }
+}
+
+/// Encodes the report answering a discovery message, which tells the engine
+/// ID of the source.
+pub(super) fn encode_report(
+    engine: &Engine,
+    msg_id: i64,
+    request_id: i64,
+    user: &[u8],
+    unknown_engine_ids: u32,
+) -> Vec<u8> {
+    let varbinds = [VarBind {
+        // usmStatsUnknownEngineIDs.0
+        oid: Oid(vec![1, 3, 6, 1, 6, 3, 15, 1, 1, 4, 0]),
+        value: VarValue::Counter32(u64::from(unknown_engine_ids)),
+    }];
+    let scoped = ber::encode_sequence(&[
+        ber::encode_octet_string(&engine.id),
+        ber::encode_octet_string(&[]),
+        encode_pdu(REPORT, request_id, &varbinds),
+    ]);
+    encode_v3(engine, msg_id, 0, user, None, scoped)
+        .expect("unauthenticated messages can always be encoded")
+}
+
+fn encode_pdu(tag: u8, request_id: i64, varbinds: &[VarBind]) -> Vec<u8> {
+    let varbinds = varbinds.iter().map(VarBind::encode).collect::<Vec<_>>();
+    ber::encode(
+        tag,
+        &[
+            ber::encode_integer(request_id),
+            ber::encode_integer(0),
+            ber::encode_integer(0),
+            ber::encode_sequence(&varbinds),
+        ]
+        .concat(),
+    )
+}
+
+/// Encodes a SNMPv3 message from the source, which is authoritative for it,
+/// at the security level of the given flags.
+fn encode_v3(
+    engine: &Engine,
+    msg_id: i64,
+    flags: u8,
+    user_name: &[u8],
+    user: Option<&User>,
+    scoped: Vec<u8>,
+) -> Option<Vec<u8>> {
+    let (boots, time) = (engine.boots(), engine.time());
+    let auth = match user.and_then(User::auth) {
+        Some(auth) if flags & FLAG_AUTH != 0 => Some(auth),
+        _ => None,
+    };
+    let (scoped, priv_params) = match user.and_then(User::privacy) {
+        Some(privacy) if flags & FLAG_PRIV != 0 => {
+            let (encrypted, salt) = privacy.encrypt(&engine.id, boots, time, &scoped).ok()?;
+            (ber::encode_octet_string(&encrypted), salt)
+        }
+        _ => (scoped, Vec::new()),
+    };
+    let flags = flags & (FLAG_AUTH | FLAG_PRIV);
+
+    let usm = ber::encode_sequence(&[
+        ber::encode_octet_string(&engine.id),
+        ber::encode_integer(boots.into()),
+        ber::encode_integer(time.into()),
+        ber::encode_octet_string(user_name),
+        ber::encode_octet_string(&vec![0; auth.map_or(0, |auth| auth.mac_length())]),
+        ber::encode_octet_string(&priv_params),
+    ]);
+    let mut message = ber::encode_sequence(&[
+        ber::encode_integer(VERSION_3),
+        ber::encode_sequence(&[
+            ber::encode_integer(msg_id),
+            ber::encode_integer(MAX_MESSAGE_SIZE as i64),
+            ber::encode_octet_string(&[flags]),
+            ber::encode_integer(USER_BASED_SECURITY_MODEL),
+        ]),
+        ber::encode_octet_string(&usm),
+        scoped,
+    ]);
+
+    if let Some(auth) = auth {
+        let mac = auth.sign(&engine.id, &message).ok()?;
+        // The authentication parameters are found by decoding the message
+        // back.
+        let mut reader = Reader::new(&message).sequence().ok()?;
+        reader.integer().ok()?;
+        let start = Header::decode(&mut reader).ok()?.auth_params.offset();
+        message[start..start + mac.len()].copy_from_slice(&mac);
+    }
+    Some(message)
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+    use crate::sources::snmp_trap::usm::{
+        tests::user_config, AuthProtocol, PrivProtocol, UserConfig,
+    };
+
+    // sysUpTime.0 and snmpTrapOID.0
+    pub(crate) fn notification_varbinds(trap_oid: &str) -> Vec<VarBind> {
+        vec![
+            VarBind {
+                oid: "1.3.6.1.2.1.1.3.0".parse().unwrap(),
+                value: VarValue::TimeTicks(4200),
+            },
+            VarBind {
+                oid: "1.3.6.1.6.3.1.1.4.1.0".parse().unwrap(),
+                value: VarValue::Oid(trap_oid.parse().unwrap()),
+            },
+            VarBind {
+                oid: "1.3.6.1.4.1.8072.2.3.2.1".parse().unwrap(),
+                value: VarValue::OctetString(b"hello".to_vec()),
+            },
+        ]
+    }
+
+    /// Encodes a SNMPv2c notification, as sent by an agent.
+    pub(crate) fn v2c_message(community: &str, tag: u8, request_id: i64) -> Vec<u8> {
+        ber::encode_sequence(&[
+            ber::encode_integer(VERSION_2C),
+            ber::encode_octet_string(community.as_bytes()),
+            encode_pdu(
+                tag,
+                request_id,
+                &notification_varbinds("1.3.6.1.6.3.1.1.5.4"),
+            ),
+        ])
+    }
+
+    pub(crate) const fn trap_v2_tag() -> u8 {
+        TRAP_V2
+    }
+
+    pub(crate) const fn inform_tag() -> u8 {
+        INFORM
+    }
+
+    fn v1_message(community: &str) -> Vec<u8> {
+        ber::encode_sequence(&[
+            ber::encode_integer(VERSION_1),
+            ber::encode_octet_string(community.as_bytes()),
+            ber::encode(
+                TRAP_V1,
+                &[
+                    ber::encode_oid(&"1.3.6.1.4.1.8072".parse().unwrap()),
+                    ber::encode(ber::IP_ADDRESS, &[192, 0, 2, 1]),
+                    ber::encode_integer(6),
+                    ber::encode_integer(17),
+                    ber::encode_unsigned(ber::TIME_TICKS, 300),
+                    ber::encode_sequence(&[]),
+                ]
+                .concat(),
+            ),
+        ])
+    }
+
+    /// Encodes a SNMPv3 notification of an agent, whose engine is
+    /// authoritative for traps.
+    fn v3_message(sender: &Engine, tag: u8, flags: u8, user: &str) -> Vec<u8> {
+        let scoped = ber::encode_sequence(&[
+            ber::encode_octet_string(&sender.id),
+            ber::encode_octet_string(b""),
+            encode_pdu(tag, 7, &notification_varbinds("1.3.6.1.6.3.1.1.5.1")),
+        ]);
+        encode_v3(
+            sender,
+            42,
+            flags,
+            user.as_bytes(),
+            sender.user(user.as_bytes()),
+            scoped,
+        )
+        .unwrap()
+    }
+
+    fn users() -> Vec<UserConfig> {
+        vec![
+            user_config("none", None, None),
+            user_config("md5", Some(AuthProtocol::Md5), None),
+            user_config("sha_aes", Some(AuthProtocol::Sha), Some(PrivProtocol::Aes)),
+            user_config(
+                "sha512_aes",
+                Some(AuthProtocol::Sha512),
+                Some(PrivProtocol::Aes),
+            ),
+        ]
+    }
+
+    #[test]
+    fn decodes_v1_traps() {
+        let engine = Engine::new(b"receiver".to_vec(), &[]).unwrap();
+        let message = match decode(&v1_message("public"), &engine, None).unwrap() {
+            Incoming::Message(message) => message,
+            incoming => panic!("unexpected {:?}", incoming),
+        };
+
+        assert_eq!(
+            message,
+            Message {
+                security: Security::V1 {
+                    community: b"public".to_vec()
+                },
+                pdu: Pdu::TrapV1 {
+                    enterprise: "1.3.6.1.4.1.8072".parse().unwrap(),
+                    agent_address: Ipv4Addr::new(192, 0, 2, 1),
+                    generic_trap: 6,
+                    specific_trap: 17,
+                    timestamp: 300,
+                    varbinds: Vec::new(),
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn checks_communities() {
+        let engine = Engine::new(b"receiver".to_vec(), &[]).unwrap();
+        let communities = vec!["private".to_owned()];
+        let message = v2c_message("public", TRAP_V2, 1);
+
+        assert_eq!(
+            decode(&message, &engine, Some(communities.as_slice())),
+            Err(DecodeError::UnknownCommunity)
+        );
+        assert!(decode(&message, &engine, None).is_ok());
+    }
+
+    #[test]
+    fn responds_to_v2c_informs() {
+        let engine = Engine::new(b"receiver".to_vec(), &[]).unwrap();
+        let message = match decode(&v2c_message("public", INFORM, 99), &engine, None).unwrap() {
+            Incoming::Message(message) => message,
+            incoming => panic!("unexpected {:?}", incoming),
+        };
+
+        let response = encode_response(&message, &engine).unwrap();
+
+        let mut reader = Reader::new(&response).sequence().unwrap();
+        assert_eq!(reader.integer(), Ok(VERSION_2C));
+        assert_eq!(reader.octet_string(), Ok(&b"public"[..]));
+        let mut pdu = reader.expect(RESPONSE).unwrap();
+        assert_eq!(pdu.integer(), Ok(99));
+        assert_eq!(pdu.integer(), Ok(0));
+    }
+
+    #[test]
+    fn decodes_v3_traps_of_all_security_levels() {
+        let receiver = Engine::new(b"receiver".to_vec(), &users()).unwrap();
+        let sender = Engine::new(b"sender".to_vec(), &users()).unwrap();
+
+        for (user, flags) in &[
+            ("none", 0),
+            ("md5", FLAG_AUTH),
+            ("sha_aes", FLAG_AUTH | FLAG_PRIV),
+            ("sha512_aes", FLAG_AUTH | FLAG_PRIV),
+        ] {
+            let data = v3_message(&sender, TRAP_V2, *flags, user);
+            let message = match decode(&data, &receiver, None).unwrap() {
+                Incoming::Message(message) => message,
+                incoming => panic!("unexpected {:?}", incoming),
+            };
+
+            assert_eq!(
+                message.security,
+                Security::V3 {
+                    msg_id: 42,
+                    flags: *flags,
+                    user: user.as_bytes().to_vec(),
+                    engine_id: b"sender".to_vec(),
+                    context_engine_id: b"sender".to_vec(),
+                    context_name: Vec::new(),
+                },
+                "{}",
+                user
+            );
+            assert_eq!(
+                message.pdu.varbinds(),
+                notification_varbinds("1.3.6.1.6.3.1.1.5.1").as_slice()
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_v3_messages() {
+        let receiver = Engine::new(b"receiver".to_vec(), &users()).unwrap();
+        let sender = Engine::new(b"sender".to_vec(), &users()).unwrap();
+
+        let mut data = v3_message(&sender, TRAP_V2, FLAG_AUTH, "md5");
+        let last = data.len() - 1;
+        data[last] ^= 1;
+        assert_eq!(
+            decode(&data, &receiver, None),
+            Err(DecodeError::AuthenticationFailed {
+                user: "md5".to_owned()
+            })
+        );
+
+        let data = v3_message(&sender, TRAP_V2, FLAG_AUTH, "none");
+        assert_eq!(
+            decode(&data, &receiver, None),
+            Err(DecodeError::UnsupportedSecurityLevel {
+                user: "none".to_owned()
+            })
+        );
+
+        let other = Engine::new(b"sender".to_vec(), &[user_config("eve", None, None)]).unwrap();
+        let data = v3_message(&other, TRAP_V2, 0, "eve");
+        assert_eq!(
+            decode(&data, &receiver, None),
+            Err(DecodeError::UnknownUser {
+                user: "eve".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn answers_discovery_and_informs() {
+        let receiver = Engine::new(b"receiver".to_vec(), &users()).unwrap();
+        let sender = Engine::new(Vec::new(), &users()).unwrap();
+
+        let probe = v3_message(&sender, INFORM, 0, "");
+        let (msg_id, request_id, user) = match decode(&probe, &receiver, None).unwrap() {
+            Incoming::Discovery {
+                msg_id,
+                request_id,
+                user,
+            } => (msg_id, request_id, user),
+            incoming => panic!("unexpected {:?}", incoming),
+        };
+        assert_eq!((msg_id, request_id), (42, 7));
+        let report = encode_report(&receiver, msg_id, request_id, &user, 1);
+        let mut reader = Reader::new(&report).sequence().unwrap();
+        reader.integer().unwrap();
+        assert_eq!(Header::decode(&mut reader).unwrap().engine_id, b"receiver");
+        let mut scoped = reader.sequence().unwrap();
+        scoped.octet_string().unwrap();
+        scoped.octet_string().unwrap();
+        assert_eq!(scoped.expect(REPORT).unwrap().integer(), Ok(7));
+
+        // The inform is then sent to the discovered engine ID.
+        let sender = Engine::new(b"receiver".to_vec(), &users()).unwrap();
+        let inform = v3_message(&sender, INFORM, FLAG_AUTH | FLAG_PRIV, "sha512_aes");
+        let message = match decode(&inform, &receiver, None).unwrap() {
+            Incoming::Message(message) => message,
+            incoming => panic!("unexpected {:?}", incoming),
+        };
+        let response = encode_response(&message, &receiver).unwrap();
+        let response = match decode(&response, &sender, None) {
+            // Responses are not notifications, but are authenticated and
+            // decrypted before their PDU is decoded.
+            Err(DecodeError::UnsupportedPdu { tag }) => tag,
+            result => panic!("unexpected {:?}", result),
+        };
+        assert_eq!(response, RESPONSE);
+    }
+}
@@ -0,0 +1,424 @@
+//! Translation of object identifiers to names, with the definitions of MIB
+//! modules.
+//!
+//! Only the object identifiers are extracted from the modules, which are
+//! otherwise not validated.
+
+use super::ber::Oid;
+use snafu::{ResultExt, Snafu};
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// The macros whose values are object identifiers.
+const OID_MACROS: &[&str] = &[
+    "OBJECT-TYPE",
+    "MODULE-IDENTITY",
+    "OBJECT-IDENTITY",
+    "NOTIFICATION-TYPE",
+    "OBJECT-GROUP",
+    "NOTIFICATION-GROUP",
+    "MODULE-COMPLIANCE",
+    "AGENT-CAPABILITIES",
+];
+
+/// Definitions of the base modules, which are imported by most others and
+/// so are known even if their files are not loaded.
+const BUILTINS: &[(&str, &str, &str)] = &[
+    ("SNMPv2-SMI", "org", "1.3"),
+    ("SNMPv2-SMI", "dod", "1.3.6"),
+    ("SNMPv2-SMI", "internet", "1.3.6.1"),
+    ("SNMPv2-SMI", "directory", "1.3.6.1.1"),
+    ("SNMPv2-SMI", "mgmt", "1.3.6.1.2"),
+    ("SNMPv2-SMI", "mib-2", "1.3.6.1.2.1"),
+    ("SNMPv2-SMI", "transmission", "1.3.6.1.2.1.10"),
+    ("SNMPv2-SMI", "experimental", "1.3.6.1.3"),
+    ("SNMPv2-SMI", "private", "1.3.6.1.4"),
+    ("SNMPv2-SMI", "enterprises", "1.3.6.1.4.1"),
+    ("SNMPv2-SMI", "security", "1.3.6.1.5"),
+    ("SNMPv2-SMI", "snmpV2", "1.3.6.1.6"),
+    ("SNMPv2-SMI", "snmpDomains", "1.3.6.1.6.1"),
+    ("SNMPv2-SMI", "snmpProxys", "1.3.6.1.6.2"),
+    ("SNMPv2-SMI", "snmpModules", "1.3.6.1.6.3"),
+    ("SNMPv2-MIB", "system", "1.3.6.1.2.1.1"),
+    ("SNMPv2-MIB", "sysUpTime", "1.3.6.1.2.1.1.3"),
+    ("SNMPv2-MIB", "snmpTrapOID", "1.3.6.1.6.3.1.1.4.1"),
+    ("SNMPv2-MIB", "snmpTrapEnterprise", "1.3.6.1.6.3.1.1.4.3"),
+    ("SNMPv2-MIB", "snmpTraps", "1.3.6.1.6.3.1.1.5"),
+    ("SNMPv2-MIB", "coldStart", "1.3.6.1.6.3.1.1.5.1"),
+    ("SNMPv2-MIB", "warmStart", "1.3.6.1.6.3.1.1.5.2"),
+    ("IF-MIB", "linkDown", "1.3.6.1.6.3.1.1.5.3"),
+    ("IF-MIB", "linkUp", "1.3.6.1.6.3.1.1.5.4"),
+    ("SNMPv2-MIB", "authenticationFailure", "1.3.6.1.6.3.1.1.5.5"),
+];
+
+#[derive(Debug, Snafu)]
+pub(super) enum MibError {
+    #[snafu(display("could not read MIB {:?}: {}", path, source))]
+    Read { path: PathBuf, source: io::Error },
+}
+
+/// The names of object identifiers.
+#[derive(Debug)]
+pub(super) struct Mib {
+    names: HashMap<Oid, String>,
+}
+
+impl Mib {
+    /// Loads the modules of the given files, and of the files of the given
+    /// directories.
+    pub(super) fn load(paths: &[PathBuf]) -> Result<Self, MibError> {
+        let mut definitions = Vec::new();
+        for path in paths {
+            if path.is_dir() {
+                let entries = fs::read_dir(path).with_context(|| Read { path: path.clone() })?;
+                for entry in entries {
+                    let path = entry.with_context(|| Read { path: path.clone() })?.path();
+                    if path.is_file() {
+                        definitions.extend(read(&path)?);
+                    }
+                }
+            } else {
+                definitions.extend(read(path)?);
+            }
+        }
+        Ok(Self::resolve(definitions))
+    }
+
+    fn resolve(mut definitions: Vec<Definition>) -> Self {
+        let mut oids = ["ccitt", "iso", "joint-iso-ccitt"]
+            .iter()
+            .enumerate()
+            .map(|(arc, name)| (name.to_string(), Oid(vec![arc as u32])))
+            .collect::<HashMap<_, _>>();
+        let mut names = HashMap::new();
+        for (module, name, oid) in BUILTINS {
+            let oid = oid
+                .parse::<Oid>()
+                .expect("builtin object identifiers are valid");
+            oids.insert(name.to_string(), oid.clone());
+            names.insert(oid, format!("{}::{}", module, name));
+        }
+
+        // Definitions may refer to others defined later, or in modules
+        // loaded later, so they are resolved until no more can be.
+        loop {
+            let count = definitions.len();
+            definitions.retain(|definition| {
+                let oid = match &definition.parent {
+                    None => Oid(definition.arcs.clone()),
+                    Some(parent) => match oids.get(parent) {
+                        Some(parent) => Oid([parent.0.as_slice(), &definition.arcs].concat()),
+                        None => return true,
+                    },
+                };
+                oids.insert(definition.name.clone(), oid.clone());
+                names.insert(oid, format!("{}::{}", definition.module, definition.name));
+                false
+            });
+            if definitions.is_empty() || definitions.len() == count {
+                break;
+            }
+        }
+        for definition in definitions {
+            debug!(
+                message = "Ignoring MIB definition of unknown parent.",
+                module = %definition.module,
+                name = %definition.name,
+            );
+        }
+
+        Self { names }
+    }
+
+    /// Translates an object identifier to the name of its longest defined
+    /// prefix, followed by the remaining arcs, as in `IF-MIB::ifIndex.2`.
+    pub(super) fn translate(&self, oid: &Oid) -> Option<String> {
+        (1..=oid.0.len()).rev().find_map(|length| {
+            let name = self.names.get(&Oid(oid.0[..length].to_vec()))?;
+            let suffix = &oid.0[length..];
+            Some(if suffix.is_empty() {
+                name.clone()
+            } else {
+                format!("{}.{}", name, Oid(suffix.to_vec()))
+            })
+        })
+    }
+}
+
+/// An object identifier defined relative to its parent, or absolutely if it
+/// has none.
+#[derive(Debug, PartialEq)]
+struct Definition {
+    module: String,
+    name: String,
+    parent: Option<String>,
+    arcs: Vec<u32>,
+}
+
+fn read(path: &Path) -> Result<Vec<Definition>, MibError> {
+    let bytes = fs::read(path).with_context(|| Read {
+        path: path.to_owned(),
+    })?;
+    Ok(parse(&String::from_utf8_lossy(&bytes)))
+}
+
+fn parse(text: &str) -> Vec<Definition> {
+    let tokens = tokenize(text);
+    let token = |index: usize| tokens.get(index).map(String::as_str).unwrap_or("");
+
+    let mut definitions = Vec::new();
+    let mut module = String::new();
+    let mut index = 0;
+    while index < tokens.len() {
+        let current = token(index);
+        if token(index + 1) == "DEFINITIONS" {
+            module = current.to_owned();
+        } else if current == "MACRO" {
+            // The syntax of macros is not of interest.
+            while index < tokens.len() && token(index) != "END" {
+                index += 1;
+            }
+        } else if current.starts_with(|c: char| c.is_ascii_lowercase()) {
+            let kind = token(index + 1);
+            let value = if kind == "OBJECT"
+                && token(index + 2) == "IDENTIFIER"
+                && token(index + 3) == "::="
+            {
+                Some(index + 4)
+            } else if OID_MACROS.contains(&kind) || kind == "TRAP-TYPE" {
+                (index + 2..tokens.len())
+                    .find(|index| token(*index) == "::=")
+                    .map(|index| index + 1)
+            } else {
+                None
+            };
+
+            if let Some(value) = value {
+                let definition = if kind == "TRAP-TYPE" {
+                    // Traps of SNMPv1 are numbered within their enterprise.
+                    let enterprise = (index + 2..value)
+                        .find(|index| token(*index) == "ENTERPRISE")
+                        .map(|index| token(index + 1).to_owned());
+                    match (enterprise, token(value).parse()) {
+                        (Some(enterprise), Ok(number)) => Some((Some(enterprise), vec![0, number])),
+                        _ => None,
+                    }
+                } else {
+                    parse_oid(&tokens[value.min(tokens.len())..])
+                };
+                if let Some((parent, arcs)) = definition {
+                    definitions.push(Definition {
+                        module: module.clone(),
+                        name: current.to_owned(),
+                        parent,
+                        arcs,
+                    });
+                }
+                index = value;
+                continue;
+            }
+        }
+        index += 1;
+    }
+    definitions
+}
+
+/// Parses an object identifier value, such as `{ iso org(3) dod(6) 1 }`.
+fn parse_oid(tokens: &[String]) -> Option<(Option<String>, Vec<u32>)> {
+    let end = tokens.iter().position(|token| token == "}")?;
+    if tokens.first()? != "{" {
+        return None;
+    }
+
+    let mut parent = None;
+    let mut arcs = Vec::new();
+    let mut index = 1;
+    while index < end {
+        let token = &tokens[index];
+        if let Ok(arc) = token.parse() {
+            arcs.push(arc);
+        } else if tokens.get(index + 1).map(String::as_str) == Some("(") {
+            // Named arcs, such as `org(3)`, are given with their number.
+            arcs.push(tokens.get(index + 2)?.parse().ok()?);
+            index += 3;
+        } else if index == 1 {
+            parent = Some(token.clone());
+        } else {
+            return None;
+        }
+        index += 1;
+    }
+    Some((parent, arcs))
+}
+
+fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            // Comments end at the end of their line, or at the next `--`.
+            '-' if chars.peek() == Some(&'-') => {
+                chars.next();
+                while let Some(c) = chars.next() {
+                    if c == '\n' || (c == '-' && chars.next_if_eq(&'-').is_some()) {
+                        break;
+                    }
+                }
+            }
+            // Strings, such as descriptions, are not of interest.
+            '"' => {
+                chars.by_ref().find(|c| *c == '"');
+                tokens.push("\"\"".to_owned());
+            }
+            ':' if chars.peek() == Some(&':') => {
+                chars.next();
+                if chars.next_if_eq(&'=').is_some() {
+                    tokens.push("::=".to_owned());
+                } else {
+                    tokens.push("::".to_owned());
+                }
+            }
+            c if c.is_ascii_alphanumeric() => {
+                let mut token = c.to_string();
+                while let Some(c) =
+                    chars.next_if(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+                {
+                    token.push(c);
+                }
+                tokens.push(token);
+            }
+            c => tokens.push(c.to_string()),
+        }
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    const MIB: &str = r#"
+EXAMPLE-MIB DEFINITIONS ::= BEGIN
+
+IMPORTS
+    MODULE-IDENTITY, OBJECT-TYPE, NOTIFICATION-TYPE, enterprises
+        FROM SNMPv2-SMI
+    TRAP-TYPE
+        FROM RFC-1215;
+
+ExampleMacro MACRO ::=
+BEGIN
+    TYPE NOTATION ::= "EXAMPLE" -- value ::= { nothing 1 }
+END
+
+exampleMIB MODULE-IDENTITY
+    LAST-UPDATED "202101010000Z"
+    ORGANIZATION "Example ::= { organization 1 }"
+    DESCRIPTION  "An example module."
+    ::= { exampleRoot 1 }
+
+exampleNotifications OBJECT IDENTIFIER ::= { exampleMIB 0 }
+
+exampleRoot OBJECT IDENTIFIER ::= { enterprises 99999 } -- Defined later.
+
+exampleTable OBJECT-TYPE
+    SYNTAX      SEQUENCE OF ExampleEntry
+    MAX-ACCESS  not-accessible
+    STATUS      current
+    DESCRIPTION "A table."
+    ::= { exampleMIB 2 }
+
+exampleAlarm NOTIFICATION-TYPE
+    OBJECTS     { exampleTable }
+    STATUS      current
+    DESCRIPTION "An alarm."
+    ::= { exampleNotifications 1 }
+
+exampleLegacy TRAP-TYPE
+    ENTERPRISE  exampleRoot
+    VARIABLES   { exampleTable }
+    DESCRIPTION "A trap of SNMPv1."
+    ::= 3
+
+exampleAbsolute OBJECT IDENTIFIER ::= { iso(1) org(3) 42 }
+
+END
+"#;
+
+    #[test]
+    fn parses_definitions() {
+        let definitions = parse(MIB);
+
+        let names = definitions
+            .iter()
+            .map(|definition| definition.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec![
+                "exampleMIB",
+                "exampleNotifications",
+                "exampleRoot",
+                "exampleTable",
+                "exampleAlarm",
+                "exampleLegacy",
+                "exampleAbsolute",
+            ]
+        );
+        assert_eq!(
+            definitions[5],
+            Definition {
+                module: "EXAMPLE-MIB".to_owned(),
+                name: "exampleLegacy".to_owned(),
+                parent: Some("exampleRoot".to_owned()),
+                arcs: vec![0, 3],
+            }
+        );
+        assert_eq!(definitions[6].parent, None);
+        assert_eq!(definitions[6].arcs, vec![1, 3, 42]);
+    }
+
+    #[test]
+    fn translates_oids() {
+        let mib = Mib::resolve(parse(MIB));
+        let translate = |oid: &str| mib.translate(&oid.parse().unwrap());
+
+        assert_eq!(
+            translate("1.3.6.1.4.1.99999.1.0.1"),
+            Some("EXAMPLE-MIB::exampleAlarm".to_owned())
+        );
+        assert_eq!(
+            translate("1.3.6.1.4.1.99999.1.2.1.4"),
+            Some("EXAMPLE-MIB::exampleTable.1.4".to_owned())
+        );
+        assert_eq!(
+            translate("1.3.6.1.4.1.99999.0.3"),
+            Some("EXAMPLE-MIB::exampleLegacy".to_owned())
+        );
+        assert_eq!(
+            translate("1.3.6.1.2.1.1.3.0"),
+            Some("SNMPv2-MIB::sysUpTime.0".to_owned())
+        );
+        assert_eq!(translate("2.5"), None);
+    }
+
+    #[test]
+    fn loads_directories() {
+        let dir = temp_dir();
+        fs::create_dir(&dir).unwrap();
+        fs::write(dir.join("EXAMPLE-MIB.txt"), MIB).unwrap();
+
+        let mib = Mib::load(&[dir.clone()]).unwrap();
+        assert_eq!(
+            mib.translate(&"1.3.6.1.4.1.99999.1".parse().unwrap()),
+            Some("EXAMPLE-MIB::exampleMIB".to_owned())
+        );
+
+        assert!(Mib::load(&[dir.join("missing")]).is_err());
+    }
+}
@@ -0,0 +1,511 @@
+use crate::{
+    config::{
+        log_schema, DataType, GenerateConfig, Resource, SourceConfig, SourceContext,
+        SourceDescription,
+    },
+    event::{Event, LogEvent, Value},
+    internal_events::{
+        SnmpTrapDecodeFailed, SnmpTrapEventReceived, SnmpTrapReceiveFailed, SnmpTrapResponseFailed,
+    },
+    shutdown::ShutdownSignal,
+    sources::util::saturating_counter,
+    udp, Pipeline,
+};
+use ber::Oid;
+use bytes::Bytes;
+use chrono::Utc;
+use futures::SinkExt;
+use message::{Incoming, Message, Pdu, Security, VarBind, VarValue, MAX_MESSAGE_SIZE};
+use mib::{Mib, MibError};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{collections::BTreeMap, net::SocketAddr, path::PathBuf};
+use tokio::net::UdpSocket;
+use usm::{Engine, UserConfig, UserError};
+
+mod ber;
+mod message;
+mod mib;
+mod usm;
+
+/// The engine ID used by default, made of the text format of RFC 3411
+/// followed by `vector`.
+const DEFAULT_ENGINE_ID: &[u8] = b"\x80\x00\x00\x00\x04vector";
+
+// sysUpTime.0 and snmpTrapOID.0
+const SYS_UP_TIME: &[u32] = &[1, 3, 6, 1, 2, 1, 1, 3, 0];
+const SNMP_TRAP_OID: &[u32] = &[1, 3, 6, 1, 6, 3, 1, 1, 4, 1, 0];
+const SNMP_TRAPS: &[u32] = &[1, 3, 6, 1, 6, 3, 1, 1, 5];
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+struct SnmpTrapConfig {
+    address: SocketAddr,
+    communities: Option<Vec<String>>,
+    #[serde(default)]
+    mib_paths: Vec<PathBuf>,
+    #[serde(default)]
+    users: Vec<UserConfig>,
+    engine_id: Option<String>,
+    receive_buffer_bytes: Option<usize>,
+}
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Invalid user: {}", source))]
+    InvalidUser { source: UserError },
+    #[snafu(display("Failed to load MIBs: {}", source))]
+    LoadMibs { source: MibError },
+    #[snafu(display(
+        "Invalid engine_id {:?}, expected 5 to 32 bytes in hexadecimal",
+        engine_id
+    ))]
+    InvalidEngineId { engine_id: String },
+}
+
+inventory::submit! {
+    SourceDescription::new::<SnmpTrapConfig>("snmp_trap")
+}
+
+impl GenerateConfig for SnmpTrapConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(r#"address = "0.0.0.0:162""#).unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "snmp_trap")]
+impl SourceConfig for SnmpTrapConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let engine_id = match &self.engine_id {
+            Some(engine_id) => parse_hex(engine_id)
+                .filter(|id| (5..=32).contains(&id.len()))
+                .ok_or_else(|| BuildError::InvalidEngineId {
+                    engine_id: engine_id.clone(),
+                })?,
+            None => DEFAULT_ENGINE_ID.to_vec(),
+        };
+        let engine = Engine::new(engine_id, &self.users).context(InvalidUser)?;
+        let mib = if self.mib_paths.is_empty() {
+            None
+        } else {
+            Some(Mib::load(&self.mib_paths).context(LoadMibs)?)
+        };
+
+        Ok(Box::pin(snmp_trap(
+            self.clone(),
+            engine,
+            mib,
+            cx.shutdown,
+            cx.out,
+        )))
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn source_type(&self) -> &'static str {
+        "snmp_trap"
+    }
+
+    fn resources(&self) -> Vec<Resource> {
+        vec![Resource::udp(self.address)]
+    }
+}
+
+async fn snmp_trap(
+    config: SnmpTrapConfig,
+    engine: Engine,
+    mib: Option<Mib>,
+    mut shutdown: ShutdownSignal,
+    out: Pipeline,
+) -> Result<(), ()> {
+    let mut out = out.sink_map_err(|error| error!(message = "Error sending event.", %error));
+
+    let socket = UdpSocket::bind(&config.address).await.map_err(|error| {
+        error!(
+            message = "Failed to bind to UDP listener socket.",
+            address = %config.address,
+            %error
+        )
+    })?;
+    if let Some(receive_buffer_bytes) = config.receive_buffer_bytes {
+        if let Err(error) = udp::set_receive_buffer_size(&socket, receive_buffer_bytes) {
+            warn!(message = "Failed configuring receive buffer size on UDP socket.", %error);
+        }
+    }
+    info!(message = "Listening.", address = %config.address);
+
+    let mut unknown_engine_ids: u32 = 0;
+    let mut buf = vec![0; MAX_MESSAGE_SIZE];
+    loop {
+        let (size, peer) = tokio::select! {
+            _ = &mut shutdown => break,
+            result = socket.recv_from(&mut buf) => match result {
+                Ok(received) => received,
+                Err(error) => {
+                    emit!(SnmpTrapReceiveFailed { error });
+                    continue;
+                }
+            },
+        };
+
+        let communities = config.communities.as_deref();
+        let message = match message::decode(&buf[..size], &engine, communities) {
+            Ok(Incoming::Message(message)) => message,
+            Ok(Incoming::Discovery {
+                msg_id,
+                request_id,
+                user,
+            }) => {
+                unknown_engine_ids = unknown_engine_ids.wrapping_add(1);
+                let report =
+                    message::encode_report(&engine, msg_id, request_id, &user, unknown_engine_ids);
+                if let Err(error) = socket.send_to(&report, peer).await {
+                    emit!(SnmpTrapResponseFailed { peer, error });
+                }
+                continue;
+            }
+            Err(error) => {
+                emit!(SnmpTrapDecodeFailed { peer, error });
+                continue;
+            }
+        };
+
+        emit!(SnmpTrapEventReceived { byte_size: size });
+        let response = message::encode_response(&message, &engine);
+        out.send(Event::from(to_log(message, peer, mib.as_ref())))
+            .await?;
+
+        // Informs are acknowledged once their event is sent.
+        if let Some(response) = response {
+            if let Err(error) = socket.send_to(&response, peer).await {
+                emit!(SnmpTrapResponseFailed { peer, error });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn to_log(message: Message, peer: SocketAddr, mib: Option<&Mib>) -> LogEvent {
+    let mut log = LogEvent::default();
+    log.insert(log_schema().host_key(), peer.ip().to_string());
+    log.insert(log_schema().source_type_key(), Bytes::from("snmp_trap"));
+    log.insert(log_schema().timestamp_key(), Utc::now());
+
+    match message.security {
+        Security::V1 { community } => {
+            log.insert("version", "1");
+            log.insert(
+                "community",
+                String::from_utf8_lossy(&community).into_owned(),
+            );
+        }
+        Security::V2c { community } => {
+            log.insert("version", "2c");
+            log.insert(
+                "community",
+                String::from_utf8_lossy(&community).into_owned(),
+            );
+        }
+        Security::V3 {
+            user,
+            engine_id,
+            context_engine_id,
+            context_name,
+            ..
+        } => {
+            log.insert("version", "3");
+            log.insert("user", String::from_utf8_lossy(&user).into_owned());
+            log.insert("engine_id", format_hex(&engine_id));
+            log.insert("context_engine_id", format_hex(&context_engine_id));
+            log.insert(
+                "context_name",
+                String::from_utf8_lossy(&context_name).into_owned(),
+            );
+        }
+    }
+
+    let pdu_type = match message.pdu {
+        Pdu::Inform { .. } => "inform",
+        Pdu::TrapV1 { .. } | Pdu::TrapV2 { .. } => "trap",
+    };
+    log.insert("pdu_type", pdu_type);
+    let (uptime, trap_oid, varbinds) = match message.pdu {
+        Pdu::TrapV1 {
+            enterprise,
+            agent_address,
+            generic_trap,
+            specific_trap,
+            timestamp,
+            varbinds,
+        } => {
+            log.insert("enterprise", enterprise.to_string());
+            log.insert("agent_address", agent_address.to_string());
+            log.insert("generic_trap", generic_trap);
+            log.insert("specific_trap", specific_trap);
+            // Traps of SNMPv1 are mapped to notifications as in RFC 3584.
+            let trap_oid = match generic_trap {
+                6 => Some(Oid(
+                    [enterprise.0.as_slice(), &[0, specific_trap as u32]].concat()
+                )),
+                0..=5 => Some(Oid([SNMP_TRAPS, &[generic_trap as u32 + 1]].concat())),
+                _ => None,
+            };
+            (Some(timestamp), trap_oid, varbinds)
+        }
+        Pdu::TrapV2 {
+            request_id,
+            varbinds,
+        }
+        | Pdu::Inform {
+            request_id,
+            varbinds,
+        } => {
+            log.insert("request_id", request_id);
+            lift_notification(varbinds)
+        }
+    };
+    if let Some(uptime) = uptime {
+        log.insert("uptime", saturating_counter(uptime));
+    }
+    if let Some(trap_oid) = trap_oid {
+        log.insert("trap_oid", trap_oid.to_string());
+        if let Some(name) = mib.and_then(|mib| mib.translate(&trap_oid)) {
+            log.insert("trap_name", name);
+        }
+    }
+    let varbinds = varbinds
+        .into_iter()
+        .map(|varbind| varbind_to_value(varbind, mib))
+        .collect::<Vec<_>>();
+    log.insert("varbinds", varbinds);
+
+    log
+}
+
+/// Takes the uptime and trap OID out of the variable bindings of a
+/// SNMPv2 notification, which start with them.
+fn lift_notification(mut varbinds: Vec<VarBind>) -> (Option<u64>, Option<Oid>, Vec<VarBind>) {
+    let mut uptime = None;
+    let mut trap_oid = None;
+    varbinds.retain(|varbind| match (varbind.oid.0.as_slice(), &varbind.value) {
+        (SYS_UP_TIME, VarValue::TimeTicks(ticks)) => {
+            uptime = Some(*ticks);
+            false
+        }
+        (SNMP_TRAP_OID, VarValue::Oid(oid)) => {
+            trap_oid = Some(oid.clone());
+            false
+        }
+        _ => true,
+    });
+    (uptime, trap_oid, varbinds)
+}
+
+fn varbind_to_value(varbind: VarBind, mib: Option<&Mib>) -> Value {
+    let mut map = BTreeMap::new();
+    map.insert("oid".to_owned(), Value::from(varbind.oid.to_string()));
+    if let Some(name) = mib.and_then(|mib| mib.translate(&varbind.oid)) {
+        map.insert("name".to_owned(), Value::from(name));
+    }
+
+    let (value_type, value) = match varbind.value {
+        VarValue::Integer(value) => ("integer", Value::from(value)),
+        VarValue::OctetString(value) => ("octet_string", octet_string(&value)),
+        VarValue::Null => ("null", Value::Null),
+        VarValue::Oid(value) => {
+            if let Some(name) = mib.and_then(|mib| mib.translate(&value)) {
+                map.insert("value_name".to_owned(), Value::from(name));
+            }
+            ("oid", Value::from(value.to_string()))
+        }
+        VarValue::IpAddress(value) => ("ip_address", Value::from(value.to_string())),
+        VarValue::Counter32(value) => ("counter32", saturating_counter(value)),
+        VarValue::Gauge32(value) => ("gauge32", saturating_counter(value)),
+        VarValue::TimeTicks(value) => ("timeticks", saturating_counter(value)),
+        VarValue::Opaque(value) => ("opaque", Value::from(format_hex(&value))),
+        VarValue::Counter64(value) => ("counter64", saturating_counter(value)),
+        VarValue::NoSuchObject => ("no_such_object", Value::Null),
+        VarValue::NoSuchInstance => ("no_such_instance", Value::Null),
+        VarValue::EndOfMibView => ("end_of_mib_view", Value::Null),
+    };
+    map.insert("type".to_owned(), Value::from(value_type));
+    map.insert("value".to_owned(), value);
+    Value::from(map)
+}
+
+/// Converts an octet string to text if it is printable, as most are, and
+/// to hexadecimal otherwise.
+fn octet_string(bytes: &[u8]) -> Value {
+    match std::str::from_utf8(bytes) {
+        Ok(text) if !text.chars().any(|c| c.is_control() && !c.is_whitespace()) => {
+            Value::from(text)
+        }
+        _ => Value::from(format_hex(bytes)),
+    }
+}
+
+fn format_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn parse_hex(text: &str) -> Option<Vec<u8>> {
+    let text = text.trim_start_matches("0x");
+    if text.len() % 2 != 0 {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(text.get(index..index + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{collect_n, next_addr, trace_init};
+    use std::time::Duration;
+    use tokio::time;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<SnmpTrapConfig>();
+    }
+
+    #[test]
+    fn parses_engine_ids() {
+        assert_eq!(
+            parse_hex("0x800000000476"),
+            Some(vec![0x80, 0, 0, 0, 4, 0x76])
+        );
+        assert_eq!(parse_hex("80000"), None);
+        assert_eq!(parse_hex("80zz"), None);
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_configs() {
+        let config: SnmpTrapConfig = toml::from_str(
+            r#"
+            address = "127.0.0.1:0"
+            engine_id = "8000"
+            "#,
+        )
+        .unwrap();
+        let (tx, _rx) = Pipeline::new_test();
+        assert!(config.build(SourceContext::new_test(tx)).await.is_err());
+
+        let config: SnmpTrapConfig = toml::from_str(
+            r#"
+            address = "127.0.0.1:0"
+            [[users]]
+            name = "alice"
+            auth_protocol = "sha"
+            auth_password = "short"
+            "#,
+        )
+        .unwrap();
+        let (tx, _rx) = Pipeline::new_test();
+        assert!(config.build(SourceContext::new_test(tx)).await.is_err());
+    }
+
+    #[test]
+    fn converts_v1_traps() {
+        let message = Message {
+            security: Security::V1 {
+                community: b"public".to_vec(),
+            },
+            pdu: Pdu::TrapV1 {
+                enterprise: "1.3.6.1.4.1.8072".parse().unwrap(),
+                agent_address: [192, 0, 2, 1].into(),
+                generic_trap: 3,
+                specific_trap: 0,
+                timestamp: 300,
+                varbinds: vec![VarBind {
+                    oid: "1.3.6.1.2.1.2.2.1.1.2".parse().unwrap(),
+                    value: VarValue::Integer(2),
+                }],
+            },
+        };
+        let mib = Mib::load(&[]).unwrap();
+
+        let log = to_log(message, "192.0.2.1:162".parse().unwrap(), Some(&mib));
+
+        assert_eq!(log["version"], "1".into());
+        assert_eq!(log["community"], "public".into());
+        assert_eq!(log["agent_address"], "192.0.2.1".into());
+        assert_eq!(log["uptime"], 300.into());
+        assert_eq!(log["trap_oid"], "1.3.6.1.6.3.1.1.5.4".into());
+        assert_eq!(log["trap_name"], "IF-MIB::linkUp".into());
+        assert_eq!(log["varbinds[0].oid"], "1.3.6.1.2.1.2.2.1.1.2".into());
+        assert_eq!(log["varbinds[0].type"], "integer".into());
+        assert_eq!(log["varbinds[0].value"], 2.into());
+    }
+
+    #[test]
+    fn converts_octet_strings() {
+        assert_eq!(octet_string(b"eth0 is down\n"), "eth0 is down\n".into());
+        assert_eq!(octet_string(&[0, 0x1b, 0xff]), "001bff".into());
+    }
+
+    #[tokio::test]
+    async fn receives_traps_and_responds_to_informs() {
+        trace_init();
+
+        let address = next_addr();
+        let config: SnmpTrapConfig = toml::from_str(&format!(
+            r#"
+            address = "{}"
+            communities = ["public"]
+            "#,
+            address
+        ))
+        .unwrap();
+        let (tx, rx) = Pipeline::new_test();
+        tokio::spawn(config.build(SourceContext::new_test(tx)).await.unwrap());
+        // Give the source time to bind.
+        time::sleep(Duration::from_millis(100)).await;
+
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let trap_tag = message::tests::trap_v2_tag();
+        let inform_tag = message::tests::inform_tag();
+        for (community, tag, request_id) in &[
+            ("private", trap_tag, 1),
+            ("public", trap_tag, 2),
+            ("public", inform_tag, 3),
+        ] {
+            let data = message::tests::v2c_message(community, *tag, *request_id);
+            socket.send_to(&data, address).await.unwrap();
+        }
+
+        let mut buf = vec![0; MAX_MESSAGE_SIZE];
+        let size = time::timeout(Duration::from_secs(5), socket.recv(&mut buf))
+            .await
+            .unwrap()
+            .unwrap();
+        let mut response = ber::Reader::new(&buf[..size]).sequence().unwrap();
+        assert_eq!(response.integer(), Ok(1));
+        assert_eq!(response.octet_string(), Ok(&b"public"[..]));
+        let (_, mut pdu) = response.read().unwrap();
+        assert_eq!(pdu.integer(), Ok(3));
+
+        let events = collect_n(rx, 2).await;
+
+        let pdu_types = events
+            .iter()
+            .map(|event| event.as_log()["pdu_type"].to_string_lossy())
+            .collect::<Vec<_>>();
+        assert_eq!(pdu_types, vec!["trap", "inform"]);
+        let log = events[0].as_log();
+        assert_eq!(log["version"], "2c".into());
+        assert_eq!(log["request_id"], 2.into());
+        assert_eq!(log["uptime"], 4200.into());
+        assert_eq!(log["trap_oid"], "1.3.6.1.6.3.1.1.5.4".into());
+        assert_eq!(log[log_schema().host_key()], "127.0.0.1".into());
+        assert_eq!(log[log_schema().source_type_key()], "snmp_trap".into());
+        assert_eq!(log["varbinds[0].value"], "hello".into());
+        assert!(log.get("varbinds[1]").is_none());
+    }
+}
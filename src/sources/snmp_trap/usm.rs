@@ -0,0 +1,450 @@
+//! The User-based Security Model of SNMPv3, authenticating and decrypting
+//! messages with keys derived from the passwords of users.
+//!
+//! See <https://tools.ietf.org/html/rfc3414> and <https://tools.ietf.org/html/rfc7860>.
+
+use openssl::{
+    error::ErrorStack,
+    hash::{Hasher, MessageDigest},
+    memcmp,
+    pkey::PKey,
+    rand::rand_bytes,
+    sign::Signer,
+    symm::{Cipher, Crypter, Mode},
+};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{collections::HashMap, time::Instant};
+
+/// The number of bytes of password hashed to derive a key.
+const PASSWORD_KEY_LENGTH: usize = 1_048_576;
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub(super) enum AuthProtocol {
+    Md5,
+    Sha,
+    Sha224,
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl AuthProtocol {
+    fn digest(self) -> MessageDigest {
+        match self {
+            Self::Md5 => MessageDigest::md5(),
+            Self::Sha => MessageDigest::sha1(),
+            Self::Sha224 => MessageDigest::sha224(),
+            Self::Sha256 => MessageDigest::sha256(),
+            Self::Sha384 => MessageDigest::sha384(),
+            Self::Sha512 => MessageDigest::sha512(),
+        }
+    }
+
+    /// The length of the truncated HMAC sent in messages.
+    pub(super) const fn mac_length(self) -> usize {
+        match self {
+            Self::Md5 | Self::Sha => 12,
+            Self::Sha224 => 16,
+            Self::Sha256 => 24,
+            Self::Sha384 => 32,
+            Self::Sha512 => 48,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub(super) enum PrivProtocol {
+    Des,
+    Aes,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub(super) struct UserConfig {
+    name: String,
+    auth_protocol: Option<AuthProtocol>,
+    auth_password: Option<String>,
+    priv_protocol: Option<PrivProtocol>,
+    priv_password: Option<String>,
+}
+
+#[derive(Debug, Snafu)]
+pub(super) enum UserError {
+    #[snafu(display(
+        "user {:?} must set both auth_protocol and auth_password, or neither",
+        name
+    ))]
+    IncompleteAuth { name: String },
+    #[snafu(display(
+        "user {:?} must set both priv_protocol and priv_password, or neither",
+        name
+    ))]
+    IncompletePriv { name: String },
+    #[snafu(display("user {:?} needs authentication to use privacy", name))]
+    PrivWithoutAuth { name: String },
+    #[snafu(display("passwords of user {:?} must be at least 8 characters long", name))]
+    PasswordTooShort { name: String },
+    #[snafu(display("failed to derive keys of user {:?}: {}", name, source))]
+    DeriveKeys { name: String, source: ErrorStack },
+    #[snafu(display(
+        "privacy protocol of user {:?} is not supported by OpenSSL: {}",
+        name,
+        source
+    ))]
+    UnsupportedPrivProtocol { name: String, source: ErrorStack },
+}
+
+/// A user, with keys derived from their passwords which are yet to be
+/// localized to an engine.
+pub(super) struct User {
+    auth: Option<Auth>,
+    privacy: Option<Privacy>,
+}
+
+impl User {
+    fn from_config(config: &UserConfig) -> Result<Self, UserError> {
+        let name = &config.name;
+        let auth = match (config.auth_protocol, &config.auth_password) {
+            (Some(protocol), Some(password)) => Some((protocol, password)),
+            (None, None) => None,
+            _ => return Err(UserError::IncompleteAuth { name: name.clone() }),
+        };
+        let privacy = match (config.priv_protocol, &config.priv_password) {
+            (Some(protocol), Some(password)) => Some((protocol, password)),
+            (None, None) => None,
+            _ => return Err(UserError::IncompletePriv { name: name.clone() }),
+        };
+
+        let (auth_protocol, auth_password) = match auth {
+            Some(auth) => auth,
+            None if privacy.is_some() => {
+                return Err(UserError::PrivWithoutAuth { name: name.clone() })
+            }
+            None => {
+                return Ok(Self {
+                    auth: None,
+                    privacy: None,
+                })
+            }
+        };
+        let mut passwords =
+            std::iter::once(auth_password).chain(privacy.map(|(_, password)| password));
+        if passwords.any(|password| password.len() < 8) {
+            return Err(UserError::PasswordTooShort { name: name.clone() });
+        }
+        // DES is only available from the legacy provider of OpenSSL 3.
+        if let Some((PrivProtocol::Des, _)) = privacy {
+            Crypter::new(Cipher::des_cbc(), Mode::Decrypt, &[0; 8], Some(&[0; 8]))
+                .with_context(|| UnsupportedPrivProtocol { name: name.clone() })?;
+        }
+
+        // Both keys are derived with the hash function of the authentication
+        // protocol.
+        let digest = auth_protocol.digest();
+        let derive = |password: &str| {
+            password_to_key(digest, password.as_bytes())
+                .with_context(|| DeriveKeys { name: name.clone() })
+        };
+        Ok(Self {
+            auth: Some(Auth {
+                protocol: auth_protocol,
+                key: derive(auth_password)?,
+            }),
+            privacy: match privacy {
+                Some((protocol, password)) => Some(Privacy {
+                    protocol,
+                    digest,
+                    key: derive(password)?,
+                }),
+                None => None,
+            },
+        })
+    }
+
+    pub(super) const fn auth(&self) -> Option<&Auth> {
+        self.auth.as_ref()
+    }
+
+    pub(super) const fn privacy(&self) -> Option<&Privacy> {
+        self.privacy.as_ref()
+    }
+}
+
+pub(super) struct Auth {
+    protocol: AuthProtocol,
+    key: Vec<u8>,
+}
+
+impl Auth {
+    pub(super) const fn mac_length(&self) -> usize {
+        self.protocol.mac_length()
+    }
+
+    /// Computes the truncated HMAC of a message, whose authentication
+    /// parameters are zeroed.
+    pub(super) fn sign(&self, engine_id: &[u8], message: &[u8]) -> Result<Vec<u8>, ErrorStack> {
+        let digest = self.protocol.digest();
+        let key = PKey::hmac(&localize_key(digest, &self.key, engine_id)?)?;
+        let mut signer = Signer::new(digest, &key)?;
+        signer.update(message)?;
+        let mut mac = signer.sign_to_vec()?;
+        mac.truncate(self.mac_length());
+        Ok(mac)
+    }
+
+    pub(super) fn verify(
+        &self,
+        engine_id: &[u8],
+        message: &[u8],
+        mac: &[u8],
+    ) -> Result<bool, ErrorStack> {
+        let expected = self.sign(engine_id, message)?;
+        Ok(expected.len() == mac.len() && memcmp::eq(&expected, mac))
+    }
+}
+
+pub(super) struct Privacy {
+    protocol: PrivProtocol,
+    /// The hash function of the authentication protocol, used to localize
+    /// the key.
+    digest: MessageDigest,
+    key: Vec<u8>,
+}
+
+impl Privacy {
+    pub(super) fn decrypt(
+        &self,
+        engine_id: &[u8],
+        boots: u32,
+        time: u32,
+        salt: &[u8],
+        data: &[u8],
+    ) -> Result<Vec<u8>, ErrorStack> {
+        let (cipher, key, iv) = self.cipher(engine_id, boots, time, salt)?;
+        crypt(cipher, Mode::Decrypt, &key, &iv, data)
+    }
+
+    /// Encrypts data, and returns it along with the salt it was encrypted
+    /// with.
+    pub(super) fn encrypt(
+        &self,
+        engine_id: &[u8],
+        boots: u32,
+        time: u32,
+        data: &[u8],
+    ) -> Result<(Vec<u8>, Vec<u8>), ErrorStack> {
+        let mut salt = vec![0; 8];
+        rand_bytes(&mut salt)?;
+        let mut data = data.to_vec();
+        if self.protocol == PrivProtocol::Des {
+            // The salt of DES starts with the boots of the engine.
+            salt[..4].copy_from_slice(&boots.to_be_bytes());
+            // DES only encrypts whole blocks.
+            data.resize((data.len() + 7) / 8 * 8, 0);
+        }
+        let (cipher, key, iv) = self.cipher(engine_id, boots, time, &salt)?;
+        Ok((crypt(cipher, Mode::Encrypt, &key, &iv, &data)?, salt))
+    }
+
+    fn cipher(
+        &self,
+        engine_id: &[u8],
+        boots: u32,
+        time: u32,
+        salt: &[u8],
+    ) -> Result<(Cipher, Vec<u8>, Vec<u8>), ErrorStack> {
+        let key = localize_key(self.digest, &self.key, engine_id)?;
+        Ok(match self.protocol {
+            PrivProtocol::Des => {
+                let iv = key[8..16]
+                    .iter()
+                    .zip(salt)
+                    .map(|(pre_iv, salt)| pre_iv ^ salt)
+                    .collect();
+                (Cipher::des_cbc(), key[..8].to_vec(), iv)
+            }
+            PrivProtocol::Aes => {
+                let mut iv = Vec::with_capacity(16);
+                iv.extend_from_slice(&boots.to_be_bytes());
+                iv.extend_from_slice(&time.to_be_bytes());
+                iv.extend_from_slice(salt);
+                (Cipher::aes_128_cfb128(), key[..16].to_vec(), iv)
+            }
+        })
+    }
+}
+
+/// The local SNMP engine, which is authoritative for the informs it
+/// receives, and its users.
+pub(super) struct Engine {
+    pub(super) id: Vec<u8>,
+    users: HashMap<Vec<u8>, User>,
+    start: Instant,
+}
+
+impl Engine {
+    pub(super) fn new(id: Vec<u8>, users: &[UserConfig]) -> Result<Self, UserError> {
+        let users = users
+            .iter()
+            .map(|config| Ok((config.name.as_bytes().to_vec(), User::from_config(config)?)))
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            id,
+            users,
+            start: Instant::now(),
+        })
+    }
+
+    pub(super) fn user(&self, name: &[u8]) -> Option<&User> {
+        self.users.get(name)
+    }
+
+    /// The engine does not persist its boots, so it always boots once.
+    pub(super) const fn boots(&self) -> u32 {
+        1
+    }
+
+    pub(super) fn time(&self) -> u32 {
+        self.start.elapsed().as_secs() as u32
+    }
+}
+
+fn password_to_key(digest: MessageDigest, password: &[u8]) -> Result<Vec<u8>, ErrorStack> {
+    let mut hasher = Hasher::new(digest)?;
+    let mut repeated = password.iter().cycle();
+    let mut buf = [0; 64];
+    for _ in 0..PASSWORD_KEY_LENGTH / buf.len() {
+        for byte in buf.iter_mut() {
+            *byte = *repeated.next().unwrap();
+        }
+        hasher.update(&buf)?;
+    }
+    Ok(hasher.finish()?.to_vec())
+}
+
+fn localize_key(
+    digest: MessageDigest,
+    key: &[u8],
+    engine_id: &[u8],
+) -> Result<Vec<u8>, ErrorStack> {
+    let mut hasher = Hasher::new(digest)?;
+    hasher.update(key)?;
+    hasher.update(engine_id)?;
+    hasher.update(key)?;
+    Ok(hasher.finish()?.to_vec())
+}
+
+fn crypt(
+    cipher: Cipher,
+    mode: Mode,
+    key: &[u8],
+    iv: &[u8],
+    data: &[u8],
+) -> Result<Vec<u8>, ErrorStack> {
+    let mut crypter = Crypter::new(cipher, mode, key, Some(iv))?;
+    crypter.pad(false);
+    let mut output = vec![0; data.len() + cipher.block_size()];
+    let mut length = crypter.update(data, &mut output)?;
+    length += crypter.finalize(&mut output[length..])?;
+    output.truncate(length);
+    Ok(output)
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+
+    pub(crate) fn user_config(
+        name: &str,
+        auth: Option<AuthProtocol>,
+        privacy: Option<PrivProtocol>,
+    ) -> UserConfig {
+        UserConfig {
+            name: name.to_owned(),
+            auth_protocol: auth,
+            auth_password: auth.map(|_| "authpassword".to_owned()),
+            priv_protocol: privacy,
+            priv_password: privacy.map(|_| "privpassword".to_owned()),
+        }
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    #[test]
+    fn derives_keys_of_rfc3414() {
+        let engine_id = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2];
+
+        let key = password_to_key(MessageDigest::md5(), b"maplesyrup").unwrap();
+        assert_eq!(hex(&key), "9faf3283884e92834ebc9847d8edd963");
+        let key = localize_key(MessageDigest::md5(), &key, &engine_id).unwrap();
+        assert_eq!(hex(&key), "526f5eed9fcce26f8964c2930787d82b");
+
+        let key = password_to_key(MessageDigest::sha1(), b"maplesyrup").unwrap();
+        assert_eq!(hex(&key), "9fb5cc0381497b3793528939ff788d5d79145211");
+        let key = localize_key(MessageDigest::sha1(), &key, &engine_id).unwrap();
+        assert_eq!(hex(&key), "6695febc9288e36282235fc7151f128497b38f3f");
+    }
+
+    #[test]
+    fn signs_and_verifies() {
+        let config = user_config("alice", Some(AuthProtocol::Sha256), None);
+        let user = User::from_config(&config).unwrap();
+
+        let auth = user.auth().unwrap();
+
+        let mac = auth.sign(b"engine", b"message").unwrap();
+
+        assert_eq!(mac.len(), 24);
+        assert!(auth.verify(b"engine", b"message", &mac).unwrap());
+        assert!(!auth.verify(b"other", b"message", &mac).unwrap());
+        assert!(!auth.verify(b"engine", b"message", &mac[..12]).unwrap());
+    }
+
+    #[test]
+    fn encrypts_and_decrypts() {
+        for privacy in &[PrivProtocol::Des, PrivProtocol::Aes] {
+            let config = user_config("alice", Some(AuthProtocol::Sha), Some(*privacy));
+            let user = match User::from_config(&config) {
+                Err(UserError::UnsupportedPrivProtocol { .. }) if *privacy == PrivProtocol::Des => {
+                    continue
+                }
+                user => user.unwrap(),
+            };
+            let privacy = user.privacy().unwrap();
+
+            let (encrypted, salt) = privacy.encrypt(b"engine", 3, 1000, b"scoped pdu").unwrap();
+            assert_ne!(&encrypted[..10], b"scoped pdu");
+            let decrypted = privacy
+                .decrypt(b"engine", 3, 1000, &salt, &encrypted)
+                .unwrap();
+            assert_eq!(&decrypted[..10], b"scoped pdu");
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_users() {
+        let mut config = user_config("alice", None, Some(PrivProtocol::Aes));
+        assert!(matches!(
+            User::from_config(&config),
+            Err(UserError::PrivWithoutAuth { .. })
+        ));
+
+        config = user_config("alice", Some(AuthProtocol::Md5), None);
+        config.auth_password = Some("short".to_owned());
+        assert!(matches!(
+            User::from_config(&config),
+            Err(UserError::PasswordTooShort { .. })
+        ));
+
+        config.auth_password = None;
+        assert!(matches!(
+            User::from_config(&config),
+            Err(UserError::IncompleteAuth { .. })
+        ));
+    }
+}
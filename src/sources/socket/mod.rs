@@ -5,15 +5,16 @@ mod unix;
 
 use super::util::TcpSource;
 use crate::{
-    codecs::{BytesParser, Decoder, NewlineDelimitedCodec},
+    codecs::{BytesParser, BytesParserConfig, Decoder, NewlineDelimitedCodec, ParserConfig},
     config::{
-        log_schema, DataType, GenerateConfig, Resource, SourceConfig, SourceContext,
+        log_schema, schema, DataType, GenerateConfig, Resource, SourceConfig, SourceContext,
         SourceDescription,
     },
     tls::MaybeTlsSettings,
 };
 use serde::{Deserialize, Serialize};
 use std::net::SocketAddr;
+use vrl::prelude::TypeDef;
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 // TODO: add back when https://github.com/serde-rs/serde/issues/1358 is addressed
@@ -161,6 +162,24 @@ impl SourceConfig for SocketConfig {
         DataType::Log
     }
 
+    fn output_schema(&self) -> TypeDef {
+        let host_key = match &self.mode {
+            Mode::Tcp(config) => config.host_key().clone(),
+            Mode::Udp(config) => config.host_key().clone(),
+            #[cfg(unix)]
+            Mode::UnixDatagram(config) | Mode::UnixStream(config) => config.host_key.clone(),
+        }
+        .unwrap_or_else(|| log_schema().host_key().to_string());
+
+        schema::with_fields(
+            BytesParserConfig::new().schema(),
+            vec![
+                (host_key.as_str(), TypeDef::new().bytes()),
+                (log_schema().source_type_key(), TypeDef::new().bytes()),
+            ],
+        )
+    }
+
     fn source_type(&self) -> &'static str {
         "socket"
     }
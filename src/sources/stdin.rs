@@ -1,6 +1,8 @@
 use crate::{
     codecs::DecodingConfig,
-    config::{log_schema, DataType, Resource, SourceConfig, SourceContext, SourceDescription},
+    config::{
+        log_schema, schema, DataType, Resource, SourceConfig, SourceContext, SourceDescription,
+    },
     internal_events::StdinEventsReceived,
     shutdown::ShutdownSignal,
     sources::util::TcpError,
@@ -12,6 +14,7 @@ use futures::{channel::mpsc, executor, SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
 use std::{io, thread};
 use tokio_util::{codec::FramedRead, io::StreamReader};
+use vrl::prelude::TypeDef;
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(deny_unknown_fields, default)]
@@ -55,10 +58,29 @@ impl SourceConfig for StdinConfig {
         DataType::Log
     }
 
+    fn output_schema(&self) -> TypeDef {
+        let host_key = self
+            .host_key
+            .as_deref()
+            .unwrap_or_else(|| log_schema().host_key());
+
+        schema::with_fields(
+            self.decoding.schema(),
+            vec![
+                (host_key, TypeDef::new().bytes()),
+                (log_schema().source_type_key(), TypeDef::new().bytes()),
+            ],
+        )
+    }
+
     fn source_type(&self) -> &'static str {
         "stdin"
     }
 
+    fn decoding(&self) -> Option<&DecodingConfig> {
+        Some(&self.decoding)
+    }
+
     fn resources(&self) -> Vec<Resource> {
         vec![Resource::Stdin]
     }
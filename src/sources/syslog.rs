@@ -3,7 +3,7 @@ use crate::sources::util::build_unix_stream_source;
 use crate::{
     codecs::{self, BytesCodec, OctetCountingCodec, SyslogParser},
     config::{
-        log_schema, DataType, GenerateConfig, Resource, SourceConfig, SourceContext,
+        log_schema, schema, DataType, GenerateConfig, Resource, SourceConfig, SourceContext,
         SourceDescription,
     },
     event::Event,
@@ -27,6 +27,7 @@ use std::net::SocketAddr;
 use std::path::PathBuf;
 use tokio::net::UdpSocket;
 use tokio_util::udp::UdpFramed;
+use vrl::prelude::TypeDef;
 
 #[derive(Deserialize, Serialize, Debug)]
 // TODO: add back when serde-rs/serde#1358 is addressed
@@ -154,6 +155,30 @@ impl SourceConfig for SyslogConfig {
         DataType::Log
     }
 
+    fn output_schema(&self) -> TypeDef {
+        let host_key = self
+            .host_key
+            .as_deref()
+            .unwrap_or_else(|| log_schema().host_key());
+
+        // The structured data of messages is added as fields named after its
+        // elements.
+        schema::open_log_fields(vec![
+            (log_schema().message_key(), TypeDef::new().bytes()),
+            (log_schema().timestamp_key(), TypeDef::new().timestamp()),
+            (log_schema().source_type_key(), TypeDef::new().bytes()),
+            (host_key, TypeDef::new().bytes()),
+            ("source_ip", TypeDef::new().bytes()),
+            ("hostname", TypeDef::new().bytes()),
+            ("severity", TypeDef::new().bytes()),
+            ("facility", TypeDef::new().bytes()),
+            ("version", TypeDef::new().integer()),
+            ("appname", TypeDef::new().bytes()),
+            ("msgid", TypeDef::new().bytes()),
+            ("procid", TypeDef::new().integer().add_bytes()),
+        ])
+    }
+
     fn source_type(&self) -> &'static str {
         "syslog"
     }
@@ -1,11 +1,19 @@
 mod encoding_config;
-#[cfg(any(feature = "sources-file", feature = "sources-kafka"))]
+#[cfg(any(
+    feature = "sources-aws_kinesis_streams",
+    feature = "sources-file",
+    feature = "sources-kafka",
+    feature = "sources-mqtt",
+    feature = "sources-redis"
+))]
 pub mod finalizer;
 #[cfg(all(unix, feature = "sources-dnstap"))]
 pub mod framestream;
 #[cfg(feature = "sources-utils-http")]
 mod http;
 pub mod multiline_config;
+#[cfg(any(feature = "sources-netflow", feature = "sources-snmp_trap"))]
+mod saturating_counter;
 #[cfg(all(feature = "sources-utils-tls", feature = "listenfd"))]
 mod tcp;
 #[cfg(any(
@@ -26,6 +34,8 @@ pub use self::http::decode;
 pub use self::http::{ErrorMessage, HttpSource, HttpSourceAuthConfig};
 pub use encoding_config::EncodingConfig;
 pub use multiline_config::MultilineConfig;
+#[cfg(any(feature = "sources-netflow", feature = "sources-snmp_trap"))]
+pub use saturating_counter::saturating_counter;
 #[cfg(all(feature = "sources-utils-tls", feature = "listenfd"))]
 pub use tcp::{SocketListenAddr, TcpSource};
 #[cfg(any(
@@ -0,0 +1,24 @@
+use crate::{event::Value, internal_events::CounterSaturated};
+use std::convert::TryFrom;
+
+/// Converts an unsigned counter, which may not fit in the signed integers of
+/// events, saturating it at the largest one.
+pub fn saturating_counter(value: u64) -> Value {
+    let value = i64::try_from(value).unwrap_or_else(|_| {
+        emit!(CounterSaturated { value });
+        i64::MAX
+    });
+    Value::from(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saturates_counters() {
+        assert_eq!(saturating_counter(42), Value::from(42));
+        assert_eq!(saturating_counter(i64::MAX as u64), Value::from(i64::MAX));
+        assert_eq!(saturating_counter(u64::MAX), Value::from(i64::MAX));
+    }
+}
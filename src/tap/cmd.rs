@@ -1,7 +1,70 @@
-use crate::config;
+use crate::config::{self, CapturedEvent, CapturedEventData, ComponentKey};
+use std::{collections::HashSet, fs::File, io::Write};
 use tokio_stream::StreamExt;
 use url::Url;
-use vector_api_client::{connect_subscription_client, gql::TapSubscriptionExt, Client};
+use vector_api_client::{
+    connect_subscription_client,
+    gql::{
+        output_events_subscription::{EventDirection, OutputEventsSubscriptionOutputEvents},
+        TapEncodingFormat, TapFilter, TapSubscriptionExt,
+    },
+    Client,
+};
+
+/// A tapped log or metric event.
+struct TappedEvent<'a> {
+    component_id: &'a str,
+    string: &'a str,
+    is_metric: bool,
+    is_input: bool,
+    correlation_id: Option<&'a str>,
+    /// The fields that differ from the input the event was emitted for, as paths with the
+    /// input and output values.
+    diff: Vec<(&'a str, Option<&'a str>, Option<&'a str>)>,
+}
+
+impl<'a> TappedEvent<'a> {
+    fn new(event: &'a OutputEventsSubscriptionOutputEvents) -> Option<Self> {
+        macro_rules! tapped {
+            ($ev:expr, $is_metric:expr) => {
+                Self {
+                    component_id: &$ev.component_id,
+                    string: &$ev.string,
+                    is_metric: $is_metric,
+                    is_input: matches!($ev.direction, EventDirection::INPUT),
+                    correlation_id: $ev.correlation_id.as_deref(),
+                    diff: $ev
+                        .diff
+                        .iter()
+                        .flatten()
+                        .map(|diff| {
+                            (
+                                diff.path.as_str(),
+                                diff.input.as_deref(),
+                                diff.output.as_deref(),
+                            )
+                        })
+                        .collect(),
+                }
+            };
+        }
+
+        match event {
+            OutputEventsSubscriptionOutputEvents::Log(ev) => Some(tapped!(ev, false)),
+            OutputEventsSubscriptionOutputEvents::Metric(ev) => Some(tapped!(ev, true)),
+            _ => None,
+        }
+    }
+
+    /// Whether this is the input `output` was emitted for.
+    fn is_input_of(&self, output: &Self) -> bool {
+        self.is_input
+            && !output.is_input
+            && self.component_id == output.component_id
+            && self.correlation_id.is_some()
+            && self.correlation_id == output.correlation_id
+    }
+}
 
 /// CLI command func for issuing 'tap' queries, and communicating with a local/remote
 /// Vector API server via HTTP/WebSockets.
@@ -28,6 +91,13 @@ pub async fn cmd(opts: &super::Opts) -> exitcode::ExitCode {
     })
     .expect("Couldn't build WebSocket URL. Please report.");
 
+    // Observing inputs is for comparing them with outputs, which needs a human-readable
+    // format.
+    if opts.inputs && opts.output.is_some() {
+        eprintln!("Inputs can't be recorded; `--inputs` and `--output` are exclusive.");
+        return exitcode::USAGE;
+    }
+
     let subscription_client = match connect_subscription_client(url).await {
         Ok(c) => c,
         Err(e) => {
@@ -36,29 +106,182 @@ pub async fn cmd(opts: &super::Opts) -> exitcode::ExitCode {
         }
     };
 
+    // Captures need the events in their native format, regardless of how they would be
+    // printed.
+    let mut output = match &opts.output {
+        Some(path) => match File::create(path) {
+            Ok(file) => Some(file),
+            Err(error) => {
+                eprintln!("Couldn't create {:?}: {}", path, error);
+                return exitcode::CANTCREAT;
+            }
+        },
+        None => None,
+    };
+    let format = if output.is_some() {
+        TapEncodingFormat::Json
+    } else {
+        opts.format
+    };
+
     // Issue the 'tap' request, printing to stdout.
+    let inputs_of = if opts.inputs {
+        opts.components.clone()
+    } else {
+        vec![]
+    };
     let res = subscription_client.output_events_subscription(
         opts.components.clone(),
-        opts.format,
+        inputs_of,
+        format,
         opts.limit as i64,
         opts.interval as i64,
+        TapFilter {
+            expression: opts.filter.clone(),
+            language: opts.filter_language,
+            sample_rate: opts.sample_rate as i64,
+        },
     );
 
     tokio::pin! {
         let stream = res.stream();
     };
 
-    // Loop over the returned results, printing out log events.
+    // Loop over the returned results, printing out log and metric events.
     // NOTE: This will currently ignore notifications. A later `--verbose` option is planned
     // to include these.
     // TODO: https://github.com/timberio/vector/issues/6870
     while let Some(Some(res)) = stream.next().await {
+        // Errors, such as an invalid filter, end the subscription.
+        if let Some(errors) = res.errors {
+            for error in errors {
+                eprintln!("{}", error.message);
+            }
+            return exitcode::USAGE;
+        }
+
         if let Some(d) = res.data {
-            for log_event in d.output_events.iter().filter_map(|ev| ev.as_log()) {
-                println!("{}", log_event.string);
+            let events = d
+                .output_events
+                .iter()
+                .filter_map(TappedEvent::new)
+                .collect::<Vec<_>>();
+
+            match &mut output {
+                Some(file) => {
+                    for event in events {
+                        if let Err(error) =
+                            capture(file, event.component_id, event.string, event.is_metric)
+                        {
+                            eprintln!("Couldn't record event: {}", error);
+                            return exitcode::IOERR;
+                        }
+                    }
+                }
+                None if opts.inputs => print_with_inputs(&events),
+                None => {
+                    for event in events {
+                        println!("{}", event.string);
+                    }
+                }
             }
         }
     }
 
     exitcode::OK
 }
+
+/// Appends a tapped log or metric event, encoded as JSON, to a capture file.
+fn capture(file: &mut File, component_id: &str, event: &str, is_metric: bool) -> crate::Result<()> {
+    let event = if is_metric {
+        CapturedEventData::Metric(serde_json::from_str(event)?)
+    } else {
+        CapturedEventData::Log(serde_json::from_str(event)?)
+    };
+    let captured = CapturedEvent {
+        component_id: ComponentKey::from(component_id),
+        event,
+    };
+    serde_json::to_writer(&mut *file, &captured)?;
+    writeln!(file)?;
+    Ok(())
+}
+
+/// Prints the outputs of components side by side with the inputs they were emitted for,
+/// followed by the fields that differ. Events without a counterpart in the same batch are
+/// printed on their own.
+fn print_with_inputs(events: &[TappedEvent]) {
+    // Inputs printed along with their outputs.
+    let paired = events
+        .iter()
+        .enumerate()
+        .filter(|(_, input)| events.iter().any(|output| input.is_input_of(output)))
+        .map(|(index, _)| index)
+        .collect::<HashSet<_>>();
+
+    for (index, event) in events.iter().enumerate() {
+        if paired.contains(&index) {
+            continue;
+        }
+
+        let direction = if event.is_input { "input" } else { "output" };
+        let input = events.iter().find(|input| input.is_input_of(event));
+        match input {
+            Some(input) => {
+                println!("{} (input -> output)", event.component_id);
+                println!(
+                    "{}",
+                    side_by_side(&pretty(input.string), &pretty(event.string))
+                );
+                for (path, input, output) in &event.diff {
+                    match (input, output) {
+                        (Some(input), Some(output)) => {
+                            println!("  ~ {}: {} -> {}", path, input, output)
+                        }
+                        (Some(input), None) => println!("  - {}: {}", path, input),
+                        (None, Some(output)) => println!("  + {}: {}", path, output),
+                        (None, None) => {}
+                    }
+                }
+            }
+            None => {
+                println!("{} ({})", event.component_id, direction);
+                println!("{}", pretty(event.string));
+            }
+        }
+        println!();
+    }
+}
+
+/// Pretty-prints JSON events, leaving other encodings as they are.
+fn pretty(event: &str) -> String {
+    serde_json::from_str::<serde_json::Value>(event)
+        .ok()
+        .and_then(|value| serde_json::to_string_pretty(&value).ok())
+        .unwrap_or_else(|| event.trim_end().to_owned())
+}
+
+/// Lays out two blocks of text in columns.
+fn side_by_side(left: &str, right: &str) -> String {
+    let width = left
+        .lines()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0);
+    let mut left = left.lines();
+    let mut right = right.lines();
+
+    let mut rows = vec![];
+    loop {
+        match (left.next(), right.next()) {
+            (None, None) => break,
+            (l, r) => rows.push(format!(
+                "{:width$} | {}",
+                l.unwrap_or(""),
+                r.unwrap_or(""),
+                width = width
+            )),
+        }
+    }
+    rows.join("\n")
+}
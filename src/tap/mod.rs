@@ -1,8 +1,9 @@
 mod cmd;
 
+use std::path::PathBuf;
 use structopt::StructOpt;
 use url::Url;
-use vector_api_client::gql::TapEncodingFormat;
+use vector_api_client::gql::{TapEncodingFormat, TapFilterLanguage};
 
 pub use cmd::cmd;
 
@@ -17,14 +18,37 @@ pub struct Opts {
     #[structopt(short, long)]
     url: Option<Url>,
 
-    /// Sample log events to the provided limit
+    /// Sample events to the provided limit
     #[structopt(default_value = "100", short = "l", long)]
     limit: u32,
 
-    /// Encoding format for logs printed to screen
+    /// Encoding format for events printed to screen
     #[structopt(default_value = "json", possible_values = &["json", "yaml"], short = "f", long)]
     format: TapEncodingFormat,
 
+    /// Only observe events matching this expression, evaluated by Vector before events
+    /// are sent
+    #[structopt(long)]
+    filter: Option<String>,
+
+    /// Language of the filter expression
+    #[structopt(default_value = "vrl", possible_values = &["vrl", "datadog_search"], long)]
+    filter_language: TapFilterLanguage,
+
+    /// Only observe 1 in every N events matching the filter, per component
+    #[structopt(default_value = "1", short = "s", long)]
+    sample_rate: u32,
+
+    /// Also observe the inputs of the components, and print what they emit side by side
+    /// with the input it was emitted for, followed by the differences
+    #[structopt(long)]
+    inputs: bool,
+
+    /// Record tapped events to the provided file instead of printing them. The
+    /// capture can be turned into unit tests with `vector test --generate`.
+    #[structopt(short, long)]
+    output: Option<PathBuf>,
+
     /// Components to observe (comma-separated; accepts glob patterns)
     #[structopt(default_value = "*", use_delimiter(true))]
     components: Vec<String>,
@@ -15,30 +15,65 @@ pub async fn cmd(opts: &super::Opts) -> exitcode::ExitCode {
         return exitcode::IOERR;
     }
 
-    // Use the provided URL as the Vector GraphQL API server, or default to the local port
+    // Use the provided URLs as the Vector GraphQL API servers, or default to the local port
     // provided by the API config. This will work despite `api` and `api-client` being distinct
     // features; the config is available even if `api` is disabled
-    let url = opts.url.clone().unwrap_or_else(|| {
+    let urls = if opts.url.is_empty() {
         let addr = config::api::default_address().unwrap();
-        Url::parse(&*format!("http://{}/graphql", addr))
-            .expect("Couldn't parse default API URL. Please report this.")
-    });
+        vec![Url::parse(&*format!("http://{}/graphql", addr))
+            .expect("Couldn't parse default API URL. Please report this.")]
+    } else {
+        opts.url.clone()
+    };
+
+    // Subscribe to the metrics of each instance, merging them when there are several
+    let mut receivers = Vec::with_capacity(urls.len());
+    for url in &urls {
+        match subscribe(url, opts.interval as i64).await {
+            Ok(rx) => receivers.push(rx),
+            Err(code) => return code,
+        }
+    }
+    let state_rx = if receivers.len() == 1 {
+        receivers.remove(0)
+    } else {
+        state::combine(receivers)
+    };
+
+    let url_string = urls.iter().map(Url::as_str).collect::<Vec<_>>().join(", ");
+
+    // Initialize the dashboard
+    match init_dashboard(&url_string, opts, state_rx).await {
+        // Even though we could return an `exitcode::OK` here, the upstream block_on won't
+        // return due to lingering subscriptions, which manifests as a hung cursor in the
+        // terminal. To work around this for now, exit immediately.
+        // TODO: https://github.com/vectordotdev/vector/issues/9206
+        Ok(_) => std::process::exit(0),
+        _ => {
+            eprintln!("Your terminal doesn't support building a dashboard. Exiting.");
+            exitcode::IOERR
+        }
+    }
+}
 
+/// Queries the components of the Vector instance at `url`, and subscribes to their metrics,
+/// returning a receiver of its updated state.
+async fn subscribe(url: &Url, interval: i64) -> Result<state::StateRx, exitcode::ExitCode> {
     // Create a new API client for connecting to the local/remote Vector instance.
     let client = match Client::new_with_healthcheck(url.clone()).await {
         Some(client) => client,
-        None => return exitcode::UNAVAILABLE,
+        None => return Err(exitcode::UNAVAILABLE),
     };
 
     // Create a metrics state updater
     let (tx, rx) = tokio::sync::mpsc::channel(20);
 
     // Get the initial component state
-    let sender = match metrics::init_components(&client).await {
+    let receiver = match metrics::init_components(&client).await {
         Ok(state) => state::updater(state, rx).await,
         _ => {
-            eprintln!("Couldn't query Vector components.");
-            return exitcode::UNAVAILABLE;
+            eprintln!("Couldn't query Vector components at {}.", url);
+            return Err(exitcode::UNAVAILABLE);
         }
     };
 
@@ -55,23 +90,12 @@ pub async fn cmd(opts: &super::Opts) -> exitcode::ExitCode {
         Ok(c) => c,
         Err(e) => {
             eprintln!("Couldn't connect to Vector API via WebSockets: {:?}", e);
-            return exitcode::UNAVAILABLE;
+            return Err(exitcode::UNAVAILABLE);
         }
     };
 
     // Subscribe to updated metrics
-    metrics::subscribe(subscription_client, tx.clone(), opts.interval as i64);
+    metrics::subscribe(subscription_client, tx, interval);
 
-    // Initialize the dashboard
-    match init_dashboard(url.as_str(), opts, sender).await {
-        // Even though we could return an `exitcode::OK` here, the upstream block_on won't
-        // return due to lingering subscriptions, which manifests as a hung cursor in the
-        // terminal. To work around this for now, exit immediately.
-        // TODO: https://github.com/vectordotdev/vector/issues/9206
-        Ok(_) => std::process::exit(0),
-        _ => {
-            eprintln!("Your terminal doesn't support building a dashboard. Exiting.");
-            exitcode::IOERR
-        }
-    }
+    Ok(receiver)
 }
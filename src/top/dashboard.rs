@@ -9,13 +9,14 @@ use crossterm::{
 };
 use num_format::{Locale, ToFormattedString};
 use number_prefix::NumberPrefix;
-use std::io::stdout;
+use regex::Regex;
+use std::{cmp::Ordering, collections::VecDeque, io::stdout};
 use tui::{
     backend::{Backend, CrosstermBackend},
-    layout::{Alignment, Constraint, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Span, Spans},
-    widgets::{Block, Borders, Cell, Paragraph, Row, Table, Wrap},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Sparkline, Table, TableState, Wrap},
     Frame, Terminal,
 };
 
@@ -83,7 +84,20 @@ impl HumanFormatter for i64 {
     }
 }
 
-static HEADER: [&str; 8] = [
+/// Format a duration in seconds, using the most readable unit
+fn format_seconds(secs: f64) -> String {
+    if secs <= 0.0 {
+        "--".into()
+    } else if secs < 0.001 {
+        format!("{:.0}µs", secs * 1_000_000.0)
+    } else if secs < 1.0 {
+        format!("{:.1}ms", secs * 1000.0)
+    } else {
+        format!("{:.2}s", secs)
+    }
+}
+
+static HEADER: [&str; 11] = [
     "ID",
     "Pipeline",
     "Kind",
@@ -91,9 +105,156 @@ static HEADER: [&str; 8] = [
     "Events In",
     "Events Out",
     "Bytes",
+    "Latency",
+    "Age",
     "Errors",
+    "Health",
 ];
 
+/// Format a sink buffer configuration, e.g. "memory, 500 events, block when full"
+fn format_buffer(buffer: &state::SinkBuffer) -> String {
+    let mut parts = vec![buffer.buffer_type.clone()];
+    if let Some(max_events) = buffer.max_events {
+        parts.push(format!("{} events", max_events.thousands_format()));
+    }
+    if let Some(max_size) = buffer.max_size {
+        parts.push(max_size.human_format_bytes());
+    }
+    parts.push(format!("{} when full", buffer.when_full));
+    parts.join(", ")
+}
+
+/// Color the health status of a component is displayed with
+const fn health_color(health: state::Health) -> Color {
+    match health {
+        state::Health::Healthy => Color::Green,
+        state::Health::Degraded => Color::Yellow,
+        state::Health::Unhealthy => Color::Red,
+        state::Health::Unknown => Color::Gray,
+    }
+}
+
+/// Compares two rows by the column at `column` in `HEADER`
+fn compare_rows(column: usize, a: &state::ComponentRow, b: &state::ComponentRow) -> Ordering {
+    let floats = |a: f64, b: f64| a.partial_cmp(&b).unwrap_or(Ordering::Equal);
+    match column {
+        0 => a.key.id().cmp(b.key.id()),
+        1 => a.key.pipeline_str().cmp(&b.key.pipeline_str()),
+        2 => a.kind.cmp(&b.kind),
+        3 => a.component_type.cmp(&b.component_type),
+        4 => a.events_in_total.cmp(&b.events_in_total),
+        5 => a.events_out_total.cmp(&b.events_out_total),
+        6 => a.processed_bytes_total.cmp(&b.processed_bytes_total),
+        7 => floats(a.latency_mean_sec, b.latency_mean_sec),
+        8 => floats(a.event_age_mean_sec, b.event_age_mean_sec),
+        9 => a.errors.cmp(&b.errors),
+        _ => a.health.cmp(&b.health),
+    }
+    .then_with(|| a.key.cmp(&b.key))
+}
+
+/// Interactive state of the dashboard, driven by key presses
+#[derive(Debug, Default)]
+struct Ui {
+    /// Index in `HEADER` of the column rows are sorted by
+    sort_column: usize,
+    sort_descending: bool,
+    /// Regex matching the ids of the displayed components, as typed
+    filter: String,
+    /// The latest valid filter
+    filter_regex: Option<Regex>,
+    /// The filter to restore if editing it is cancelled, while editing it
+    editing_filter: Option<String>,
+    /// Index of the selected row, among the displayed rows
+    selected: usize,
+    show_details: bool,
+}
+
+impl Ui {
+    /// Updates the state following a key press. Returns `false` to quit the dashboard.
+    fn handle_key(&mut self, key: KeyCode) -> bool {
+        if let Some(previous) = self.editing_filter.clone() {
+            match key {
+                KeyCode::Enter => self.editing_filter = None,
+                KeyCode::Esc => {
+                    self.editing_filter = None;
+                    self.set_filter(previous);
+                }
+                KeyCode::Backspace => {
+                    let mut filter = self.filter.clone();
+                    filter.pop();
+                    self.set_filter(filter);
+                }
+                KeyCode::Char(c) => {
+                    let filter = format!("{}{}", self.filter, c);
+                    self.set_filter(filter);
+                }
+                _ => {}
+            }
+            return true;
+        }
+
+        match key {
+            KeyCode::Char('q') => return false,
+            KeyCode::Esc if self.show_details => self.show_details = false,
+            KeyCode::Esc => return false,
+            KeyCode::Left => {
+                self.sort_column = (self.sort_column + HEADER.len() - 1) % HEADER.len()
+            }
+            KeyCode::Right => self.sort_column = (self.sort_column + 1) % HEADER.len(),
+            KeyCode::Char('r') => self.sort_descending = !self.sort_descending,
+            KeyCode::Char('/') => self.editing_filter = Some(self.filter.clone()),
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down => self.selected += 1,
+            KeyCode::Enter => self.show_details = !self.show_details,
+            _ => {}
+        }
+        true
+    }
+
+    /// Sets the filter, which only applies once it is a valid regex.
+    fn set_filter(&mut self, filter: String) {
+        if filter.is_empty() {
+            self.filter_regex = None;
+        } else if let Ok(regex) = Regex::new(&filter) {
+            self.filter_regex = Some(regex);
+        }
+        self.filter = filter;
+    }
+
+    /// Whether the filter as typed isn't a valid regex
+    fn filter_is_invalid(&self) -> bool {
+        !self.filter.is_empty()
+            && self.filter_regex.as_ref().map(Regex::as_str) != Some(self.filter.as_str())
+    }
+
+    /// Returns the rows matching the filter, in display order.
+    fn rows<'a>(&self, state: &'a state::State) -> Vec<&'a state::ComponentRow> {
+        let mut rows = state
+            .values()
+            .filter(|r| match &self.filter_regex {
+                Some(regex) => regex.is_match(r.key.id()),
+                None => true,
+            })
+            .collect::<Vec<_>>();
+
+        rows.sort_by(|a, b| {
+            let ordering = compare_rows(self.sort_column, a, b);
+            if self.sort_descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+        rows
+    }
+
+    /// Keeps the selection within the displayed rows.
+    fn clamp_selection(&mut self, rows: usize) {
+        self.selected = self.selected.min(rows.saturating_sub(1));
+    }
+}
+
 struct Widgets<'a> {
     constraints: Vec<Constraint>,
     url_string: &'a str,
@@ -138,16 +299,41 @@ impl<'a> Widgets<'a> {
     }
 
     /// Renders a components table, showing sources, transforms and sinks in tabular form, with
-    /// statistics pulled from `ComponentsState`,
-    fn components_table<B: Backend>(&self, f: &mut Frame<B>, state: &state::State, area: Rect) {
-        // Header columns
+    /// statistics pulled from `ComponentsState`, sorted and filtered as set in `Ui`
+    fn components_table<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        state: &state::State,
+        ui: &Ui,
+        area: Rect,
+    ) {
+        // Header columns, marking the one rows are sorted by
         let header = HEADER
             .iter()
-            .map(|s| Cell::from(*s).style(Style::default().add_modifier(Modifier::BOLD)))
+            .enumerate()
+            .map(|(i, s)| {
+                let label = match (i == ui.sort_column, ui.sort_descending) {
+                    (false, _) => s.to_string(),
+                    (true, false) => format!("{} ▲", s),
+                    (true, true) => format!("{} ▼", s),
+                };
+                Cell::from(label).style(Style::default().add_modifier(Modifier::BOLD))
+            })
             .collect::<Vec<_>>();
 
+        let rows = ui.rows(state);
+        let title = match ui.filter_regex {
+            Some(_) => format!("Components ({} of {})", rows.len(), state.len()),
+            None => "Components".to_string(),
+        };
+
+        let mut table_state = TableState::default();
+        if !rows.is_empty() {
+            table_state.select(Some(ui.selected));
+        }
+
         // Data columns
-        let items = state.iter().map(|(_, r)| {
+        let items = rows.into_iter().map(|r| {
             let mut data = vec![
                 r.key.id().to_string(),
                 r.key.pipeline_str().unwrap_or_default().into(),
@@ -192,6 +378,8 @@ impl<'a> Widgets<'a> {
                         r.processed_bytes_throughput_sec.human_format_bytes()
                     ),
                 },
+                format_seconds(r.latency_mean_sec),
+                format_seconds(r.event_age_mean_sec),
                 if self.opts.human_metrics {
                     r.errors.human_format()
                 } else {
@@ -200,24 +388,152 @@ impl<'a> Widgets<'a> {
             ];
 
             data.extend_from_slice(&formatted_metrics);
-            Row::new(data).style(Style::default())
+            let mut cells = data.into_iter().map(Cell::from).collect::<Vec<_>>();
+            cells.push(
+                Cell::from(r.health.to_string()).style(Style::default().fg(health_color(r.health))),
+            );
+            Row::new(cells).style(Style::default())
         });
 
         let w = Table::new(items)
             .header(Row::new(header).bottom_margin(1))
-            .block(Block::default().borders(Borders::ALL).title("Components"))
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
             .column_spacing(2)
             .widths(&[
-                Constraint::Percentage(19),
-                Constraint::Percentage(14),
-                Constraint::Percentage(8),
-                Constraint::Percentage(8),
-                Constraint::Percentage(18),
-                Constraint::Percentage(18),
-                Constraint::Percentage(18),
+                Constraint::Percentage(13),
+                Constraint::Percentage(9),
+                Constraint::Percentage(6),
                 Constraint::Percentage(8),
+                Constraint::Percentage(13),
+                Constraint::Percentage(13),
+                Constraint::Percentage(13),
+                Constraint::Percentage(7),
+                Constraint::Percentage(6),
+                Constraint::Percentage(5),
+                Constraint::Percentage(7),
             ]);
 
+        f.render_stateful_widget(w, area, &mut table_state);
+    }
+
+    /// Renders the details of the selected component: its throughput over time, health,
+    /// errors, buffer, and the latest warnings and errors it logged.
+    fn details<B: Backend>(&self, f: &mut Frame<B>, row: &state::ComponentRow, area: Rect) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!("{} ({} {})", row.key, row.component_type, row.kind));
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+            .split(inner);
+        let sparklines = Layout::default()
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+            .split(columns[0]);
+
+        self.sparkline(
+            f,
+            format!(
+                "Events in ({}/s)",
+                row.events_in_throughput_sec.human_format()
+            ),
+            &row.events_in_history,
+            Color::Green,
+            sparklines[0],
+        );
+        self.sparkline(
+            f,
+            format!(
+                "Events out ({}/s)",
+                row.events_out_throughput_sec.human_format()
+            ),
+            &row.events_out_history,
+            Color::Blue,
+            sparklines[1],
+        );
+
+        let label = |s: &'static str| Span::styled(s, Style::default().fg(Color::Gray));
+        let mut text = vec![
+            Spans::from(vec![
+                label("Health: "),
+                Span::styled(
+                    row.health.to_string(),
+                    Style::default().fg(health_color(row.health)),
+                ),
+            ]),
+            Spans::from(vec![
+                label("Errors: "),
+                Span::from(row.errors.thousands_format()),
+            ]),
+            Spans::from(vec![
+                label("Latency: "),
+                Span::from(format_seconds(row.latency_mean_sec)),
+            ]),
+            Spans::from(vec![
+                label("Age: "),
+                Span::from(format_seconds(row.event_age_mean_sec)),
+            ]),
+        ];
+        if let Some(buffer) = &row.buffer {
+            text.push(Spans::from(vec![
+                label("Buffer: "),
+                Span::from(format_buffer(buffer)),
+            ]));
+        }
+        if row.instances > 1 {
+            text.push(Spans::from(vec![
+                label("Instances: "),
+                Span::from(row.instances.to_string()),
+            ]));
+        }
+
+        text.push(Spans::from(""));
+        text.push(Spans::from(Span::styled(
+            "Recent errors",
+            Style::default().add_modifier(Modifier::BOLD),
+        )));
+        if row.error_logs.is_empty() {
+            text.push(Spans::from(label("None")));
+        }
+        for log in row.error_logs.iter().rev() {
+            let color = match log.level.as_str() {
+                "ERROR" => Color::Red,
+                _ => Color::Yellow,
+            };
+            text.push(Spans::from(vec![
+                Span::styled(
+                    log.timestamp.format("%H:%M:%S ").to_string(),
+                    Style::default().fg(Color::Gray),
+                ),
+                Span::styled(format!("{} ", log.level), Style::default().fg(color)),
+                Span::from(log.message.clone()),
+            ]));
+        }
+
+        let w = Paragraph::new(text).wrap(Wrap { trim: true });
+        f.render_widget(w, columns[1]);
+    }
+
+    /// Renders the latest samples of a history that fit in `area`, as a sparkline.
+    fn sparkline<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        title: String,
+        history: &VecDeque<u64>,
+        color: Color,
+        area: Rect,
+    ) {
+        let skip = history.len().saturating_sub(area.width as usize);
+        let data = history.iter().skip(skip).copied().collect::<Vec<_>>();
+
+        let w = Sparkline::default()
+            .block(Block::default().title(title))
+            .data(&data)
+            .style(Style::default().fg(color));
+
         f.render_widget(w, area);
     }
 
@@ -231,23 +547,46 @@ impl<'a> Widgets<'a> {
         f.render_widget(w, area);
     }
 
-    /// Renders a box showing instructions on how to exit from `vector top`.
-    fn quit_box<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
-        let text = vec![Spans::from("To quit, press ESC or 'q'")];
+    /// Renders a box showing the filter being edited, or the available key bindings, including
+    /// how to exit from `vector top`.
+    fn help_box<B: Backend>(&self, f: &mut Frame<B>, ui: &Ui, area: Rect) {
+        let gray = Style::default().fg(Color::Gray);
+        let filter_style = if ui.filter_is_invalid() {
+            Style::default().fg(Color::Red)
+        } else {
+            Style::default()
+        };
+
+        let text = match ui.editing_filter {
+            Some(_) => vec![Spans::from(vec![
+                Span::from("Filter component IDs: "),
+                Span::styled(format!("{}_", ui.filter), filter_style),
+                Span::styled(" (Enter to apply, ESC to cancel)", gray),
+            ])],
+            None => {
+                let mut spans = Vec::new();
+                if !ui.filter.is_empty() {
+                    spans.push(Span::from("Filter: "));
+                    spans.push(Span::styled(ui.filter.clone(), filter_style));
+                    spans.push(Span::styled(" | ", gray));
+                }
+                spans.push(Span::styled(
+                    "←/→ sort, 'r' reverse, '/' filter, ↑/↓ select, Enter details. \
+                     To quit, press ESC or 'q'",
+                    gray,
+                ));
+                vec![Spans::from(spans)]
+            }
+        };
 
-        let block = Block::default()
-            .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Gray));
-        let w = Paragraph::new(text)
-            .block(block)
-            .style(Style::default().fg(Color::Gray))
-            .alignment(Alignment::Left);
+        let block = Block::default().borders(Borders::ALL).border_style(gray);
+        let w = Paragraph::new(text).block(block).alignment(Alignment::Left);
 
         f.render_widget(w, area);
     }
 
     /// Draw a single frame. Creates a layout and renders widgets into it.
-    fn draw<B: Backend>(&self, f: &mut Frame<B>, state: state::State) {
+    fn draw<B: Backend>(&self, f: &mut Frame<B>, state: &state::State, ui: &Ui) {
         let size = f.size();
         let rects = Layout::default()
             .constraints(self.constraints.as_ref())
@@ -257,12 +596,24 @@ impl<'a> Widgets<'a> {
 
         // Require a minimum of 80 chars of line width to display the table
         if size.width >= 80 {
-            self.components_table(f, &state, rects[1]);
+            let selected = ui.rows(state).get(ui.selected).copied();
+            match selected.filter(|_| ui.show_details) {
+                Some(row) => {
+                    let panes = Layout::default()
+                        .constraints(
+                            [Constraint::Percentage(50), Constraint::Percentage(50)].as_ref(),
+                        )
+                        .split(rects[1]);
+                    self.components_table(f, state, ui, panes[0]);
+                    self.details(f, row, panes[1]);
+                }
+                None => self.components_table(f, state, ui, rects[1]),
+            }
         } else {
             self.components_resize_window(f, rects[1]);
         }
 
-        self.quit_box(f, rects[2]);
+        self.help_box(f, ui, rects[2]);
     }
 }
 
@@ -300,19 +651,23 @@ pub async fn init_dashboard<'a>(
     terminal.clear()?;
 
     let widgets = Widgets::new(url, opts);
+    let mut ui = Ui::default();
+    let mut state = state::State::new();
 
     loop {
+        // Redraw on both updated states and key presses, using the latest state
         tokio::select! {
-            Some(state) = state_rx.recv() => {
-                terminal.draw(|f| widgets.draw(f, state))?;
-            },
+            Some(updated) = state_rx.recv() => state = updated,
             k = key_press_rx.recv() => {
-                if let KeyCode::Esc | KeyCode::Char('q') = k.unwrap() {
+                if !ui.handle_key(k.unwrap()) {
                     let _ = key_press_kill_tx.send(());
                     break
                 }
             }
         }
+
+        ui.clamp_selection(ui.rows(&state).len());
+        terminal.draw(|f| widgets.draw(f, &state, &ui))?;
     }
 
     // Clean-up terminal
@@ -421,4 +776,94 @@ mod tests {
         assert_eq!((N * (N * (N * N))).human_format_bytes(), "1.00 TiB");
         assert_eq!((N * (N * (N * (N * N)))).human_format_bytes(), "1.00 PiB");
     }
+
+    fn state() -> state::State {
+        [("a", 3, 0.5), ("b", 1, 0.0), ("c", 2, 0.1)]
+            .iter()
+            .map(|(id, events, latency)| {
+                let key = crate::config::ComponentKey::from(*id);
+                let mut row = state::ComponentRow::new(key.clone(), "sink".into(), "http".into());
+                row.events_in_total = *events;
+                row.latency_mean_sec = *latency;
+                (key, row)
+            })
+            .collect()
+    }
+
+    fn ids(ui: &Ui, state: &state::State) -> Vec<String> {
+        ui.rows(state)
+            .into_iter()
+            .map(|r| r.key.id().to_string())
+            .collect()
+    }
+
+    #[test]
+    /// Rows should be sorted by the selected column, in either direction
+    fn sort_rows() {
+        let state = state();
+        let mut ui = Ui::default();
+        assert_eq!(ids(&ui, &state), vec!["a", "b", "c"]);
+
+        // Events in
+        for _ in 0..4 {
+            ui.handle_key(KeyCode::Right);
+        }
+        assert_eq!(ids(&ui, &state), vec!["b", "c", "a"]);
+
+        ui.handle_key(KeyCode::Char('r'));
+        assert_eq!(ids(&ui, &state), vec!["a", "c", "b"]);
+
+        // Latency, moving left past the first column
+        for _ in 0..8 {
+            ui.handle_key(KeyCode::Left);
+        }
+        assert_eq!(ui.sort_column, 7);
+        assert_eq!(ids(&ui, &state), vec!["a", "c", "b"]);
+    }
+
+    #[test]
+    /// Rows should be filtered by the latest valid regex typed
+    fn filter_rows() {
+        let state = state();
+        let mut ui = Ui::default();
+
+        ui.handle_key(KeyCode::Char('/'));
+        for c in "[ab".chars() {
+            ui.handle_key(KeyCode::Char(c));
+        }
+        assert!(ui.filter_is_invalid());
+        ui.handle_key(KeyCode::Char(']'));
+        assert!(!ui.filter_is_invalid());
+        ui.handle_key(KeyCode::Enter);
+        assert_eq!(ids(&ui, &state), vec!["a", "b"]);
+
+        // Cancelling restores the previous filter
+        ui.handle_key(KeyCode::Char('/'));
+        ui.handle_key(KeyCode::Char('c'));
+        ui.handle_key(KeyCode::Esc);
+        assert_eq!(ui.filter, "[ab]");
+        assert_eq!(ids(&ui, &state), vec!["a", "b"]);
+
+        assert!(!ui.handle_key(KeyCode::Char('q')));
+    }
+
+    #[test]
+    /// The selection should stay within the displayed rows
+    fn select_rows() {
+        let state = state();
+        let mut ui = Ui::default();
+
+        for _ in 0..5 {
+            ui.handle_key(KeyCode::Down);
+        }
+        ui.clamp_selection(ui.rows(&state).len());
+        assert_eq!(ui.selected, 2);
+
+        ui.handle_key(KeyCode::Enter);
+        assert!(ui.show_details);
+        // ESC closes the details before quitting
+        assert!(ui.handle_key(KeyCode::Esc));
+        assert!(!ui.show_details);
+        assert!(!ui.handle_key(KeyCode::Esc));
+    }
 }
@@ -3,10 +3,19 @@ use crate::config::ComponentKey;
 use std::sync::Arc;
 use tokio_stream::StreamExt;
 use vector_api_client::{
-    gql::{ComponentsQueryExt, ComponentsSubscriptionExt, MetricsSubscriptionExt},
+    gql::{
+        component_added_subscription, component_healths_subscription, components_query,
+        ComponentsQueryExt, ComponentsSubscriptionExt, HealthSubscriptionExt, LogsSubscriptionExt,
+        MetricsSubscriptionExt,
+    },
     Client, SubscriptionClient,
 };
 
+/// Lowercases the name of a GraphQL enum value, e.g. `DROP_NEWEST` -> `drop_newest`
+fn enum_name(value: impl std::fmt::Debug) -> String {
+    format!("{:?}", value).to_lowercase()
+}
+
 /// Components that have been added
 async fn component_added(client: Arc<SubscriptionClient>, tx: state::EventTx) {
     let res = client.component_added();
@@ -19,20 +28,19 @@ async fn component_added(client: Arc<SubscriptionClient>, tx: state::EventTx) {
         if let Some(d) = res.data {
             let c = d.component_added;
             let key = ComponentKey::from((c.pipeline_id, c.component_id));
-            let _ = tx
-                .send(state::EventType::ComponentAdded(state::ComponentRow {
-                    key,
-                    kind: c.on.to_string(),
-                    component_type: c.component_type,
-                    events_in_total: 0,
-                    events_in_throughput_sec: 0,
-                    events_out_total: 0,
-                    events_out_throughput_sec: 0,
-                    processed_bytes_total: 0,
-                    processed_bytes_throughput_sec: 0,
-                    errors: 0,
-                }))
-                .await;
+            let mut row = state::ComponentRow::new(key, c.on.to_string(), c.component_type);
+            row.buffer = match c.on {
+                component_added_subscription::ComponentAddedSubscriptionComponentAddedOn::Sink(
+                    s,
+                ) => Some(state::SinkBuffer {
+                    buffer_type: enum_name(s.buffer.type_),
+                    max_events: s.buffer.max_events,
+                    max_size: s.buffer.max_size,
+                    when_full: enum_name(s.buffer.when_full),
+                }),
+                _ => None,
+            };
+            let _ = tx.send(state::EventType::ComponentAdded(row)).await;
         }
     }
 }
@@ -210,6 +218,134 @@ async fn processed_bytes_throughputs(
     }
 }
 
+async fn latencies(client: Arc<SubscriptionClient>, tx: state::EventTx, interval: i64) {
+    let res = client.component_latencies_subscription(interval);
+
+    tokio::pin! {
+        let stream = res.stream();
+    };
+
+    while let Some(Some(res)) = stream.next().await {
+        if let Some(d) = res.data {
+            let c = d.component_latencies;
+            let _ = tx
+                .send(state::EventType::Latencies(
+                    c.into_iter()
+                        .map(|c| (ComponentKey::from(&c.component_id), c.mean))
+                        .collect(),
+                ))
+                .await;
+        }
+    }
+}
+
+async fn event_ages(client: Arc<SubscriptionClient>, tx: state::EventTx, interval: i64) {
+    let res = client.component_event_ages_subscription(interval);
+
+    tokio::pin! {
+        let stream = res.stream();
+    };
+
+    while let Some(Some(res)) = stream.next().await {
+        if let Some(d) = res.data {
+            let c = d.component_event_ages;
+            let _ = tx
+                .send(state::EventType::EventAges(
+                    c.into_iter()
+                        .map(|c| (ComponentKey::from(&c.component_id), c.mean))
+                        .collect(),
+                ))
+                .await;
+        }
+    }
+}
+
+async fn errors_totals(client: Arc<SubscriptionClient>, tx: state::EventTx, interval: i64) {
+    let res = client.component_errors_totals_subscription(interval);
+
+    tokio::pin! {
+        let stream = res.stream();
+    };
+
+    while let Some(Some(res)) = stream.next().await {
+        if let Some(d) = res.data {
+            let c = d.component_errors_totals;
+            let _ = tx
+                .send(state::EventType::ErrorsTotals(
+                    c.into_iter()
+                        .map(|c| {
+                            (
+                                ComponentKey::from(&c.component_id),
+                                c.metric.errors_total as i64,
+                            )
+                        })
+                        .collect(),
+                ))
+                .await;
+        }
+    }
+}
+
+/// Health status of components, derived from their healthchecks and requests
+async fn healths(client: Arc<SubscriptionClient>, tx: state::EventTx, interval: i64) {
+    use component_healths_subscription::HealthStatus;
+
+    let res = client.component_healths_subscription(interval);
+
+    tokio::pin! {
+        let stream = res.stream();
+    };
+
+    while let Some(Some(res)) = stream.next().await {
+        if let Some(d) = res.data {
+            let c = d.component_healths;
+            let _ = tx
+                .send(state::EventType::Healths(
+                    c.into_iter()
+                        .map(|c| {
+                            let health = match c.status {
+                                HealthStatus::HEALTHY => state::Health::Healthy,
+                                HealthStatus::DEGRADED => state::Health::Degraded,
+                                HealthStatus::UNHEALTHY => state::Health::Unhealthy,
+                                _ => state::Health::Unknown,
+                            };
+                            (ComponentKey::from(&c.component_id), health)
+                        })
+                        .collect(),
+                ))
+                .await;
+        }
+    }
+}
+
+/// Warnings and errors logged by components
+async fn error_logs(client: Arc<SubscriptionClient>, tx: state::EventTx) {
+    let res = client.internal_logs_subscription();
+
+    tokio::pin! {
+        let stream = res.stream();
+    };
+
+    while let Some(Some(res)) = stream.next().await {
+        if let Some(d) = res.data {
+            let l = d.internal_logs;
+            if let Some(component_id) = l.component_id {
+                let log = state::ErrorLog {
+                    timestamp: l.timestamp,
+                    level: l.level,
+                    message: l.message,
+                };
+                let _ = tx
+                    .send(state::EventType::ErrorLog(
+                        ComponentKey::from(&component_id),
+                        log,
+                    ))
+                    .await;
+            }
+        }
+    }
+}
+
 /// Subscribe to each metrics channel through a separate client. This is a temporary workaround
 /// until client multiplexing is fixed. In future, we should be able to use a single client
 pub fn subscribe(client: SubscriptionClient, tx: state::EventTx, interval: i64) {
@@ -236,9 +372,14 @@ pub fn subscribe(client: SubscriptionClient, tx: state::EventTx, interval: i64)
     ));
     tokio::spawn(processed_bytes_throughputs(
         Arc::clone(&client),
-        tx,
+        tx.clone(),
         interval,
     ));
+    tokio::spawn(latencies(Arc::clone(&client), tx.clone(), interval));
+    tokio::spawn(event_ages(Arc::clone(&client), tx.clone(), interval));
+    tokio::spawn(errors_totals(Arc::clone(&client), tx.clone(), interval));
+    tokio::spawn(healths(Arc::clone(&client), tx.clone(), interval));
+    tokio::spawn(error_logs(Arc::clone(&client), tx));
 }
 
 /// Retrieve the initial components/metrics for first paint. Further updating the metrics
@@ -260,22 +401,20 @@ pub async fn init_components(client: &Client) -> Result<state::State, ()> {
             d.into_iter().filter_map(|edge| {
                 let d = edge?.node;
                 let key = ComponentKey::from((d.pipeline_id, d.component_id));
-                Some((
-                    key.clone(),
-                    state::ComponentRow {
-                        key,
-                        kind: d.on.to_string(),
-                        component_type: d.component_type,
-                        events_in_total: d.on.events_in_total(),
-                        events_in_throughput_sec: 0,
-                        events_out_total: d.on.events_out_total(),
-                        events_out_throughput_sec: 0,
-                        processed_bytes_total: d.on.processed_bytes_total(),
-                        processed_bytes_throughput_sec: 0,
-
-                        errors: 0,
-                    },
-                ))
+                let mut row =
+                    state::ComponentRow::new(key.clone(), d.on.to_string(), d.component_type);
+                row.events_in_total = d.on.events_in_total();
+                row.events_out_total = d.on.events_out_total();
+                row.processed_bytes_total = d.on.processed_bytes_total();
+                if let components_query::ComponentsQueryComponentsEdgesNodeOn::Sink(s) = d.on {
+                    row.buffer = Some(state::SinkBuffer {
+                        buffer_type: enum_name(s.buffer.type_),
+                        max_events: s.buffer.max_events,
+                        max_size: s.buffer.max_size,
+                        when_full: enum_name(s.buffer.when_full),
+                    });
+                }
+                Some((key, row))
             })
         })
         .collect::<state::State>();
@@ -16,9 +16,10 @@ pub struct Opts {
     #[structopt(default_value = "500", short = "i", long)]
     interval: u32,
 
-    /// Vector GraphQL API server endpoint
+    /// Vector GraphQL API server endpoint. Repeat to merge the components of several
+    /// instances, such as a fleet of aggregators, into a single table
     #[structopt(short, long)]
-    url: Option<Url>,
+    url: Vec<Url>,
 
     /// Humanize metrics, using numeric suffixes - e.g. 1,100 = 1.10 k, 1,000,000 = 1.00 M
     #[structopt(short, long)]
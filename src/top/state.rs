@@ -1,6 +1,14 @@
 use crate::config::ComponentKey;
-use std::collections::btree_map::BTreeMap;
+use chrono::{DateTime, Utc};
+use std::collections::{btree_map::BTreeMap, VecDeque};
 use tokio::sync::mpsc;
+use tokio_stream::{wrappers::ReceiverStream, StreamExt};
+
+/// Number of throughput samples kept for the sparklines of the detail pane
+pub const HISTORY_LEN: usize = 60;
+
+/// Number of error logs kept per component
+pub const ERROR_LOGS_LEN: usize = 10;
 
 type IdentifiedMetric = (ComponentKey, i64);
 
@@ -15,6 +23,14 @@ pub enum EventType {
     ProcessedBytesTotals(Vec<IdentifiedMetric>),
     /// Interval + identified metric
     ProcessedBytesThroughputs(i64, Vec<IdentifiedMetric>),
+    /// Mean latency in seconds, by component
+    Latencies(Vec<(ComponentKey, f64)>),
+    /// Mean event age in seconds, by component
+    EventAges(Vec<(ComponentKey, f64)>),
+    ErrorsTotals(Vec<IdentifiedMetric>),
+    /// Warning or error logged by a component
+    ErrorLog(ComponentKey, ErrorLog),
+    Healths(Vec<(ComponentKey, Health)>),
     ComponentAdded(ComponentRow),
     ComponentRemoved(ComponentKey),
 }
@@ -24,6 +40,43 @@ pub type EventTx = mpsc::Sender<EventType>;
 pub type EventRx = mpsc::Receiver<EventType>;
 pub type StateRx = mpsc::Receiver<State>;
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct SinkBuffer {
+    pub buffer_type: String,
+    pub max_events: Option<i64>,
+    pub max_size: Option<i64>,
+    pub when_full: String,
+}
+
+/// Health status of a component, ordered from best to worst so that merged rows report the
+/// worst status of their instances.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Health {
+    Unknown,
+    Healthy,
+    Degraded,
+    Unhealthy,
+}
+
+impl std::fmt::Display for Health {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Health::Unknown => "unknown",
+            Health::Healthy => "healthy",
+            Health::Degraded => "degraded",
+            Health::Unhealthy => "unhealthy",
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorLog {
+    pub timestamp: DateTime<Utc>,
+    pub level: String,
+    pub message: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct ComponentRow {
     pub key: ComponentKey,
@@ -35,7 +88,133 @@ pub struct ComponentRow {
     pub events_in_throughput_sec: i64,
     pub events_out_total: i64,
     pub events_out_throughput_sec: i64,
+    pub latency_mean_sec: f64,
+    pub event_age_mean_sec: f64,
     pub errors: i64,
+    pub health: Health,
+    /// Latest events in throughputs, oldest first
+    pub events_in_history: VecDeque<u64>,
+    /// Latest events out throughputs, oldest first
+    pub events_out_history: VecDeque<u64>,
+    /// Buffer configuration, for sinks
+    pub buffer: Option<SinkBuffer>,
+    /// Latest warnings and errors logged by the component, oldest first
+    pub error_logs: VecDeque<ErrorLog>,
+    /// Number of Vector instances running the component
+    pub instances: usize,
+}
+
+impl ComponentRow {
+    /// Returns a new `ComponentRow` for a component of a single instance, without metrics.
+    pub fn new(key: ComponentKey, kind: String, component_type: String) -> Self {
+        Self {
+            key,
+            kind,
+            component_type,
+            processed_bytes_total: 0,
+            processed_bytes_throughput_sec: 0,
+            events_in_total: 0,
+            events_in_throughput_sec: 0,
+            events_out_total: 0,
+            events_out_throughput_sec: 0,
+            latency_mean_sec: 0.0,
+            event_age_mean_sec: 0.0,
+            errors: 0,
+            health: Health::Unknown,
+            events_in_history: VecDeque::new(),
+            events_out_history: VecDeque::new(),
+            buffer: None,
+            error_logs: VecDeque::new(),
+            instances: 1,
+        }
+    }
+
+    /// Combines the rows of the same component running on several instances. Counters and
+    /// throughputs are summed, while latencies and ages are averaged over the instances
+    /// reporting them, and the worst health is kept.
+    fn merged(rows: &[&ComponentRow]) -> Self {
+        let first = rows[0];
+        let sum = |metric: fn(&ComponentRow) -> i64| rows.iter().map(|r| metric(r)).sum::<i64>();
+        let mean = |metric: fn(&ComponentRow) -> f64| {
+            let values = rows
+                .iter()
+                .map(|r| metric(r))
+                .filter(|v| *v > 0.0)
+                .collect::<Vec<_>>();
+            match values.len() {
+                0 => 0.0,
+                n => values.iter().sum::<f64>() / n as f64,
+            }
+        };
+        // Histories are aligned on their latest sample, as instances may have been
+        // sampled for different durations.
+        let history = |history: fn(&ComponentRow) -> &VecDeque<u64>| {
+            let len = rows.iter().map(|r| history(r).len()).max().unwrap_or(0);
+            (0..len)
+                .map(|i| {
+                    rows.iter()
+                        .filter_map(|r| {
+                            let h = history(r);
+                            (i + h.len()).checked_sub(len).map(|i| h[i])
+                        })
+                        .sum::<u64>()
+                })
+                .collect()
+        };
+
+        let mut error_logs = rows
+            .iter()
+            .flat_map(|r| r.error_logs.iter().cloned())
+            .collect::<Vec<_>>();
+        error_logs.sort_by_key(|log| log.timestamp);
+        let skip = error_logs.len().saturating_sub(ERROR_LOGS_LEN);
+
+        Self {
+            key: first.key.clone(),
+            kind: first.kind.clone(),
+            component_type: first.component_type.clone(),
+            processed_bytes_total: sum(|r| r.processed_bytes_total),
+            processed_bytes_throughput_sec: sum(|r| r.processed_bytes_throughput_sec),
+            events_in_total: sum(|r| r.events_in_total),
+            events_in_throughput_sec: sum(|r| r.events_in_throughput_sec),
+            events_out_total: sum(|r| r.events_out_total),
+            events_out_throughput_sec: sum(|r| r.events_out_throughput_sec),
+            latency_mean_sec: mean(|r| r.latency_mean_sec),
+            event_age_mean_sec: mean(|r| r.event_age_mean_sec),
+            errors: sum(|r| r.errors),
+            health: rows
+                .iter()
+                .map(|r| r.health)
+                .max()
+                .unwrap_or(Health::Unknown),
+            events_in_history: history(|r| &r.events_in_history),
+            events_out_history: history(|r| &r.events_out_history),
+            buffer: first.buffer.clone(),
+            error_logs: error_logs.into_iter().skip(skip).collect(),
+            instances: rows.iter().map(|r| r.instances).sum(),
+        }
+    }
+}
+
+/// Merges the states of several Vector instances, such as a fleet of aggregators, into a
+/// single state in which the rows of components sharing a key are combined.
+pub fn merge(states: &[State]) -> State {
+    let mut rows = BTreeMap::<_, Vec<_>>::new();
+    for (key, row) in states.iter().flatten() {
+        rows.entry(key).or_default().push(row);
+    }
+
+    rows.into_iter()
+        .map(|(key, rows)| (key.clone(), ComponentRow::merged(&rows)))
+        .collect()
+}
+
+/// Pushes a sample to a bounded history, dropping the oldest sample once full.
+fn push_bounded<T>(history: &mut VecDeque<T>, value: T, len: usize) {
+    if history.len() >= len {
+        history.pop_front();
+    }
+    history.push_back(value);
 }
 
 /// Takes the receiver `EventRx` channel, and returns a `StateTx` state transmitter. This
@@ -63,6 +242,11 @@ pub async fn updater(mut state: State, mut event_rx: EventRx) -> StateRx {
                             if let Some(r) = state.get_mut(&key) {
                                 r.events_in_throughput_sec =
                                     (v as f64 * (1000.0 / interval as f64)) as i64;
+                                push_bounded(
+                                    &mut r.events_in_history,
+                                    r.events_in_throughput_sec.max(0) as u64,
+                                    HISTORY_LEN,
+                                );
                             }
                         }
                     }
@@ -78,6 +262,11 @@ pub async fn updater(mut state: State, mut event_rx: EventRx) -> StateRx {
                             if let Some(r) = state.get_mut(&key) {
                                 r.events_out_throughput_sec =
                                     (v as f64 * (1000.0 / interval as f64)) as i64;
+                                push_bounded(
+                                    &mut r.events_out_history,
+                                    r.events_out_throughput_sec.max(0) as u64,
+                                    HISTORY_LEN,
+                                );
                             }
                         }
                     }
@@ -96,6 +285,39 @@ pub async fn updater(mut state: State, mut event_rx: EventRx) -> StateRx {
                             }
                         }
                     }
+                    EventType::Latencies(rows) => {
+                        for (key, v) in rows {
+                            if let Some(r) = state.get_mut(&key) {
+                                r.latency_mean_sec = v;
+                            }
+                        }
+                    }
+                    EventType::EventAges(rows) => {
+                        for (key, v) in rows {
+                            if let Some(r) = state.get_mut(&key) {
+                                r.event_age_mean_sec = v;
+                            }
+                        }
+                    }
+                    EventType::ErrorsTotals(rows) => {
+                        for (key, v) in rows {
+                            if let Some(r) = state.get_mut(&key) {
+                                r.errors = v;
+                            }
+                        }
+                    }
+                    EventType::ErrorLog(key, log) => {
+                        if let Some(r) = state.get_mut(&key) {
+                            push_bounded(&mut r.error_logs, log, ERROR_LOGS_LEN);
+                        }
+                    }
+                    EventType::Healths(rows) => {
+                        for (key, health) in rows {
+                            if let Some(r) = state.get_mut(&key) {
+                                r.health = health;
+                            }
+                        }
+                    }
                     EventType::ComponentAdded(c) => {
                         let _ = state.insert(c.key.clone(), c);
                     }
@@ -112,3 +334,106 @@ pub async fn updater(mut state: State, mut event_rx: EventRx) -> StateRx {
 
     rx
 }
+
+/// Combines the states received from several instances, sending their merge each time one of
+/// them is updated.
+pub fn combine(receivers: Vec<StateRx>) -> StateRx {
+    let (tx, rx) = mpsc::channel(20);
+
+    tokio::spawn(async move {
+        let mut states = vec![State::new(); receivers.len()];
+        let mut updates = tokio_stream::StreamMap::new();
+        for (i, receiver) in receivers.into_iter().enumerate() {
+            updates.insert(i, ReceiverStream::new(receiver));
+        }
+
+        while let Some((i, state)) = updates.next().await {
+            states[i] = state;
+            if tx.send(merge(&states)).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    rx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn row(id: &str) -> ComponentRow {
+        ComponentRow::new(ComponentKey::from(id), "sink".into(), "http".into())
+    }
+
+    fn error_log(secs: i64, message: &str) -> ErrorLog {
+        ErrorLog {
+            timestamp: chrono::Utc.timestamp(secs, 0),
+            level: "ERROR".into(),
+            message: message.into(),
+        }
+    }
+
+    #[test]
+    fn merge_combines_shared_components() {
+        let mut a = row("out");
+        a.events_in_total = 10;
+        a.errors = 1;
+        a.latency_mean_sec = 0.2;
+        a.events_in_history = vec![1, 2, 3].into();
+        a.error_logs = vec![error_log(2, "a")].into();
+        a.health = Health::Healthy;
+
+        let mut b = row("out");
+        b.events_in_total = 5;
+        b.errors = 2;
+        b.events_in_history = vec![10].into();
+        b.error_logs = vec![error_log(1, "b")].into();
+        b.health = Health::Degraded;
+
+        let states = vec![
+            vec![(a.key.clone(), a)].into_iter().collect(),
+            vec![(b.key.clone(), b), (ComponentKey::from("in"), row("in"))]
+                .into_iter()
+                .collect(),
+        ];
+        let merged = merge(&states);
+
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[&ComponentKey::from("in")].instances, 1);
+
+        let out = &merged[&ComponentKey::from("out")];
+        assert_eq!(out.instances, 2);
+        assert_eq!(out.events_in_total, 15);
+        assert_eq!(out.errors, 3);
+        assert_eq!(out.health, Health::Degraded);
+        // Only one instance reported a latency.
+        assert!((out.latency_mean_sec - 0.2).abs() < f64::EPSILON);
+        assert_eq!(out.events_in_history, VecDeque::from(vec![1, 2, 13]));
+        assert_eq!(
+            out.error_logs,
+            VecDeque::from(vec![error_log(1, "b"), error_log(2, "a")])
+        );
+    }
+
+    #[test]
+    fn merge_keeps_latest_error_logs() {
+        let mut a = row("out");
+        a.error_logs = (0..ERROR_LOGS_LEN as i64)
+            .map(|i| error_log(i * 2, "a"))
+            .collect();
+        let mut b = row("out");
+        b.error_logs = vec![error_log(1, "b"), error_log(100, "b")].into();
+
+        let states = vec![
+            vec![(a.key.clone(), a)].into_iter().collect(),
+            vec![(b.key.clone(), b)].into_iter().collect(),
+        ];
+        let logs = &merge(&states)[&ComponentKey::from("out")].error_logs;
+
+        assert_eq!(logs.len(), ERROR_LOGS_LEN);
+        assert_eq!(logs.back(), Some(&error_log(100, "b")));
+        assert_eq!(logs.front(), Some(&error_log(2, "a")));
+    }
+}
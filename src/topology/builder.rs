@@ -6,9 +6,10 @@ use super::{
 use crate::{
     buffers,
     config::{ComponentKey, DataType, ProxyConfig, SinkContext, SourceContext, TransformContext},
-    event::Event,
-    internal_events::{EventsReceived, EventsSent},
+    event::{BatchNotifier, Event},
+    internal_events::{ComponentLatency, EventsReceived, EventsSent, SinkHealthcheckCompleted},
     shutdown::SourceShutdownCoordinator,
+    sinks::HealthcheckBuilder,
     transforms::Transform,
     Pipeline,
 };
@@ -19,11 +20,15 @@ use std::{
     collections::HashMap,
     future::ready,
     sync::{Arc, Mutex},
+    time::Instant,
 };
 use stream_cancel::{StreamExt as StreamCancelExt, Trigger, Tripwire};
 use tokio::time::{timeout, Duration};
+use tracing::Instrument;
 use vector_core::ByteSizeOf;
 
+const HEALTHCHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
 lazy_static! {
     static ref ENRICHMENT_TABLES: enrichment::TableRegistry = enrichment::TableRegistry::default();
 }
@@ -87,6 +92,11 @@ pub async fn build_pieces(
     let (enrichment_tables, enrichment_errors) = load_enrichment_tables(config, diff).await;
     errors.extend(enrichment_errors);
 
+    let memory_budget = config
+        .global
+        .memory_budget_bytes
+        .map(vector_core::memory_budget::init_global);
+
     // Build sources
     for (key, source) in config
         .sources
@@ -94,7 +104,7 @@ pub async fn build_pieces(
         .filter(|(key, _)| diff.sources.contains_new(key))
     {
         let (tx, rx) = futures::channel::mpsc::channel(1000);
-        let pipeline = Pipeline::from_sender(tx, vec![]);
+        let pipeline = Pipeline::from_sender(tx, vec![]).with_memory_budget(memory_budget.clone());
 
         let typetag = source.inner.source_type();
 
@@ -117,7 +127,17 @@ pub async fn build_pieces(
         };
 
         let (output, control) = Fanout::new();
-        let pump = rx.map(Ok).forward(output).map_ok(|_| TaskOutput::Source);
+        let pump = rx
+            .map(|mut event: Event| {
+                if event.metadata().ingest_timestamp().is_none() {
+                    event
+                        .metadata_mut()
+                        .set_ingest_timestamp(Some(Instant::now()));
+                }
+                Ok(event)
+            })
+            .forward(output)
+            .map_ok(|_| TaskOutput::Source);
         let pump = Task::new(key.clone(), typetag, pump);
 
         // The force_shutdown_tripwire is a Future that when it resolves means that this source
@@ -144,6 +164,7 @@ pub async fn build_pieces(
     let context = TransformContext {
         globals: config.global.clone(),
         enrichment_tables: enrichment_tables.clone(),
+        ..Default::default()
     };
 
     // Build transforms
@@ -178,12 +199,7 @@ pub async fn build_pieces(
         let transform = match transform {
             Transform::Function(mut t) => input_rx
                 .filter(move |event| ready(filter_event_type(event, input_type)))
-                .inspect(|event| {
-                    emit!(EventsReceived {
-                        count: 1,
-                        byte_size: event.size_of(),
-                    })
-                })
+                .map(transform_received)
                 .flat_map(move |v| {
                     let mut buf = Vec::with_capacity(1);
                     t.transform(&mut buf, v);
@@ -191,19 +207,14 @@ pub async fn build_pieces(
                         count: buf.len(),
                         byte_size: buf.iter().map(|event| event.size_of()).sum(),
                     });
-                    stream::iter(buf.into_iter()).map(Ok)
+                    stream::iter(buf.into_iter().map(transform_sent)).map(Ok)
                 })
                 .forward(output)
                 .boxed(),
             Transform::Task(t) => {
                 let filtered = input_rx
                     .filter(move |event| ready(filter_event_type(event, input_type)))
-                    .inspect(|event| {
-                        emit!(EventsReceived {
-                            count: 1,
-                            byte_size: event.size_of(),
-                        })
-                    });
+                    .map(transform_received);
                 t.transform(Box::pin(filtered))
                     .map(Ok)
                     .forward(output.with(|event: Event| async {
@@ -211,7 +222,7 @@ pub async fn build_pieces(
                             count: 1,
                             byte_size: event.size_of(),
                         });
-                        Ok(event)
+                        Ok(transform_sent(event))
                     }))
                     .boxed()
             }
@@ -236,6 +247,10 @@ pub async fn build_pieces(
         let sink_inputs = &sink.inputs;
         let healthcheck = sink.healthcheck();
         let enable_healthcheck = healthcheck.enabled && config.healthchecks.enabled;
+        let healthcheck_interval = healthcheck
+            .interval_secs
+            .filter(|_| enable_healthcheck)
+            .map(Duration::from_secs);
 
         let typetag = sink.inner.sink_type();
         let input_type = sink.inner.input_type();
@@ -260,6 +275,24 @@ pub async fn build_pieces(
             proxy: ProxyConfig::merge_with_env(&config.global.proxy, sink.proxy()),
         };
 
+        let runtime_healthchecks = match healthcheck_interval {
+            Some(interval) => match sink.inner.build_healthcheck(&cx) {
+                Ok(Some(healthcheck)) => Some(runtime_healthchecks(healthcheck, interval)),
+                Ok(None) => {
+                    warn!(
+                        message = "Sink healthcheck can't be repeated; ignoring `healthcheck.interval_secs`.",
+                        component_id = %key.id(),
+                    );
+                    None
+                }
+                Err(error) => {
+                    errors.push(format!("Sink \"{}\": {}", key, error));
+                    continue;
+                }
+            },
+            None => None,
+        };
+
         let (sink, healthcheck) = match sink.inner.build(cx).await {
             Err(error) => {
                 errors.push(format!("Sink \"{}\": {}", key, error));
@@ -285,19 +318,23 @@ pub async fn build_pieces(
 
             let mut rx = Box::pin(crate::utilization::wrap(rx));
 
-            sink.run(
-                rx.by_ref()
-                    .filter(|event| ready(filter_event_type(event, input_type)))
-                    .inspect(|event| {
-                        emit!(EventsReceived {
-                            count: 1,
-                            byte_size: event.size_of(),
-                        })
-                    })
-                    .take_until_if(tripwire),
-            )
-            .await
-            .map(|_| {
+            let runtime_healthchecks =
+                runtime_healthchecks.map(|task| tokio::spawn(task.in_current_span()));
+
+            let result = sink
+                .run(
+                    rx.by_ref()
+                        .filter(|event| ready(filter_event_type(event, input_type)))
+                        .map(sink_received)
+                        .take_until_if(tripwire),
+                )
+                .await;
+
+            if let Some(runtime_healthchecks) = runtime_healthchecks {
+                runtime_healthchecks.abort();
+            }
+
+            result.map(|_| {
                 debug!("Finished.");
                 TaskOutput::Sink(rx, acker)
             })
@@ -306,13 +343,22 @@ pub async fn build_pieces(
         let task = Task::new(key.clone(), typetag, sink);
 
         let component_key = key.clone();
+        let span = error_span!(
+            "healthcheck",
+            component_kind = "sink",
+            component_id = %key.id(),
+            component_scope = %key.scope(),
+            component_type = typetag,
+            // maintained for compatibility
+            component_name = %key.id(),
+        );
         let healthcheck_task = async move {
             if enable_healthcheck {
-                let duration = Duration::from_secs(10);
-                timeout(duration, healthcheck)
+                timeout(HEALTHCHECK_TIMEOUT, healthcheck)
                     .map(|result| match result {
                         Ok(Ok(_)) => {
                             info!("Healthcheck: Passed.");
+                            emit!(SinkHealthcheckCompleted { passed: true });
                             Ok(TaskOutput::Healthcheck)
                         }
                         Ok(Err(error)) => {
@@ -326,6 +372,7 @@ pub async fn build_pieces(
                                 // maintained for compatibility
                                 component_name = %component_key.id(),
                             );
+                            emit!(SinkHealthcheckCompleted { passed: false });
                             Err(())
                         }
                         Err(_) => {
@@ -338,6 +385,7 @@ pub async fn build_pieces(
                                 // maintained for compatibility
                                 component_name = %component_key.id(),
                             );
+                            emit!(SinkHealthcheckCompleted { passed: false });
                             Err(())
                         }
                     })
@@ -346,7 +394,8 @@ pub async fn build_pieces(
                 info!("Healthcheck: Disabled.");
                 Ok(TaskOutput::Healthcheck)
             }
-        };
+        }
+        .instrument(span);
 
         let healthcheck_task = Task::new(key.clone(), typetag, healthcheck_task);
 
@@ -378,6 +427,39 @@ pub async fn build_pieces(
     }
 }
 
+/// Counts an event received by a transform, and stamps when it was received so the
+/// latency of the transform can be measured once it's sent.
+fn transform_received(mut event: Event) -> Event {
+    emit!(EventsReceived {
+        count: 1,
+        byte_size: event.size_of(),
+    });
+    event.metadata_mut().set_received_at(Some(Instant::now()));
+    event
+}
+
+/// Counts an event received by a sink, and attaches a timer to it which measures the
+/// latency of the sink and the age of the event once the sink finalizes it.
+fn sink_received(mut event: Event) -> Event {
+    emit!(EventsReceived {
+        count: 1,
+        byte_size: event.size_of(),
+    });
+    let timer = BatchNotifier::with_timer(Instant::now(), event.metadata().ingest_timestamp());
+    event.add_batch_notifier(timer);
+    event
+}
+
+fn transform_sent(mut event: Event) -> Event {
+    if let Some(received_at) = event.metadata().received_at() {
+        emit!(ComponentLatency {
+            latency: received_at.elapsed(),
+        });
+        event.metadata_mut().set_received_at(None);
+    }
+    event
+}
+
 const fn filter_event_type(event: &Event, data_type: DataType) -> bool {
     match data_type {
         DataType::Any => true,
@@ -385,3 +467,21 @@ const fn filter_event_type(event: &Event, data_type: DataType) -> bool {
         DataType::Metric => matches!(event, Event::Metric(_)),
     }
 }
+
+/// Runs the healthcheck of a running sink every `period`.
+async fn runtime_healthchecks(healthcheck: HealthcheckBuilder, period: Duration) {
+    let mut interval = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+    loop {
+        interval.tick().await;
+
+        let result = timeout(HEALTHCHECK_TIMEOUT, healthcheck())
+            .await
+            .unwrap_or_else(|_| Err("Healthcheck timed out.".into()));
+        if let Err(error) = &result {
+            warn!(message = "Runtime healthcheck failed.", %error);
+        }
+        emit!(SinkHealthcheckCompleted {
+            passed: result.is_ok()
+        });
+    }
+}
@@ -2,15 +2,23 @@ use crate::config::ComponentKey;
 use crate::event::Event;
 use futures::{channel::mpsc, future, stream::Fuse, Sink, Stream, StreamExt};
 use std::{
+    collections::HashSet,
     fmt,
     pin::Pin,
+    sync::atomic::{AtomicU64, Ordering},
     task::{Context, Poll},
 };
 
 pub type RouterSink = Box<dyn Sink<Event, Error = ()> + 'static + Send>;
 
+/// Source of the ids assigned to events while they're correlated, unique across fanouts.
+static NEXT_CORRELATION_ID: AtomicU64 = AtomicU64::new(1);
+
 pub enum ControlMessage {
     Add(ComponentKey, RouterSink),
+    /// Like `Add`, but events are assigned a correlation id before being sent, so that they
+    /// can be matched up with what downstream components make of them.
+    AddCorrelated(ComponentKey, RouterSink),
     Remove(ComponentKey),
     /// Will stop accepting events until Some with given id is replaced.
     Replace(ComponentKey, Option<RouterSink>),
@@ -21,6 +29,7 @@ impl fmt::Debug for ControlMessage {
         write!(f, "ControlMessage::")?;
         match self {
             Self::Add(id, _) => write!(f, "Add({:?})", id),
+            Self::AddCorrelated(id, _) => write!(f, "AddCorrelated({:?})", id),
             Self::Remove(id) => write!(f, "Remove({:?})", id),
             Self::Replace(id, _) => write!(f, "Replace({:?})", id),
         }
@@ -31,6 +40,7 @@ pub type ControlChannel = mpsc::UnboundedSender<ControlMessage>;
 
 pub struct Fanout {
     sinks: Vec<(ComponentKey, Option<Pin<RouterSink>>)>,
+    correlated: HashSet<ComponentKey>,
     i: usize,
     control_channel: Fuse<mpsc::UnboundedReceiver<ControlMessage>>,
 }
@@ -41,6 +51,7 @@ impl Fanout {
 
         let fanout = Self {
             sinks: vec![],
+            correlated: HashSet::new(),
             i: 0,
             control_channel: control_rx.fuse(),
         };
@@ -62,6 +73,7 @@ impl Fanout {
         let i = i.expect("Didn't find output in fanout");
 
         let (_id, removed) = self.sinks.remove(i);
+        self.correlated.remove(id);
 
         if let Some(mut removed) = removed {
             tokio::spawn(future::poll_fn(move |cx| removed.as_mut().poll_close(cx)));
@@ -84,6 +96,10 @@ impl Fanout {
         while let Poll::Ready(Some(message)) = Pin::new(&mut self.control_channel).poll_next(cx) {
             match message {
                 ControlMessage::Add(id, sink) => self.add(id, sink),
+                ControlMessage::AddCorrelated(id, sink) => {
+                    self.add(id.clone(), sink);
+                    self.correlated.insert(id);
+                }
                 ControlMessage::Remove(id) => self.remove(&id),
                 ControlMessage::Replace(id, sink) => self.replace(id, sink),
             }
@@ -98,7 +114,8 @@ impl Fanout {
         if self.sinks.len() == 1 {
             Err(())
         } else {
-            self.sinks.remove(index);
+            let (id, _) = self.sinks.remove(index);
+            self.correlated.remove(&id);
             Ok(())
         }
     }
@@ -157,7 +174,14 @@ impl Sink<Event> for Fanout {
         Poll::Ready(Ok(()))
     }
 
-    fn start_send(mut self: Pin<&mut Self>, item: Event) -> Result<(), ()> {
+    fn start_send(mut self: Pin<&mut Self>, mut item: Event) -> Result<(), ()> {
+        // Events keep the id they were first assigned, so they can be followed through
+        // several components.
+        if !self.correlated.is_empty() && item.metadata().correlation_id().is_none() {
+            let id = NEXT_CORRELATION_ID.fetch_add(1, Ordering::Relaxed);
+            item.metadata_mut().set_correlation_id(Some(id));
+        }
+
         let mut i = 1;
         while let Some((_, sink)) = self.sinks.get_mut(i) {
             if let Some(sink) = sink.as_mut() {
@@ -451,6 +475,47 @@ mod tests {
         assert_eq!(collect_ready(rx_a2).await, &recs[2..]);
     }
 
+    #[tokio::test]
+    async fn fanout_correlates() {
+        let (tx_a, rx_a) = mpsc::unbounded();
+        let tx_a = Box::new(tx_a.sink_map_err(|_| unreachable!()));
+        let (tx_b, rx_b) = mpsc::unbounded();
+        let tx_b = Box::new(tx_b.sink_map_err(|_| unreachable!()));
+
+        let (mut fanout, fanout_control) = Fanout::new();
+
+        fanout.add(ComponentKey::from("a"), tx_a);
+        fanout_control
+            .unbounded_send(ControlMessage::AddCorrelated(ComponentKey::from("b"), tx_b))
+            .unwrap();
+
+        let recs = make_events(3);
+
+        fanout.send(recs[0].clone()).await.unwrap();
+        fanout.send(recs[1].clone()).await.unwrap();
+
+        fanout_control
+            .unbounded_send(ControlMessage::Remove(ComponentKey::from("b")))
+            .unwrap();
+
+        fanout.send(recs[2].clone()).await.unwrap();
+
+        let ids = |events: Vec<Event>| {
+            events
+                .iter()
+                .map(|event| event.metadata().correlation_id())
+                .collect::<Vec<_>>()
+        };
+        let ids_a = ids(collect_ready(rx_a).await);
+        let ids_b = ids(collect_ready(rx_b).await);
+
+        assert_eq!(ids_a.len(), 3);
+        assert!(ids_a[0].is_some() && ids_a[1].is_some());
+        assert_ne!(ids_a[0], ids_a[1]);
+        assert_eq!(ids_a[2], None);
+        assert_eq!(ids_b, &ids_a[..2]);
+    }
+
     #[tokio::test]
     async fn fanout_wait() {
         let (tx_a1, rx_a1) = mpsc::unbounded();
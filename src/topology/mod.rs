@@ -43,11 +43,17 @@ type BuiltBuffer = (
 
 type Outputs = HashMap<ComponentKey, fanout::ControlChannel>;
 
-// Watcher types for topology changes. These are currently specific to receiving
-// `Outputs`. This could be expanded in the future to send an enum of types if,
-// for example, this included a new 'Inputs' type.
-type WatchTx = watch::Sender<Outputs>;
-pub type WatchRx = watch::Receiver<Outputs>;
+/// The parts of the topology observed by 'tap': the output of each component, and the
+/// components each transform and sink receives its inputs from.
+#[derive(Debug, Clone, Default)]
+pub struct TapResource {
+    pub outputs: Outputs,
+    pub inputs: HashMap<ComponentKey, Vec<ComponentKey>>,
+}
+
+// Watcher types for topology changes.
+type WatchTx = watch::Sender<TapResource>;
+pub type WatchRx = watch::Receiver<TapResource>;
 
 pub async fn start_validated(
     config: Config,
@@ -66,6 +72,7 @@ pub async fn start_validated(
     }
     running_topology.connect_diff(&diff, &mut pieces).await;
     running_topology.spawn_diff(&diff, pieces);
+    running_topology.broadcast_changes();
 
     Some((running_topology, abort_rx))
 }
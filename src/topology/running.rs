@@ -1,7 +1,7 @@
 use crate::topology::builder;
 use crate::topology::fanout::{ControlChannel, ControlMessage};
 use crate::topology::{
-    build_or_log_errors, handle_errors, retain, take_healthchecks, BuiltBuffer, Outputs,
+    build_or_log_errors, handle_errors, retain, take_healthchecks, BuiltBuffer, TapResource,
     TaskHandle, WatchRx, WatchTx,
 };
 use crate::{
@@ -47,7 +47,7 @@ impl RunningTopology {
             source_tasks: HashMap::new(),
             tasks: HashMap::new(),
             abort_tx,
-            watch: watch::channel(HashMap::new()),
+            watch: watch::channel(TapResource::default()),
         }
     }
 
@@ -196,6 +196,7 @@ impl RunningTopology {
                 self.connect_diff(&diff, &mut new_pieces).await;
                 self.spawn_diff(&diff, new_pieces);
                 self.config = new_config;
+                self.broadcast_changes();
                 // We have successfully changed to new config.
                 return Ok(true);
             }
@@ -211,6 +212,7 @@ impl RunningTopology {
             {
                 self.connect_diff(&diff, &mut new_pieces).await;
                 self.spawn_diff(&diff, new_pieces);
+                self.broadcast_changes();
                 // We have successfully returned to old config.
                 return Ok(false);
             }
@@ -458,17 +460,33 @@ impl RunningTopology {
         for key in &diff.sinks.to_add {
             self.setup_inputs(key, new_pieces).await;
         }
+    }
 
-        // Broadcast changes to subscribers.
+    /// Broadcasts the outputs and inputs of the running components to subscribers. This
+    /// must be called once the config matches the connected topology.
+    pub(crate) fn broadcast_changes(&self) {
         if !self.watch.0.is_closed() {
-            self.watch
-                .0
-                .send(
-                    self.outputs
+            let inputs = self
+                .config
+                .transforms
+                .iter()
+                .map(|(key, transform)| (key, &transform.inputs))
+                .chain(
+                    self.config
+                        .sinks
                         .iter()
-                        .map(|item| (item.0.clone(), item.1.clone()))
-                        .collect::<HashMap<_, _>>(),
+                        .map(|(key, sink)| (key, &sink.inputs)),
                 )
+                .filter(|(key, _)| self.inputs.contains_key(key))
+                .map(|(key, inputs)| (key.clone(), inputs.clone()))
+                .collect();
+
+            self.watch
+                .0
+                .send(TapResource {
+                    outputs: self.outputs.clone(),
+                    inputs,
+                })
                 .expect("Couldn't broadcast config changes.");
         }
     }
@@ -719,10 +737,9 @@ impl RunningTopology {
         &self.config
     }
 
-    /// Subscribe to topology changes. This will receive an `Outputs` currently, but may be
-    /// expanded in the future to accommodate `Inputs`. This is used by the 'tap' API to observe
-    /// config changes, and re-wire tap sinks.
-    pub fn watch(&self) -> watch::Receiver<Outputs> {
+    /// Subscribe to topology changes, receiving the outputs and inputs of components. This is
+    /// used by the 'tap' API to observe config changes, and re-wire tap sinks.
+    pub fn watch(&self) -> watch::Receiver<TapResource> {
         self.watch.1.clone()
     }
 }
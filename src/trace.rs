@@ -1,10 +1,17 @@
-use crate::event::LogEvent;
+use crate::event::{LogEvent, Value};
 use metrics_tracing_context::MetricsLayer;
 use once_cell::sync::OnceCell;
-use std::sync::{Mutex, MutexGuard};
+use std::{
+    collections::BTreeMap,
+    convert::TryFrom,
+    fmt::Debug,
+    sync::{Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
 use tokio::sync::broadcast::{self, Receiver, Sender};
 use tracing::{
     dispatcher::{set_global_default, Dispatch},
+    field::{Field, Visit},
     span::Span,
     subscriber::Interest,
     Id, Metadata, Subscriber,
@@ -12,7 +19,10 @@ use tracing::{
 use tracing_core::span;
 use tracing_limit::RateLimitedLayer;
 use tracing_log::LogTracer;
-use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::{
+    layer::{Context, Layer, SubscriberExt},
+    registry::{LookupSpan, SpanRef},
+};
 
 /// BUFFER contains all of the internal log events generated by Vector
 /// before the topology has been initialized. It will be cleared (set to
@@ -24,6 +34,14 @@ static BUFFER: OnceCell<Mutex<Option<Vec<LogEvent>>>> = OnceCell::new();
 /// initialized.
 static SENDER: OnceCell<Sender<LogEvent>> = OnceCell::new();
 
+/// SPAN_SENDER holds the sender handle that will receive the start and
+/// end records of the spans created while it has receivers.
+static SPAN_SENDER: OnceCell<Sender<LogEvent>> = OnceCell::new();
+
+/// The span fields identifying the component a span belongs to, which are
+/// inherited by the spans nested in it and added to the events logged in them.
+const COMPONENT_FIELDS: [&str; 3] = ["component_kind", "component_id", "component_type"];
+
 pub use tracing_futures::Instrument;
 pub use tracing_tower::{InstrumentableService, InstrumentedService};
 
@@ -40,7 +58,8 @@ pub fn init(color: bool, json: bool, levels: &str) {
     let metrics_layer_enabled = metrics_layer_enabled();
 
     let subscriber = tracing_subscriber::registry::Registry::default()
-        .with(tracing_subscriber::filter::EnvFilter::from(levels));
+        .with(tracing_subscriber::filter::EnvFilter::from(levels))
+        .with(SpanBroadcastLayer);
 
     // dev note: we attempted to refactor to reduce duplication but it was starting to seem like
     // the refactored code would be introducing more complexity than it was worth to remove this
@@ -126,11 +145,233 @@ pub fn subscribe() -> TraceSubscription {
     TraceSubscription { buffer, receiver }
 }
 
+/// Subscribes to the internal log events generated from now on, leaving the
+/// events buffered before the topology started to `subscribe`.
+pub fn subscribe_logs() -> Receiver<LogEvent> {
+    SENDER.get_or_init(|| broadcast::channel(99).0).subscribe()
+}
+
+/// Subscribes to the start and end records of spans. Only spans created after
+/// subscribing are recorded.
+pub fn subscribe_spans() -> Receiver<LogEvent> {
+    SPAN_SENDER
+        .get_or_init(|| broadcast::channel(99).0)
+        .subscribe()
+}
+
+/// The span sender, if anything listens to it, so that spans aren't tracked otherwise.
+fn span_sender() -> Option<&'static Sender<LogEvent>> {
+    SPAN_SENDER
+        .get()
+        .filter(|sender| sender.receiver_count() > 0)
+}
+
+/// Tracks the spans enabled by the log level, and broadcasts their start and end.
+struct SpanBroadcastLayer;
+
+/// Stored in the extensions of the spans tracked by `SpanBroadcastLayer`.
+struct SpanTiming {
+    started: Instant,
+    entered: Option<Instant>,
+    busy: Duration,
+    fields: BTreeMap<String, Value>,
+    component: ComponentContext,
+}
+
+impl SpanTiming {
+    fn record(&self, id: &Id, parent_id: Option<Id>, meta: &Metadata<'_>, kind: &str) -> LogEvent {
+        let mut log = LogEvent::default();
+        log.insert("timestamp", chrono::Utc::now());
+        log.insert("kind", kind);
+        log.insert("span.id", id.into_u64().to_string());
+        if let Some(parent_id) = parent_id {
+            log.insert("span.parent_id", parent_id.into_u64().to_string());
+        }
+        log.insert("span.name", meta.name());
+        log.insert("span.target", meta.target());
+        log.insert("span.level", meta.level().to_string());
+        for (name, value) in &self.fields {
+            log.insert(format!("fields.{}", name), value.clone());
+        }
+        self.component.insert_into(&mut log);
+        log
+    }
+}
+
+/// The component fields of a span, stored in the extensions of the spans
+/// declaring any of them, whether or not spans are being broadcast, so that
+/// internal logs can be attributed to components.
+#[derive(Clone, Debug, Default)]
+struct ComponentContext(BTreeMap<String, Value>);
+
+impl ComponentContext {
+    fn insert_into(&self, log: &mut LogEvent) {
+        for (name, value) in &self.0 {
+            log.insert(name.as_str(), value.clone());
+        }
+    }
+}
+
+/// The component context of the closest span, starting from `span`, having one.
+fn component_context<'a, S: LookupSpan<'a>>(span: SpanRef<'a, S>) -> Option<ComponentContext> {
+    std::iter::successors(Some(span), |span| span.parent()).find_map(|span| {
+        let extensions = span.extensions();
+        extensions.get::<ComponentContext>().cloned()
+    })
+}
+
+impl<S> Layer<S> for SpanBroadcastLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let sender = span_sender();
+        let has_component = COMPONENT_FIELDS
+            .iter()
+            .any(|name| attrs.metadata().fields().field(name).is_some());
+        if sender.is_none() && !has_component {
+            return;
+        }
+        let span = match ctx.span(id) {
+            Some(span) => span,
+            None => return,
+        };
+
+        let mut fields = SpanFields::default();
+        attrs.record(&mut fields);
+        let fields = fields.0;
+
+        let mut component = span
+            .parent()
+            .and_then(component_context)
+            .unwrap_or_default();
+        if has_component {
+            for name in COMPONENT_FIELDS.iter() {
+                if let Some(value) = fields.get(*name) {
+                    component.0.insert((*name).to_owned(), value.clone());
+                }
+            }
+            span.extensions_mut().insert(component.clone());
+        }
+
+        if let Some(sender) = sender {
+            let timing = SpanTiming {
+                started: Instant::now(),
+                entered: None,
+                busy: Duration::default(),
+                fields,
+                component,
+            };
+            let parent_id = span.parent().map(|parent| parent.id());
+            let _ = sender.send(timing.record(id, parent_id, span.metadata(), "start"));
+            span.extensions_mut().insert(timing);
+        }
+    }
+
+    fn on_record(&self, id: &Id, values: &span::Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(timing) = span.extensions_mut().get_mut::<SpanTiming>() {
+                let mut fields = SpanFields::default();
+                values.record(&mut fields);
+                timing.fields.extend(fields.0);
+            }
+        }
+    }
+
+    fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(timing) = span.extensions_mut().get_mut::<SpanTiming>() {
+                timing.entered = Some(Instant::now());
+            }
+        }
+    }
+
+    fn on_exit(&self, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(timing) = span.extensions_mut().get_mut::<SpanTiming>() {
+                if let Some(entered) = timing.entered.take() {
+                    timing.busy += entered.elapsed();
+                }
+            }
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let span = match ctx.span(&id) {
+            Some(span) => span,
+            None => return,
+        };
+        let timing = match span.extensions_mut().remove::<SpanTiming>() {
+            Some(timing) => timing,
+            None => return,
+        };
+        if let Some(sender) = span_sender() {
+            let parent_id = span.parent().map(|parent| parent.id());
+            let mut log = timing.record(&id, parent_id, span.metadata(), "end");
+            log.insert("duration_seconds", timing.started.elapsed().as_secs_f64());
+            log.insert("busy_seconds", timing.busy.as_secs_f64());
+            let _ = sender.send(log);
+        }
+    }
+}
+
+/// Collects the fields of a span.
+#[derive(Default)]
+struct SpanFields(BTreeMap<String, Value>);
+
+impl Visit for SpanFields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_owned(), value.into());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.0
+            .insert(field.name().to_owned(), format!("{:?}", value).into());
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_owned(), value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        let value = match i64::try_from(value) {
+            Ok(value) => value.into(),
+            Err(_) => value.to_string().into(),
+        };
+        self.0.insert(field.name().to_owned(), value);
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_owned(), value.into());
+    }
+}
+
 struct BroadcastSubscriber<S> {
     subscriber: S,
 }
 
-impl<S: Subscriber + 'static> Subscriber for BroadcastSubscriber<S> {
+impl<S> BroadcastSubscriber<S>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    /// The component context of the span an event is logged in, if any.
+    fn event_component(&self, event: &tracing::Event<'_>) -> Option<ComponentContext> {
+        let id = if event.is_root() {
+            return None;
+        } else if let Some(id) = event.parent() {
+            id.clone()
+        } else {
+            let current = self.subscriber.current_span();
+            current.id()?.clone()
+        };
+        component_context(self.subscriber.span(&id)?)
+    }
+}
+
+impl<S> Subscriber for BroadcastSubscriber<S>
+where
+    S: Subscriber + for<'a> LookupSpan<'a> + 'static,
+{
     #[inline]
     fn enabled(&self, metadata: &Metadata<'_>) -> bool {
         self.subscriber.enabled(metadata)
@@ -153,11 +394,21 @@ impl<S: Subscriber + 'static> Subscriber for BroadcastSubscriber<S> {
 
     #[inline]
     fn event(&self, event: &tracing::Event<'_>) {
-        if let Some(buffer) = early_buffer().as_mut() {
-            buffer.push(event.into());
-        }
-        if let Some(sender) = SENDER.get() {
-            let _ = sender.send(event.into()); // Ignore errors
+        {
+            let mut buffer = early_buffer();
+            let sender = SENDER.get();
+            if buffer.is_some() || sender.is_some() {
+                let mut log = LogEvent::from(event);
+                if let Some(component) = self.event_component(event) {
+                    component.insert_into(&mut log);
+                }
+                if let Some(sender) = sender {
+                    let _ = sender.send(log.clone()); // Ignore errors
+                }
+                if let Some(buffer) = buffer.as_mut() {
+                    buffer.push(log);
+                }
+            }
         }
         self.subscriber.event(event)
     }
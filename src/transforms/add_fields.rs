@@ -5,6 +5,7 @@ use crate::{
     internal_events::{
         AddFieldsFieldNotOverwritten, AddFieldsFieldOverwritten, TemplateRenderingFailed,
     },
+    migrate::{vrl_literal, vrl_path, Migration},
     template::Template,
     transforms::{FunctionTransform, Transform},
 };
@@ -55,6 +56,34 @@ impl GenerateConfig for AddFieldsConfig {
     }
 }
 
+impl AddFieldsConfig {
+    /// The equivalent `remap` program, for `vector migrate`.
+    pub fn migrate(&self) -> crate::Result<Migration> {
+        let mut statements = Vec::new();
+        for (key, value) in self.fields.clone().all_fields() {
+            let value = Value::try_from(value)?;
+            if let Value::Bytes(bytes) = &value {
+                let template = Template::try_from(String::from_utf8_lossy(bytes).as_ref());
+                if template.map_or(false, |template| template.is_dynamic()) {
+                    return Err(
+                        format!("the template of {:?} has no equivalent in VRL", key).into(),
+                    );
+                }
+            }
+
+            let path = vrl_path(&key)?;
+            let assignment = format!("{} = {}", path, vrl_literal(&value));
+            statements.push(if self.overwrite {
+                assignment
+            } else {
+                format!("if !exists({}) {{ {} }}", path, assignment)
+            });
+        }
+
+        Ok(Migration::new(statements.join("\n")))
+    }
+}
+
 #[async_trait::async_trait]
 #[typetag::serde(name = "add_fields")]
 impl TransformConfig for AddFieldsConfig {
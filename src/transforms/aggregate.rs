@@ -12,6 +12,7 @@ use std::{
     pin::Pin,
     time::Duration,
 };
+use vector_core::time::Clock;
 
 #[derive(Deserialize, Serialize, Debug, Default, Clone)]
 #[serde(deny_unknown_fields, default)]
@@ -34,8 +35,8 @@ impl_generate_config_from_default!(AggregateConfig);
 #[async_trait::async_trait]
 #[typetag::serde(name = "aggregate")]
 impl TransformConfig for AggregateConfig {
-    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
-        Aggregate::new(self).map(Transform::task)
+    async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
+        Aggregate::new(self, context.clock.clone()).map(Transform::task)
     }
 
     fn input_type(&self) -> DataType {
@@ -58,13 +59,15 @@ type MetricEntry = (metric::MetricData, EventMetadata);
 #[derive(Debug)]
 pub struct Aggregate {
     interval: Duration,
+    clock: Clock,
     map: HashMap<metric::MetricSeries, MetricEntry>,
 }
 
 impl Aggregate {
-    pub fn new(config: &AggregateConfig) -> crate::Result<Self> {
+    pub fn new(config: &AggregateConfig, clock: Clock) -> crate::Result<Self> {
         Ok(Self {
             interval: Duration::from_millis(config.interval_ms),
+            clock,
             map: HashMap::new(),
         })
     }
@@ -115,14 +118,14 @@ impl TaskTransform for Aggregate {
     where
         Self: 'static,
     {
-        let mut flush_stream = tokio::time::interval(self.interval);
+        let mut flush_stream = self.clock.interval(self.interval);
 
         Box::pin(stream! {
             let mut output = Vec::new();
             let mut done = false;
             while !done {
                 tokio::select! {
-                    _ = flush_stream.tick() => {
+                    _ = flush_stream.next() => {
                         self.flush_into(&mut output);
                     },
                     maybe_event = input_rx.next() => {
@@ -165,9 +168,12 @@ mod tests {
 
     #[test]
     fn incremental() {
-        let mut agg = Aggregate::new(&AggregateConfig {
-            interval_ms: 1000_u64,
-        })
+        let mut agg = Aggregate::new(
+            &AggregateConfig {
+                interval_ms: 1000_u64,
+            },
+            Clock::default(),
+        )
         .unwrap();
 
         let counter_a_1 = make_metric(
@@ -235,9 +241,12 @@ mod tests {
 
     #[test]
     fn absolute() {
-        let mut agg = Aggregate::new(&AggregateConfig {
-            interval_ms: 1000_u64,
-        })
+        let mut agg = Aggregate::new(
+            &AggregateConfig {
+                interval_ms: 1000_u64,
+            },
+            Clock::default(),
+        )
         .unwrap();
 
         let gauge_a_1 = make_metric(
@@ -300,9 +309,12 @@ mod tests {
 
     #[test]
     fn conflicting_value_type() {
-        let mut agg = Aggregate::new(&AggregateConfig {
-            interval_ms: 1000_u64,
-        })
+        let mut agg = Aggregate::new(
+            &AggregateConfig {
+                interval_ms: 1000_u64,
+            },
+            Clock::default(),
+        )
         .unwrap();
 
         let counter = make_metric(
@@ -357,9 +369,12 @@ mod tests {
 
     #[test]
     fn conflicting_kinds() {
-        let mut agg = Aggregate::new(&AggregateConfig {
-            interval_ms: 1000_u64,
-        })
+        let mut agg = Aggregate::new(
+            &AggregateConfig {
+                interval_ms: 1000_u64,
+            },
+            Clock::default(),
+        )
         .unwrap();
 
         let incremental = make_metric(
@@ -515,7 +530,7 @@ interval_ms = 999999
 
         tokio::time::pause();
 
-        // tokio interval is always immediately ready, so we poll once to make sure
+        // The interval is always immediately ready, so we poll once to make sure
         // we trip it/set the interval in the future
         assert_eq!(Poll::Pending, futures::poll!(out_stream.next()));
 
@@ -2,6 +2,7 @@ use crate::{
     config::{DataType, TransformConfig, TransformContext, TransformDescription},
     event::{Event, LogEvent, Value},
     internal_events::CoercerConversionFailed,
+    migrate::{vrl_conversions, vrl_path, Migration},
     transforms::{FunctionTransform, Transform},
     types::{parse_conversion_map, Conversion},
 };
@@ -24,6 +25,37 @@ inventory::submit! {
 
 impl_generate_config_from_default!(CoercerConfig);
 
+impl CoercerConfig {
+    /// The equivalent `remap` program, for `vector migrate`.
+    pub fn migrate(&self) -> crate::Result<Migration> {
+        let mut source = vrl_conversions("", &self.types)?;
+
+        if self.drop_unspecified {
+            let mut fields = self.types.keys().collect::<Vec<_>>();
+            fields.sort();
+
+            source.push_str("\nkept = {}");
+            for field in fields {
+                if field.contains(|c| c == '.' || c == '[') {
+                    return Err(format!(
+                        "nested field {:?} can't be kept with `drop_unspecified`",
+                        field
+                    )
+                    .into());
+                }
+                let path = vrl_path(field)?;
+                source.push_str(&format!(
+                    "\nif exists({path}) {{ kept{path} = {path} }}",
+                    path = path
+                ));
+            }
+            source.push_str("\n. = kept");
+        }
+
+        Ok(Migration::new(source).timezone(self.timezone))
+    }
+}
+
 #[async_trait::async_trait]
 #[typetag::serde(name = "coercer")]
 impl TransformConfig for CoercerConfig {
@@ -1,11 +1,15 @@
 use crate::{
     conditions::{AnyCondition, Condition},
-    config::{DataType, GenerateConfig, TransformConfig, TransformContext, TransformDescription},
+    config::{
+        DataType, GenerateConfig, TransformConfig, TransformContext, TransformDescription,
+        TransformSchema,
+    },
     event::Event,
     internal_events::FilterEventDiscarded,
     transforms::{FunctionTransform, Transform},
 };
 use serde::{Deserialize, Serialize};
+use vrl::prelude::TypeDef;
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(deny_unknown_fields)]
@@ -47,6 +51,10 @@ impl TransformConfig for FilterConfig {
     fn transform_type(&self) -> &'static str {
         "filter"
     }
+
+    fn schema(&self, input: &TypeDef) -> TransformSchema {
+        TransformSchema::passthrough(input)
+    }
 }
 
 #[derive(Derivative, Clone)]
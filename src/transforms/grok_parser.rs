@@ -2,6 +2,7 @@ use crate::{
     config::{log_schema, DataType, TransformConfig, TransformContext, TransformDescription},
     event::{Event, PathComponent, PathIter, Value},
     internal_events::{GrokParserConversionFailed, GrokParserFailedMatch, GrokParserMissingField},
+    migrate::{vrl_conversions, vrl_field_or_message, vrl_string, Migration},
     transforms::{FunctionTransform, Transform},
     types::{parse_conversion_map, Conversion},
 };
@@ -37,6 +38,28 @@ inventory::submit! {
 
 impl_generate_config_from_default!(GrokParserConfig);
 
+impl GrokParserConfig {
+    /// The equivalent `remap` program, for `vector migrate`.
+    pub fn migrate(&self) -> crate::Result<Migration> {
+        let field = vrl_field_or_message(self.field.as_deref())?;
+
+        let mut body = vec![];
+        if self.drop_field {
+            body.push(format!("del({})", field));
+        }
+        body.push(". = merge(., parsed)".to_owned());
+        body.push(vrl_conversions("", &self.types)?);
+
+        Ok(Migration::new(format!(
+            "parsed, err = parse_grok({}, {})\nif err == null {{\n{}\n}}",
+            field,
+            vrl_string(&self.pattern),
+            body.join("\n")
+        ))
+        .timezone(self.timezone))
+    }
+}
+
 #[async_trait::async_trait]
 #[typetag::serde(name = "grok_parser")]
 impl TransformConfig for GrokParserConfig {
@@ -2,6 +2,7 @@ use crate::{
     config::{log_schema, DataType, TransformConfig, TransformContext, TransformDescription},
     event::Event,
     internal_events::{JsonParserFailedParse, JsonParserTargetExists},
+    migrate::{vrl_field_or_message, vrl_path, Migration},
     transforms::{FunctionTransform, Transform},
 };
 use serde::{Deserialize, Serialize};
@@ -25,6 +26,47 @@ inventory::submit! {
 
 impl_generate_config_from_default!(JsonParserConfig);
 
+impl JsonParserConfig {
+    /// The equivalent `remap` program, for `vector migrate`.
+    pub fn migrate(&self) -> crate::Result<Migration> {
+        let field = vrl_field_or_message(self.field.as_deref())?;
+
+        let mut body = Vec::new();
+        match &self.target_field {
+            Some(target_field) => {
+                let target = vrl_path(target_field)?;
+                if !self.overwrite_target.unwrap_or(false) {
+                    body.push(format!("if !exists({}) {{", target));
+                }
+                if self.drop_field {
+                    body.push(format!("del({})", field));
+                }
+                body.push(format!("{} = parsed", target));
+                if !self.overwrite_target.unwrap_or(false) {
+                    body.push("}".to_owned());
+                }
+            }
+            None => {
+                if self.drop_field {
+                    body.push(format!("del({})", field));
+                }
+                body.push(". = merge(., object!(parsed))".to_owned());
+            }
+        }
+
+        let mut source = format!(
+            "parsed, err = parse_json({})\nif err == null && is_object(parsed) {{\n{}\n}}",
+            field,
+            body.join("\n")
+        );
+        if self.drop_invalid {
+            source.push_str(" else {\nabort\n}");
+        }
+
+        Ok(Migration::new(source).drop_on_abort(self.drop_invalid))
+    }
+}
+
 #[async_trait::async_trait]
 #[typetag::serde(name = "json_parser")]
 impl TransformConfig for JsonParserConfig {
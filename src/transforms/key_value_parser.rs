@@ -2,6 +2,7 @@ use crate::{
     config::{log_schema, DataType, TransformConfig, TransformContext, TransformDescription},
     event::{Event, Value},
     internal_events::{KeyValueFieldDoesNotExist, KeyValueParseFailed, KeyValueTargetExists},
+    migrate::{vrl_conversions, vrl_field_or_message, vrl_path, vrl_string, Migration},
     transforms::{FunctionTransform, Transform},
     types::{parse_conversion_map, Conversion},
 };
@@ -34,6 +35,56 @@ inventory::submit! {
 
 impl_generate_config_from_default!(KeyValueConfig);
 
+impl KeyValueConfig {
+    /// The equivalent `remap` program, for `vector migrate`.
+    pub fn migrate(&self) -> crate::Result<Migration> {
+        if self.trim_key.is_some() || self.trim_value.is_some() {
+            return Err("`trim_key` and `trim_value` have no equivalent in VRL".into());
+        }
+
+        let field = vrl_field_or_message(self.field.as_deref())?;
+        let field_split = match self.field_split.as_deref() {
+            None | Some("") => "=",
+            Some(field_split) => field_split,
+        };
+        let separator = self.separator.as_deref().unwrap_or(" ");
+
+        let mut body = Vec::new();
+        match &self.target_field {
+            Some(target_field) => {
+                let target = vrl_path(target_field)?;
+                if !self.overwrite_target {
+                    body.push(format!("if !exists({}) {{", target));
+                }
+                if self.drop_field && target != field {
+                    body.push(format!("del({})", field));
+                }
+                body.push(format!("{} = parsed", target));
+                body.push(vrl_conversions("", &self.types)?);
+                if !self.overwrite_target {
+                    body.push("}".to_owned());
+                }
+            }
+            None => {
+                if self.drop_field {
+                    body.push(format!("del({})", field));
+                }
+                body.push(". = merge(., parsed)".to_owned());
+                body.push(vrl_conversions("", &self.types)?);
+            }
+        }
+
+        Ok(Migration::new(format!(
+            "parsed, err = parse_key_value({}, key_value_delimiter: {}, field_delimiter: {})\nif err == null {{\n{}\n}}",
+            field,
+            vrl_string(field_split),
+            vrl_string(separator),
+            body.join("\n")
+        ))
+        .timezone(self.timezone))
+    }
+}
+
 #[async_trait::async_trait]
 #[typetag::serde(name = "key_value_parser")]
 impl TransformConfig for KeyValueConfig {
@@ -2,6 +2,7 @@ use crate::{
     config::{DataType, TransformConfig, TransformContext, TransformDescription},
     event::{Event, Value},
     internal_events::{LogfmtParserConversionFailed, LogfmtParserMissingField},
+    migrate::{vrl_conversions, vrl_field_or_message, Migration},
     transforms::{FunctionTransform, Transform},
     types::{parse_conversion_map, Conversion},
 };
@@ -25,6 +26,27 @@ inventory::submit! {
 
 impl_generate_config_from_default!(LogfmtConfig);
 
+impl LogfmtConfig {
+    /// The equivalent `remap` program, for `vector migrate`.
+    pub fn migrate(&self) -> crate::Result<Migration> {
+        let field = vrl_field_or_message(self.field.as_deref())?;
+
+        let mut body = vec![];
+        if self.drop_field {
+            body.push(format!("del({})", field));
+        }
+        body.push(". = merge(., parsed)".to_owned());
+        body.push(vrl_conversions("", &self.types)?);
+
+        Ok(Migration::new(format!(
+            "parsed, err = parse_logfmt({})\nif err == null {{\n{}\n}}",
+            field,
+            body.join("\n")
+        ))
+        .timezone(self.timezone))
+    }
+}
+
 #[async_trait::async_trait]
 #[typetag::serde(name = "logfmt_parser")]
 impl TransformConfig for LogfmtConfig {
@@ -8,7 +8,10 @@ use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
 use std::path::PathBuf;
 pub use vector_core::event::lua;
-use vector_core::transform::runtime_transform::{RuntimeTransform, Timer};
+use vector_core::{
+    time::Clock,
+    transform::runtime_transform::{RuntimeTransform, Timer},
+};
 
 #[derive(Debug, Snafu)]
 pub enum BuildError {
@@ -87,8 +90,8 @@ struct TimerConfig {
 // possible configuration options for `transforms` section, but such internal name should not
 // be exposed to users.
 impl LuaConfig {
-    pub fn build(&self) -> crate::Result<Transform> {
-        Lua::new(self).map(Transform::task)
+    pub fn build(&self, clock: Clock) -> crate::Result<Transform> {
+        Lua::new(self, clock).map(Transform::task)
     }
 
     pub const fn input_type(&self) -> DataType {
@@ -119,6 +122,7 @@ pub struct Lua {
     hook_process: mlua::RegistryKey,
     hook_shutdown: Option<mlua::RegistryKey>,
     timers: Vec<(Timer, mlua::RegistryKey)>,
+    clock: Clock,
 }
 
 // Helper to create `RegistryKey` from Lua function code
@@ -129,7 +133,7 @@ fn make_registry_value(lua: &mlua::Lua, source: &str) -> mlua::Result<mlua::Regi
 }
 
 impl Lua {
-    pub fn new(config: &LuaConfig) -> crate::Result<Self> {
+    pub fn new(config: &LuaConfig, clock: Clock) -> crate::Result<Self> {
         // In order to support loading C modules in Lua, we need to create unsafe instance
         // without debug library.
         let lua = unsafe {
@@ -194,6 +198,7 @@ impl Lua {
             hook_init,
             hook_process,
             hook_shutdown,
+            clock,
         })
     }
 
@@ -329,6 +334,10 @@ impl RuntimeTransform for Lua {
     fn timers(&self) -> Vec<Timer> {
         self.timers.iter().map(|(timer, _)| *timer).collect()
     }
+
+    fn clock(&self) -> Clock {
+        self.clock.clone()
+    }
 }
 
 #[cfg(test)]
@@ -353,7 +362,7 @@ mod tests {
     use futures::{stream, StreamExt};
 
     fn from_config(config: &str) -> crate::Result<Box<Lua>> {
-        Lua::new(&toml::from_str(config).unwrap()).map(Box::new)
+        Lua::new(&toml::from_str(config).unwrap(), Clock::default()).map(Box::new)
     }
 
     #[tokio::test]
@@ -14,6 +14,7 @@ use std::{
     pin::Pin,
     time::{Duration, Instant},
 };
+use vector_core::time::Clock;
 
 mod merge_strategy;
 
@@ -52,7 +53,7 @@ impl_generate_config_from_default!(ReduceConfig);
 #[typetag::serde(name = "reduce")]
 impl TransformConfig for ReduceConfig {
     async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
-        Reduce::new(self, &context.enrichment_tables).map(Transform::task)
+        Reduce::new(self, &context.enrichment_tables, context.clock.clone()).map(Transform::task)
     }
 
     fn input_type(&self) -> DataType {
@@ -76,10 +77,10 @@ struct ReduceState {
 }
 
 impl ReduceState {
-    fn new(e: LogEvent, strategies: &IndexMap<String, MergeStrategy>) -> Self {
+    fn new(e: LogEvent, strategies: &IndexMap<String, MergeStrategy>, now: Instant) -> Self {
         let (fields, metadata) = e.into_parts();
         Self {
-            stale_since: Instant::now(),
+            stale_since: now,
             fields: fields
                 .into_iter()
                 .filter_map(|(k, v)| {
@@ -100,7 +101,12 @@ impl ReduceState {
         }
     }
 
-    fn add_event(&mut self, e: LogEvent, strategies: &IndexMap<String, MergeStrategy>) {
+    fn add_event(
+        &mut self,
+        e: LogEvent,
+        strategies: &IndexMap<String, MergeStrategy>,
+        now: Instant,
+    ) {
         let (fields, metadata) = e.into_parts();
         self.metadata.merge(metadata);
 
@@ -128,7 +134,7 @@ impl ReduceState {
                 }
             }
         }
-        self.stale_since = Instant::now();
+        self.stale_since = now;
     }
 
     fn flush(mut self) -> LogEvent {
@@ -152,12 +158,14 @@ pub struct Reduce {
     reduce_merge_states: HashMap<Discriminant, ReduceState>,
     ends_when: Option<Box<dyn Condition>>,
     starts_when: Option<Box<dyn Condition>>,
+    clock: Clock,
 }
 
 impl Reduce {
     pub fn new(
         config: &ReduceConfig,
         enrichment_tables: &enrichment::TableRegistry,
+        clock: Clock,
     ) -> crate::Result<Self> {
         if config.ends_when.is_some() && config.starts_when.is_some() {
             return Err("only one of `ends_when` and `starts_when` can be provided".into());
@@ -183,13 +191,15 @@ impl Reduce {
             reduce_merge_states: HashMap::new(),
             ends_when,
             starts_when,
+            clock,
         })
     }
 
     fn flush_into(&mut self, output: &mut Vec<Event>) {
+        let now = self.clock.now();
         let mut flush_discriminants = Vec::new();
         for (k, t) in &self.reduce_merge_states {
-            if t.stale_since.elapsed() >= self.expire_after {
+            if now.saturating_duration_since(t.stale_since) >= self.expire_after {
                 flush_discriminants.push(k.clone());
             }
         }
@@ -208,12 +218,15 @@ impl Reduce {
     }
 
     fn push_or_new_reduce_state(&mut self, event: LogEvent, discriminant: Discriminant) {
+        let now = self.clock.now();
         match self.reduce_merge_states.entry(discriminant) {
             hash_map::Entry::Vacant(entry) => {
-                entry.insert(ReduceState::new(event, &self.merge_strategies));
+                entry.insert(ReduceState::new(event, &self.merge_strategies, now));
             }
             hash_map::Entry::Occupied(mut entry) => {
-                entry.get_mut().add_event(event, &self.merge_strategies);
+                entry
+                    .get_mut()
+                    .add_event(event, &self.merge_strategies, now);
             }
         }
     }
@@ -242,10 +255,10 @@ impl Reduce {
         } else if ends_here {
             output.push(match self.reduce_merge_states.remove(&discriminant) {
                 Some(mut state) => {
-                    state.add_event(event, &self.merge_strategies);
+                    state.add_event(event, &self.merge_strategies, self.clock.now());
                     state.flush().into()
                 }
-                None => ReduceState::new(event, &self.merge_strategies)
+                None => ReduceState::new(event, &self.merge_strategies, self.clock.now())
                     .flush()
                     .into(),
             })
@@ -269,14 +282,14 @@ impl TaskTransform for Reduce {
 
         let poll_period = me.flush_period;
 
-        let mut flush_stream = tokio::time::interval(poll_period);
+        let mut flush_stream = me.clock.interval(poll_period);
 
         Box::pin(
             stream! {
               loop {
                 let mut output = Vec::new();
                 let done = tokio::select! {
-                    _ = flush_stream.tick() => {
+                    _ = flush_stream.next() => {
                       me.flush_into(&mut output);
                       false
                     }
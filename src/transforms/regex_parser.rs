@@ -5,6 +5,7 @@ use crate::{
         RegexParserConversionFailed, RegexParserFailedMatch, RegexParserMissingField,
         RegexParserTargetExists,
     },
+    migrate::{vrl_conversions, vrl_field_or_message, vrl_path, vrl_regex, Migration},
     transforms::{FunctionTransform, Transform},
     types::{parse_check_conversion_map, Conversion},
 };
@@ -43,6 +44,64 @@ inventory::submit! {
 
 impl_generate_config_from_default!(RegexParserConfig);
 
+impl RegexParserConfig {
+    /// The equivalent `remap` program, for `vector migrate`.
+    pub fn migrate(&self) -> crate::Result<Migration> {
+        let patterns = match (&self.regex, self.patterns.len()) {
+            (None, 0) => return Err("`patterns` is empty".into()),
+            (None, _) => self.patterns.clone(),
+            (Some(regex), 0) => vec![regex.clone()],
+            _ => return Err("`patterns = [...]` is not defined".into()),
+        };
+        let field = vrl_field_or_message(self.field.as_deref())?;
+
+        let mut statements = Vec::new();
+        for (index, pattern) in patterns.iter().enumerate() {
+            let parse = format!(
+                "parsed, err = parse_regex({}, {})",
+                field,
+                vrl_regex(pattern)
+            );
+            statements.push(if index == 0 {
+                parse
+            } else {
+                format!("if err != null {{ {} }}", parse)
+            });
+        }
+
+        let mut body = vec![vrl_conversions("parsed", &self.types)?];
+        match &self.target_field {
+            Some(target_field) => {
+                let target = vrl_path(target_field)?;
+                if !self.overwrite_target {
+                    body.push(format!("if !exists({}) {{", target));
+                }
+                if self.drop_field {
+                    body.push(format!("del({})", field));
+                }
+                body.push(format!("{} = parsed", target));
+                if !self.overwrite_target {
+                    body.push("}".to_owned());
+                }
+            }
+            None => {
+                if self.drop_field {
+                    body.push(format!("del({})", field));
+                }
+                body.push(". = merge(., parsed)".to_owned());
+            }
+        }
+        statements.push(format!("if err == null {{\n{}\n}}", body.join("\n")));
+        if self.drop_failed {
+            statements.push("if err != null { abort }".to_owned());
+        }
+
+        Ok(Migration::new(statements.join("\n"))
+            .drop_on_abort(self.drop_failed)
+            .timezone(self.timezone))
+    }
+}
+
 #[async_trait::async_trait]
 #[typetag::serde(name = "regex_parser")]
 impl TransformConfig for RegexParserConfig {
@@ -1,5 +1,5 @@
 use crate::{
-    config::{DataType, TransformConfig, TransformContext, TransformDescription},
+    config::{DataType, TransformConfig, TransformContext, TransformDescription, TransformSchema},
     event::{Event, VrlTarget},
     internal_events::{RemapMappingAbort, RemapMappingError},
     transforms::{FunctionTransform, Transform},
@@ -9,11 +9,13 @@ use crate::{
 use serde::{Deserialize, Serialize};
 use shared::TimeZone;
 use snafu::{ResultExt, Snafu};
+use std::collections::HashSet;
 use std::fs::File;
 use std::io::{self, Read};
 use std::path::PathBuf;
-use vrl::diagnostic::Formatter;
-use vrl::{Program, Runtime, Terminate};
+use vrl::diagnostic::{DiagnosticError, Formatter};
+use vrl::prelude::TypeDef;
+use vrl::{Function, Program, Runtime, Terminate};
 
 #[derive(Deserialize, Serialize, Debug, Clone, Derivative)]
 #[serde(deny_unknown_fields, default)]
@@ -28,6 +30,34 @@ pub struct RemapConfig {
     pub drop_on_abort: bool,
 }
 
+impl RemapConfig {
+    fn source(&self) -> Result<String> {
+        match (&self.source, &self.file) {
+            (Some(source), None) => Ok(source.to_owned()),
+            (None, Some(path)) => {
+                let mut buffer = String::new();
+
+                File::open(path)
+                    .with_context(|| FileOpenFailed { path })?
+                    .read_to_string(&mut buffer)
+                    .with_context(|| FileReadFailed { path })?;
+
+                Ok(buffer)
+            }
+            _ => Err(Box::new(BuildError::SourceAndOrFile)),
+        }
+    }
+}
+
+/// The VRL error code for aborting on errors of an infallible function call.
+const ABORT_INFALLIBLE_ERROR_CODE: usize = 620;
+
+fn functions() -> Vec<Box<dyn Function>> {
+    let mut functions = vrl_stdlib::all();
+    functions.append(&mut enrichment::vrl_functions());
+    functions
+}
+
 inventory::submit! {
     TransformDescription::new::<RemapConfig>("remap")
 }
@@ -52,6 +82,80 @@ impl TransformConfig for RemapConfig {
     fn transform_type(&self) -> &'static str {
         "remap"
     }
+
+    fn schema(&self, input: &TypeDef) -> TransformSchema {
+        // Without a known input schema there is nothing to check the program
+        // against.
+        if input.is_unknown() {
+            return TransformSchema::passthrough(input);
+        }
+
+        let source = match self.source() {
+            Ok(source) => source,
+            Err(_) => return TransformSchema::passthrough(&TypeDef::new().unknown()),
+        };
+        let functions = functions();
+        let enrichment_tables = enrichment::TableRegistry::default();
+
+        // Programs that don't compile on their own are reported when building
+        // the transform.
+        let mut state = vrl::state::Compiler::new();
+        state.set_external_context(Some(Box::new(enrichment_tables.clone())));
+        if vrl::compile_with_state(&source, &functions, &mut state).is_err() {
+            return TransformSchema::passthrough(&TypeDef::new().unknown());
+        }
+
+        let mut state = vrl::state::Compiler::new_with_type_def(input.clone());
+        state.set_external_context(Some(Box::new(enrichment_tables)));
+
+        let result = vrl::compile_with_state(&source, &functions, &mut state);
+
+        let mut schema = TransformSchema::passthrough(state.target_type_def().unwrap_or(input));
+
+        // Error handling that becomes unnecessary with known input types is
+        // harmless, anything else only fails because of the upstream types.
+        let (can_fail, can_abort) = match result {
+            Ok(program) => (program.can_fail(), program.can_abort()),
+            Err(diagnostics) => {
+                let diagnostics = diagnostics
+                    .into_iter()
+                    .filter(|diagnostic| diagnostic.code() != ABORT_INFALLIBLE_ERROR_CODE)
+                    .collect::<Vec<_>>();
+
+                if !diagnostics.is_empty() {
+                    schema.warnings.push(format!(
+                        "fails to compile against the schema of its input events:\n{}",
+                        Formatter::new(&source, diagnostics)
+                    ));
+                }
+
+                (true, true)
+            }
+        };
+
+        let mut reported = HashSet::new();
+        for query in state.external_queries() {
+            let never_exists = query
+                .target
+                .as_ref()
+                .map_or(false, |target| target.path_never_exists(&query.path));
+
+            if never_exists && reported.insert(query.path.to_string()) {
+                schema.warnings.push(format!(
+                    "reads field \".{}\", which can never exist in its input events.",
+                    query.path
+                ));
+            }
+        }
+
+        // Events are forwarded unmodified when the program fails or aborts,
+        // unless configured to drop them.
+        if (can_fail && !self.drop_on_error) || (can_abort && !self.drop_on_abort) {
+            schema.output = schema.output.merge(input.clone());
+        }
+
+        schema
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -67,23 +171,8 @@ impl Remap {
         config: RemapConfig,
         enrichment_tables: &enrichment::TableRegistry,
     ) -> crate::Result<Self> {
-        let source = match (&config.source, &config.file) {
-            (Some(source), None) => source.to_owned(),
-            (None, Some(path)) => {
-                let mut buffer = String::new();
-
-                File::open(path)
-                    .with_context(|| FileOpenFailed { path })?
-                    .read_to_string(&mut buffer)
-                    .with_context(|| FileReadFailed { path })?;
-
-                buffer
-            }
-            _ => return Err(Box::new(BuildError::SourceAndOrFile)),
-        };
-
-        let mut functions = vrl_stdlib::all();
-        functions.append(&mut enrichment::vrl_functions());
+        let source = config.source()?;
+        let functions = functions();
 
         let program = vrl::compile(
             &source,
@@ -124,7 +213,8 @@ impl FunctionTransform for Remap {
 
         let mut runtime = Runtime::default();
 
-        let result = runtime.resolve(&mut target, &self.program, &self.timezone);
+        let result = debug_span!("vrl")
+            .in_scope(|| runtime.resolve(&mut target, &self.program, &self.timezone));
 
         match result {
             Ok(_) => {
@@ -465,4 +555,73 @@ mod tests {
             )
         );
     }
+
+    fn schema_config(source: &str) -> RemapConfig {
+        RemapConfig {
+            source: Some(source.to_owned()),
+            drop_on_error: true,
+            ..Default::default()
+        }
+    }
+
+    fn message_schema() -> TypeDef {
+        crate::config::schema::log_fields(vec![
+            ("message", TypeDef::new().bytes()),
+            ("host", TypeDef::new().bytes()),
+        ])
+    }
+
+    #[test]
+    fn schema_reads_missing_field() {
+        let schema = schema_config(indoc! {r#"
+            del(.host)
+            .copy = .message
+            .host_copy = .host
+            .other = .missing
+            .missing = "now it exists"
+            .again = .missing
+        "#})
+        .schema(&message_schema());
+
+        assert_eq!(
+            schema.warnings,
+            vec![
+                "reads field \".host\", which can never exist in its input events.",
+                "reads field \".missing\", which can never exist in its input events.",
+            ]
+        );
+        assert!(schema.preserves_data_type);
+    }
+
+    #[test]
+    fn schema_output() {
+        let schema = schema_config(indoc! {r#"
+            del(.host)
+            .level = "info"
+        "#})
+        .schema(&message_schema());
+
+        assert!(schema.warnings.is_empty());
+        assert!(schema
+            .output
+            .path_never_exists(&lookup::LookupBuf::from_str("host").unwrap()));
+        assert!(!schema
+            .output
+            .path_never_exists(&lookup::LookupBuf::from_str("level").unwrap()));
+    }
+
+    #[test]
+    fn schema_ignores_unneeded_error_handling() {
+        let schema = schema_config(".upper = upcase!(.message)").schema(&message_schema());
+
+        assert!(schema.warnings.is_empty());
+    }
+
+    #[test]
+    fn schema_unknown_input() {
+        let schema = schema_config(".other = .missing").schema(&TypeDef::new().unknown());
+
+        assert!(schema.warnings.is_empty());
+        assert!(schema.output.is_unknown());
+    }
 }
@@ -1,10 +1,15 @@
 use crate::{
-    config::{DataType, GenerateConfig, TransformConfig, TransformContext, TransformDescription},
+    config::{
+        schema, DataType, GenerateConfig, TransformConfig, TransformContext, TransformDescription,
+        TransformSchema,
+    },
     event::Event,
     internal_events::RemoveFieldsFieldMissing,
+    migrate::{vrl_path, Migration},
     transforms::{FunctionTransform, Transform},
 };
 use serde::{Deserialize, Serialize};
+use vrl::prelude::TypeDef;
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
@@ -33,6 +38,23 @@ impl GenerateConfig for RemoveFieldsConfig {
     }
 }
 
+impl RemoveFieldsConfig {
+    /// The equivalent `remap` program, for `vector migrate`.
+    pub fn migrate(&self) -> crate::Result<Migration> {
+        if self.drop_empty.unwrap_or(false) {
+            return Err("`drop_empty` has no equivalent in VRL".into());
+        }
+
+        let statements = self
+            .fields
+            .iter()
+            .map(|field| Ok(format!("del({})", vrl_path(field)?)))
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        Ok(Migration::new(statements.join("\n")))
+    }
+}
+
 #[async_trait::async_trait]
 #[typetag::serde(name = "remove_fields")]
 impl TransformConfig for RemoveFieldsConfig {
@@ -52,6 +74,16 @@ impl TransformConfig for RemoveFieldsConfig {
     fn transform_type(&self) -> &'static str {
         "remove_fields"
     }
+
+    fn schema(&self, input: &TypeDef) -> TransformSchema {
+        let output = self
+            .fields
+            .iter()
+            .filter_map(|field| schema::field_lookup(field))
+            .fold(input.clone(), |output, path| output.remove_path(&path));
+
+        TransformSchema::passthrough(&output)
+    }
 }
 
 impl RemoveFields {
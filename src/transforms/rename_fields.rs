@@ -2,6 +2,7 @@ use crate::{
     config::{DataType, GenerateConfig, TransformConfig, TransformContext, TransformDescription},
     event::Event,
     internal_events::{RenameFieldsFieldDoesNotExist, RenameFieldsFieldOverwritten},
+    migrate::{vrl_path, Migration},
     serde::Fields,
     transforms::{FunctionTransform, Transform},
 };
@@ -31,6 +32,31 @@ impl GenerateConfig for RenameFieldsConfig {
     }
 }
 
+impl RenameFieldsConfig {
+    /// The equivalent `remap` program, for `vector migrate`.
+    pub fn migrate(&self) -> crate::Result<Migration> {
+        if self.drop_empty.unwrap_or(false) {
+            return Err("`drop_empty` has no equivalent in VRL".into());
+        }
+
+        let statements = self
+            .fields
+            .clone()
+            .all_fields()
+            .map(|(from, to)| {
+                let from = vrl_path(&from)?;
+                Ok(format!(
+                    "if exists({from}) {{ {to} = del({from}) }}",
+                    from = from,
+                    to = vrl_path(&to)?
+                ))
+            })
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        Ok(Migration::new(statements.join("\n")))
+    }
+}
+
 #[async_trait::async_trait]
 #[typetag::serde(name = "rename_fields")]
 impl TransformConfig for RenameFieldsConfig {
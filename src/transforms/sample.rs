@@ -1,11 +1,15 @@
 use crate::{
     conditions::{AnyCondition, Condition},
-    config::{DataType, GenerateConfig, TransformConfig, TransformContext, TransformDescription},
+    config::{
+        DataType, GenerateConfig, TransformConfig, TransformContext, TransformDescription,
+        TransformSchema,
+    },
     event::Event,
     internal_events::SampleEventDiscarded,
     transforms::{FunctionTransform, Transform},
 };
 use serde::{Deserialize, Serialize};
+use vrl::prelude::TypeDef;
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
@@ -59,6 +63,10 @@ impl TransformConfig for SampleConfig {
     fn transform_type(&self) -> &'static str {
         "sample"
     }
+
+    fn schema(&self, input: &TypeDef) -> TransformSchema {
+        TransformSchema::passthrough(input)
+    }
 }
 
 // Add a compatibility alias to avoid breaking existing configs
@@ -83,6 +91,10 @@ impl TransformConfig for SampleCompatConfig {
     fn transform_type(&self) -> &'static str {
         self.0.transform_type()
     }
+
+    fn schema(&self, input: &TypeDef) -> TransformSchema {
+        self.0.schema(input)
+    }
 }
 
 #[derive(Clone)]
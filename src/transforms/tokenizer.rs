@@ -2,6 +2,7 @@ use crate::{
     config::{DataType, TransformConfig, TransformContext, TransformDescription},
     event::{Event, PathComponent, PathIter, Value},
     internal_events::{TokenizerConvertFailed, TokenizerFieldMissing},
+    migrate::{vrl_conversions, vrl_field_or_message, vrl_path, Migration},
     transforms::{FunctionTransform, Transform},
     types::{parse_check_conversion_map, Conversion},
 };
@@ -27,6 +28,30 @@ inventory::submit! {
 
 impl_generate_config_from_default!(TokenizerConfig);
 
+impl TokenizerConfig {
+    /// The equivalent `remap` program, for `vector migrate`.
+    pub fn migrate(&self) -> crate::Result<Migration> {
+        let field = vrl_field_or_message(self.field.as_deref())?;
+
+        let mut statements = vec![format!("tokens, err = parse_tokens({})", field)];
+        let mut body = Vec::new();
+        for (index, name) in self.field_names.iter().enumerate() {
+            body.push(format!(
+                "if length(tokens) > {index} {{ {path} = tokens[{index}] }}",
+                index = index,
+                path = vrl_path(name)?
+            ));
+        }
+        body.push(vrl_conversions("", &self.types)?);
+        if self.drop_field {
+            body.push(format!("del({})", field));
+        }
+        statements.push(format!("if err == null {{\n{}\n}}", body.join("\n")));
+
+        Ok(Migration::new(statements.join("\n")).timezone(self.timezone))
+    }
+}
+
 #[async_trait::async_trait]
 #[typetag::serde(name = "tokenizer")]
 impl TransformConfig for TokenizerConfig {
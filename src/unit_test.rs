@@ -1,6 +1,11 @@
 use crate::config;
 use colored::*;
-use std::path::PathBuf;
+use serde::Serialize;
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+    path::{Path, PathBuf},
+};
 use structopt::StructOpt;
 
 #[derive(StructOpt, Debug)]
@@ -48,6 +53,14 @@ pub struct Opts {
         use_delimiter(true)
     )]
     pub pipeline_dirs: Vec<PathBuf>,
+
+    /// Instead of running tests, generate tests from events captured with
+    /// `vector tap --output`. Captured events are fed into the transforms
+    /// consuming the component they were captured from, and the current outputs
+    /// of downstream transforms are recorded as expected outputs. The tests are
+    /// printed as TOML, to be run later as regression tests.
+    #[structopt(long)]
+    generate: Option<PathBuf>,
 }
 
 impl Opts {
@@ -78,6 +91,10 @@ pub async fn cmd(opts: &Opts) -> exitcode::ExitCode {
         None => return exitcode::CONFIG,
     };
 
+    if let Some(capture) = &opts.generate {
+        return generate(&paths, &opts.pipeline_dirs, capture).await;
+    }
+
     println!("Running tests");
     match config::build_unit_tests(&paths, &opts.pipeline_dirs).await {
         Ok(mut tests) => {
@@ -127,3 +144,55 @@ pub async fn cmd(opts: &Opts) -> exitcode::ExitCode {
         exitcode::OK
     }
 }
+
+async fn generate(
+    paths: &[config::ConfigPath],
+    pipeline_dirs: &[PathBuf],
+    capture: &Path,
+) -> exitcode::ExitCode {
+    let captured = match read_capture(capture) {
+        Ok(captured) => captured,
+        Err(error) => {
+            error!(
+                "Failed to read captured events from {:?}: {}.",
+                capture, error
+            );
+            return exitcode::NOINPUT;
+        }
+    };
+
+    #[derive(Serialize)]
+    struct GeneratedTests {
+        tests: Vec<config::TestDefinition>,
+    }
+
+    match config::generate_unit_tests(paths, pipeline_dirs, captured).await {
+        Ok(tests) => {
+            // Serializing through a `toml::Value` orders tables after plain values.
+            match toml::Value::try_from(GeneratedTests { tests })
+                .and_then(|tests| toml::to_string_pretty(&tests))
+            {
+                Ok(tests) => {
+                    println!("{}", tests);
+                    exitcode::OK
+                }
+                Err(error) => {
+                    error!("Failed to serialize generated tests: {}.", error);
+                    exitcode::SOFTWARE
+                }
+            }
+        }
+        Err(errs) => {
+            error!("Failed to generate tests:\n{}.", errs.join("\n"));
+            exitcode::CONFIG
+        }
+    }
+}
+
+pub(crate) fn read_capture(path: &Path) -> crate::Result<Vec<config::CapturedEvent>> {
+    BufReader::new(File::open(path)?)
+        .lines()
+        .filter(|line| !matches!(line, Ok(line) if line.trim().is_empty()))
+        .map(|line| Ok(serde_json::from_str(&line?)?))
+        .collect()
+}
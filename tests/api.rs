@@ -289,6 +289,23 @@ mod tests {
         assert!(res.contains("ok"));
     }
 
+    #[tokio::test]
+    /// Tests the liveness probe responds while the API is served
+    async fn api_health_live() {
+        let res = url_test(api_enabled_config(), "health/live").await;
+
+        assert!(res.status().is_success());
+        assert!(res.text().await.unwrap().contains("ok"));
+    }
+
+    #[tokio::test]
+    /// Tests the readiness probe succeeds when no component is unhealthy
+    async fn api_health_ready() {
+        let res = url_test(api_enabled_config(), "health/ready").await;
+
+        assert!(res.status().is_success());
+    }
+
     #[tokio::test]
     /// Tests that the API playground is enabled when playground = true (implicit)
     async fn api_playground_enabled() {
@@ -0,0 +1,5 @@
+#!/bin/bash
+set -e
+
+# Allow replication connections from outside the container.
+echo "host replication all all md5" >> /var/lib/postgresql/data/pg_hba.conf
@@ -85,6 +85,39 @@ api: {
 				}
 			}
 		}
+		"/health/live": {
+			GET: {
+				description: """
+					Liveness endpoint, suitable for a Kubernetes liveness
+					probe. Responds as long as Vector is serving its API.
+					"""
+				responses: {
+					"200": {
+						description: "Vector is running."
+					}
+				}
+			}
+		}
+		"/health/ready": {
+			GET: {
+				description: """
+					Readiness endpoint, suitable for a Kubernetes readiness
+					probe. Vector isn't ready while any component is
+					unhealthy: its latest healthcheck failed without a
+					request succeeding since, its latest requests
+					failed five times in a row, or its circuit breaker
+					is open.
+					"""
+				responses: {
+					"200": {
+						description: "No component is unhealthy."
+					}
+					"503": {
+						description: "Some components are unhealthy. Their IDs are listed in the `unhealthy` field of the response."
+					}
+				}
+			}
+		}
 		"/playground": {
 			GET: {
 				description: """
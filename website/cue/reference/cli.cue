@@ -247,6 +247,37 @@ cli: {
 			}
 		}
 
+		"migrate": {
+			description: """
+				Rewrite deprecated transforms of a config file as equivalent `remap`
+				transforms, then exit. Transforms using options without a VRL equivalent
+				are left unchanged with a warning. Unit tests checking that the migrated
+				transforms behave like the original ones are appended to the config.
+				"""
+
+			options: {
+				"output": {
+					description: "Write the migrated config to this file instead of stdout"
+					type:        "string"
+				}
+				"events": {
+					description: """
+						Events captured with `vector tap --output` used as inputs of the
+						generated unit tests. Inputs without captured events are tested
+						with a sample event.
+						"""
+					type: "string"
+				}
+			}
+
+			args: {
+				path: {
+					description: "The config file to migrate"
+					type:        "string"
+				}
+			}
+		}
+
 		"test": {
 			description: """
 				Run Vector config unit tests, then exit. This command is experimental and
@@ -279,6 +310,15 @@ cli: {
 					type:    "string"
 					env_var: "VECTOR_CONFIG_YAML"
 				}
+				"generate": {
+					description: """
+						Instead of running tests, generate tests from events captured with
+						`vector tap --output`. The current outputs of the transforms
+						downstream of each captured component are recorded as expected
+						outputs, and the tests are printed as TOML.
+						"""
+					type: "string"
+				}
 			}
 
 			args: {
@@ -293,10 +333,18 @@ cli: {
 
 		"tap": {
 			description: """
-				Observe log events from topology components.
+				Observe log and metric events from topology components.
 				"""
 
-			flags: _default_flags
+			flags: _default_flags & {
+				"inputs": {
+					description: """
+						Also observe the events received by the components, and print what
+						each emits side by side with the input it was emitted for, followed
+						by the fields that differ
+						"""
+				}
+			}
 
 			options: {
 				"interval": {
@@ -312,13 +360,13 @@ cli: {
 				}
 				"limit": {
 					_short:      "l"
-					description: "Sample log events to the provided limit"
+					description: "Sample events to the provided limit"
 					type:        "integer"
 					default:     100
 				}
 				"format": {
 					_short:      "f"
-					description: "Encoding format for logs printed to screen"
+					description: "Encoding format for events printed to screen"
 					type:        "enum"
 					default:     "json"
 					enum: {
@@ -326,6 +374,36 @@ cli: {
 						yaml: "Output events as YAML"
 					}
 				}
+				"filter": {
+					description: """
+						Only observe events matching this expression. The filter is evaluated
+						by the observed Vector instance, before events are sent.
+						"""
+					type: "string"
+				}
+				"filter-language": {
+					description: "Language of the filter expression"
+					type:        "enum"
+					default:     "vrl"
+					enum: {
+						vrl:            "A Vector Remap Language condition"
+						datadog_search: "A Datadog search query"
+					}
+				}
+				"sample-rate": {
+					_short:      "s"
+					description: "Only observe 1 in every N events matching the filter, per component"
+					type:        "integer"
+					default:     1
+				}
+				"output": {
+					_short: "o"
+					description: """
+						Record tapped events to the provided file instead of printing them.
+						The capture can be turned into unit tests with `vector test --generate`.
+						"""
+					type: "string"
+				}
 			}
 
 			args: {
@@ -342,7 +420,13 @@ cli: {
 		"top": {
 			description: """
 				Display topology and metrics in the console, for a local or remote Vector
-				instance
+				instance, or merged for several instances.
+
+				Use the left and right arrows to change the column components are sorted
+				by, `r` to reverse the order, and `/` to filter components by a regular
+				expression matched against their IDs. Use the up and down arrows to select
+				a component, and Enter to show its throughput, health, errors, buffer and recent
+				error logs.
 				"""
 
 			flags: _default_flags & {
@@ -363,9 +447,13 @@ cli: {
 					default:     500
 				}
 				"url": {
-					_short:      "u"
-					description: "The URL for the GraphQL endpoint of the running Vector instance"
-					type:        "string"
+					_short: "u"
+					description: """
+						The URL for the GraphQL endpoint of the running Vector instance. Repeat
+						to merge the components of several instances, such as a fleet of
+						aggregators, into a single table
+						"""
+					type: "string"
 				}
 			}
 		}
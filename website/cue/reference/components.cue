@@ -195,6 +195,9 @@ components: {
 			// `healtcheck` notes if a component offers a healthcheck on boot.
 			healthcheck: {
 				enabled: bool
+				// `repeatable` notes if the healthcheck can also be repeated
+				// while Vector runs.
+				repeatable: bool | *false
 			}
 
 			exposes?: #FeaturesExpose
@@ -377,6 +380,7 @@ components: {
 				retry_max_duration_secs:    uint64 | *3600
 				timeout_secs:               uint64 | *60
 				headers:                    bool
+				ordering_key:               bool | *false
 				relevant_when?:             string
 			}
 		}
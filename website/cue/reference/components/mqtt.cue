@@ -0,0 +1,160 @@
+package metadata
+
+components: _mqtt: {
+	features: {
+		collect: from: {
+			service: services.mqtt
+			interface: {
+				socket: {
+					api: {
+						title: "MQTT protocol"
+						url:   urls.mqtt
+					}
+					direction: "outgoing"
+					port:      1883
+					protocols: ["tcp"]
+					ssl: "optional"
+				}
+			}
+		}
+
+		send: to: {
+			service: services.mqtt
+			interface: {
+				socket: {
+					api: {
+						title: "MQTT protocol"
+						url:   urls.mqtt
+					}
+					direction: "outgoing"
+					protocols: ["tcp"]
+					ssl: "optional"
+				}
+			}
+		}
+
+		tls: {
+			enabled:                true
+			can_enable:             false
+			can_verify_certificate: false
+			can_verify_hostname:    false
+			enabled_default:        false
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		host: {
+			description: "The host name or IP address of the MQTT broker."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["127.0.0.1", "mqtt.example.com"]
+				syntax: "literal"
+			}
+		}
+		port: {
+			common:      false
+			description: "The port of the MQTT broker."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 1883
+				examples: [1883, 8883]
+				unit: null
+			}
+		}
+		client_id: {
+			description: "The client identifier presented to the broker. Brokers disconnect an existing client when another one connects with the same identifier, so it must be unique for each component."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["vector-telemetry"]
+				syntax: "literal"
+			}
+		}
+		user: {
+			common:      false
+			description: "The user name to authenticate with."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["vector"]
+				syntax: "literal"
+			}
+		}
+		password: {
+			common:      false
+			description: "The password to authenticate with. Only used when `user` is set."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["${MQTT_PASSWORD}"]
+				syntax: "literal"
+			}
+		}
+		keep_alive_secs: {
+			common:      false
+			description: "The interval at which the client pings the broker when no other packets are sent."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 60
+				unit:    "seconds"
+			}
+		}
+		clean_session: {
+			common:      false
+			description: "Start a clean session on every connection. When disabled, the broker keeps the session of the `client_id` across connections, including subscriptions and unacknowledged messages."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
+		qos: {
+			common:      true
+			description: "The [quality of service](\(urls.mqtt_qos)) level: `0` (at most once), `1` (at least once) or `2` (exactly once)."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 1
+				examples: [0, 1, 2]
+				unit: null
+			}
+		}
+	}
+
+	how_it_works: {
+		rumqttc: {
+			title: "rumqttc"
+			body:  """
+				The `mqtt` source/sink uses [`rumqttc`](\(urls.rumqttc)) under the hood and speaks
+				MQTT 3.1.1.
+				"""
+		}
+		tls: {
+			title: "TLS"
+			body:  """
+				TLS is enabled by setting `tls.ca_file`. The MQTT client is built on rustls rather than
+				OpenSSL, so only PEM encoded files are supported and neither certificate nor hostname
+				verification can be disabled. Encrypted keys (`tls.key_pass`) are not supported.
+				"""
+		}
+	}
+}
@@ -259,6 +259,17 @@ components: sinks: [Name=string]: {
 								required:    false
 								type: bool: default: true
 							}
+							if features.healthcheck.repeatable {
+								interval_secs: {
+									common:      false
+									description: "Interval at which the healthcheck is repeated while Vector runs. Healthchecks only run upon Vector boot when unset."
+									required:    false
+									type: uint: {
+										default: null
+										unit:    "seconds"
+									}
+								}
+							}
 						}
 					}
 				}
@@ -313,6 +324,58 @@ components: sinks: [Name=string]: {
 									}
 								}
 							}
+							circuit_breaker: {
+								common:      false
+								description: "Holds back requests while the downstream service keeps failing. The circuit opens after `consecutive_failures` failed requests in a row, or once the ratio of failed requests among the latest `window` ones reaches `error_rate`. While it is open, requests and their retries wait instead of being sent. After `open_secs`, a single request probes the service, closing the circuit if it succeeds, or opening it again otherwise."
+								required:    false
+								type: object: {
+									examples: []
+									options: {
+										enabled: {
+											common:      false
+											description: "Enables the circuit breaker."
+											required:    false
+											type: bool: default: false
+										}
+										consecutive_failures: {
+											common:      false
+											description: "The number of consecutive failed requests opening the circuit, or 0 to only rely on `error_rate`."
+											required:    false
+											type: uint: {
+												default: 5
+												unit:    "requests"
+											}
+										}
+										error_rate: {
+											common:      false
+											description: "The ratio of failed requests among the latest `window` ones opening the circuit, between 0 and 1."
+											required:    false
+											type: float: {
+												default: null
+												examples: [0.5]
+											}
+										}
+										window: {
+											common:      false
+											description: "The number of latest requests `error_rate` is computed over."
+											required:    false
+											type: uint: {
+												default: 100
+												unit:    "requests"
+											}
+										}
+										open_secs: {
+											common:      false
+											description: "How long the circuit stays open before a request probes the service."
+											required:    false
+											type: uint: {
+												default: 30
+												unit:    "seconds"
+											}
+										}
+									}
+								}
+							}
 							concurrency: {
 								common: true
 								if features.send.request.adaptive_concurrency {
@@ -327,6 +390,24 @@ components: sinks: [Name=string]: {
 									unit:    "requests"
 								}
 							}
+							if features.send.request.ordering_key {
+								ordering_key: {
+									common: false
+									description: """
+										Events rendering the same key are delivered in order: a request is only
+										sent once the previous request with the same key has completed, including
+										all of its retries, while requests with different keys are still sent
+										concurrently. Events are batched separately for each key. Events whose
+										key fails to render are ordered together.
+										"""
+									required: false
+									type: string: {
+										default: null
+										examples: ["{{ user_id }}"]
+										syntax: "template"
+									}
+								}
+							}
 							rate_limit_duration_secs: {
 								common:      true
 								description: "The time window, in seconds, used for the `rate_limit_num` option."
@@ -503,6 +584,12 @@ components: sinks: [Name=string]: {
 					accessible and ready to accept data. This check is performed
 					upon sink initialization. If the health check fails an error
 					will be logged and Vector will proceed to start.
+
+					The health of each sink is also tracked while Vector runs, from
+					the outcome of its requests and, for the sinks supporting
+					`healthcheck.interval_secs` when it is set, of periodic health
+					checks. It is exposed by the `componentHealths` GraphQL query,
+					the `/health/ready` API endpoint, and `vector top`.
 					"""
 				sub_sections: [
 					{
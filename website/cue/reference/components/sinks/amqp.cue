@@ -0,0 +1,90 @@
+package metadata
+
+components: sinks: amqp: {
+	title: "AMQP"
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: []
+		stateful: false
+	}
+
+	features: {
+		buffer: enabled:      true
+		healthcheck: enabled: true
+		send: {
+			compression: enabled: false
+			batch: {
+				enabled:      true
+				common:       false
+				max_bytes:    null
+				max_events:   100
+				timeout_secs: 1
+			}
+			encoding: {
+				enabled: true
+				codec: {
+					enabled: true
+					enum: ["json", "text"]
+				}
+			}
+			request: {
+				enabled: true
+				headers: false
+			}
+			tls: enabled: false
+			to: components._amqp.features.send.to
+		}
+	}
+
+	support: components._amqp.support
+
+	configuration: components._amqp.configuration & {
+		exchange: {
+			description: "The exchange to publish messages to. Use an empty string for the default exchange."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["telemetry", "{{ application }}"]
+				syntax: "template"
+			}
+		}
+		routing_key: {
+			common:      true
+			description: "The routing key to publish messages with."
+			required:    false
+			warnings: []
+			type: string: {
+				default: ""
+				examples: ["sensors.{{ host }}"]
+				syntax: "template"
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: components._amqp.how_it_works & {
+		publisher_confirms: {
+			title: "Publisher confirms"
+			body:  """
+				Messages are published on a channel in confirm mode, and a batch is only considered
+				sent once the broker has confirmed every message in it. Batches with unconfirmed
+				messages, as well as batches that failed because the connection was lost, are
+				retried; the connection is reopened as needed.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		processed_bytes_total:  components.sources.internal_metrics.output.metrics.processed_bytes_total
+		processed_events_total: components.sources.internal_metrics.output.metrics.processed_events_total
+		send_errors_total:      components.sources.internal_metrics.output.metrics.send_errors_total
+	}
+}
@@ -13,8 +13,11 @@ components: sinks: aws_s3: components._aws & {
 	}
 
 	features: {
-		buffer: enabled:      true
-		healthcheck: enabled: true
+		buffer: enabled: true
+		healthcheck: {
+			enabled:    true
+			repeatable: true
+		}
 		send: {
 			batch: {
 				enabled:      true
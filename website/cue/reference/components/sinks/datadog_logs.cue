@@ -6,8 +6,11 @@ components: sinks: datadog_logs: {
 	classes: sinks._datadog.classes
 
 	features: {
-		buffer: enabled:      true
-		healthcheck: enabled: true
+		buffer: enabled: true
+		healthcheck: {
+			enabled:    true
+			repeatable: true
+		}
 		send: {
 			batch: {
 				enabled:      true
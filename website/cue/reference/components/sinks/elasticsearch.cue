@@ -13,8 +13,11 @@ components: sinks: elasticsearch: {
 	}
 
 	features: {
-		buffer: enabled:      true
-		healthcheck: enabled: true
+		buffer: enabled: true
+		healthcheck: {
+			enabled:    true
+			repeatable: true
+		}
 		send: {
 			batch: {
 				enabled:      true
@@ -34,8 +37,9 @@ components: sinks: elasticsearch: {
 			}
 			proxy: enabled: true
 			request: {
-				enabled: true
-				headers: true
+				enabled:      true
+				headers:      true
+				ordering_key: true
 			}
 			tls: {
 				enabled:                true
@@ -13,8 +13,11 @@ components: sinks: http: {
 	}
 
 	features: {
-		buffer: enabled:      true
-		healthcheck: enabled: true
+		buffer: enabled: true
+		healthcheck: {
+			enabled:    true
+			repeatable: true
+		}
 		send: {
 			batch: {
 				enabled:      true
@@ -38,8 +41,9 @@ components: sinks: http: {
 			}
 			proxy: enabled: true
 			request: {
-				enabled: true
-				headers: true
+				enabled:      true
+				headers:      true
+				ordering_key: true
 			}
 			tls: {
 				enabled:                true
@@ -88,16 +92,119 @@ components: sinks: http: {
 			password_example: "${HTTP_PASSWORD}"
 			username_example: "${HTTP_USERNAME}"
 		}}
+		oauth2: {
+			common:      false
+			description: "Fetches the bearer token of the requests with the OAuth 2.0 client credentials grant. The token is refreshed before it expires, or after a request is rejected with a 401 or 403 status. Can't be used with `auth`."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					token_endpoint: {
+						description: "The URI of the token endpoint of the authorization server."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["https://auth.example.com/oauth2/token"]
+							syntax: "literal"
+						}
+					}
+					client_id: {
+						description: "The client identifier, sent with HTTP basic authentication to the token endpoint."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["vector"]
+							syntax: "literal"
+						}
+					}
+					client_secret: {
+						description: "The client secret, sent with HTTP basic authentication to the token endpoint."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["${OAUTH2_CLIENT_SECRET}"]
+							syntax: "literal"
+						}
+					}
+					scopes: {
+						common:      false
+						description: "The scopes requested for the token."
+						required:    false
+						warnings: []
+						type: array: {
+							default: []
+							items: type: string: {
+								examples: ["logs.write"]
+								syntax: "literal"
+							}
+						}
+					}
+				}
+			}
+		}
+		query: {
+			common:      false
+			description: "Query parameters appended to the URI of the requests. Values are templatable, in which case events are batched separately for each rendered value."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [{"tenant": "{{ tenant_id }}", "source": "vector"}]
+				options: {
+					"*": {
+						common:      false
+						description: "A query parameter, templatable."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["vector", "{{ event_field }}"]
+							syntax: "template"
+						}
+					}
+				}
+			}
+		}
 		uri: {
 			description: """
 				The full URI to make HTTP requests to. This should include the protocol and host,
-				but can also include the port, path, and any other valid part of a URI.
+				but can also include the port, path, and any other valid part of a URI. The URI is
+				templatable, in which case events are batched separately for each rendered URI.
 				"""
 			required: true
 			warnings: []
 			type: string: {
-				examples: ["https://10.22.212.22:9000/endpoint"]
-				syntax: "literal"
+				examples: ["https://10.22.212.22:9000/endpoint", "https://10.22.212.22:9000/{{ tenant_id }}/endpoint"]
+				syntax: "template"
+			}
+		}
+		wrapper: {
+			common:      false
+			description: "Wraps the JSON array of the events of each batch into a JSON document. Only valid with the `json` encoding."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					key: {
+						description: "The key of the document holding the array of events."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["events"]
+							syntax: "literal"
+						}
+					}
+					fields: {
+						common:      false
+						description: "The other fields of the document."
+						required:    false
+						warnings: []
+						type: object: {
+							examples: [{"source": "vector"}]
+							options: {}
+						}
+					}
+				}
 			}
 		}
 		healthcheck: type: object: options: uri: {
@@ -116,6 +223,20 @@ components: sinks: http: {
 		}
 	}
 
+	how_it_works: {
+		partitioning: {
+			title: "Partitioning"
+			body: """
+				The `uri`, the values of `query` and the values of `request.headers`
+				are templatable. Events are batched separately for each combination
+				of their rendered values, and each batch is sent in its own request.
+				Events whose templates fail to render, or render header values which
+				are not valid, are dropped. Keep the number of
+				distinct rendered values low, as each of them holds its own batch.
+				"""
+		}
+	}
+
 	input: {
 		logs:    true
 		metrics: null
@@ -126,5 +247,6 @@ components: sinks: http: {
 		http_bad_requests_total: components.sources.internal_metrics.output.metrics.http_bad_requests_total
 		processed_bytes_total:   components.sources.internal_metrics.output.metrics.processed_bytes_total
 		processed_events_total:  components.sources.internal_metrics.output.metrics.processed_events_total
+		processing_errors_total: components.sources.internal_metrics.output.metrics.processing_errors_total
 	}
 }
@@ -4,7 +4,7 @@ components: sinks: humio_logs: {
 	title: "Humio Logs"
 
 	classes:       sinks._humio.classes
-	features:      sinks._humio.features
+	features:      sinks._humio.features & {healthcheck: repeatable: true}
 	support:       sinks._humio.support
 	configuration: sinks._humio.configuration
 
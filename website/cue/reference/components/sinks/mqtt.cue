@@ -0,0 +1,75 @@
+package metadata
+
+components: sinks: mqtt: {
+	title: "MQTT"
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		service_providers: []
+		stateful: false
+	}
+
+	features: {
+		buffer: enabled:      false
+		healthcheck: enabled: true
+		send: {
+			compression: enabled: false
+			encoding: {
+				enabled: true
+				codec: {
+					enabled: true
+					enum: ["json", "text"]
+				}
+			}
+			request: enabled: false
+			tls: components._mqtt.features.tls
+			to:  components._mqtt.features.send.to
+		}
+	}
+
+	support: components._mqtt.support
+
+	configuration: components._mqtt.configuration & {
+		topic: {
+			description: "The topic to publish messages to."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["vector", "sensors/{{ host }}/telemetry"]
+				syntax: "template"
+			}
+		}
+		retain: {
+			common:      false
+			description: "Set the retain flag on published messages, so the broker keeps the last message of each topic for new subscribers."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: components._mqtt.how_it_works & {
+		delivery: {
+			title: "Delivery"
+			body:  """
+				Events are only marked as delivered once the broker has acknowledged them: with a
+				`PUBACK` for QoS 1 and a `PUBCOMP` for QoS 2. QoS 0 messages are considered
+				delivered as soon as they are written to the connection.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		connection_errors_total: components.sources.internal_metrics.output.metrics.connection_errors_total
+		processed_bytes_total:   components.sources.internal_metrics.output.metrics.processed_bytes_total
+		send_errors_total:       components.sources.internal_metrics.output.metrics.send_errors_total
+	}
+}
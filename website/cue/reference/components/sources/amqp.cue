@@ -0,0 +1,168 @@
+package metadata
+
+components: sources: amqp: {
+	title: "AMQP"
+
+	features: {
+		collect: {
+			checkpoint: enabled: false
+			from: components._amqp.features.collect.from
+		}
+		multiline: enabled: false
+	}
+
+	classes: {
+		commonly_used: false
+		deployment_roles: ["aggregator"]
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	support: components._amqp.support
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: components._amqp.configuration & {
+		acknowledgements: configuration._acknowledgements
+		queue: {
+			description: "The queue to consume messages from."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["vector", "telemetry"]
+				syntax: "literal"
+			}
+		}
+		consumer: {
+			common:      false
+			description: "The consumer tag identifying this consumer on the channel."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "vector"
+				examples: ["vector-telemetry"]
+				syntax: "literal"
+			}
+		}
+		prefetch_count: {
+			common:      false
+			description: "The maximum number of messages delivered to Vector that have not been acknowledged yet. Only applies when `acknowledgements` are enabled."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 100
+				unit:    null
+			}
+		}
+		exchange_key: {
+			common:      false
+			description: "The log field name to use for the exchange the message was published to."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "exchange"
+				examples: ["exchange"]
+				syntax: "literal"
+			}
+		}
+		routing_key_field: {
+			common:      false
+			description: "The log field name to use for the routing key of the message."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "routing"
+				examples: ["routing"]
+				syntax: "literal"
+			}
+		}
+		headers_key: {
+			common:      false
+			description: "The log field name to use for the headers of the message."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "headers"
+				examples: ["headers"]
+				syntax: "literal"
+			}
+		}
+	}
+
+	output: logs: record: {
+		description: "An individual AMQP message"
+		fields: {
+			exchange: {
+				description: "The exchange the message was published to. Empty for the default exchange."
+				required:    true
+				type: string: {
+					examples: ["telemetry"]
+					syntax: "literal"
+				}
+			}
+			headers: {
+				description: "The headers of the message."
+				required:    true
+				type: object: {
+					examples: [{"sensor": "kitchen"}]
+					options: {}
+				}
+			}
+			message: {
+				description: "The raw line from the message body."
+				required:    true
+				type: string: {
+					examples: ["{\"temperature\": 21.5}"]
+					syntax: "literal"
+				}
+			}
+			routing: {
+				description: "The routing key of the message."
+				required:    true
+				type: string: {
+					examples: ["sensors.kitchen"]
+					syntax: "literal"
+				}
+			}
+			timestamp: fields._current_timestamp & {
+				description: "The timestamp property of the message, or the current time if it is not set."
+			}
+		}
+	}
+
+	telemetry: metrics: {
+		component_received_events_total:  components.sources.internal_metrics.output.metrics.component_received_events_total
+		connection_errors_total:          components.sources.internal_metrics.output.metrics.connection_errors_total
+		connection_send_ack_errors_total: components.sources.internal_metrics.output.metrics.connection_send_ack_errors_total
+		events_failed_total:              components.sources.internal_metrics.output.metrics.events_failed_total
+		events_in_total:                  components.sources.internal_metrics.output.metrics.events_in_total
+		processed_bytes_total:            components.sources.internal_metrics.output.metrics.processed_bytes_total
+	}
+
+	how_it_works: components._amqp.how_it_works & {
+		acknowledgements: {
+			title: "Acknowledgements"
+			body:  """
+				With `acknowledgements` enabled, each message is acknowledged once all of its events
+				have been delivered. Messages whose events failed to be delivered because of an error
+				are requeued, while messages whose events were rejected by a sink are rejected
+				without requeueing, so they are dead-lettered if the queue is configured to.
+
+				When `acknowledgements` are disabled, the broker considers messages acknowledged as
+				soon as they are sent to Vector.
+				"""
+		}
+		reconnection: {
+			title: "Reconnection"
+			body:  """
+				When the channel or the connection to the broker fails, Vector connects again with an
+				exponential backoff of up to a minute between attempts. Messages that were delivered
+				to Vector but not acknowledged yet are requeued by the broker.
+				"""
+		}
+	}
+}
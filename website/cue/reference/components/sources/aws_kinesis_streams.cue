@@ -0,0 +1,198 @@
+package metadata
+
+components: sources: aws_kinesis_streams: components._aws & {
+	title: "AWS Kinesis Data Streams"
+
+	features: {
+		multiline: enabled: false
+		collect: {
+			tls: enabled:        false
+			checkpoint: enabled: true
+			proxy: enabled:      true
+			from: service:       services.aws_kinesis_data_streams
+		}
+	}
+
+	classes: {
+		commonly_used: false
+		deployment_roles: ["aggregator"]
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		acknowledgements: configuration._acknowledgements
+		max_records: {
+			common:      false
+			description: "The maximum number of records to read from a shard with each request. At most 10000."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 1000
+				unit:    null
+			}
+		}
+		poll_interval_ms: {
+			common:      false
+			description: "How long to wait before reading a shard again after it returned no records."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 1000
+				unit:    "milliseconds"
+			}
+		}
+		shard_refresh_secs: {
+			common:      false
+			description: "How often to list the shards of the stream, to pick up shards created by resharding."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 10
+				unit:    "seconds"
+			}
+		}
+		start_position: {
+			common:      true
+			description: "Where to start reading shards that have no checkpoint yet."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "latest"
+				enum: {
+					latest:       "Only read records added after Vector started."
+					trim_horizon: "Read every record still retained in the stream."
+				}
+				syntax: "literal"
+			}
+		}
+		stream_name: {
+			description: "The name of the stream to read from."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["my-stream"]
+				syntax: "literal"
+			}
+		}
+	}
+
+	output: logs: record: {
+		description: "An individual Kinesis record"
+		fields: {
+			message: {
+				description: "The raw line from the record data."
+				required:    true
+				type: string: {
+					examples: ["53.126.150.246 - - [01/Oct/2020:11:25:58 -0400] \"GET /disintermediate HTTP/2.0\" 401 20308"]
+					syntax: "literal"
+				}
+			}
+			partition_key: {
+				description: "The partition key of the record."
+				required:    true
+				type: string: {
+					examples: ["my-key"]
+					syntax: "literal"
+				}
+			}
+			sequence_number: {
+				description: "The sequence number of the record within its shard."
+				required:    true
+				type: string: {
+					examples: ["49590338271490256608559692538361571095921575989136588898"]
+					syntax: "literal"
+				}
+			}
+			shard_id: {
+				description: "The ID of the shard the record was read from."
+				required:    true
+				type: string: {
+					examples: ["shardId-000000000000"]
+					syntax: "literal"
+				}
+			}
+			timestamp: fields._current_timestamp & {
+				description: "The time the record was added to the stream."
+			}
+		}
+	}
+
+	how_it_works: {
+		acknowledgements: {
+			title: "Acknowledgements"
+			body:  """
+				With `acknowledgements` enabled, the checkpoint of a shard only advances once the
+				events of the records before it have been delivered. If they fail to be delivered,
+				the shard is read again from its checkpoint on the next shard refresh. Otherwise,
+				checkpoints advance as soon as events are handed to the next component.
+				"""
+		}
+		aggregation: {
+			title: "KPL aggregation"
+			body:  """
+				Records [aggregated](\(urls.aws_kinesis_streams_kpl_aggregation)) by the Kinesis
+				Producer Library are split into the user records they contain, each of which is
+				decoded separately and keeps its own partition key.
+				"""
+		}
+		resharding: {
+			title: "Resharding"
+			body:  """
+				The shards of the stream are listed every `shard_refresh_secs`, and each open shard
+				is read by its own task. After a shard is [split](\(urls.aws_kinesis_split_shards))
+				or merged, its child shards are only read once every record of the parent shards
+				has been processed, so records with the same partition key are emitted in order.
+				"""
+		}
+	}
+
+	permissions: iam: [
+		{
+			platform: "aws"
+			_service: "kinesis"
+
+			policies: [
+				{
+					_action: "ListShards"
+				},
+				{
+					_action: "GetShardIterator"
+				},
+				{
+					_action: "GetRecords"
+				},
+			]
+		},
+	]
+
+	telemetry: metrics: {
+		checkpoint_write_errors_total:   components.sources.internal_metrics.output.metrics.checkpoint_write_errors_total
+		component_received_events_total: components.sources.internal_metrics.output.metrics.component_received_events_total
+		events_in_total:                 components.sources.internal_metrics.output.metrics.events_in_total
+		processed_bytes_total:           components.sources.internal_metrics.output.metrics.processed_bytes_total
+		request_errors_total:            components.sources.internal_metrics.output.metrics.request_errors_total
+	}
+}
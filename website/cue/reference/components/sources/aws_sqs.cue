@@ -0,0 +1,178 @@
+package metadata
+
+components: sources: aws_sqs: components._aws & {
+	title: "AWS SQS"
+
+	features: {
+		multiline: enabled: false
+		collect: {
+			tls: enabled:        false
+			checkpoint: enabled: false
+			proxy: enabled:      true
+			from: service:       services.aws_sqs
+		}
+	}
+
+	classes: {
+		commonly_used: false
+		deployment_roles: ["aggregator"]
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		acknowledgements: configuration._acknowledgements
+		client_concurrency: {
+			common:      false
+			description: "The number of tasks receiving messages from the queue concurrently. Defaults to the number of available CPUs."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: null
+				examples: [4]
+				unit: null
+			}
+		}
+		delete_message: {
+			common:      true
+			description: "Whether to delete messages once their events have been processed. It can be useful to set this to `false` to debug or during initial Vector setup."
+			required:    false
+			warnings: []
+			type: bool: default: true
+		}
+		poll_secs: {
+			common:      true
+			description: "How long to wait when polling SQS for new messages. At most 20 seconds."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 15
+				unit:    "seconds"
+			}
+		}
+		queue_url: {
+			description: "The URL of the SQS queue to receive messages from."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["https://sqs.us-east-2.amazonaws.com/123456789012/MyQueue"]
+				syntax: "literal"
+			}
+		}
+		visibility_timeout_secs: {
+			common:      false
+			description: "The visibility timeout to use for messages in seconds. This controls how long a message is left unavailable when Vector receives it. With `acknowledgements` enabled, the timeout is extended by this much every half of it until the events of the message are delivered."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 300
+				unit:    "seconds"
+			}
+		}
+	}
+
+	output: logs: record: {
+		description: "An individual SQS message"
+		fields: {
+			message: {
+				description: "The raw line from the message body."
+				required:    true
+				type: string: {
+					examples: ["53.126.150.246 - - [01/Oct/2020:11:25:58 -0400] \"GET /disintermediate HTTP/2.0\" 401 20308"]
+					syntax: "literal"
+				}
+			}
+			message_attributes: {
+				description: "The message attributes of the message."
+				required:    true
+				type: object: {
+					examples: [{"sensor": "kitchen"}]
+					options: {}
+				}
+			}
+			message_id: {
+				description: "The ID of the message, assigned by SQS."
+				required:    true
+				type: string: {
+					examples: ["5fea7756-0ea4-451a-a703-a558b933e274"]
+					syntax: "literal"
+				}
+			}
+			timestamp: fields._current_timestamp & {
+				description: "The time the message was sent to the queue."
+			}
+		}
+	}
+
+	how_it_works: {
+		acknowledgements: {
+			title: "Acknowledgements"
+			body:  """
+				With `acknowledgements` enabled, messages are only deleted once all of their events
+				have been delivered, and their visibility timeout is extended until then. Messages
+				whose events failed to be delivered are made visible again right away, so they can
+				be received again; configure a [dead-letter queue](\(urls.aws_sqs)) on the queue to
+				stop receiving messages that can never be delivered.
+
+				Without `acknowledgements`, messages are deleted as soon as their events are handed
+				to the next component.
+				"""
+		}
+	}
+
+	permissions: iam: [
+		{
+			platform:  "aws"
+			_service:  "sqs"
+			_docs_tag: "AWSSimpleQueueService"
+
+			policies: [
+				{
+					_action: "ReceiveMessage"
+				},
+				{
+					_action:       "ChangeMessageVisibility"
+					required_when: "[`acknowledgements`](#acknowledgements) are enabled"
+				},
+				{
+					_action:       "DeleteMessage"
+					required_when: "[`delete_message`](#delete_message) is set to `true`"
+				},
+			]
+		},
+	]
+
+	telemetry: metrics: {
+		component_received_events_total:            components.sources.internal_metrics.output.metrics.component_received_events_total
+		events_in_total:                            components.sources.internal_metrics.output.metrics.events_in_total
+		processed_bytes_total:                      components.sources.internal_metrics.output.metrics.processed_bytes_total
+		sqs_message_delete_failed_total:            components.sources.internal_metrics.output.metrics.sqs_message_delete_failed_total
+		sqs_message_delete_succeeded_total:         components.sources.internal_metrics.output.metrics.sqs_message_delete_succeeded_total
+		sqs_message_receive_failed_total:           components.sources.internal_metrics.output.metrics.sqs_message_receive_failed_total
+		sqs_message_receive_succeeded_total:        components.sources.internal_metrics.output.metrics.sqs_message_receive_succeeded_total
+		sqs_message_received_messages_total:        components.sources.internal_metrics.output.metrics.sqs_message_received_messages_total
+		sqs_message_visibility_change_failed_total: components.sources.internal_metrics.output.metrics.sqs_message_visibility_change_failed_total
+	}
+}
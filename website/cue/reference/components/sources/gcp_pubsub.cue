@@ -0,0 +1,254 @@
+package metadata
+
+components: sources: gcp_pubsub: {
+	title: "GCP PubSub"
+
+	features: {
+		collect: {
+			checkpoint: enabled: false
+			from: {
+				service: services.gcp_pubsub
+				interface: {
+					socket: {
+						api: {
+							title: "GCP PubSub REST API"
+							url:   urls.gcp_pubsub_rest
+						}
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "required"
+					}
+				}
+			}
+			proxy: enabled: true
+			tls: {
+				enabled:                true
+				can_enable:             false
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+		}
+		multiline: enabled: false
+	}
+
+	classes: {
+		commonly_used: false
+		deployment_roles: ["aggregator"]
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		acknowledgements: configuration._acknowledgements
+		ack_deadline_secs: {
+			common:      false
+			description: "The acknowledgement deadline to set on pulled messages. While their events are in flight, the deadline of messages is extended by this much every half of it."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 60
+				unit:    "seconds"
+			}
+		}
+		api_key: {
+			common:      false
+			description: "A [Google Cloud API key](\(urls.gcp_authentication_api_key)) used to authenticate access the pubsub project and subscription. Either this or `credentials_path` must be set."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["${GCP_API_KEY}", "ef8d5de700e7989468166c40fc8a0ccd"]
+				syntax: "literal"
+			}
+		}
+		credentials_path: {
+			common:      true
+			description: "The filename for a Google Cloud service account credentials JSON file used to authenticate access to the pubsub project and subscription. If this is unset, Vector checks the `GOOGLE_APPLICATION_CREDENTIALS` environment variable for a filename.\n\nIf no filename is named, Vector will attempt to fetch an instance service account for the compute instance the program is running on. If Vector is not running on a GCE instance, you must define a credentials file as above."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["/path/to/credentials.json"]
+				syntax: "literal"
+			}
+		}
+		endpoint: {
+			common:      false
+			description: "The endpoint from which to pull data."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "https://pubsub.googleapis.com"
+				examples: ["https://us-central1-pubsub.googleapis.com"]
+				syntax: "literal"
+			}
+		}
+		max_messages: {
+			common:      false
+			description: "The maximum number of messages to pull at once."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 1000
+				unit:    null
+			}
+		}
+		project: {
+			description: "The project name from which to pull logs."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["vector-123456"]
+				syntax: "literal"
+			}
+		}
+		retry_delay_secs: {
+			common:      false
+			description: "The amount of time to wait before pulling again after an error."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 1
+				unit:    "seconds"
+			}
+		}
+		skip_authentication: {
+			common:      false
+			description: "Skip all authentication handling. For use with integration tests and the Pub/Sub emulator only."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
+		subscription: {
+			description: "The subscription within the project from which to pull logs."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["this-is-a-subscription"]
+				syntax: "literal"
+			}
+		}
+	}
+
+	env_vars: {
+		GOOGLE_APPLICATION_CREDENTIALS: {
+			description:   "The filename for a Google Cloud service account credentials JSON file used for authentication."
+			relevant_when: "endpoint = null"
+			type: string: {
+				default: null
+				examples: ["/path/to/credentials.json"]
+				syntax: "literal"
+			}
+		}
+	}
+
+	output: logs: record: {
+		description: "An individual Pub/Sub message"
+		fields: {
+			attributes: {
+				description: "The attributes of the message."
+				required:    true
+				type: object: {
+					examples: [{"sensor": "kitchen"}]
+					options: {}
+				}
+			}
+			message: {
+				description: "The raw line from the message data."
+				required:    true
+				type: string: {
+					examples: ["2021-11-05T10:00:00.000Z INFO Hello world"]
+					syntax: "literal"
+				}
+			}
+			message_id: {
+				description: "The ID of the message, assigned by Pub/Sub."
+				required:    true
+				type: string: {
+					examples: ["2070443601311540"]
+					syntax: "literal"
+				}
+			}
+			timestamp: fields._current_timestamp & {
+				description: "The time the message was published to Pub/Sub."
+			}
+		}
+	}
+
+	permissions: iam: [
+		{
+			platform: "gcp"
+			_service: "pubsub"
+
+			policies: [
+				{
+					_action: "subscriptions.consume"
+					required_for: ["operation"]
+				},
+			]
+		},
+	]
+
+	telemetry: metrics: {
+		component_received_events_total:  components.sources.internal_metrics.output.metrics.component_received_events_total
+		connection_send_ack_errors_total: components.sources.internal_metrics.output.metrics.connection_send_ack_errors_total
+		events_in_total:                  components.sources.internal_metrics.output.metrics.events_in_total
+		processed_bytes_total:            components.sources.internal_metrics.output.metrics.processed_bytes_total
+		request_errors_total:             components.sources.internal_metrics.output.metrics.request_errors_total
+	}
+
+	how_it_works: {
+		acknowledgements: {
+			title: "Acknowledgements"
+			body:  """
+				With `acknowledgements` enabled, messages are only acknowledged once all of their events
+				have been delivered, and their acknowledgement deadline is extended until then. Messages
+				whose events failed to be delivered are made available for redelivery right away; configure
+				a [dead-letter topic](\(urls.gcp_pubsub)) on the subscription to stop redelivering
+				messages that can never be delivered.
+
+				Without `acknowledgements`, messages are acknowledged as soon as their events are handed
+				to the next component.
+				"""
+		}
+		gcp_authentication: {
+			title: "GCP Authentication"
+			body:  """
+				GCP offers a [variety of authentication methods](\(urls.gcp_authentication)) and
+				Vector is concerned with the [server to server methods](\(urls.gcp_authentication_server_to_server))
+				and will find credentials in the following order:
+
+				1. If the [`credentials_path`](#credentials_path) option is set.
+				1. If the `api_key` option is set.
+				1. If the [`GOOGLE_APPLICATION_CREDENTIALS`](#google_application_credentials) environment variable is set.
+				1. Finally, Vector will check for an [instance service account](\(urls.gcp_authentication_service_account)).
+
+				If credentials aren't found, the source fails to start and an error is
+				[logged](\(urls.vector_monitoring)).
+				"""
+		}
+	}
+}
@@ -445,6 +445,15 @@ components: sources: internal_metrics: {
 				reason: _reason
 			}
 		}
+		event_age_seconds: {
+			description: """
+				The time between events entering the topology and being finalized
+				by this sink, once delivered or dropped.
+				"""
+			type:              "histogram"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		events_failed_total: {
 			description:       "The total number of failures to read a Kafka message."
 			type:              "counter"
@@ -539,6 +548,94 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		component_latency_seconds: {
+			description: """
+				The time taken by events to cross this component, from their receipt
+				to their output for transforms, or to their finalization for sinks.
+				"""
+			type:              "histogram"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		sink_circuit_breaker_opened_total: {
+			description:       "The number of times the circuit breaker of this sink opened."
+			type:              "counter"
+			default_namespace: "vector"
+			tags: _component_tags & {
+				reason: {
+					description: "Why the circuit opened."
+					required:    true
+					enum: {
+						consecutive_failures: "Too many consecutive requests failed."
+						error_rate:           "The ratio of failed requests reached the threshold."
+						probe_failed:         "The request probing the half-open circuit failed."
+					}
+				}
+			}
+		}
+		sink_circuit_breaker_state: {
+			description:       "The state of the circuit breaker of this sink: 0 when closed, 1 when half-open, and 2 when open."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		sink_consecutive_failures: {
+			description:       "The number of requests of this sink that failed since its last successful one."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		sink_healthcheck_passed: {
+			description:       "Whether the latest healthcheck of this sink passed (1) or failed (0)."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		sink_last_failure_timestamp_seconds: {
+			description:       "The Unix timestamp of the latest failed request of this sink."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		sink_last_healthcheck_timestamp_seconds: {
+			description:       "The Unix timestamp of the latest healthcheck of this sink."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		sink_last_success_timestamp_seconds: {
+			description:       "The Unix timestamp of the latest successful request of this sink."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		sink_request_failures_total: {
+			description:       "The total number of failed requests of this sink, including those retried."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		memory_budget_held_bytes: {
+			description: """
+				The number of bytes of the memory budget held by the events admitted by
+				this source and not yet finalized.
+				"""
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		memory_budget_limit_bytes: {
+			description:       "The maximum number of bytes held by events set by the `memory_budget_bytes` option."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _internal_metrics_tags
+		}
+		memory_budget_waits_total: {
+			description:       "The number of times this source waited for room in the memory budget before admitting events."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		kafka_queue_messages: {
 			description:       "Current number of messages in producer queues."
 			type:              "gauge"
@@ -748,6 +845,17 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		netflow_template_misses_total: {
+			description:       "The total number of NetFlow v9 and IPFIX data sets dropped as their template is unknown."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				flow_type: {
+					description: "The protocol of the dropped data set, `netflow_v9` or `ipfix`."
+					required:    true
+				}
+			}
+		}
 		open_connections: {
 			description:       "The number of current open connections to Vector."
 			type:              "gauge"
@@ -842,6 +950,12 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		saturated_counters_total: {
+			description:       "The total number of unsigned counters saturated at the largest signed 64-bit integer, as they exceed it."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		send_errors_total: {
 			description:       "The total number of errors sending messages."
 			type:              "counter"
@@ -896,6 +1010,12 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		sqs_message_visibility_change_failed_total: {
+			description:       "The total number of failures to change the visibility timeout of SQS messages."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		sqs_s3_event_record_ignored_total: {
 			description:       "The total number of times an S3 record in an SQS message was ignored (for an event that was not `ObjectCreated`)."
 			type:              "counter"
@@ -0,0 +1,237 @@
+package metadata
+
+components: sources: internal_traces: {
+	title:       "Internal Traces"
+	description: "The internal traces source exposes the start and end of the tracing spans of the running Vector instance, to profile where time is spent."
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["aggregator", "daemon", "sidecar"]
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		collect: {
+			checkpoint: enabled: false
+			from: service: {
+				name:     "Vector instance"
+				thing:    "a \(name)"
+				url:      urls.vector_docs
+				versions: ">= 0.17.0"
+			}
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		notices: []
+		requirements: []
+		warnings: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		host_key: {
+			category:    "Context"
+			common:      false
+			description: """
+				The key name added to each event representing the current host. This can also be globally set via the
+				[global `host_key` option](\(urls.vector_configuration)/global-options#log_schema.host_key).
+
+				Set to "" to suppress this key.
+				"""
+			required:    false
+			warnings: []
+			type: string: {
+				default: "host"
+				syntax:  "literal"
+			}
+		}
+		pid_key: {
+			category: "Context"
+			common:   false
+			description: """
+				The key name added to each event representing the current process ID.
+
+				Set to "" to suppress this key.
+				"""
+			required: false
+			warnings: []
+			type: string: {
+				default: "pid"
+				syntax:  "literal"
+			}
+		}
+	}
+
+	output: logs: span: {
+		description: "The start or the end of a span."
+		fields: {
+			kind: {
+				description: "Whether the span started or ended."
+				required:    true
+				type: string: {
+					enum: {
+						start: "The span was created."
+						end:   "The span was closed."
+					}
+					syntax: "literal"
+				}
+			}
+			timestamp: fields._current_timestamp & {
+				description: "The exact time the span started or ended."
+			}
+			host: fields._local_host
+			pid: {
+				description: "The process ID of the Vector instance."
+				required:    true
+				type: uint: {
+					examples: [4232]
+					unit: null
+				}
+			}
+			span: {
+				description: "Describes the span."
+				required:    true
+				type: object: {
+					examples: []
+					options: {
+						id: {
+							description: "The id of the span, unique among the spans open at the same time."
+							required:    true
+							type: string: {
+								examples: ["2251799813685249"]
+								syntax: "literal"
+							}
+						}
+						parent_id: {
+							description: "The id of the span this span is nested in, if any."
+							required:    false
+							type: string: {
+								default: null
+								examples: ["2251799813685249"]
+								syntax: "literal"
+							}
+						}
+						name: {
+							description: "The name of the span."
+							required:    true
+							type: string: {
+								examples: ["vrl", "build_request", "sink"]
+								syntax: "literal"
+							}
+						}
+						level: {
+							description: "The level of verbosity of the span."
+							required:    true
+							type: string: {
+								examples: ["ERROR", "DEBUG"]
+								syntax: "literal"
+							}
+						}
+						target: {
+							description: "The module where the span was created."
+							required:    true
+							type: string: {
+								examples: ["vector::transforms::remap"]
+								syntax: "literal"
+							}
+						}
+					}
+				}
+			}
+			fields: {
+				description: "The fields recorded on the span."
+				required:    false
+				type: object: {
+					examples: []
+					options: {}
+				}
+			}
+			component_kind: {
+				description: "The kind of the component the span belongs to, inherited from the spans it's nested in."
+				required:    false
+				type: string: {
+					default: null
+					enum: {
+						source:    "The span belongs to a source."
+						transform: "The span belongs to a transform."
+						sink:      "The span belongs to a sink."
+					}
+					syntax: "literal"
+				}
+			}
+			component_id: {
+				description: "The id of the component the span belongs to."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["my_remap"]
+					syntax: "literal"
+				}
+			}
+			component_type: {
+				description: "The type of the component the span belongs to."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["remap"]
+					syntax: "literal"
+				}
+			}
+			duration_seconds: {
+				description: "For ended spans, the time elapsed since the span started."
+				required:    false
+				type: float: {
+					default: null
+					examples: [0.0042]
+				}
+			}
+			busy_seconds: {
+				description: "For ended spans, the time spent executing inside the span, excluding the time it was idle."
+				required:    false
+				type: float: {
+					default: null
+					examples: [0.0013]
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		limited_spans: {
+			title: "Spans are limited by startup options"
+			body: """
+				As with the `internal_logs` source, the spans that are recorded are selected by the
+				`LOG` environment variable and the `--quiet` and `--verbose` command-line options.
+				Spans for component tasks are always recorded, while spans around hot paths such as
+				VRL execution (`vrl`) and sink request building (`build_request`) are recorded at the
+				debug level.
+				"""
+		}
+		overhead: {
+			title: "Overhead"
+			body: """
+				Spans are only tracked while an `internal_traces` source is running, and only spans
+				created after it started are recorded. Since events for every span are generated,
+				avoid sending them through components that create many spans themselves.
+				"""
+		}
+	}
+}
@@ -0,0 +1,97 @@
+package metadata
+
+components: sources: mqtt: {
+	title: "MQTT"
+
+	features: {
+		collect: {
+			checkpoint: enabled: false
+			tls:  components._mqtt.features.tls
+			from: components._mqtt.features.collect.from
+		}
+		multiline: enabled: false
+	}
+
+	classes: {
+		commonly_used: false
+		deployment_roles: ["aggregator"]
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	support: components._mqtt.support
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: components._mqtt.configuration & {
+		acknowledgements: configuration._acknowledgements
+		topics: {
+			description: "The topic filters to subscribe to. Filters may contain the `+` (single level) and `#` (multi level) wildcards."
+			required:    true
+			warnings: []
+			type: array: items: type: string: {
+				examples: ["sensors/+/temperature", "devices/#"]
+				syntax: "literal"
+			}
+		}
+		topic_key: {
+			common:      false
+			description: "The log field name to use for the topic the message was published to."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "topic"
+				examples: ["topic"]
+				syntax: "literal"
+			}
+		}
+	}
+
+	output: logs: record: {
+		description: "An individual MQTT message"
+		fields: {
+			message: {
+				description: "The raw line from the MQTT message payload."
+				required:    true
+				type: string: {
+					examples: ["{\"temperature\": 21.5}"]
+					syntax: "literal"
+				}
+			}
+			timestamp: fields._current_timestamp
+			topic: {
+				description: "The topic the message was published to."
+				required:    true
+				type: string: {
+					examples: ["sensors/kitchen/temperature"]
+					syntax: "literal"
+				}
+			}
+		}
+	}
+
+	telemetry: metrics: {
+		component_received_events_total:  components.sources.internal_metrics.output.metrics.component_received_events_total
+		connection_errors_total:          components.sources.internal_metrics.output.metrics.connection_errors_total
+		connection_send_ack_errors_total: components.sources.internal_metrics.output.metrics.connection_send_ack_errors_total
+		events_in_total:                  components.sources.internal_metrics.output.metrics.events_in_total
+		processed_bytes_total:            components.sources.internal_metrics.output.metrics.processed_bytes_total
+	}
+
+	how_it_works: components._mqtt.how_it_works & {
+		acknowledgements: {
+			title: "Acknowledgements"
+			body:  """
+				With `acknowledgements` enabled, messages received with QoS 1 or 2 are only
+				acknowledged to the broker once their events have been delivered by the sinks, in
+				the order the messages were received. Messages whose events fail to be delivered are
+				left unacknowledged. Unacknowledged messages are redelivered by the broker after a
+				reconnection, provided `clean_session` is disabled so the broker keeps the session.
+				"""
+		}
+	}
+}
@@ -0,0 +1,183 @@
+package metadata
+
+components: sources: netflow: {
+	_port: 2055
+
+	title: "NetFlow"
+
+	description: """
+		Receives flow records from network devices over UDP, and turns each of them into a log
+		event. [NetFlow v5](\(urls.netflow_v5)), [NetFlow v9](\(urls.netflow_v9)),
+		[IPFIX](\(urls.ipfix)) and [sFlow v5](\(urls.sflow_v5)) are supported on the same socket,
+		told apart by the version of each datagram.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		multiline: enabled: false
+		receive: {
+			from: {
+				service: {
+					name:     "NetFlow exporter"
+					thing:    "a \(name)"
+					url:      urls.ipfix
+					versions: null
+				}
+				interface: socket: {
+					direction: "incoming"
+					port:      _port
+					protocols: ["udp"]
+					ssl: "disabled"
+				}
+			}
+			receive_buffer_bytes: enabled: true
+			keepalive: enabled:            false
+			tls: enabled:                  false
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		address: {
+			description: "The address to listen for datagrams on. It _must_ include a port."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["0.0.0.0:\(_port)", "0.0.0.0:6343"]
+				syntax: "literal"
+			}
+		}
+		template_expiry_secs: {
+			common:      false
+			description: "The time after which a NetFlow v9 or IPFIX template is dropped when the exporter does not send it again. Records of unknown templates are dropped."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 1800
+				unit:    "seconds"
+			}
+		}
+	}
+
+	output: logs: flow: {
+		description: """
+			An individual flow record, or sFlow sample. The fields of NetFlow v9 and IPFIX records
+			are named after the [IANA information elements](\(urls.iana_ipfix_elements)) in snake
+			case, such as `source_ipv4_address` or `octet_delta_count`, and NetFlow v5 records and
+			sFlow samples use the same names for the same fields. Fields of unknown elements are
+			named `field_<id>`, or `enterprise_<enterprise number>_<id>` for enterprise specific
+			ones.
+			"""
+		fields: {
+			exporter_address: {
+				description: "The IP address the datagram was received from."
+				required:    true
+				type: string: {
+					examples: ["192.0.2.1"]
+					syntax: "literal"
+				}
+			}
+			flow_type: {
+				description: "The protocol of the datagram."
+				required:    true
+				type: string: {
+					enum: {
+						netflow_v5: "A NetFlow v5 flow record."
+						netflow_v9: "A NetFlow v9 data record."
+						ipfix:      "An IPFIX data record."
+						sflow_v5:   "An sFlow v5 flow or counter sample."
+					}
+					syntax: "literal"
+				}
+			}
+			record_type: {
+				description: "Whether a NetFlow v9 or IPFIX record is a flow record, or a record of an options template, such as sampling settings."
+				required:    false
+				type: string: {
+					enum: {
+						flow:    "A flow record."
+						options: "An options record."
+					}
+					syntax: "literal"
+				}
+			}
+			sample_type: {
+				description: "The type of an sFlow sample."
+				required:    false
+				type: string: {
+					enum: {
+						flow:    "A sample of a packet."
+						counter: "A sample of the counters of an interface."
+					}
+					syntax: "literal"
+				}
+			}
+			"*": {
+				description: "The fields of the record."
+				required:    true
+				type: "*": {}
+			}
+			timestamp: {
+				description: "The export time of the datagram, or the time it was received for sFlow."
+				required:    true
+				type: timestamp: {}
+			}
+		}
+	}
+
+	how_it_works: {
+		templates: {
+			title: "Templates"
+			body:  """
+				NetFlow v9 and IPFIX exporters describe their records with templates, sent periodically
+				alongside the records. Templates are kept per exporter address and observation domain,
+				until they are not sent again for `template_expiry_secs`. Records received before their
+				template are dropped, and counted by the `netflow_template_misses_total` metric.
+				"""
+		}
+		unsigned_values: {
+			title: "Unsigned values"
+			body:  """
+				Unsigned 64-bit values, such as counters, above the largest signed 64-bit integer of
+				events are saturated to it, and counted by the `saturated_counters_total` metric.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_received_events_total: components.sources.internal_metrics.output.metrics.component_received_events_total
+		connection_errors_total:         components.sources.internal_metrics.output.metrics.connection_errors_total
+		decode_errors_total:             components.sources.internal_metrics.output.metrics.decode_errors_total
+		events_in_total:                 components.sources.internal_metrics.output.metrics.events_in_total
+		netflow_template_misses_total:   components.sources.internal_metrics.output.metrics.netflow_template_misses_total
+		processed_bytes_total:           components.sources.internal_metrics.output.metrics.processed_bytes_total
+		saturated_counters_total:        components.sources.internal_metrics.output.metrics.saturated_counters_total
+	}
+}
@@ -0,0 +1,240 @@
+package metadata
+
+components: sources: redis: {
+	title: "Redis"
+
+	features: {
+		collect: {
+			checkpoint: enabled: false
+			from: {
+				service: services.redis
+				interface: {
+					socket: {
+						direction: "outgoing"
+						protocols: ["tcp"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+		multiline: enabled: false
+	}
+
+	classes: {
+		commonly_used: false
+		deployment_roles: ["aggregator"]
+		delivery:      "best_effort"
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		acknowledgements: configuration._acknowledgements
+		url: {
+			description: "The Redis URL to connect to. The url _must_ take the form of `protocol://server:port/db` where the protocol can either be `redis` or `rediss` for connections secured via TLS."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["redis://127.0.0.1:6379/0"]
+				syntax: "literal"
+			}
+		}
+		key: {
+			description: "The Redis key to read messages from: the list, the channel (or channel pattern) or the stream."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["vector"]
+				syntax: "literal"
+			}
+		}
+		data_type: {
+			common:      false
+			description: "The Redis data type (`list`, `channel` or `stream`) to use."
+			required:    false
+			type: string: {
+				default: "list"
+				enum: {
+					list:    "Use the Redis `list` data type."
+					channel: "Use the Redis `channel` data type."
+					stream:  "Use the Redis `stream` data type."
+				}
+				syntax: "literal"
+			}
+		}
+		list: {
+			common:      false
+			description: "Options for the Redis `list` data type."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					method: {
+						common:      false
+						description: "The method (`lpop` or `rpop`) to pop messages when `data_type` is list."
+						required:    false
+						type: string: {
+							default: "lpop"
+							enum: {
+								lpop: "Use the `blpop` method to pop messages."
+								rpop: "Use the `brpop` method to pop messages."
+							}
+							syntax: "literal"
+						}
+					}
+				}
+			}
+		}
+		channel: {
+			common:      false
+			description: "Options for the Redis `channel` data type."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					pattern: {
+						common:      false
+						description: "Whether `key` is a channel pattern, subscribed to with `psubscribe`, rather than a channel name."
+						required:    false
+						type: bool: default: false
+					}
+				}
+			}
+		}
+		stream: {
+			common:      false
+			description: "Options for the Redis `stream` data type."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					group: {
+						common:      true
+						description: "The consumer group to read the stream as."
+						required:    false
+						type: string: {
+							default: "vector"
+							examples: ["vector-aggregators"]
+							syntax: "literal"
+						}
+					}
+					consumer: {
+						common:      true
+						description: "The name of the consumer within the group. Each Vector instance reading from the same group should use a distinct name."
+						required:    false
+						type: string: {
+							default: "vector"
+							examples: ["vector-1"]
+							syntax: "literal"
+						}
+					}
+					field: {
+						common:      false
+						description: "The field of stream entries holding the message."
+						required:    false
+						type: string: {
+							default: "message"
+							examples: ["payload"]
+							syntax: "literal"
+						}
+					}
+					batch_size: {
+						common:      false
+						description: "The maximum number of entries to read at once."
+						required:    false
+						type: uint: {
+							default: 100
+							unit:    null
+						}
+					}
+					create_group: {
+						common:      false
+						description: "Whether to create the consumer group, and the stream, if they don't exist yet. A new group starts reading from the end of the stream."
+						required:    false
+						type: bool: default: true
+					}
+				}
+			}
+		}
+		redis_key: {
+			common:      false
+			description: "The log field name to use for the Redis key the message was read from. When using a channel pattern, this is the name of the channel the message was published to."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["redis_key"]
+				syntax: "literal"
+			}
+		}
+	}
+
+	output: logs: record: {
+		description: "An individual Redis message"
+		fields: {
+			message: {
+				description: "The raw line from the message."
+				required:    true
+				type: string: {
+					examples: ["2021-11-05T10:00:00.000Z INFO Hello world"]
+					syntax: "literal"
+				}
+			}
+			timestamp: fields._current_timestamp
+		}
+	}
+
+	telemetry: metrics: {
+		component_received_events_total:  components.sources.internal_metrics.output.metrics.component_received_events_total
+		connection_send_ack_errors_total: components.sources.internal_metrics.output.metrics.connection_send_ack_errors_total
+		events_in_total:                  components.sources.internal_metrics.output.metrics.events_in_total
+		processed_bytes_total:            components.sources.internal_metrics.output.metrics.processed_bytes_total
+	}
+
+	how_it_works: {
+		redis_rs: {
+			title: "redis-rs"
+			body:  """
+				The `redis` source uses [`redis-rs`](\(urls.redis_rs)) under the hood, which is a high level Redis library
+				for Rust. It provides convenient access to all Redis functionality through a very flexible but low-level
+				API.
+				"""
+		}
+		delivery: {
+			title: "Delivery guarantees"
+			body:  """
+				Messages popped from a list or published to a channel are gone from Redis as soon as Vector has read them,
+				so they are lost if Vector stops before delivering them.
+
+				Streams are read as part of a consumer group. With `acknowledgements` enabled, entries are only
+				acknowledged with `xack` once their events have been delivered, and entries left pending by a previous
+				run of the same consumer are read again on startup, giving at-least-once delivery.
+				"""
+		}
+	}
+}
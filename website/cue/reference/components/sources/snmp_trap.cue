@@ -0,0 +1,405 @@
+package metadata
+
+components: sources: snmp_trap: {
+	_port: 162
+
+	title: "SNMP Trap"
+
+	description: """
+		Receives [SNMP](\(urls.snmp)) notifications over UDP, and turns each of them into a log
+		event. Traps of SNMPv1, and traps and informs of SNMPv2c and SNMPv3 are supported, with the
+		[user-based security model](\(urls.snmp_usm)) of SNMPv3. Informs are acknowledged with a
+		response once their event is sent.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		multiline: enabled: false
+		receive: {
+			from: {
+				service: {
+					name:     "SNMP agent"
+					thing:    "an \(name)"
+					url:      urls.snmp
+					versions: null
+				}
+				interface: socket: {
+					direction: "incoming"
+					port:      _port
+					protocols: ["udp"]
+					ssl: "disabled"
+				}
+			}
+			receive_buffer_bytes: enabled: true
+			keepalive: enabled:            false
+			tls: enabled:                  false
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		address: {
+			description: "The address to listen for notifications on. It _must_ include a port."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["0.0.0.0:\(_port)", "0.0.0.0:1162"]
+				syntax: "literal"
+			}
+		}
+		communities: {
+			common:      true
+			description: "The communities SNMPv1 and SNMPv2c notifications are accepted from. Notifications of any community are accepted when unset."
+			required:    false
+			warnings: []
+			type: array: {
+				default: null
+				items: type: string: {
+					examples: ["public"]
+					syntax: "literal"
+				}
+			}
+		}
+		engine_id: {
+			common:      false
+			description: "The engine ID of the source, in hexadecimal, which SNMPv3 agents discover before sending informs. Agents sending traps use their own engine ID instead."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "8000000004766563746f72"
+				examples: ["8000000004766563746f72", "80001f8880e1f5c36e3c5d5e61"]
+				syntax: "literal"
+			}
+		}
+		mib_paths: {
+			common:      true
+			description: "The [MIB](\(urls.snmp_mib)) files, or directories of MIB files, used to translate object identifiers to names. Object identifiers are not translated when unset."
+			required:    false
+			warnings: []
+			type: array: {
+				default: []
+				items: type: string: {
+					examples: ["/usr/share/snmp/mibs", "/etc/vector/mibs/EXAMPLE-MIB.txt"]
+					syntax: "literal"
+				}
+			}
+		}
+		users: {
+			common:      false
+			description: "The users SNMPv3 notifications are accepted from."
+			required:    false
+			warnings: []
+			type: array: {
+				default: []
+				items: type: object: {
+					examples: []
+					options: {
+						name: {
+							description: "The name of the user."
+							required:    true
+							warnings: []
+							type: string: {
+								examples: ["vector"]
+								syntax: "literal"
+							}
+						}
+						auth_protocol: {
+							description: "The authentication protocol of the user. Messages of users without one are neither authenticated nor encrypted."
+							required:    false
+							common:      true
+							warnings: []
+							type: string: {
+								default: null
+								enum: {
+									md5:    "HMAC-MD5-96."
+									sha:    "HMAC-SHA-96."
+									sha224: "HMAC-SHA-224-128."
+									sha256: "HMAC-SHA-256-192."
+									sha384: "HMAC-SHA-384-256."
+									sha512: "HMAC-SHA-512-384."
+								}
+								syntax: "literal"
+							}
+						}
+						auth_password: {
+							description: "The authentication password of the user, of at least 8 characters."
+							required:    false
+							common:      true
+							warnings: []
+							type: string: {
+								default: null
+								examples: ["${SNMP_AUTH_PASSWORD}"]
+								syntax: "literal"
+							}
+						}
+						priv_protocol: {
+							description: "The privacy protocol of the user, which also needs an authentication protocol. Messages of users without one are not encrypted."
+							required:    false
+							common:      true
+							warnings: []
+							type: string: {
+								default: null
+								enum: {
+									des: "CBC-DES."
+									aes: "CFB128-AES-128."
+								}
+								syntax: "literal"
+							}
+						}
+						priv_password: {
+							description: "The privacy password of the user, of at least 8 characters."
+							required:    false
+							common:      true
+							warnings: []
+							type: string: {
+								default: null
+								examples: ["${SNMP_PRIV_PASSWORD}"]
+								syntax: "literal"
+							}
+						}
+					}
+				}
+			}
+		}
+	}
+
+	output: logs: notification: {
+		description: "An individual trap or inform."
+		fields: {
+			agent_address: {
+				description: "The address of the agent, for SNMPv1 traps."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["192.0.2.1"]
+					syntax: "literal"
+				}
+			}
+			community: {
+				description: "The community of SNMPv1 and SNMPv2c notifications."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["public"]
+					syntax: "literal"
+				}
+			}
+			context_engine_id: {
+				description: "The context engine ID of SNMPv3 notifications, in hexadecimal."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["80001f8880e1f5c36e3c5d5e61"]
+					syntax: "literal"
+				}
+			}
+			context_name: {
+				description: "The context name of SNMPv3 notifications."
+				required:    false
+				type: string: {
+					default: null
+					examples: [""]
+					syntax: "literal"
+				}
+			}
+			engine_id: {
+				description: "The authoritative engine ID of SNMPv3 notifications, in hexadecimal."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["80001f8880e1f5c36e3c5d5e61"]
+					syntax: "literal"
+				}
+			}
+			enterprise: {
+				description: "The enterprise of SNMPv1 traps."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["1.3.6.1.4.1.8072.3.2.10"]
+					syntax: "literal"
+				}
+			}
+			generic_trap: {
+				description: "The generic trap number of SNMPv1 traps."
+				required:    false
+				type: uint: {
+					default: null
+					examples: [6]
+					unit: null
+				}
+			}
+			host: {
+				description: "The IP address the notification was received from."
+				required:    true
+				type: string: {
+					examples: ["192.0.2.1"]
+					syntax: "literal"
+				}
+			}
+			pdu_type: {
+				description: "The type of the notification."
+				required:    true
+				type: string: {
+					enum: {
+						trap:   "A trap, which is not acknowledged."
+						inform: "An inform, which is acknowledged with a response."
+					}
+					syntax: "literal"
+				}
+			}
+			request_id: {
+				description: "The request ID of SNMPv2c and SNMPv3 notifications."
+				required:    false
+				type: int: {
+					default: null
+					examples: [1804289383]
+				}
+			}
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: {
+					examples: ["snmp_trap"]
+					syntax: "literal"
+				}
+			}
+			specific_trap: {
+				description: "The specific trap number of SNMPv1 traps."
+				required:    false
+				type: uint: {
+					default: null
+					examples: [17]
+					unit: null
+				}
+			}
+			timestamp: fields._current_timestamp
+			trap_name: {
+				description: "The name of `trap_oid`, when `mib_paths` is set and its object identifier is defined."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["IF-MIB::linkDown"]
+					syntax: "literal"
+				}
+			}
+			trap_oid: {
+				description: "The object identifier of the notification. For SNMPv1 traps, it is derived from the generic and specific trap numbers as in RFC 3584."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["1.3.6.1.6.3.1.1.5.3"]
+					syntax: "literal"
+				}
+			}
+			uptime: {
+				description: "The time since the agent started, in hundredths of seconds."
+				required:    false
+				type: uint: {
+					default: null
+					examples: [4200]
+					unit: null
+				}
+			}
+			user: {
+				description: "The user of SNMPv3 notifications."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["vector"]
+					syntax: "literal"
+				}
+			}
+			varbinds: {
+				description: """
+					The variable bindings of the notification, as objects with their `oid`, `type` and
+					`value`, along with their `name` when `mib_paths` is set, and the `value_name` of
+					object identifier values. Octet strings which are not printable text are encoded in
+					hexadecimal, and `counter64` values above the largest signed 64-bit integer are
+					saturated to it. The `sysUpTime.0` and `snmpTrapOID.0` bindings of SNMPv2c and
+					SNMPv3 notifications are moved to the `uptime` and `trap_oid` fields instead.
+					"""
+				required: true
+				type: array: items: type: object: {
+					examples: [{
+						oid:   "1.3.6.1.2.1.2.2.1.1.2"
+						name:  "IF-MIB::ifIndex.2"
+						type:  "integer"
+						value: 2
+					}]
+					options: {}
+				}
+			}
+			version: {
+				description: "The SNMP version of the notification."
+				required:    true
+				type: string: {
+					enum: {
+						"1":  "SNMPv1."
+						"2c": "SNMPv2c."
+						"3":  "SNMPv3."
+					}
+					syntax: "literal"
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		informs: {
+			title: "Informs"
+			body:  """
+				Informs are acknowledged with a response once their event is sent. SNMPv3 agents
+				first discover the engine ID of the source, which answers them with a report, and
+				then send their informs to that engine ID, authenticated and encrypted with keys
+				localized to it.
+				"""
+		}
+		mibs: {
+			title: "MIBs"
+			body:  """
+				Only the object identifiers of the modules of `mib_paths` are read, so that modules
+				need not be complete. Object identifiers are translated to the name of their longest
+				defined prefix, followed by the remaining arcs, as in `IF-MIB::ifIndex.2`. The base
+				definitions of `SNMPv2-SMI` and the standard notifications are always known.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_received_events_total: components.sources.internal_metrics.output.metrics.component_received_events_total
+		connection_errors_total:         components.sources.internal_metrics.output.metrics.connection_errors_total
+		connection_send_errors_total:    components.sources.internal_metrics.output.metrics.connection_send_errors_total
+		decode_errors_total:             components.sources.internal_metrics.output.metrics.decode_errors_total
+		events_in_total:                 components.sources.internal_metrics.output.metrics.events_in_total
+		processed_bytes_total:           components.sources.internal_metrics.output.metrics.processed_bytes_total
+		saturated_counters_total:        components.sources.internal_metrics.output.metrics.saturated_counters_total
+	}
+}
@@ -23,6 +23,25 @@ configuration: {
 			}
 		}
 
+		memory_budget_bytes: {
+			common: false
+			description: """
+				The maximum number of bytes held by the events admitted by
+				sources and not yet finalized, across all components. Once it
+				is reached, sources wait for events to be delivered or dropped
+				before admitting more, applying backpressure upstream instead
+				of growing memory buffers, transform state, and batches without
+				bound. Events written to disk buffers are no longer counted.
+				Unlimited when unset.
+				"""
+			required: false
+			type: uint: {
+				default: null
+				examples: [536870912]
+				unit: "bytes"
+			}
+		}
+
 		log_schema: {
 			common: false
 			description: """
@@ -0,0 +1,10 @@
+package metadata
+
+services: amqp: {
+	name:     "AMQP"
+	thing:    "an \(name) 0.9.1 broker"
+	url:      urls.amqp
+	versions: null
+
+	description: "[AMQP](\(urls.amqp)) 0.9.1 is the messaging protocol implemented by brokers such as [RabbitMQ](\(urls.rabbitmq)), routing messages published to exchanges into queues."
+}
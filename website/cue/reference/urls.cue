@@ -5,6 +5,8 @@ urls: {
 	affine_type_system:                                       "\(wikipedia)/wiki/Substructural_type_system#Affine_type_systems"
 	adaptive_request_concurrency_post:                        "/blog/adaptive-request-concurrency/"
 	amazon_linux:                                             "https://aws.amazon.com/amazon-linux-ami/"
+	amqp:                                                     "https://www.amqp.org/"
+	amqp_uri:                                                 "https://www.rabbitmq.com/uri-spec.html"
 	ansi_escape_codes:                                        "\(wikipedia)/wiki/ANSI_escape_code"
 	apache:                                                   "https://httpd.apache.org"
 	apache_common:                                            "\(apache)/docs/current/logs.html#common"
@@ -61,6 +63,7 @@ urls: {
 	aws_kinesis_streams_api:                                  "\(aws_docs)/kinesis/latest/APIReference/API_PutRecords.html"
 	aws_kinesis_streams_service_limits:                       "\(aws_docs)/streams/latest/dev/service-sizes-and-limits.html"
 	aws_kinesis_split_shards:                                 "\(aws_docs)/streams/latest/dev/kinesis-using-sdk-java-resharding-split.html"
+	aws_kinesis_streams_kpl_aggregation:                      "https://github.com/awslabs/amazon-kinesis-producer/blob/master/aggregation-format.md"
 	aws_regions:                                              "\(aws_docs)/AmazonRDS/latest/UserGuide/Concepts.RegionsAndAvailabilityZones.html"
 	aws_s3:                                                   "https://aws.amazon.com/s3/"
 	aws_s3_acl:                                               "\(aws_docs)/AmazonS3/latest/dev/acl-overview.html"
@@ -235,6 +238,7 @@ urls: {
 	humio_hec:                                                "https://docs.humio.com/integrations/data-shippers/hec/"
 	humio_hec_format_of_data:                                 "https://docs.humio.com/integrations/data-shippers/hec/#format-of-data"
 	iam_instance_profile:                                     "\(aws_docs)/IAM/latest/UserGuide/id_roles_use_switch-role-ec2_instance-profiles.html"
+	iana_ipfix_elements:                                      "https://www.iana.org/assignments/ipfix/ipfix.xhtml"
 	iana_time_zone_format:                                    "\(wikipedia)/wiki/Tz_database#Names_of_time_zones"
 	iana_time_zones:                                          "\(wikipedia)/wiki/List_of_tz_database_time_zones"
 	ieee_754:                                                 "\(wikipedia)/wiki/IEEE_754"
@@ -248,6 +252,7 @@ urls: {
 	inode:                                                    "\(wikipedia)/wiki/Inode"
 	ip_aton:                                                  "https://linux.die.net/man/3/inet_aton"
 	ip_ntoa:                                                  "https://linux.die.net/man/3/inet_ntoa"
+	ipfix:                                                    "https://tools.ietf.org/html/rfc7011"
 	iso_8601:                                                 "\(wikipedia)/wiki/ISO_8601"
 	iso3166_2:                                                "\(wikipedia)/wiki/ISO_3166-2"
 	issue_1694:                                               "\(vector_repo)/issues/1694"
@@ -274,6 +279,7 @@ urls: {
 	kubernetes_rbac:                                          "\(kubernetes)/docs/reference/access-authn-authz/rbac/"
 	kubernetes_request_verbs:                                 "\(kubernetes)/docs/reference/access-authn-authz/authorization/#determine-the-request-verb"
 	kubernetes_watch_api:                                     "\(kubernetes)/docs/reference/generated/kubernetes-api/v1.10/#watch-30"
+	lapin:                                                    "\(github)/amqp-rs/lapin"
 	leveldb:                                                  "\(github)/google/leveldb"
 	leveldb_sys_2:                                            "https://crates.io/crates/leveldb-sys"
 	leveldb_sys_3:                                            "\(github)/timberio/leveldb-sys/tree/v3.0.0"
@@ -327,9 +333,13 @@ urls: {
 	mongodb:                                                  "https://www.mongodb.com"
 	mongodb_command_server_status:                            "https://docs.mongodb.com/manual/reference/command/serverStatus/"
 	mongodb_connection_string_uri_format:                     "https://docs.mongodb.com/manual/reference/connection-string/"
+	mqtt:                                                     "https://mqtt.org/"
+	mqtt_qos:                                                 "https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/os/mqtt-v3.1.1-os.html#_Toc398718099"
 	musl_builder_docker_image:                                "\(vector_repo)/blob/master/scripts/ci-docker-images/builder-x86_64-unknown-linux-musl/Dockerfile"
 	nats:                                                     "https://nats.io/"
 	nats_rs:                                                  "\(github)/nats-io/nats.rs"
+	netflow_v5:                                               "https://www.cisco.com/c/en/us/td/docs/net_mgmt/netflow_collection_engine/3-6/user/guide/format.html#wp1006108"
+	netflow_v9:                                               "https://tools.ietf.org/html/rfc3954"
 	new_bug_report:                                           "\(vector_repo)/issues/new?labels=type%3A+bug"
 	new_feature_request:                                      "\(vector_repo)/issues/new?labels=type%3A+new+feature"
 	new_relic:                                                "https://newrelic.com/"
@@ -381,6 +391,7 @@ urls: {
 	protobuf:                                                 "https://developers.google.com/protocol-buffers"
 	pulsar:                                                   "https://pulsar.apache.org/"
 	pulsar_protocol:                                          "https://pulsar.apache.org/docs/en/develop-binary-protocol/"
+	rabbitmq:                                                 "https://www.rabbitmq.com/"
 	raspbian:                                                 "https://www.raspbian.org/"
 	rdkafka:                                                  "\(github)/edenhill/librdkafka"
 	regex:                                                    "\(wikipedia)/wiki/Regular_expression"
@@ -400,6 +411,7 @@ urls: {
 	rfc_6891:                                                 "https://tools.ietf.org/html/rfc6891"
 	rhel:                                                     "https://www.redhat.com/en/technologies/linux-platforms/enterprise-linux"
 	rpm:                                                      "https://rpm.org/"
+	rumqttc:                                                  "\(github)/bytebeamio/rumqtt"
 	rust:                                                     "https://www.rust-lang.org/"
 	rust_date_time:                                           "https://docs.rs/chrono/latest/chrono/struct.DateTime.html"
 	rust_grok_library:                                        "\(github)/daschl/grok"
@@ -421,12 +433,16 @@ urls: {
 	sematext_monitoring:                                      "https://sematext.com/docs/monitoring/"
 	sematext_registration:                                    "https://apps.sematext.com/ui/registration"
 	semver:                                                   "https://semver.org/"
+	sflow_v5:                                                 "https://sflow.org/sflow_version_5.txt"
 	sha1:                                                     "\(wikipedia)/wiki/SHA-1"
 	sha2:                                                     "\(wikipedia)/wiki/SHA-2"
 	sha3:                                                     "\(wikipedia)/wiki/SHA-3"
 	signal:                                                   "\(wikipedia)/wiki/Signal_(IPC)"
 	snake_case:                                               "\(wikipedia)/wiki/Snake_case"
 	snappy:                                                   "https://google.github.io/snappy/"
+	snmp:                                                     "https://tools.ietf.org/html/rfc3416"
+	snmp_mib:                                                 "https://tools.ietf.org/html/rfc2578"
+	snmp_usm:                                                 "https://tools.ietf.org/html/rfc3414"
 	socket:                                                   "\(wikipedia)/wiki/Network_socket"
 	splunk:                                                   "https://www.splunk.com"
 	splunk_hec:                                               "https://dev.splunk.com/enterprise/docs/dataapps/httpeventcollector/"